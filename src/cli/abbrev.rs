@@ -36,7 +36,8 @@ pub fn find_unique_command<'a>(prefix: &str, commands: &'a [&str]) -> Result<&'a
 /// Top-level commands in Tatl
 pub const TOP_LEVEL_COMMANDS: &[&str] = &[
     "projects", "add", "list", "modify", "on", "off", "offon", "onoff", "dequeue",
-    "annotate", "finish", "close", "reopen", "delete", "enqueue", "sessions", "show"
+    "annotate", "finish", "close", "reopen", "delete", "enqueue", "sessions", "show",
+    "history", "undo", "queue"
 ];
 
 /// Project subcommands
@@ -46,12 +47,12 @@ pub const PROJECT_COMMANDS: &[&str] = &[
 
 /// Sessions subcommands
 pub const SESSIONS_COMMANDS: &[&str] = &[
-    "list", "show", "modify", "delete", "report"
+    "list", "show", "modify", "delete", "report", "export", "audit"
 ];
 
 /// Queue subcommands
 pub const QUEUE_COMMANDS: &[&str] = &[
-    "sort"
+    "list", "switch", "move", "roll", "swap", "clear", "sort"
 ];
 
 /// Task subcommands (used with task <id> <subcommand> pattern)
@@ -0,0 +1,312 @@
+// Billing rules for `tatl sessions report --billing`
+
+use crate::config::Config;
+use crate::utils::parse_duration;
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashMap};
+
+/// How billed time is rounded to the increment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rounding {
+    Up,
+    Nearest,
+    Down,
+}
+
+impl Rounding {
+    fn parse(value: &str) -> Option<Rounding> {
+        match value {
+            "up" => Some(Rounding::Up),
+            "nearest" => Some(Rounding::Nearest),
+            "down" => Some(Rounding::Down),
+            _ => None,
+        }
+    }
+}
+
+/// What is rounded: each session, or each day's total
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundPer {
+    Session,
+    Day,
+}
+
+impl RoundPer {
+    fn parse(value: &str) -> Option<RoundPer> {
+        match value {
+            "session" => Some(RoundPer::Session),
+            "day" => Some(RoundPer::Day),
+            _ => None,
+        }
+    }
+}
+
+/// Billing settings that apply to a task
+#[derive(Debug, Clone, PartialEq)]
+pub struct BillingRule {
+    /// Rounding increment in seconds; 0 bills exact time
+    pub increment: i64,
+    pub rounding: Rounding,
+    pub round_per: RoundPer,
+    /// Hourly rate; time without a rate is billed at zero
+    pub rate: Option<f64>,
+    pub billable: bool,
+}
+
+impl BillingRule {
+    /// Billed seconds for tracked seconds. Non-billable time bills nothing.
+    pub fn billed_secs(&self, secs: i64) -> i64 {
+        if !self.billable {
+            return 0;
+        }
+        if self.increment <= 0 || secs <= 0 {
+            return secs.max(0);
+        }
+        let increment = self.increment;
+        let units = match self.rounding {
+            Rounding::Up => (secs + increment - 1) / increment,
+            Rounding::Nearest => (secs + increment / 2) / increment,
+            Rounding::Down => secs / increment,
+        };
+        units * increment
+    }
+
+    pub fn amount(&self, billed_secs: i64) -> f64 {
+        self.rate.unwrap_or(0.0) * billed_secs as f64 / 3600.0
+    }
+}
+
+/// Settings configured for one scope; unset fields fall back to a wider scope
+#[derive(Debug, Clone, Default)]
+struct Overrides {
+    increment: Option<i64>,
+    rounding: Option<Rounding>,
+    round_per: Option<RoundPer>,
+    rate: Option<f64>,
+    billable: Option<bool>,
+}
+
+impl Overrides {
+    fn set(&mut self, setting: &str, value: &str) -> Result<(), String> {
+        let invalid = || format!("invalid value '{}'", value);
+        match setting {
+            "increment" => self.increment = Some(parse_duration(value).map_err(|_| invalid())?),
+            "rounding" => self.rounding = Some(Rounding::parse(value).ok_or_else(invalid)?),
+            "round_per" => self.round_per = Some(RoundPer::parse(value).ok_or_else(invalid)?),
+            "rate" => self.rate = Some(value.parse().map_err(|_| invalid())?),
+            "billable" => self.billable = Some(value == "true"),
+            _ => return Err(format!("unknown billing setting '{}'", setting)),
+        }
+        Ok(())
+    }
+}
+
+/// Billing settings, read from `billing.*` configuration keys
+///
+/// Each setting has a global key and may be overridden for a project (which
+/// also covers its subprojects) or for tasks with a given UDA value:
+///
+/// ```text
+/// billing.increment=15m                      # round to 15 minutes
+/// billing.rounding=up                        # up, nearest or down
+/// billing.round_per=session                  # session or day
+/// billing.rate=100                           # hourly rate
+/// billing.billable=true
+/// billing.rate.project.work.acme=150         # project work.acme (and work.acme.*)
+/// billing.increment.uda.client.globex=6m     # tasks with uda.client=globex
+/// billing.billable.project.internal=false
+/// ```
+///
+/// A UDA setting takes precedence over a project setting, and the most specific
+/// project wins over its ancestors. UDA values match regardless of case.
+#[derive(Debug, Clone, Default)]
+pub struct BillingRules {
+    defaults: Overrides,
+    projects: HashMap<String, Overrides>,
+    /// Keyed by (UDA key, value)
+    udas: BTreeMap<(String, String), Overrides>,
+    /// UDA whose value names the client
+    pub client_uda: String,
+}
+
+impl BillingRules {
+    /// Rules from the configuration. Keys with an unknown scope are reported on stderr.
+    pub fn from_config(config: &Config) -> BillingRules {
+        let mut rules = BillingRules {
+            client_uda: config.get("billing.client_uda").unwrap_or("client").to_string(),
+            ..Default::default()
+        };
+        for setting in ["increment", "rounding", "round_per", "rate", "billable"] {
+            if let Some(value) = config.get(&format!("billing.{}", setting)) {
+                if let Err(e) = rules.defaults.set(setting, value) {
+                    eprintln!("Warning: ignoring 'billing.{}': {}", setting, e);
+                }
+            }
+            let prefix = format!("billing.{}.", setting);
+            for (scope, value) in config.entries_with_prefix(&prefix) {
+                if let Err(e) = rules.set_scoped(setting, scope, value) {
+                    eprintln!("Warning: ignoring '{}{}': {}", prefix, scope, e);
+                }
+            }
+        }
+        rules
+    }
+
+    /// Set a setting for a `project.<path>` or `uda.<key>.<value>` scope
+    fn set_scoped(&mut self, setting: &str, scope: &str, value: &str) -> Result<(), String> {
+        let overrides = if let Some(project) = scope.strip_prefix("project.") {
+            self.projects.entry(project.to_string()).or_default()
+        } else if let Some((key, uda_value)) = scope.strip_prefix("uda.").and_then(|rest| rest.split_once('.')) {
+            self.udas.entry((key.to_string(), uda_value.to_string())).or_default()
+        } else {
+            return Err("expected project.<path> or uda.<key>.<value>".to_string());
+        };
+        overrides.set(setting, value)
+    }
+
+    /// The rule for a task with this project and these UDAs
+    pub fn rule_for(&self, project: Option<&str>, udas: &HashMap<String, String>) -> BillingRule {
+        let mut scopes: Vec<&Overrides> = self.udas
+            .iter()
+            .filter(|((key, value), _)| udas.get(key).is_some_and(|v| v.eq_ignore_ascii_case(value)))
+            .map(|(_, overrides)| overrides)
+            .collect();
+        if let Some(project) = project {
+            let mut projects: Vec<(&String, &Overrides)> = self.projects
+                .iter()
+                .filter(|(name, _)| project == name.as_str() || project.starts_with(&format!("{}.", name)))
+                .collect();
+            projects.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+            scopes.extend(projects.into_iter().map(|(_, overrides)| overrides));
+        }
+        scopes.push(&self.defaults);
+
+        BillingRule {
+            increment: scopes.iter().find_map(|s| s.increment).unwrap_or(0),
+            rounding: scopes.iter().find_map(|s| s.rounding).unwrap_or(Rounding::Up),
+            round_per: scopes.iter().find_map(|s| s.round_per).unwrap_or(RoundPer::Session),
+            rate: scopes.iter().find_map(|s| s.rate),
+            billable: scopes.iter().find_map(|s| s.billable).unwrap_or(true),
+        }
+    }
+}
+
+/// Time tracked in one session
+#[derive(Debug, Clone)]
+pub struct TrackedTime {
+    pub project: Option<String>,
+    pub client: Option<String>,
+    /// Local date the session started
+    pub day: NaiveDate,
+    pub secs: i64,
+    pub rule: BillingRule,
+}
+
+/// Tracked time after rounding
+#[derive(Debug, Clone, PartialEq)]
+pub struct BilledTime {
+    pub project: Option<String>,
+    pub client: Option<String>,
+    pub secs: i64,
+    pub billed_secs: i64,
+    pub amount: f64,
+}
+
+/// Round tracked time. Time billed per day is summed by day, project, client
+/// and rule before rounding; other time is rounded session by session.
+pub fn bill(items: Vec<TrackedTime>) -> Vec<BilledTime> {
+    let mut daily: Vec<TrackedTime> = Vec::new();
+    let mut billed = Vec::new();
+    for item in items {
+        if item.rule.round_per == RoundPer::Session {
+            billed.push(item);
+            continue;
+        }
+        match daily.iter_mut().find(|d| {
+            d.day == item.day && d.project == item.project && d.client == item.client && d.rule == item.rule
+        }) {
+            Some(day) => day.secs += item.secs,
+            None => daily.push(item),
+        }
+    }
+    billed.extend(daily);
+
+    billed
+        .into_iter()
+        .map(|item| {
+            let billed_secs = item.rule.billed_secs(item.secs);
+            BilledTime {
+                amount: item.rule.amount(billed_secs),
+                project: item.project,
+                client: item.client,
+                secs: item.secs,
+                billed_secs,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn rules(text: &str) -> BillingRules {
+        BillingRules::from_config(&Config::parse(text, Path::new("rc")))
+    }
+
+    #[test]
+    fn test_billed_secs() {
+        let mut rule = rules("billing.increment=15m\n").rule_for(None, &HashMap::new());
+        assert_eq!(rule.billed_secs(60), 900);
+        assert_eq!(rule.billed_secs(900), 900);
+        rule.rounding = Rounding::Nearest;
+        assert_eq!(rule.billed_secs(7 * 60), 0);
+        assert_eq!(rule.billed_secs(8 * 60), 900);
+        rule.rounding = Rounding::Down;
+        assert_eq!(rule.billed_secs(29 * 60), 900);
+        rule.billable = false;
+        assert_eq!(rule.billed_secs(3600), 0);
+    }
+
+    #[test]
+    fn test_rule_precedence() {
+        let rules = rules(
+            "billing.rate=100\n\
+             billing.increment=15m\n\
+             billing.rate.project.work=120\n\
+             billing.rate.project.work.acme=150\n\
+             billing.increment.uda.client.globex=6m\n\
+             billing.rate.uda.client.globex=90\n\
+             billing.billable.project.internal=false\n",
+        );
+        let none = HashMap::new();
+        let globex: HashMap<String, String> = [("client".to_string(), "globex".to_string())].into_iter().collect();
+
+        assert_eq!(rules.rule_for(None, &none).rate, Some(100.0));
+        assert_eq!(rules.rule_for(Some("work.email"), &none).rate, Some(120.0));
+        assert_eq!(rules.rule_for(Some("work.acme.api"), &none).rate, Some(150.0));
+        assert_eq!(rules.rule_for(Some("workshop"), &none).rate, Some(100.0));
+        let rule = rules.rule_for(Some("work.acme"), &globex);
+        assert_eq!((rule.rate, rule.increment), (Some(90.0), 360));
+        assert!(!rules.rule_for(Some("internal.admin"), &none).billable);
+        assert_eq!(rules.client_uda, "client");
+    }
+
+    #[test]
+    fn test_bill_per_day() {
+        let rule = BillingRule {
+            increment: 900,
+            rounding: Rounding::Up,
+            round_per: RoundPer::Day,
+            rate: Some(100.0),
+            billable: true,
+        };
+        let day = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let item = |secs, day| TrackedTime { project: Some("work".to_string()), client: None, day, secs, rule: rule.clone() };
+        let billed = bill(vec![item(600, day), item(600, day), item(600, day.succ_opt().unwrap())]);
+        assert_eq!(billed.len(), 2);
+        assert_eq!((billed[0].secs, billed[0].billed_secs, billed[0].amount), (1200, 1800, 50.0));
+        assert_eq!(billed[1].billed_secs, 900);
+    }
+}
@@ -1,16 +1,23 @@
 use clap::{Parser, Subcommand};
 use rusqlite::Connection;
 use chrono::{Local, TimeZone};
+use crate::config::Config;
 use crate::db::DbConnection;
-use crate::repo::{ProjectRepo, TaskRepo, StackRepo, SessionRepo, AnnotationRepo, TemplateRepo, ViewRepo, ExternalRepo, StageRepo};
-use crate::models::TaskStatus;
+use crate::repo::{ProjectRepo, TaskRepo, StackRepo, SessionRepo, AnnotationRepo, TemplateRepo, TemplateChild, ViewRepo, ViewOptions, ExternalRepo, StageRepo, EventRepo, UdaRepo, DependencyRepo};
+use crate::models::{Pomodoro, TaskStatus, UdaDefinition, UdaType};
 use crate::cli::parser::{parse_task_args, join_description};
-use crate::cli::commands_sessions::{handle_task_sessions_list_with_filter, handle_task_sessions_show_with_filter, handle_sessions_modify, handle_sessions_delete, handle_sessions_report};
-use crate::cli::output::{format_task_list_table, format_task_summary, TaskListOptions};
-use crate::cli::error::{user_error, validate_task_id, validate_project_name, parse_task_id_spec, parse_task_id_list};
-use crate::utils::{parse_date_expr, parse_duration, fuzzy};
+use crate::cli::session_audit::SessionLimits;
+use crate::cli::pomodoro::{self, Phase};
+use crate::cli::commands_sessions::{SessionAttributes, handle_task_sessions_list_with_filter, handle_task_sessions_show_with_filter, handle_sessions_modify, handle_sessions_delete, handle_sessions_report, handle_sessions_export, handle_sessions_audit};
+use crate::cli::commands_history::{handle_history, handle_undo};
+use crate::cli::commands_config::handle_config;
+use crate::cli::output::{format_task_list_table, format_task_summary, format_priority_explanation, format_date, format_occurrence, TaskListOptions};
+use crate::cli::priority::{priority_terms, UrgencyCoefficients};
+use crate::cli::status::compute_respawn_status;
+use crate::cli::error::{user_error, validate_task_id, validate_project_name, validate_queue_name, validate_uda_key, parse_task_id_spec, parse_task_id_list};
+use crate::utils::{parse_date_expr, parse_duration, parse_time_of_day, fuzzy, Calendar, Period};
 use crate::filter::{parse_filter, filter_tasks};
-use crate::respawn::respawn_task;
+use crate::respawn::{respawn_task, skip_occurrence, project_occurrences, remaining_occurrences, RespawnRule};
 use crate::cli::abbrev;
 use std::collections::HashMap;
 use anyhow::{Context, Result};
@@ -61,21 +68,33 @@ The task description is all text that doesn't match field patterns. Field syntax
   template=<name>    - Use template
   respawn=<pattern>  - Set respawn rule (see RESPAWN PATTERNS below)
   parent=<id>        - Set parent task (creates nesting)
+  depends=<ids>      - Depend on other tasks (e.g., \"depends=12,15\"); blocked until they close
   +<tag>             - Add tag
   -<tag>             - Remove tag
   uda.<key>=<value>  - Set user-defined attribute
 
 DATE EXPRESSIONS:
-  Relative: tomorrow, +3d, -1w, +2m, +1y
-  Absolute: 2024-01-15, 2024-01-15 14:30
-  Time-only: 09:00, 14:30
+  Relative: tomorrow, yesterday, +3d, -1w, +2m, +1y
+  Absolute: 2024-01-15, 2024-01-15 14:30, 2024-W12
+  Weekdays: friday, next tue, last mon
+  Periods: eod, sow, eow, som, eom, soq, eoq, soy, eoy
+  Time-only: 09:00, 14:30, 9am, noon
+  Combined: \"friday 14:00\", \"tomorrow 9am\", \"2024-03-01 +3d\"
 
 RESPAWN PATTERNS:
   Simple: daily, weekly, monthly, yearly
   Interval: 2d, 3w, 2m, 1y
   Weekdays: mon,wed,fri
   Monthdays: 1,15
-  Nth weekday: 2nd-tue, 1st-mon, last-fri
+  Nth weekday: 2nd-tue, 1st-mon, last-fri, 2nd,4th-thu, 1st-mon-after-10
+  Business days: weekdays, 3rd-bday, last-bday
+  Month/quarter: last-day, quarterly, quarterly-15
+  Cron: \"cron:0 9 * * mon-fri\" (minute hour day month weekday, local time)
+  Options: append :anchor=due to count from the previous due date instead of
+  completion (e.g., monthly:anchor=due). End a series with ;until=2026-12-31 or
+  ;count=6. Scheduled and wait dates keep their offset from the due date.
+  Add ;carry=annotations,children,externals (or all) to copy pinned annotations,
+  subtasks and the external recipient to each new instance.
 
 PIPE OPERATOR ( : ):
   Chain commands using the pipe operator (space-colon-space).
@@ -127,27 +146,38 @@ FILTER SYNTAX:
   Tag filters:
     +<tag>               - Tasks with tag
     -<tag>               - Tasks without tag
+    +blocked / -blocked  - Tasks that do / don't depend on an open task
+    +blocking / -blocking - Tasks that an open task does / doesn't depend on
 
   Derived filters:
     waiting              - Tasks with wait_ts in the future
 
+  User-defined attributes (support =, >, <, >=, <=, !=):
+    uda.<key>=<value>    - Match attribute value (numeric compare when both sides are numbers)
+    uda.<key>=none       - Tasks without the attribute
+
   Operators:
     (implicit AND)       - Adjacent terms are ANDed together
     or                   - OR operator (lowest precedence)
-    not                  - NOT operator (highest precedence)
+    not                  - NOT operator
+    ( ... )              - Grouping (highest precedence)
 
   Examples:
     project=work +urgent
     +urgent or +important
     not +waiting
     project=work +urgent or project=home +important
+    project=work '(+urgent or due<eod)' not +blocked
     desc=bug status=open
     due=tomorrow stage=planned
 
 DATE EXPRESSIONS (for due=, scheduled=, wait=):
-  Relative: tomorrow, +3d, -1w, +2m, +1y
-  Absolute: 2024-01-15, 2024-01-15 14:30
-  Time-only: 09:00, 14:30
+  Relative: tomorrow, yesterday, +3d, -1w, +2m, +1y
+  Absolute: 2024-01-15, 2024-01-15 14:30, 2024-W12
+  Weekdays: friday, next tue, last mon
+  Periods: eod, sow, eow, som, eom, soq, eoq, soy, eoy
+  Time-only: 09:00, 14:30, 9am, noon
+  Combined: \"friday 14:00\", \"tomorrow 9am\", \"2024-03-01 +3d\"
   Intervals: -7d..now, 2024-01-01..2024-01-31
 
 EXAMPLES:
@@ -157,7 +187,8 @@ EXAMPLES:
   tatl list desc=bug status=open
   tatl list due=tomorrow stage=planned --relative
   tatl list due>tomorrow
-  tatl list due!=none")]
+  tatl list due!=none
+  tatl list uda.estimate>3 sort:-uda.estimate show:uda.customer")]
     List {
         /// Filter arguments. Multiple filters are ANDed together. Use 'or' for OR, 'not' for NOT. Examples: \"project=work +urgent\", \"+urgent or +important\", \"desc=bug status=open\"
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
@@ -183,13 +214,22 @@ TARGET SYNTAX:
 
 The output includes task details, annotations, sessions, and related information.
 
+With --explain-priority, prints the breakdown of each task's priority score instead:
+every urgency term that applies, its value, its coefficient and its contribution.
+Coefficients are set in ~/.tatl/rc, e.g. urgency.tag.urgent=6.0, urgency.project.work=1.0,
+urgency.scheduled=4.0, urgency.uda.severity.high=5.0.
+
 EXAMPLES:
   tatl show 10
   tatl show 1-5
-  tatl show project=work +urgent")]
+  tatl show project=work +urgent
+  tatl show --explain-priority 10")]
     Show {
         /// Task ID, ID range, ID list, or filter expression. If omitted, shows the currently active task.
         target: Option<String>,
+        /// Print the per-term breakdown of the priority score
+        #[arg(long)]
+        explain_priority: bool,
     },
     /// Modify tasks
     #[command(long_about = "Modify one or more tasks. Target can be a task ID, ID range (e.g., \"1-5\"), ID list (e.g., \"1,3,5\"), or filter expression.
@@ -204,6 +244,7 @@ MODIFICATION SYNTAX:
     template=<name>       - Set template (use \"template=none\" to clear)
     respawn=<pattern>     - Set respawn rule (use \"respawn=none\" to clear, see RESPAWN PATTERNS)
     parent=<id>           - Set parent task (use \"parent=none\" to clear)
+    depends=<ids>         - Replace dependencies (\"depends=12,15\"), edit them (\"depends=+12,-15\"), or clear (\"depends=none\")
     uda.<key>=<value>     - Set user-defined attribute (use \"uda.<key>=none\" to clear)
 
   Tag modifications:
@@ -218,14 +259,25 @@ RESPAWN PATTERNS:
   Interval: 2d, 3w, 2m, 1y
   Weekdays: mon,wed,fri
   Monthdays: 1,15
-  Nth weekday: 2nd-tue, 1st-mon, last-fri
+  Nth weekday: 2nd-tue, 1st-mon, last-fri, 2nd,4th-thu, 1st-mon-after-10
+  Business days: weekdays, 3rd-bday, last-bday
+  Month/quarter: last-day, quarterly, quarterly-15
+  Cron: \"cron:0 9 * * mon-fri\" (minute hour day month weekday, local time)
+  Options: append :anchor=due to count from the previous due date instead of
+  completion (e.g., monthly:anchor=due). End a series with ;until=2026-12-31 or
+  ;count=6. Scheduled and wait dates keep their offset from the due date.
+  Add ;carry=annotations,children,externals (or all) to copy pinned annotations,
+  subtasks and the external recipient to each new instance.
 
   Respawn rules are validated on modification. A preview message shows what will happen when the task is closed.
 
 DATE EXPRESSIONS:
-  Relative: tomorrow, +3d, -1w, +2m, +1y
-  Absolute: 2024-01-15, 2024-01-15 14:30
-  Time-only: 09:00, 14:30
+  Relative: tomorrow, yesterday, +3d, -1w, +2m, +1y
+  Absolute: 2024-01-15, 2024-01-15 14:30, 2024-W12
+  Weekdays: friday, next tue, last mon
+  Periods: eod, sow, eow, som, eom, soq, eoq, soy, eoy
+  Time-only: 09:00, 14:30, 9am, noon
+  Combined: \"friday 14:00\", \"tomorrow 9am\", \"2024-03-01 +3d\"
 
 FILTER SYNTAX (for target selection):
   Same as 'tatl list' filter syntax. See 'tatl list --help' for details.
@@ -256,10 +308,23 @@ TIME EXPRESSIONS:
   Date + time:     2024-01-15 09:00 (starts session at specific date/time)
   Interval:        09:00..11:00 (creates session from 09:00 to 11:00 today)
 
-If an interval is provided, creates a historical session instead of starting a new one.")]
+If an interval is provided, creates a historical session instead of starting a new one.
+
+POMODORO:
+  --pomodoro times the session as a work interval followed by a break, e.g.
+  --pomodoro=25m/5m (work/break). Without a value, pomodoro.work and
+  pomodoro.break are used. The session ends when the work interval is over and
+  the break is left as a gap: the next 'tatl on', 'tatl off' or 'tatl timer'
+  closes it at the end of the interval. 'tatl status' shows the time remaining
+  and 'tatl timer' counts down and starts the next interval.
+
+Starting a blocked task (one that depends on open tasks) prints a warning naming the blockers.")]
     On {
         /// Task ID (optional, defaults to queue[0]). If provided, pushes task to queue[0] and starts timing.
         task_id: Option<String>,
+        /// Time the session as a pomodoro work interval: <work>/<break> (e.g. 25m/5m), defaults from pomodoro.work and pomodoro.break
+        #[arg(long, value_name = "WORK/BREAK", num_args = 0..=1, default_missing_value = "", require_equals = true)]
+        pomodoro: Option<String>,
         /// Time expression or interval. Time-only (e.g., \"09:00\") starts session at that time today. Interval (e.g., \"09:00..11:00\") creates historical session.
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         time_args: Vec<String>,
@@ -269,9 +334,15 @@ If an interval is provided, creates a historical session instead of starting a n
 
 TIME EXPRESSIONS:
   Time-only:       14:30 (ends session at that time today)
-  Date + time:     2024-01-15 14:30 (ends session at specific date/time)")]
+  Date + time:     2024-01-15 14:30 (ends session at specific date/time)
+
+NOTE AND TAGS:
+  Text after the end time (or note=<text>) becomes the session note, and +<tag>
+  tags the session:
+    tatl off \"fixed the retry loop\" +meeting
+    tatl off 14:30 note=\"pairing\"")]
     Off {
-        /// End time (optional, defaults to now). Time-only (e.g., \"14:30\") ends session at that time today.
+        /// End time (optional, defaults to now), then an optional note and +tags. Time-only (e.g., \"14:30\") ends session at that time today.
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         time_args: Vec<String>,
     },
@@ -299,9 +370,14 @@ INTERVAL SYNTAX:
   Date + interval:  2024-01-15 09:00..12:00 (creates session on specific date)
   Task + interval:  <task_id> 09:00..12:00 (adds session to specific task)
 
+NOTE AND TAGS:
+  note=<text>       Session note, e.g. note=\"pairing\"
+  +<tag>            Session tag
+  note:<text>       Task annotation linked to the new session
+
 If the interval overlaps with existing sessions, you'll be prompted to modify them (use -y to auto-confirm).")]
     Onoff {
-        /// Time interval or task ID + interval. Format: \"start..end\" (e.g., \"09:00..12:00\") or \"<task_id> start..end\". If task_id is omitted, uses queue[0] or prompts.
+        /// Time interval or task ID + interval, with optional note=<text> and +tags. Format: \"start..end\" (e.g., \"09:00..12:00\") or \"<task_id> start..end\". If task_id is omitted, uses queue[0] or prompts.
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
         /// Skip confirmation for overlapping session modifications
@@ -330,7 +406,9 @@ TARGET SYNTAX:
   ID list:                 1,3,5
   Filter:                  project=work +urgent
 
-Use --delete <annotation_id> to remove an annotation.")]
+Use --delete <annotation_id> to remove an annotation. Use --pin <annotation_id> to pin an
+annotation (a checklist or link) so a respawning task with carry=annotations copies it to
+each new instance, and --unpin to undo that.")]
     Annotate {
         /// Task ID, ID range, ID list, or filter (optional when clocked in, defaults to queue[0]). Examples: \"10\", \"1-5\", \"1,3,5\", \"project=work +urgent\"
         target: Option<String>,
@@ -349,6 +427,12 @@ Use --delete <annotation_id> to remove an annotation.")]
         /// Delete annotation by ID
         #[arg(long)]
         delete: Option<String>,
+        /// Pin annotation by ID (carried forward on respawn with carry=annotations)
+        #[arg(long, conflicts_with_all = ["delete", "unpin"])]
+        pin: Option<String>,
+        /// Unpin annotation by ID
+        #[arg(long, conflicts_with = "delete")]
+        unpin: Option<String>,
     },
     /// Close task(s) (intent fulfilled)
     #[command(long_about = "Close one or more tasks (intent fulfilled). If task has a respawn rule, a new instance will be created when closed.
@@ -404,6 +488,18 @@ TARGET SYNTAX:
         #[arg(long)]
         interactive: bool,
     },
+    /// Skip the current occurrence of a respawning task
+    #[command(long_about = "Skip the current occurrence of a respawning task. The task stays open and moves to its next occurrence: due, scheduled and wait dates advance as if it had been completed on time, but no completion is recorded. Skipped occurrences count toward a rule's count= limit.
+
+Fails if the task has no respawn rule or the rule has no further occurrences (use close or cancel instead).
+
+EXAMPLES:
+  tatl skip 10
+  tatl skip 10,12")]
+    Skip {
+        /// Task ID or ID list. Examples: \"10\", \"10,12\"
+        target: String,
+    },
     /// Reopen closed or cancelled task(s)
     #[command(long_about = "Reopen one or more closed or cancelled tasks, setting their status back to open.
 
@@ -441,10 +537,21 @@ TARGET SYNTAX:
         interactive: bool,
     },
     /// Add task to end of clock stack
-    #[command(long_about = "Add one or more tasks to the end of the queue. Tasks are added in the order specified. Does not start timing.")]
+    #[command(long_about = "Add one or more tasks to the end of the queue. Tasks are added in the order specified. Does not start timing.
+
+By default tasks go to the active queue (see 'tatl queue switch'). Use queue=<name> to add them
+to a named queue instead; the queue is created if it doesn't exist.
+
+EXAMPLES:
+  tatl enqueue 5
+  tatl enqueue 1,3,5
+  tatl enqueue 5 queue=oncall")]
     Enqueue {
         /// Task ID(s) to enqueue. Can be a single ID or comma-separated list (e.g., \"5\" or \"1,3,5\")
         task_id: String,
+        /// Optional queue=<name> to enqueue into a named queue (default: the active queue)
+        #[arg(trailing_var_arg = true)]
+        args: Vec<String>,
     },
     /// Send task to external party for review/approval
     #[command(long_about = "Send a task to an external party (colleague, supervisor, release window, etc.). The task will be removed from the queue and marked as 'external' stage.
@@ -485,6 +592,10 @@ Copies: description, project, due, scheduled, wait, allocation, template, tags,
 Does NOT copy: sessions, annotations, externals, queue position, respawn rule.
 The new task is always created with open status.
 
+With --tree, all subtasks are cloned as well, keeping the hierarchy. The clones keep
+their attributes; if the root's due date is overridden, subtask dates (due, scheduled,
+wait) shift by the same amount.
+
 OVERRIDE SYNTAX (same as 'add' and 'modify'):
   project=<name>     - Override project
   due=<expr>         - Override due date
@@ -498,7 +609,8 @@ PIPE OPERATOR:
 EXAMPLES:
   tatl clone 10
   tatl clone 10 project=other due=+7d
-  tatl clone 10 +urgent")]
+  tatl clone 10 +urgent
+  tatl clone --tree 5 \"Release 5.4\" due=+14d")]
     Clone {
         /// Source task ID
         task_id: String,
@@ -508,6 +620,9 @@ EXAMPLES:
         /// Auto-confirm prompts (create new projects)
         #[arg(short = 'y', long)]
         yes: bool,
+        /// Also clone all subtasks, keeping the hierarchy
+        #[arg(long)]
+        tree: bool,
     },
     /// List external tasks
     #[command(long_about = "List all tasks that are currently with external parties. Shows task ID, description, recipient, and when it was sent.
@@ -533,6 +648,61 @@ EXAMPLES:
         #[arg(long)]
         task: Option<String>,
     },
+    /// Show the change history of tasks
+    #[command(long_about = "Show the event history recorded for tasks: creation, field changes (with old and new values),
+status changes, tags, annotations, queue changes and sessions.
+
+With a target, shows a timeline for each matching task. Without a target, shows a feed
+of all events across tasks (default: last 7 days).
+
+TARGET:
+  Task ID:         10
+  ID range:        1-5
+  ID list:         1,3,5
+  Filter:          project=work +urgent (same filter syntax as 'tatl list')
+
+RANGE:
+  -7d, -2w         - Events since a relative date
+  <start>..<end>   - Events in an interval (e.g., 2024-01-01..2024-01-31, -30d..now)
+
+EXAMPLES:
+  tatl history 10
+  tatl history 10 -7d
+  tatl history project=work --json
+  tatl history -7d
+  tatl history 2024-01-01..2024-01-31")]
+    History {
+        /// Optional task ID, ID range, ID list, or filter expression, and/or a date range (e.g., -7d)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Undo the last command(s)
+    #[command(long_about = "Revert the changes made by the most recent command(s).
+
+Every command invocation is recorded in the task event log as one transaction. A pipe
+chain (e.g. 'tatl add \"Task\" : on') counts as a single command. Undo reverts a whole
+transaction at once and shows a preview of the changes before applying them.
+
+Repeated undos step further back in time; an undo itself is not undone by the next undo.
+
+Only changes recorded in the event log can be undone: task creation, field and tag changes,
+status changes, annotations, queue changes, and starting/stopping timing. Commands that
+permanently remove data (e.g. 'tatl delete', 'tatl sessions delete') cannot be undone.
+
+EXAMPLES:
+  tatl undo
+  tatl undo 3
+  tatl undo -y")]
+    Undo {
+        /// Number of commands to undo (default: 1)
+        n: Option<usize>,
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
     /// Show report with queue, sessions, and statistics
     #[command(long_about = "Display a composite report view showing:
 - Current work queue with immediate priorities
@@ -542,22 +712,54 @@ EXAMPLES:
 
 PERIOD:
   The --period option controls the time range for statistics:
-  - week (default): Show this week's data
+  - week (default): Show this week's data (weeks start on the week.start day)
   - month: Show this month's data
+  - quarter: Show this calendar quarter's data
   - year: Show this year's data
+  - fiscal-quarter, fiscal-year: Periods of the fiscal year set by fiscal.year_start
 
 EXAMPLES:
   tatl report
-  tatl report --period=month")]
+  tatl report --period=month
+  tatl report --period=fiscal-year")]
     Report {
-        /// Time period for statistics (week, month, year)
+        /// Time period for statistics (week, month, quarter, year, fiscal-quarter, fiscal-year)
         #[arg(long, default_value = "week")]
         period: String,
     },
+    /// Show what is being timed and the pomodoro countdown
+    #[command(long_about = "Show a one-line summary of the queue and clock, the running session, and
+the time left in the current pomodoro work interval or break.
+
+EXAMPLES:
+  tatl status")]
+    Status,
+    /// Count down pomodoro intervals in the foreground
+    #[command(long_about = "Count down the current pomodoro work interval or break (see 'tatl on --pomodoro'),
+redrawing the time left once a second.
+
+When a work interval is over its session is ended and the break starts; when the
+break is over a new work interval is started on the same task. Each time an
+interval ends, the pomodoro.hook shell command is run with TATL_INTERVAL (work or
+break), TATL_TASK_ID and TATL_SESSION_ID set.
+
+The timer exits when the pomodoro is stopped (e.g. with 'tatl off' from another
+terminal) or on Ctrl-C, which leaves the session running.
+
+EXAMPLES:
+  tatl on 5 --pomodoro=25m/5m
+  tatl timer
+  tatl timer --once
+  tatl config set pomodoro.hook 'notify-send \"tatl: $TATL_INTERVAL over\"'")]
+    Timer {
+        /// Exit when the current interval ends instead of continuing with the next one
+        #[arg(long)]
+        once: bool,
+    },
     /// View or configure stage mappings
     #[command(long_about = "View or configure the stage mapping table. Stages are derived from task state
-(status, queue membership, session history, active timer, external status) and mapped
-to configurable labels with sort order and color.
+(status, queue membership, session history, active timer, external status, open
+dependencies) and mapped to configurable labels with sort order and color.
 
 SUBCOMMANDS:
   tatl stages              Show the current stage mapping table
@@ -573,6 +775,231 @@ EXAMPLES:
         #[command(subcommand)]
         subcommand: Option<StagesCommands>,
     },
+    /// Manage UDA definitions
+    #[command(long_about = "Declare types, defaults and labels for user-defined attributes (UDAs).
+
+UDAs without a definition remain free-form strings. Defined UDAs are validated and
+normalised when set with add, modify or clone:
+  string    Any text
+  number    Numeric value (compared numerically in filters and sorts)
+  date      Date expression (e.g., tomorrow, eow, 2026-03-01); filters accept date expressions
+  duration  Duration (e.g., 30m, 1h30m)
+  enum      One of a declared list of values; sorts follow the declared order
+
+The default value is applied when a task is created without the attribute. The label
+is used as the column header in list output.
+
+SUBCOMMANDS:
+  tatl udas                      List definitions
+  tatl udas list                 Same as above
+  tatl udas define <name> ...    Create or update a definition
+  tatl udas remove <name>        Remove a definition (task values are kept)
+
+EXAMPLES:
+  tatl udas define severity type=enum values=low,medium,high default=low
+  tatl udas define review type=date label=Review
+  tatl udas define estimate type=duration
+  tatl list uda.review<eow")]
+    Udas {
+        #[command(subcommand)]
+        subcommand: Option<UdasCommands>,
+    },
+    /// Manage task templates
+    #[command(long_about = "Manage task templates. A template holds default attributes that are applied
+when a task is created with template=<name>; attributes given on add override them.
+
+Template attributes:
+  <text>              Description pattern ({desc} = description given on add, {date} = today)
+  project=<name>      Project
+  due=<expr>          Due date expression, evaluated when the template is used (e.g., +2d, friday)
+  scheduled=<expr>    Scheduled date expression
+  wait=<expr>         Wait date expression
+  allocation=<dur>    Time allocation
+  respawn=<pattern>   Respawn rule
+  parent=<id>         Default parent task
+  +tag / -tag         Add / remove tags
+  uda.<key>=<value>   User-defined attribute
+Use <field>=none to clear an attribute with modify.
+
+A template can also hold a hierarchy of subtasks (a checklist). Adding a task with the
+template creates the whole tree under it. Each subtask has its own description, project,
+tags, allocation, UDAs and a due offset relative to its parent's due date (e.g., due=-2d).
+Subtasks are addressed by 1-based paths such as 2 or 2.1.
+
+SUBCOMMANDS:
+  tatl templates                      List templates
+  tatl templates list                 Same as above
+  tatl templates show <name>          Show a template's attributes
+  tatl templates add <name> ...       Create a template
+  tatl templates modify <name> ...    Change a template's attributes
+  tatl templates delete <name>        Delete a template
+  tatl templates add-child <name> ... Add a subtask to the template's hierarchy
+  tatl templates remove-child <name> <path>  Remove a subtask (and its subtasks)
+
+EXAMPLES:
+  tatl templates add review \"Review: {desc}\" project=work due=+2d +review
+  tatl templates add standup \"Standup {date}\" respawn=mon,tue,wed,thu,fri due=09:00 allocation=15m
+  tatl templates modify review due=+3d -review
+  tatl add \"PR 42\" template=review
+  tatl templates add release \"Release {desc}\" project=eng
+  tatl templates add-child release \"Freeze branch\" due=-3d +release
+  tatl templates add-child release --under 1 \"Tag release candidate\" allocation=30m
+  tatl add 5.3 template=release due=friday")]
+    Templates {
+        #[command(subcommand)]
+        subcommand: Option<TemplatesCommands>,
+    },
+    /// List respawning tasks and preview respawn rules
+    #[command(long_about = "List open respawning tasks with their next projected due dates, or preview where a
+respawn rule's occurrences land before using it.
+
+SUBCOMMANDS:
+  tatl respawn                          List open respawning tasks
+  tatl respawn list                     Same as above
+  tatl respawn preview <rule> [options] Print the next due dates of a rule
+
+EXAMPLES:
+  tatl respawn preview 2nd-tue --from 2026-01-01 -n 12
+  tatl respawn preview \"monthly;count=6\"
+  tatl respawn preview \"cron:0 9 * * mon-fri\"")]
+    Respawn {
+        #[command(subcommand)]
+        subcommand: Option<RespawnCommands>,
+    },
+    /// Manage named queues
+    #[command(long_about = "Manage named queues. Tasks can be kept in several queues (e.g., work, oncall,
+personal). One queue is active at a time: on, off, close, dequeue, enqueue and the
+queue[0] defaults all act on the active queue. The 'default' queue is active until
+you switch.
+
+A task in any queue counts as queued for stage derivation (planned / in progress).
+
+SUBCOMMANDS:
+  tatl queue                   List queues
+  tatl queue list              Same as above
+  tatl queue switch <name>     Make a queue active (created if it doesn't exist)
+  tatl queue move <id> <pos>   Move a queued task to a position
+  tatl queue roll [n]          Rotate the queue left by n (default 1); negative n rotates right
+  tatl queue swap <pos> <pos>  Swap the tasks at two positions
+  tatl queue clear [-y]        Remove all tasks from the queue
+  tatl queue sort <field>      Sort the queue (priority, due, scheduled, alloc, id, description)
+
+Reordering subcommands act on the active queue and are recorded in the event log,
+so they show up in 'tatl history' and can be reverted with 'tatl undo'.
+
+EXAMPLES:
+  tatl enqueue 5 queue=oncall
+  tatl list queue=oncall
+  tatl queue switch oncall
+  tatl queue move 7 2
+  tatl queue roll
+  tatl queue swap 0 1
+  tatl queue sort -priority")]
+    Queue {
+        #[command(subcommand)]
+        subcommand: Option<QueueCommands>,
+    },
+    /// View and change configuration settings
+    #[command(long_about = "View and change settings in the rc file (~/.tatl/rc, or the file named by TATL_RC).
+
+The rc file holds key=value lines. Lines starting with # are comments, and include=<path>
+reads another file in place (relative to the including file). Later lines win. Every key
+is typed; invalid values are reported and the default is used. TATL_DATA overrides
+data.location.
+
+SUBCOMMANDS:
+  tatl config                   List all keys with their values and where they come from
+  tatl config list              Same as above
+  tatl config get <key>         Print a key's effective value
+  tatl config set <key> <value> Validate and write a key to the rc file
+  tatl config unset <key>       Remove a key from the rc file (back to its default)
+
+KEYS:
+  data.location       Database file (default ~/.tatl/ledger.db)
+  list.default_view   Saved view shown by a bare 'tatl list'
+  list.color          Column to color 'tatl list' rows by (e.g. stage, priority)
+  date.format         Format of dates in output (default %Y-%m-%d)
+  week.start          First day of the week for sow/eow, reports and bins (default monday)
+  week.workdays       Working days for business-day respawn patterns (default mon,tue,wed,thu,fri)
+  fiscal.year_start   First month of the fiscal year (default january)
+  abbrev.verbose      Print what abbreviated commands expand to (default false)
+  session.micro       Micro-session threshold in seconds (default 30)
+  session.max_duration  Longest plausible session, e.g. 4h (see tatl sessions audit)
+  session.auto_cap    End stopped sessions at max_duration or midnight (default off)
+  pomodoro.work       Work interval of 'tatl on --pomodoro' (default 25m)
+  pomodoro.break      Break after each pomodoro work interval (default 5m)
+  pomodoro.hook       Shell command 'tatl timer' runs when an interval ends
+  urgency.<term>      Urgency coefficients (see tatl show --explain-priority)
+
+EXAMPLES:
+  tatl config set week.start sunday
+  tatl config set date.format %d.%m.%Y
+  tatl config get data.location
+  tatl config unset list.default_view")]
+    Config {
+        #[command(subcommand)]
+        subcommand: Option<ConfigCommands>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum QueueCommands {
+    /// List queues
+    List,
+    /// Make a queue active
+    Switch {
+        /// Queue name
+        name: String,
+    },
+    /// Move a queued task to a position
+    #[command(long_about = "Move a task in the active queue to the given position (0 = top). Positions past the end move the task to the bottom.
+
+EXAMPLES:
+  tatl queue move 7 0
+  tatl queue move 7 2")]
+    Move {
+        /// Task ID
+        task_id: String,
+        /// Target position (0 = top)
+        position: usize,
+    },
+    /// Rotate the queue
+    #[command(long_about = "Rotate the active queue. 'roll 1' moves queue[0] to the bottom: [a,b,c] becomes [b,c,a]. Negative values rotate the other way.
+
+EXAMPLES:
+  tatl queue roll
+  tatl queue roll 2
+  tatl queue roll -1")]
+    Roll {
+        /// Number of positions (default: 1)
+        #[arg(allow_hyphen_values = true)]
+        n: Option<i32>,
+    },
+    /// Swap the tasks at two positions
+    Swap {
+        /// First position
+        a: usize,
+        /// Second position
+        b: usize,
+    },
+    /// Remove all tasks from the queue
+    Clear {
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+    /// Sort the queue by a field
+    #[command(long_about = "Sort the active queue by a field: priority, due, scheduled, alloc, id, description.
+Prefix with '-' for descending order. Tasks missing the field are placed at the end.
+
+EXAMPLES:
+  tatl queue sort due
+  tatl queue sort -priority")]
+    Sort {
+        /// Field to sort by, optionally prefixed with '-' for descending
+        #[arg(allow_hyphen_values = true)]
+        field: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -602,6 +1029,154 @@ EXAMPLES:
     },
 }
 
+#[derive(Subcommand)]
+pub enum UdasCommands {
+    /// List UDA definitions
+    List,
+    /// Create or update a UDA definition
+    #[command(long_about = "Create or update a UDA definition.
+
+Arguments after the name are field=value pairs:
+  type=<string|number|date|duration|enum>   Required for a new definition
+  values=a,b,c                              Allowed values, in sort order (enum only)
+  default=<value>                           Applied when a task is created without it (none to clear)
+  label=<text>                              Column header in list output (none to clear)
+
+Updating an existing definition keeps any field that is not given.
+
+EXAMPLES:
+  tatl udas define severity type=enum values=low,medium,high default=low
+  tatl udas define severity label=Sev
+  tatl udas define points type=number")]
+    Define {
+        /// Attribute name (without the uda. prefix)
+        name: String,
+        /// Field=value pairs (type=, values=, default=, label=)
+        #[arg(trailing_var_arg = true)]
+        args: Vec<String>,
+    },
+    /// Remove a UDA definition
+    Remove {
+        /// Attribute name (without the uda. prefix)
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TemplatesCommands {
+    /// List templates
+    List,
+    /// Show a template's attributes
+    Show {
+        /// Template name
+        name: String,
+    },
+    /// Create a template
+    Add {
+        /// Template name
+        name: String,
+        /// Description pattern and attributes (project=, due=, +tag, uda.key=, ...)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Change a template's attributes
+    Modify {
+        /// Template name
+        name: String,
+        /// Description pattern and attributes (use <field>=none to clear)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Delete a template
+    Delete {
+        /// Template name
+        name: String,
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+    /// Add a subtask to a template's hierarchy
+    #[command(long_about = "Add a subtask to a template's hierarchy. When a task is added with the
+template, the subtask is created as its child (or as a child of the subtask given with --under).
+
+Subtask attributes:
+  <text>              Description
+  project=<name>      Project (defaults to the parent's)
+  due=<offset>        Offset from the parent's due date (e.g., -2d, +4h); from now if the parent has none
+  allocation=<dur>    Time allocation
+  +tag                Tag
+  uda.<key>=<value>   User-defined attribute
+
+EXAMPLES:
+  tatl templates add-child release \"Freeze branch\" due=-3d +release
+  tatl templates add-child release --under 1 \"Tag release candidate\" allocation=30m
+  tatl templates add-child onboarding --under 2.1 \"Order laptop\" project=it")]
+    AddChild {
+        /// Template name
+        name: String,
+        /// Path of the subtask to add under (e.g., 2 or 2.1); top level if omitted
+        #[arg(long)]
+        under: Option<String>,
+        /// Description and attributes (project=, due=<offset>, allocation=, +tag, uda.key=)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Remove a subtask (and its subtasks) from a template's hierarchy
+    RemoveChild {
+        /// Template name
+        name: String,
+        /// Path of the subtask to remove (e.g., 2 or 2.1)
+        path: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RespawnCommands {
+    /// List open respawning tasks
+    List,
+    /// Print the next due dates of a respawn rule
+    #[command(long_about = "Print the next due dates of a respawn rule, as if each instance were completed on
+its due date. Dates are strictly after --from. until= and count= end conditions are applied.
+
+EXAMPLES:
+  tatl respawn preview 2nd-tue --from 2026-01-01 -n 12
+  tatl respawn preview \"last-bday;until=2026-12-31\"")]
+    Preview {
+        /// Respawn rule (same syntax as respawn=)
+        rule: String,
+        /// Start date expression (default: now)
+        #[arg(long)]
+        from: Option<String>,
+        /// Number of occurrences to show
+        #[arg(short = 'n', long = "number", default_value_t = 5)]
+        number: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// List all keys with their values and sources
+    List,
+    /// Print a key's effective value
+    Get {
+        /// Config key, e.g. week.start
+        key: String,
+    },
+    /// Set a key in the rc file
+    Set {
+        /// Config key, e.g. week.start
+        key: String,
+        /// Value (several words are joined with spaces)
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        value: Vec<String>,
+    },
+    /// Remove a key from the rc file
+    Unset {
+        /// Config key, e.g. week.start
+        key: String,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ProjectCommands {
     /// Create a new project
@@ -646,27 +1221,30 @@ pub enum ProjectCommands {
     /// Show burndown chart for projects
     #[command(long_about = "Display a burndown chart showing completed work (above baseline) and remaining work (below baseline) over time.
 
-The chart uses time bins (day/week/month) and can show either task counts or hours.
+The chart uses time bins (day/week/month/quarter/year, or fiscal-quarter/fiscal-year)
+and can show either task counts or hours. Week bins start on the week.start day.
 
 ARGUMENTS:
   Optional project name to filter to a specific project.
   Optional date range using interval syntax (-30d, -30d..now, 2024-01-01..2024-06-30).
 
 OPTIONS:
-  --bin day|week|month    Time bin size (default: week)
+  --bin <period>          Time bin size: day, week, month, quarter, year,
+                          fiscal-quarter or fiscal-year (default: week)
   --metric tasks|time     Measurement: task counts or hours (default: tasks)
 
 EXAMPLES:
   tatl projects report
   tatl projects report work
   tatl projects report -90d --bin month
+  tatl projects report --bin quarter 2025-01-01..now
   tatl projects report --metric time
   tatl projects report work -30d --bin day")]
     Report {
         /// Project name and/or date range
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
-        /// Time bin size (day, week, month)
+        /// Time bin size (day, week, month, quarter, year, fiscal-quarter, fiscal-year)
         #[arg(long, default_value = "week")]
         bin: String,
         /// Metric to display (tasks, time)
@@ -692,13 +1270,18 @@ FILTER SYNTAX:
     +<tag>           - Sessions for tasks with tag
     task=<id>        - Sessions for specific task
   
+  Session filters:
+    tag=<tag>        - Sessions tagged <tag> (tag!=<tag> for sessions without it)
+    note=<text>      - Sessions whose note contains <text>
+  
   Examples:
     tatl sessions list -7d
     tatl sessions list -7d..now
     tatl sessions list project=work
-    tatl sessions list -7d project=work")]
+    tatl sessions list -7d project=work
+    tatl sessions list tag=meeting")]
     List {
-        /// Filter arguments. Date filters: -7d, -7d..now, <start>..<end>. Task filters: project=<name>, +tag, task=<id>. Examples: \"-7d\", \"-7d..now\", \"project=work\"
+        /// Filter arguments. Date filters: -7d, -7d..now, <start>..<end>. Task filters: project=<name>, +tag, task=<id>. Session filters: tag=<tag>, note=<text>. Examples: \"-7d\", \"-7d..now\", \"project=work\"
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         filter: Vec<String>,
         /// Output in JSON format
@@ -708,8 +1291,8 @@ FILTER SYNTAX:
     /// Show details of the current active session
     #[command(long_about = "Show detailed information about the current active session. If no session is active, displays a message indicating so.")]
     Show,
-    /// Modify session start/end times
-    #[command(long_about = "Modify the start and/or end time of a session.
+    /// Modify session start/end times, note and tags
+    #[command(long_about = "Modify the start and/or end time, note or tags of a session.
 
 INTERVAL SYNTAX:
   <start>..<end>   - Modify both start and end times
@@ -722,11 +1305,15 @@ INTERVAL SYNTAX:
     ..17:00                   - Change end to 17:00
     2024-01-15 09:00..12:00   - Set specific date and times
 
+NOTE AND TAGS:
+  note=<text>      - Set the session note (note=none clears it)
+  +<tag> / -<tag>  - Add or remove a session tag
+
 If the modification creates overlapping sessions, you'll be prompted to resolve conflicts (use --force to allow overlaps).")]
     Modify {
         /// Session ID to modify
         session_id: i64,
-        /// Time interval: \"<start>..<end>\", \"<start>..\", or \"..<end>\". Examples: \"09:00..17:00\", \"09:00..\", \"..17:00\"
+        /// Time interval (\"<start>..<end>\", \"<start>..\", \"..<end>\"), note=<text>, +tag, -tag. Examples: \"09:00..17:00\", \"..17:00\", \"+review\"
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
         /// Apply modification without confirmation
@@ -751,17 +1338,101 @@ If the modification creates overlapping sessions, you'll be prompted to resolve
 REPORT SYNTAX:
   Date interval:     -7d, -7d..now, 2024-01-01..2024-01-31
   Task filters:      project=<name>, +tag, task=<id>
+  Session filters:   tag=<tag> (tag!=<tag>), note=<text>
   
   Examples:
     tatl sessions report
     tatl sessions report -7d
     tatl sessions report -7d..now project=work
-    tatl sessions report 2024-01-01..2024-01-31 +urgent")]
+    tatl sessions report 2024-01-01..2024-01-31 +urgent
+    tatl sessions report -7d tag!=meeting
+    tatl sessions report --billing 2024-01-01..2024-01-31
+
+BILLING:
+  --billing rounds and prices time with the billing.* settings (see 'tatl config list'),
+  showing billed hours and amounts per project and per client. Each setting can be
+  set globally or for a project or UDA value, e.g.:
+    billing.increment=15m
+    billing.rounding=up                   (up, nearest or down)
+    billing.round_per=day                 (session or day)
+    billing.rate.project.work.acme=150
+    billing.rate.uda.client.globex=120
+    billing.billable.project.internal=false
+  Clients are named by the UDA set in billing.client_uda (default: client).")]
     Report {
-        /// Report arguments. Date interval: -7d, -7d..now, <start>..<end>. Task filters: project=<name>, +tag, task=<id>. Examples: \"-7d\", \"-7d..now\", \"-7d project=work\"
+        /// Show billed hours and amounts using the billing.* settings
+        #[arg(long)]
+        billing: bool,
+        /// Report arguments. Date interval: -7d, -7d..now, <start>..<end>. Task filters: project=<name>, +tag, task=<id>. Session filters: tag=<tag>, note=<text>. Examples: \"-7d\", \"-7d..now\", \"-7d project=work\"
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Export sessions as a timesheet (CSV, iCalendar or JSON Lines)
+    #[command(long_about = "Export closed sessions as a timesheet for invoicing or calendar import.
+
+Each row or event carries the task description, full project path, tags, UDAs,
+and the session's start, end and duration. Sessions overlapping the date range
+are clipped to it. Running sessions are skipped.
+
+FORMATS:
+  csv     One row per session with a header; UDAs become uda.<key> columns (default)
+  ics     iCalendar with one VEVENT per session (times in UTC)
+  jsonl   JSON Lines, one object per session
+
+ARGUMENTS:
+  Optional date range: -7d, -7d..now, 2024-01-01..2024-01-31, som..now
+  Filters as in 'sessions list': project=<name>, +tag, task=<id>, start:<expr>, duration>30m
+
+ROUNDING:
+  --round <duration> rounds each start and end to the nearest increment (e.g. 15m, 6m, 1h).
+  Durations are computed from the rounded times.
+
+EXAMPLES:
+  tatl sessions export -7d
+  tatl sessions export --format ics 2024-01-01..2024-01-31 project=work
+  tatl sessions export --round 15m -30d +billable > timesheet.csv
+  tatl sessions export --format jsonl")]
+    Export {
+        /// Output format (csv, ics, jsonl)
+        #[arg(long, default_value = "csv")]
+        format: String,
+        /// Round start and end times to this increment (e.g. 15m)
+        #[arg(long)]
+        round: Option<String>,
+        /// Date range followed by filters. Examples: \"-7d\", \"2024-01-01..2024-01-31 project=work\"
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    /// List suspicious sessions and optionally fix them
+    #[command(long_about = "List sessions that are probably wrong, with the fix for each:
+
+  zero length                        delete it
+  overlaps session <id>              remove the overlapping time from the earlier session
+  longer than session.max_duration   end it after session.max_duration
+  crosses midnight                   split it at midnight
+
+With --fix, one fix is applied per session (after confirmation unless --yes).
+Run the audit again to see what remains.
+
+AUTOMATIC CAPPING:
+  session.max_duration=4h         Warn in 'tatl on' and 'tatl report' when the running session is longer
+  session.auto_cap=max_duration   End sessions at session.max_duration when they are stopped
+  session.auto_cap=midnight       End sessions at midnight when they are stopped
+
+EXAMPLES:
+  tatl sessions audit
+  tatl sessions audit --fix
+  tatl sessions audit --fix 12 15 --yes")]
+    Audit {
+        /// Apply the suggested fixes
+        #[arg(long)]
+        fix: bool,
+        /// Apply fixes without confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+        /// Only audit these sessions
+        session_ids: Vec<i64>,
+    },
 }
 
 
@@ -813,13 +1484,15 @@ fn execute_piped_command(task_id: i64, segment: &[String]) -> Result<i64> {
 
     match cmd.as_str() {
         "on" => {
+            let mut rest = rest.to_vec();
+            let pomodoro = take_pomodoro_flag(&mut rest);
             // Special case: a prior stage (e.g., `close` with no explicit target) can return 0
             // to mean "operate on queue[0]". For `on`, that should start timing queue[0].
             if task_id == 0 {
-                handle_on(None, rest.to_vec())?;
+                handle_on(None, rest, pomodoro)?;
                 Ok(0)
             } else {
-                handle_task_on(task_id.to_string(), rest.to_vec())?;
+                handle_task_on(task_id.to_string(), rest, parse_pomodoro_arg(pomodoro))?;
                 Ok(task_id)
             }
         }
@@ -831,7 +1504,7 @@ fn execute_piped_command(task_id: i64, segment: &[String]) -> Result<i64> {
             Ok(task_id)
         }
         "enqueue" => {
-            handle_task_enqueue(task_id.to_string())?;
+            handle_task_enqueue(task_id.to_string(), rest.to_vec())?;
             Ok(task_id)
         }
         "close" => {
@@ -906,7 +1579,7 @@ fn execute_piped_command(task_id: i64, segment: &[String]) -> Result<i64> {
         }
         "clone" => {
             // Clone the task from previous command, with optional overrides
-            let new_id = handle_clone(task_id.to_string(), rest.to_vec(), false)?;
+            let new_id = handle_clone(task_id.to_string(), rest.to_vec(), false, false)?;
             Ok(new_id)
         }
         _ => {
@@ -920,6 +1593,9 @@ fn execute_piped_command(task_id: i64, segment: &[String]) -> Result<i64> {
 }
 
 pub fn run() -> Result<()> {
+    // All events written by this invocation (including pipe chains) form one undoable batch
+    EventRepo::begin_batch();
+
     // Get raw args
     let mut args: Vec<String> = std::env::args().skip(1).collect();
     
@@ -938,12 +1614,17 @@ pub fn run() -> Result<()> {
     }
     
     // Expand command abbreviations before processing
+    let original_args = args.clone();
     args = match abbrev::expand_command_abbreviations(args) {
         Ok(expanded) => expanded,
         Err(e) => {
             user_error(&e);
         }
     };
+    if args != original_args && Config::current().abbrev_verbose() {
+        let count = args.len().min(2);
+        eprintln!("({} -> {})", original_args[..count].join(" "), args[..count].join(" "));
+    }
     
     // Normalize "task <id> clock in" to "task clock in <id>"
     if args.len() >= 3
@@ -1032,14 +1713,14 @@ pub fn run() -> Result<()> {
                     0
                 }
             }
-            Commands::Enqueue { task_id: task_id_str } => {
+            Commands::Enqueue { task_id: task_id_str, args: enqueue_args } => {
                 // Parse task ID(s) - for piping, we'll use the first one
                 let task_ids = parse_task_id_list(&task_id_str)
                     .map_err(|e| anyhow::anyhow!("Invalid task ID: {}", e))?;
                 if task_ids.is_empty() {
                     anyhow::bail!("No task IDs provided to enqueue");
                 }
-                handle_task_enqueue(task_id_str)?;
+                handle_task_enqueue(task_id_str, enqueue_args)?;
                 task_ids[0] // Return first task ID for piping
             }
             Commands::Cancel { target, yes, interactive } => {
@@ -1056,10 +1737,13 @@ pub fn run() -> Result<()> {
                 validate_task_id(&target)
                     .map_err(|_| anyhow::anyhow!("Pipe operator with reopen requires a single task ID as target"))?
             }
-            Commands::Annotate { target, note, task, yes: _, interactive: _, delete } => {
+            Commands::Annotate { target, note, task, yes: _, interactive: _, delete, pin, unpin } => {
                 if delete.is_some() {
                     anyhow::bail!("Pipe operator not supported with --delete flag");
                 }
+                if pin.is_some() || unpin.is_some() {
+                    anyhow::bail!("Pipe operator not supported with --pin or --unpin flags");
+                }
                 let target_str = target.or(task)
                     .ok_or_else(|| anyhow::anyhow!("Pipe operator with annotate requires a task ID as target"))?;
                 handle_annotation_add(Some(target_str.clone()), note)?;
@@ -1077,11 +1761,12 @@ pub fn run() -> Result<()> {
                 validate_task_id(&task_id_str)
                     .map_err(|e| anyhow::anyhow!("Invalid task ID: {}", e))?
             }
-            Commands::On { task_id: task_id_opt, time_args } => {
+            Commands::On { task_id: task_id_opt, pomodoro, mut time_args } => {
                 // For piping, we need a task ID - can't use queue[0]
                 let task_id_str = task_id_opt
                     .ok_or_else(|| anyhow::anyhow!("Pipe operator with 'on' requires a task ID"))?;
-                handle_task_on(task_id_str.clone(), time_args)?;
+                let pomodoro = parse_pomodoro_arg(pomodoro.or_else(|| take_pomodoro_flag(&mut time_args)));
+                handle_task_on(task_id_str.clone(), time_args, pomodoro)?;
                 validate_task_id(&task_id_str)
                     .map_err(|e| anyhow::anyhow!("Invalid task ID: {}", e))?
             }
@@ -1173,24 +1858,32 @@ fn handle_command(cli: Cli) -> Result<()> {
         Commands::List { filter, json, relative, full } => {
             handle_task_list(filter, json, relative, full)
         },
-        Commands::Show { target } => {
+        Commands::Show { target, explain_priority } => {
             let resolved = resolve_target_or_active(target, "show")?;
-            handle_task_summary(resolved)
+            if explain_priority {
+                handle_explain_priority(resolved)
+            } else {
+                handle_task_summary(resolved)
+            }
         },
         Commands::Modify { target, args, yes, interactive } => {
             let resolved = resolve_target_or_active(target, "modify")?;
             handle_task_modify(resolved, args, yes, interactive)
         }
-        Commands::On { task_id, time_args } => handle_on(task_id, time_args),
+        Commands::On { task_id, pomodoro, time_args } => handle_on(task_id, time_args, pomodoro),
         Commands::Off { time_args } => handle_off(time_args),
         Commands::Offon { time_args, yes } => handle_offon(time_args, yes),
         Commands::Onoff { args, yes } => handle_onoff(args, yes),
         Commands::Dequeue { task_id } => handle_dequeue(task_id),
-        Commands::Annotate { target, note, task, yes, interactive, delete } => {
+        Commands::Annotate { target, note, task, yes, interactive, delete, pin, unpin } => {
             if let Some(annotation_id) = delete {
                 let target = target.or(task)
                     .unwrap_or_else(|| user_error("Task ID is required to delete an annotation."));
                 handle_annotation_delete(target, annotation_id)
+            } else if let Some((annotation_id, pinned)) = pin.map(|id| (id, true)).or(unpin.map(|id| (id, false))) {
+                let target = target.or(task)
+                    .unwrap_or_else(|| user_error("Task ID is required to pin or unpin an annotation."));
+                handle_annotation_pin(target, annotation_id, pinned)
             } else {
                 if target.is_none() && task.is_none() && note.is_empty() {
                     let help_args = vec!["tatl".to_string(), "annotate".to_string(), "--help".to_string()];
@@ -1235,14 +1928,15 @@ fn handle_command(cli: Cli) -> Result<()> {
         Commands::Cancel { target, yes, interactive } => {
             handle_task_cancel_optional(target, yes, interactive)
         }
+        Commands::Skip { target } => handle_skip(target),
         Commands::Reopen { target, yes, interactive } => {
             handle_task_reopen(target, yes, interactive)
         }
         Commands::Delete { target, yes, interactive } => {
             handle_task_delete(target, yes, interactive)
         }
-        Commands::Enqueue { task_id } => {
-            handle_task_enqueue(task_id)
+        Commands::Enqueue { task_id, args } => {
+            handle_task_enqueue(task_id, args)
         }
         Commands::Send { task_id, recipient, request } => {
             handle_send(task_id, recipient, request)
@@ -1250,8 +1944,8 @@ fn handle_command(cli: Cli) -> Result<()> {
         Commands::Collect { task_id } => {
             handle_collect(task_id)
         },
-        Commands::Clone { task_id, args, yes } => {
-            handle_clone(task_id, args, yes)?;
+        Commands::Clone { task_id, args, yes, tree } => {
+            handle_clone(task_id, args, yes, tree)?;
             Ok(())
         },
         Commands::Externals { filter } => {
@@ -1279,15 +1973,34 @@ fn handle_command(cli: Cli) -> Result<()> {
                 SessionsCommands::Delete { session_id, yes } => {
                     handle_sessions_delete(session_id, yes)
                 }
-                SessionsCommands::Report { args } => {
-                    handle_sessions_report(args)
+                SessionsCommands::Report { billing, args } => {
+                    handle_sessions_report(args, billing)
+                }
+                SessionsCommands::Export { format, round, args } => {
+                    handle_sessions_export(format, round, args)
+                }
+                SessionsCommands::Audit { fix, yes, session_ids } => {
+                    handle_sessions_audit(session_ids, fix, yes)
                 }
                 }
             }
+        Commands::History { args, json } => {
+            handle_history(args, json)
+        }
+        Commands::Undo { n, yes } => {
+            handle_undo(n.unwrap_or(1), yes)
+        }
         Commands::Report { period } => {
             handle_report(period)
         }
+        Commands::Status => handle_status(),
+        Commands::Timer { once } => handle_timer(once),
         Commands::Stages { subcommand } => handle_stages(subcommand),
+        Commands::Udas { subcommand } => handle_udas(subcommand),
+        Commands::Templates { subcommand } => handle_templates(subcommand),
+        Commands::Respawn { subcommand } => handle_respawn(subcommand),
+        Commands::Queue { subcommand } => handle_queue(subcommand),
+        Commands::Config { subcommand } => handle_config(subcommand),
     }
 }
 
@@ -1370,7 +2083,7 @@ fn handle_projects(cmd: ProjectCommands) -> Result<()> {
             }
             
             // Check if project already exists
-            if let Some(_) = ProjectRepo::get_by_name(&conn, &name)? {
+            if ProjectRepo::get_by_name(&conn, &name)?.is_some() {
                 user_error(&format!("Project '{}' already exists", name));
             }
             
@@ -1429,7 +2142,7 @@ fn handle_projects(cmd: ProjectCommands) -> Result<()> {
             }
             
             // Check if new name already exists
-            if let Some(_) = ProjectRepo::get_by_name(&conn, &new_name)? {
+            if ProjectRepo::get_by_name(&conn, &new_name)?.is_some() {
                 if force {
                     // Merge projects
                     ProjectRepo::merge(&conn, &old_name, &new_name)
@@ -1467,7 +2180,7 @@ fn handle_projects(cmd: ProjectCommands) -> Result<()> {
 /// Handle the report command
 fn handle_report(period: String) -> Result<()> {
     use crate::models::TaskStatus;
-    use chrono::{Datelike, Duration, NaiveTime};
+    use chrono::NaiveTime;
 
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
@@ -1479,42 +2192,18 @@ fn handle_report(period: String) -> Result<()> {
         .unwrap_or(now.timestamp());
 
     // Calculate period start based on --period flag
-    let period_start_ts = match period.to_lowercase().as_str() {
-        "week" => {
-            let days_since_monday = now.weekday().num_days_from_monday() as i64;
-            let week_start = now.date_naive() - Duration::days(days_since_monday);
-            let week_start_dt = week_start.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-            Local.from_local_datetime(&week_start_dt).single()
-                .map(|dt| dt.timestamp())
-                .unwrap_or(today_start_ts - 7 * 86400)
-        }
-        "month" => {
-            let month_start = now.date_naive().with_day(1).unwrap_or(now.date_naive());
-            let month_start_dt = month_start.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-            Local.from_local_datetime(&month_start_dt).single()
-                .map(|dt| dt.timestamp())
-                .unwrap_or(today_start_ts - 30 * 86400)
-        }
-        "year" => {
-            let year_start = now.date_naive().with_month(1).and_then(|d| d.with_day(1)).unwrap_or(now.date_naive());
-            let year_start_dt = year_start.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-            Local.from_local_datetime(&year_start_dt).single()
-                .map(|dt| dt.timestamp())
-                .unwrap_or(today_start_ts - 365 * 86400)
-        }
-        _ => {
-            eprintln!("Warning: Unknown period '{}', defaulting to 'week'", period);
-            let days_since_monday = now.weekday().num_days_from_monday() as i64;
-            let week_start = now.date_naive() - Duration::days(days_since_monday);
-            let week_start_dt = week_start.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-            Local.from_local_datetime(&week_start_dt).single()
-                .map(|dt| dt.timestamp())
-                .unwrap_or(today_start_ts - 7 * 86400)
-        }
-    };
+    let calendar = Calendar::current();
+    let period = Period::parse(&period).unwrap_or_else(|| {
+        eprintln!("Warning: Unknown period '{}', defaulting to 'week'", period);
+        Period::Week
+    });
+    let period_start = period.start_of(&calendar, now.date_naive());
+    let period_start_ts = Local.from_local_datetime(&period_start.and_time(NaiveTime::MIN)).earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or(today_start_ts);
 
     // Get queue (tasks in stack)
-    let stack = StackRepo::get_or_create_default(&conn)?;
+    let stack = StackRepo::get_active(&conn)?;
     let stack_items = StackRepo::get_items(&conn, stack.id.unwrap())?;
 
     // Get open session for detecting active task
@@ -1528,7 +2217,11 @@ fn handle_report(period: String) -> Result<()> {
     println!();
 
     // SECTION 1: Queue
-    println!("📋 QUEUE ({} tasks)", stack_items.len());
+    if stack.name == "default" {
+        println!("📋 QUEUE ({} tasks)", stack_items.len());
+    } else {
+        println!("📋 QUEUE: {} ({} tasks)", stack.name, stack_items.len());
+    }
     println!("───────────────────────────────────────────────────────────────────────────");
 
     if stack_items.is_empty() {
@@ -1608,14 +2301,24 @@ fn handle_report(period: String) -> Result<()> {
             println!("    ... and {} more sessions", today_sessions.len() - 5);
         }
     }
+    if let Some(session) = &open_session {
+        let running = now.timestamp() - session.start_ts;
+        if SessionLimits::current().is_too_long(running) {
+            println!("  ⚠ Running for {}, longer than session.max_duration (see 'tatl sessions audit')",
+                format_duration_short(running));
+        }
+    }
+    if let Some((_, _, phase)) = current_pomodoro(&conn, now.timestamp())? {
+        println!("  🍅 {}", phase.line(now.timestamp()));
+    }
     println!();
 
     // SECTION 3: Period Statistics
-    let period_label = match period.to_lowercase().as_str() {
-        "week" => "THIS WEEK",
-        "month" => "THIS MONTH",
-        "year" => "THIS YEAR",
-        _ => "THIS WEEK",
+    let period_label = match period {
+        Period::Quarter | Period::FiscalQuarter | Period::FiscalYear => {
+            format!("THIS {} ({})", period.name().to_uppercase(), period.label(&calendar, period_start))
+        }
+        _ => format!("THIS {}", period.name().to_uppercase()),
     };
 
     let period_sessions = SessionRepo::list_all(&conn)?
@@ -2081,76 +2784,26 @@ fn get_task_status_at(events: &HashMap<i64, Vec<(i64, String)>>, task_id: i64, a
 
 /// Generate time bins for the burndown chart
 fn generate_time_bins(start: i64, end: i64, bin_size: &str) -> Vec<(i64, i64, String)> {
-    use chrono::{Datelike, NaiveDate, Duration};
-
-    let mut bins = Vec::new();
-    let start_dt = Local.timestamp_opt(start, 0).single()
-        .unwrap_or_else(|| Local.timestamp_opt(0, 0).single().unwrap());
-    let end_dt = Local.timestamp_opt(end, 0).single()
-        .unwrap_or_else(|| Local.timestamp_opt(0, 0).single().unwrap());
-
-    match bin_size {
-        "day" => {
-            let mut current = start_dt.date_naive();
-            let end_date = end_dt.date_naive();
-            while current <= end_date {
-                let bin_start = Local.from_local_datetime(&current.and_hms_opt(0, 0, 0).unwrap())
-                    .single().map(|dt| dt.timestamp()).unwrap_or(0);
-                let next = current + Duration::days(1);
-                let bin_end = Local.from_local_datetime(&next.and_hms_opt(0, 0, 0).unwrap())
-                    .single().map(|dt| dt.timestamp()).unwrap_or(0);
-                let label = current.format("%m/%d").to_string();
-                bins.push((bin_start, bin_end, label));
-                current = next;
-            }
-        }
-        "month" => {
-            let mut year = start_dt.year();
-            let mut month = start_dt.month();
-            loop {
-                let current = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-                let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
-                let next = NaiveDate::from_ymd_opt(next_year, next_month, 1).unwrap();
-
-                let bin_start = Local.from_local_datetime(&current.and_hms_opt(0, 0, 0).unwrap())
-                    .single().map(|dt| dt.timestamp()).unwrap_or(0);
-                let bin_end = Local.from_local_datetime(&next.and_hms_opt(0, 0, 0).unwrap())
-                    .single().map(|dt| dt.timestamp()).unwrap_or(0);
-
-                if bin_start > end {
-                    break;
-                }
-
-                let label = current.format("%b").to_string();
-                bins.push((bin_start, bin_end, label));
+    use chrono::NaiveTime;
 
-                year = next_year;
-                month = next_month;
-            }
-        }
-        _ => {
-            // Default: week
-            // Find Monday of the start week
-            let start_date = start_dt.date_naive();
-            let days_since_monday = start_date.weekday().num_days_from_monday();
-            let mut current = start_date - Duration::days(days_since_monday as i64);
-
-            loop {
-                let bin_start = Local.from_local_datetime(&current.and_hms_opt(0, 0, 0).unwrap())
-                    .single().map(|dt| dt.timestamp()).unwrap_or(0);
-                let next = current + Duration::days(7);
-                let bin_end = Local.from_local_datetime(&next.and_hms_opt(0, 0, 0).unwrap())
-                    .single().map(|dt| dt.timestamp()).unwrap_or(0);
-
-                if bin_start > end {
-                    break;
-                }
+    let calendar = Calendar::current();
+    let period = Period::parse(bin_size).unwrap_or(Period::Week);
+    let to_ts = |date: chrono::NaiveDate| Local.from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest().map(|dt| dt.timestamp()).unwrap_or(0);
+    let start_date = Local.timestamp_opt(start, 0).single()
+        .unwrap_or_else(|| Local.timestamp_opt(0, 0).single().unwrap())
+        .date_naive();
 
-                let label = current.format("%m/%d").to_string();
-                bins.push((bin_start, bin_end, label));
-                current = next;
-            }
+    let mut bins = Vec::new();
+    let mut current = period.start_of(&calendar, start_date);
+    loop {
+        let bin_start = to_ts(current);
+        if bin_start > end {
+            break;
         }
+        let next = period.next_start(current);
+        bins.push((bin_start, to_ts(next), period.label(&calendar, current)));
+        current = next;
     }
 
     bins
@@ -2265,52 +2918,236 @@ fn render_burndown_text(bins: &[(String, f64, f64)], is_time: bool) {
     }
 }
 
-fn handle_stages(subcommand: Option<StagesCommands>) -> Result<()> {
-    let conn = DbConnection::connect()?;
+fn handle_queue(subcommand: Option<QueueCommands>) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
     match subcommand {
-        None | Some(StagesCommands::List) => handle_stages_list(&conn),
-        Some(StagesCommands::Set { id, args }) => handle_stages_set(&conn, id, args),
+        None | Some(QueueCommands::List) => handle_queue_list(&conn),
+        Some(QueueCommands::Switch { name }) => handle_queue_switch(&conn, &name),
+        Some(QueueCommands::Move { task_id, position }) => handle_queue_move(&conn, &task_id, position),
+        Some(QueueCommands::Roll { n }) => handle_queue_roll(&conn, n.unwrap_or(1)),
+        Some(QueueCommands::Swap { a, b }) => handle_queue_swap(&conn, a, b),
+        Some(QueueCommands::Clear { yes }) => handle_queue_clear(&conn, yes),
+        Some(QueueCommands::Sort { field }) => handle_queue_sort(&conn, &field),
     }
 }
 
-fn handle_stages_list(conn: &Connection) -> Result<()> {
-    let mappings = StageRepo::list_all(conn)?;
+/// Print the active queue after a reorder
+fn print_queue_order(conn: &Connection, stack: &crate::models::Stack) -> Result<()> {
+    let items = StackRepo::get_items(conn, stack.id.unwrap())?;
+    if items.is_empty() {
+        println!("Queue '{}' is empty.", stack.name);
+        return Ok(());
+    }
+    for (pos, item) in items.iter().enumerate() {
+        let description = TaskRepo::get_by_id(conn, item.task_id)?
+            .map(|t| t.description)
+            .unwrap_or_default();
+        println!("  [{}] {}: {}", pos, item.task_id, description);
+    }
+    Ok(())
+}
 
-    // Column widths
-    let id_w = 4;
-    let status_w = 10;
-    let queue_w = 5;
-    let sess_w = 8;
-    let timer_w = 5;
-    let ext_w = 8;
-    let stage_w = 12;
-    let sort_w = 4;
-    let color_w = 14;
+fn handle_queue_move(conn: &Connection, task_id_str: &str, position: usize) -> Result<()> {
+    let task_id = match validate_task_id(task_id_str) {
+        Ok(id) => id,
+        Err(e) => user_error(&e),
+    };
+    let stack = StackRepo::get_active(conn)?;
+    let stack_id = stack.id.unwrap();
+    let items = StackRepo::get_items(conn, stack_id)?;
+    if !items.iter().any(|item| item.task_id == task_id) {
+        user_error(&format!("Task {} is not in queue '{}'. Use 'tatl enqueue {}' first.", task_id, stack.name, task_id));
+    }
 
-    println!(
-        "{:>id_w$}  {:<status_w$} {:<queue_w$} {:<sess_w$} {:<timer_w$} {:<ext_w$} {:<stage_w$} {:>sort_w$}  {:<color_w$}",
-        "#", "Status", "Queue", "Sessions", "Timer", "External", "Stage", "Sort", "Color",
-        id_w = id_w, status_w = status_w, queue_w = queue_w, sess_w = sess_w,
-        timer_w = timer_w, ext_w = ext_w, stage_w = stage_w, sort_w = sort_w, color_w = color_w,
-    );
-    println!(
-        "{}  {} {} {} {} {} {} {}  {}",
-        "─".repeat(id_w), "─".repeat(status_w), "─".repeat(queue_w), "─".repeat(sess_w),
-        "─".repeat(timer_w), "─".repeat(ext_w), "─".repeat(stage_w), "─".repeat(sort_w), "─".repeat(color_w),
+    let position = position.min(items.len() - 1);
+    StackRepo::move_to_position(conn, stack_id, task_id, position as i32)?;
+    println!("Moved task {} to queue[{}]", task_id, position);
+    print_queue_order(conn, &stack)
+}
+
+fn handle_queue_roll(conn: &Connection, n: i32) -> Result<()> {
+    let stack = StackRepo::get_active(conn)?;
+    StackRepo::roll(conn, stack.id.unwrap(), n)?;
+    println!("Rolled queue by {}", n);
+    print_queue_order(conn, &stack)
+}
+
+fn handle_queue_swap(conn: &Connection, a: usize, b: usize) -> Result<()> {
+    let stack = StackRepo::get_active(conn)?;
+    if let Err(e) = StackRepo::swap(conn, stack.id.unwrap(), a as i32, b as i32) {
+        user_error(&e.to_string());
+    }
+    println!("Swapped queue[{}] and queue[{}]", a, b);
+    print_queue_order(conn, &stack)
+}
+
+fn handle_queue_clear(conn: &Connection, yes: bool) -> Result<()> {
+    use std::io::{self, Write};
+
+    let stack = StackRepo::get_active(conn)?;
+    let count = StackRepo::get_items(conn, stack.id.unwrap())?.len();
+    if count == 0 {
+        println!("Queue '{}' is already empty.", stack.name);
+        return Ok(());
+    }
+
+    if !yes {
+        print!("Remove {} task(s) from queue '{}'? (y/n): ", count, stack.name);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+        if input != "y" && input != "yes" {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    StackRepo::clear(conn, stack.id.unwrap())?;
+    println!("Cleared {} task(s) from queue '{}'", count, stack.name);
+    Ok(())
+}
+
+fn handle_queue_sort(conn: &Connection, field: &str) -> Result<()> {
+    let (field, descending) = match field.strip_prefix('-') {
+        Some(f) => (f, true),
+        None => (field, false),
+    };
+
+    let stack = StackRepo::get_active(conn)?;
+    let items = StackRepo::get_items(conn, stack.id.unwrap())?;
+    let mut keyed: Vec<(i64, Option<QueueSortKey>)> = Vec::new();
+    for item in &items {
+        let task = match TaskRepo::get_by_id(conn, item.task_id)? {
+            Some(t) => t,
+            None => continue,
+        };
+        let key = match field {
+            "priority" => Some(QueueSortKey::Number(crate::cli::priority::calculate_priority(&task, conn)?)),
+            "due" => task.due_ts.map(|ts| QueueSortKey::Number(ts as f64)),
+            "scheduled" => task.scheduled_ts.map(|ts| QueueSortKey::Number(ts as f64)),
+            "alloc" => task.alloc_secs.map(|secs| QueueSortKey::Number(secs as f64)),
+            "id" => Some(QueueSortKey::Number(item.task_id as f64)),
+            "description" => Some(QueueSortKey::Text(task.description.to_lowercase())),
+            _ => user_error(&format!(
+                "Unknown sort field '{}'. Valid fields: priority, due, scheduled, alloc, id, description",
+                field
+            )),
+        };
+        keyed.push((item.task_id, key));
+    }
+
+    // Stable sort keeps the current order for ties; missing values go last in either direction
+    keyed.sort_by(|(_, a), (_, b)| match (a, b) {
+        (Some(a), Some(b)) => {
+            let ord = a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal);
+            if descending { ord.reverse() } else { ord }
+        }
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+
+    let order: Vec<i64> = keyed.iter().map(|(task_id, _)| *task_id).collect();
+    StackRepo::reorder(conn, stack.id.unwrap(), &order)?;
+    println!("Sorted queue by {}{}", if descending { "-" } else { "" }, field);
+    print_queue_order(conn, &stack)
+}
+
+/// Sort key for `tatl queue sort`
+#[derive(PartialEq, PartialOrd)]
+enum QueueSortKey {
+    Number(f64),
+    Text(String),
+}
+
+fn handle_queue_list(conn: &Connection) -> Result<()> {
+    let active = StackRepo::get_active(conn)?;
+    let stacks = StackRepo::list(conn)?;
+
+    let name_w = stacks.iter().map(|s| s.name.len()).max().unwrap_or(5).max(5);
+    println!("  {:<name_w$}  {:>5}  Top", "Queue", "Tasks", name_w = name_w);
+    println!("  {}  {}  {}", "─".repeat(name_w), "─".repeat(5), "─".repeat(30));
+    for stack in &stacks {
+        let items = StackRepo::get_items(conn, stack.id.unwrap())?;
+        let marker = if stack.id == active.id { "*" } else { " " };
+        let top = match items.first() {
+            Some(item) => match TaskRepo::get_by_id(conn, item.task_id)? {
+                Some(task) => format!("{}: {}", item.task_id, task.description),
+                None => item.task_id.to_string(),
+            },
+            None => String::new(),
+        };
+        println!("{} {:<name_w$}  {:>5}  {}", marker, stack.name, items.len(), top, name_w = name_w);
+    }
+    Ok(())
+}
+
+fn handle_queue_switch(conn: &Connection, name: &str) -> Result<()> {
+    if let Err(e) = validate_queue_name(name) {
+        user_error(&e);
+    }
+    let existed = StackRepo::get_by_name(conn, name)?.is_some();
+    let stack = StackRepo::set_active(conn, name)?;
+    let count = StackRepo::get_items(conn, stack.id.unwrap())?.len();
+    if existed {
+        println!("Switched to queue '{}' ({} tasks)", stack.name, count);
+    } else {
+        println!("Created and switched to queue '{}'", stack.name);
+    }
+    Ok(())
+}
+
+fn handle_stages(subcommand: Option<StagesCommands>) -> Result<()> {
+    let conn = DbConnection::connect()?;
+    match subcommand {
+        None | Some(StagesCommands::List) => handle_stages_list(&conn),
+        Some(StagesCommands::Set { id, args }) => handle_stages_set(&conn, id, args),
+    }
+}
+
+fn handle_stages_list(conn: &Connection) -> Result<()> {
+    let mappings = StageRepo::list_all(conn)?;
+
+    // Column widths
+    let id_w = 4;
+    let status_w = 10;
+    let queue_w = 5;
+    let sess_w = 8;
+    let timer_w = 5;
+    let ext_w = 8;
+    let blocked_w = 7;
+    let stage_w = 12;
+    let sort_w = 4;
+    let color_w = 14;
+
+    println!(
+        "{:>id_w$}  {:<status_w$} {:<queue_w$} {:<sess_w$} {:<timer_w$} {:<ext_w$} {:<blocked_w$} {:<stage_w$} {:>sort_w$}  {:<color_w$}",
+        "#", "Status", "Queue", "Sessions", "Timer", "External", "Blocked", "Stage", "Sort", "Color",
+        id_w = id_w, status_w = status_w, queue_w = queue_w, sess_w = sess_w,
+        timer_w = timer_w, ext_w = ext_w, blocked_w = blocked_w, stage_w = stage_w, sort_w = sort_w, color_w = color_w,
+    );
+    println!(
+        "{}  {} {} {} {} {} {} {} {}  {}",
+        "─".repeat(id_w), "─".repeat(status_w), "─".repeat(queue_w), "─".repeat(sess_w),
+        "─".repeat(timer_w), "─".repeat(ext_w), "─".repeat(blocked_w), "─".repeat(stage_w), "─".repeat(sort_w), "─".repeat(color_w),
     );
 
+    let flag = |value: i8| match value {
+        -1 => "*",
+        1 => "yes",
+        _ => "no",
+    };
     for m in &mappings {
-        let queue = if m.in_queue == -1 { "*".to_string() } else if m.in_queue == 1 { "yes".to_string() } else { "no".to_string() };
-        let sess = if m.has_sessions == -1 { "*".to_string() } else if m.has_sessions == 1 { "yes".to_string() } else { "no".to_string() };
-        let timer = if m.has_open_session == -1 { "*".to_string() } else if m.has_open_session == 1 { "yes".to_string() } else { "no".to_string() };
-        let ext = if m.has_externals == -1 { "*".to_string() } else if m.has_externals == 1 { "yes".to_string() } else { "no".to_string() };
         let color = m.color.as_deref().unwrap_or("").to_string();
 
         println!(
-            "{:>id_w$}  {:<status_w$} {:<queue_w$} {:<sess_w$} {:<timer_w$} {:<ext_w$} {:<stage_w$} {:>sort_w$}  {:<color_w$}",
-            m.id, m.status, queue, sess, timer, ext, m.stage, m.sort_order, color,
+            "{:>id_w$}  {:<status_w$} {:<queue_w$} {:<sess_w$} {:<timer_w$} {:<ext_w$} {:<blocked_w$} {:<stage_w$} {:>sort_w$}  {:<color_w$}",
+            m.id, m.status, flag(m.in_queue), flag(m.has_sessions), flag(m.has_open_session), flag(m.has_externals),
+            flag(m.is_blocked), m.stage, m.sort_order, color,
             id_w = id_w, status_w = status_w, queue_w = queue_w, sess_w = sess_w,
-            timer_w = timer_w, ext_w = ext_w, stage_w = stage_w, sort_w = sort_w, color_w = color_w,
+            timer_w = timer_w, ext_w = ext_w, blocked_w = blocked_w, stage_w = stage_w, sort_w = sort_w, color_w = color_w,
         );
     }
 
@@ -2378,6 +3215,616 @@ fn handle_stages_set(conn: &Connection, id: i64, args: Vec<String>) -> Result<()
     Ok(())
 }
 
+fn handle_respawn(subcommand: Option<RespawnCommands>) -> Result<()> {
+    match subcommand {
+        None | Some(RespawnCommands::List) => handle_respawn_list(),
+        Some(RespawnCommands::Preview { rule, from, number }) => handle_respawn_preview(rule, from, number),
+    }
+}
+
+fn handle_respawn_list() -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+
+    let mut tasks: Vec<_> = TaskRepo::list_all(&conn)?
+        .into_iter()
+        .map(|(task, _)| task)
+        .filter(|task| task.status == TaskStatus::Open)
+        .filter(|task| task.respawn.as_deref().is_some_and(|r| !r.is_empty()))
+        .collect();
+    if tasks.is_empty() {
+        println!("No respawning tasks. Use 'tatl add <description> respawn=<rule>' to create one.");
+        return Ok(());
+    }
+    tasks.sort_by_key(|task| (task.due_ts.is_none(), task.due_ts, task.id));
+
+    let desc_w = tasks.iter().map(|t| t.description.chars().count()).max().unwrap_or(0).clamp(11, 40);
+    let rule_w = tasks.iter().map(|t| t.respawn.as_deref().unwrap_or("").len()).max().unwrap_or(0).max(4);
+    println!("{:<4} {:<desc_w$} {:<rule_w$} {:<10} Next", "ID", "Description", "Rule", "Due");
+    println!("{} {} {} {} {}", "─".repeat(4), "─".repeat(desc_w), "─".repeat(rule_w), "─".repeat(10), "─".repeat(14));
+    for task in &tasks {
+        let respawn = task.respawn.as_deref().unwrap_or("");
+        let next = match RespawnRule::parse(respawn) {
+            Ok(rule) => {
+                let remaining = remaining_occurrences(&conn, &rule, task.id.unwrap_or(0))?;
+                let from_ts = task.due_ts.unwrap_or_else(|| chrono::Utc::now().timestamp());
+                let limit = if remaining == Some(0) { 0 } else { 1 };
+                match project_occurrences(&rule, from_ts, task.due_ts, limit).first() {
+                    Some(ts) => format_occurrence(&rule, *ts),
+                    None => "(ends)".to_string(),
+                }
+            }
+            Err(_) => "(invalid rule)".to_string(),
+        };
+        let description: String = task.description.chars().take(desc_w).collect();
+        println!(
+            "{:<4} {:<desc_w$} {:<rule_w$} {:<10} {}",
+            task.id.unwrap_or(0),
+            description,
+            respawn,
+            task.due_ts.map(format_date).unwrap_or_default(),
+            next,
+        );
+    }
+    println!();
+    println!("{}", compute_respawn_status(&conn)?);
+    Ok(())
+}
+
+fn handle_respawn_preview(rule_str: String, from: Option<String>, number: usize) -> Result<()> {
+    let rule = match RespawnRule::parse(&rule_str) {
+        Ok(rule) => rule,
+        Err(e) => user_error(&format!("Invalid respawn rule '{}': {}", rule_str, e)),
+    };
+    let from_ts = match from.as_deref() {
+        Some(expr) => match parse_date_expr(expr) {
+            Ok(ts) => ts,
+            Err(e) => user_error(&format!("Invalid --from date '{}': {}", expr, e)),
+        },
+        None => chrono::Utc::now().timestamp(),
+    };
+
+    // count= is the total number of occurrences in a new chain
+    let limit = rule.count.map_or(number, |count| number.min(count as usize));
+    let occurrences = project_occurrences(&rule, from_ts, None, limit);
+
+    println!("Rule: {}", rule_str);
+    println!("{}", rule.describe());
+    println!();
+    if occurrences.is_empty() {
+        println!("No occurrences after {}.", format_date(from_ts));
+        return Ok(());
+    }
+    println!("Next {} occurrence{} after {}:", occurrences.len(), if occurrences.len() == 1 { "" } else { "s" }, format_date(from_ts));
+    for (idx, ts) in occurrences.iter().enumerate() {
+        println!("  {:>2}. {}", idx + 1, format_occurrence(&rule, *ts));
+    }
+    Ok(())
+}
+
+fn handle_udas(subcommand: Option<UdasCommands>) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    match subcommand {
+        None | Some(UdasCommands::List) => handle_udas_list(&conn),
+        Some(UdasCommands::Define { name, args }) => handle_udas_define(&conn, name, args),
+        Some(UdasCommands::Remove { name }) => handle_udas_remove(&conn, name),
+    }
+}
+
+fn handle_udas_list(conn: &Connection) -> Result<()> {
+    let definitions = UdaRepo::list(conn)?;
+    if definitions.is_empty() {
+        println!("No UDA definitions. Use 'tatl udas define <name> type=...' to declare one.");
+        return Ok(());
+    }
+
+    let name_w = definitions.iter().map(|d| d.name.len()).max().unwrap_or(0).max(4);
+    let type_w = 8;
+    let values_w = definitions.iter().map(|d| d.values.join(",").len()).max().unwrap_or(0).max(6);
+    let default_w = definitions.iter()
+        .map(|d| d.default_value.as_deref().unwrap_or("").len())
+        .max().unwrap_or(0).max(7);
+
+    println!(
+        "{:<name_w$} {:<type_w$} {:<values_w$} {:<default_w$} Label",
+        "Name", "Type", "Values", "Default",
+    );
+    println!(
+        "{} {} {} {} {}",
+        "─".repeat(name_w), "─".repeat(type_w), "─".repeat(values_w), "─".repeat(default_w), "─".repeat(5),
+    );
+    for def in &definitions {
+        println!(
+            "{:<name_w$} {:<type_w$} {:<values_w$} {:<default_w$} {}",
+            def.name,
+            def.uda_type.as_str(),
+            def.values.join(","),
+            def.default_value.as_deref().unwrap_or(""),
+            def.label.as_deref().unwrap_or(""),
+        );
+    }
+    Ok(())
+}
+
+fn handle_udas_define(conn: &Connection, name: String, args: Vec<String>) -> Result<()> {
+    let name = name.strip_prefix("uda.").unwrap_or(&name).to_string();
+    if let Err(e) = validate_uda_key(&format!("uda.{}", name)) {
+        user_error(&e);
+    }
+
+    let existing = UdaRepo::get(conn, &name)?;
+    let mut uda_type = existing.as_ref().map(|d| d.uda_type);
+    let mut values = existing.as_ref().map(|d| d.values.clone()).unwrap_or_default();
+    let mut default_value = existing.as_ref().and_then(|d| d.default_value.clone());
+    let mut label = existing.as_ref().and_then(|d| d.label.clone());
+
+    for arg in &args {
+        if let Some(val) = arg.strip_prefix("type=") {
+            uda_type = Some(UdaType::parse(val).unwrap_or_else(|| user_error(&format!(
+                "Invalid UDA type '{}'. Valid types: string, number, date, duration, enum", val
+            ))));
+        } else if let Some(val) = arg.strip_prefix("values=") {
+            values = val.split(',')
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect();
+        } else if let Some(val) = arg.strip_prefix("default=") {
+            default_value = if val.is_empty() || val == "none" { None } else { Some(val.to_string()) };
+        } else if let Some(val) = arg.strip_prefix("label=") {
+            label = if val.is_empty() || val == "none" { None } else { Some(val.to_string()) };
+        } else {
+            user_error(&format!(
+                "Unrecognized argument '{}'. Expected type=, values=, default= or label=", arg
+            ));
+        }
+    }
+
+    let uda_type = uda_type.unwrap_or_else(|| user_error(&format!(
+        "UDA '{}' is not defined yet; type=<string|number|date|duration|enum> is required", name
+    )));
+    if uda_type == UdaType::Enum && values.is_empty() {
+        user_error(&format!("Enum UDA '{}' requires values=a,b,c", name));
+    }
+    if uda_type != UdaType::Enum {
+        values.clear();
+    }
+
+    let definition = UdaDefinition { name, uda_type, values, default_value, label };
+    if let Some(default) = &definition.default_value {
+        if let Err(e) = definition.normalize(default) {
+            user_error(&format!("Invalid default: {}", e));
+        }
+    }
+    UdaRepo::upsert(conn, &definition)?;
+
+    let mut details = vec![definition.uda_type.as_str().to_string()];
+    if !definition.values.is_empty() {
+        details.push(format!("values: {}", definition.values.join(", ")));
+    }
+    if let Some(default) = &definition.default_value {
+        details.push(format!("default: {}", default));
+    }
+    if let Some(label) = &definition.label {
+        details.push(format!("label: {}", label));
+    }
+    println!("Defined UDA '{}' ({})", definition.name, details.join("; "));
+    Ok(())
+}
+
+fn handle_udas_remove(conn: &Connection, name: String) -> Result<()> {
+    let name = name.strip_prefix("uda.").unwrap_or(&name);
+    if !UdaRepo::delete(conn, name)? {
+        user_error(&format!("UDA '{}' is not defined", name));
+    }
+    println!("Removed UDA definition '{}'. Existing values are kept.", name);
+    Ok(())
+}
+
+fn handle_templates(subcommand: Option<TemplatesCommands>) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    match subcommand {
+        None | Some(TemplatesCommands::List) => handle_templates_list(&conn),
+        Some(TemplatesCommands::Show { name }) => handle_templates_show(&conn, &name),
+        Some(TemplatesCommands::Add { name, args }) => handle_templates_add(&conn, &name, args),
+        Some(TemplatesCommands::Modify { name, args }) => handle_templates_modify(&conn, &name, args),
+        Some(TemplatesCommands::Delete { name, yes }) => handle_templates_delete(&conn, &name, yes),
+        Some(TemplatesCommands::AddChild { name, under, args }) => {
+            handle_templates_add_child(&conn, &name, under.as_deref(), args)
+        }
+        Some(TemplatesCommands::RemoveChild { name, path }) => handle_templates_remove_child(&conn, &name, &path),
+    }
+}
+
+/// Template attributes as (field, value) pairs in display order
+fn template_attributes(conn: &Connection, template: &crate::repo::Template) -> Result<Vec<(String, String)>> {
+    let payload = &template.payload;
+    let mut attrs = Vec::new();
+    if let Some(pattern) = template.description_pattern() {
+        attrs.push(("description".to_string(), pattern.to_string()));
+    }
+    if let Some(project_id) = payload.get("project_id").and_then(|v| v.as_i64()) {
+        let name = ProjectRepo::get_by_id(conn, project_id)?
+            .map(|p| p.name)
+            .unwrap_or_else(|| format!("[{}]", project_id));
+        attrs.push(("project".to_string(), name));
+    }
+    for field in ["due", "scheduled", "wait"] {
+        if let Some(expr) = template.get_str(field) {
+            attrs.push((field.to_string(), expr.to_string()));
+        } else if let Some(ts) = payload.get(&format!("{}_ts", field)).and_then(|v| v.as_i64()) {
+            // Absolute date saved by an older version
+            attrs.push((field.to_string(), crate::cli::output::format_date(ts)));
+        }
+    }
+    if let Some(alloc) = payload.get("alloc_secs").and_then(|v| v.as_i64()) {
+        attrs.push(("allocation".to_string(), crate::cli::output::format_duration(alloc)));
+    }
+    if let Some(respawn) = template.respawn() {
+        attrs.push(("respawn".to_string(), respawn.to_string()));
+    }
+    if let Some(parent_id) = template.parent_id() {
+        attrs.push(("parent".to_string(), parent_id.to_string()));
+    }
+    if let Some(tags) = payload.get("tags").and_then(|v| v.as_array()) {
+        let tags: Vec<String> = tags.iter().filter_map(|t| t.as_str()).map(|t| format!("+{}", t)).collect();
+        if !tags.is_empty() {
+            attrs.push(("tags".to_string(), tags.join(" ")));
+        }
+    }
+    if let Some(udas) = payload.get("udas").and_then(|v| v.as_object()) {
+        let mut keys: Vec<&String> = udas.keys().collect();
+        keys.sort();
+        for key in keys {
+            if let Some(value) = udas[key].as_str() {
+                attrs.push((format!("uda.{}", key), value.to_string()));
+            }
+        }
+    }
+    let subtasks: usize = template.children()?.iter().map(TemplateChild::count).sum();
+    if subtasks > 0 {
+        attrs.push(("subtasks".to_string(), subtasks.to_string()));
+    }
+    Ok(attrs)
+}
+
+/// One line per template subtask: path, description and attributes
+fn template_child_lines(conn: &Connection, children: &[TemplateChild], prefix: &str, lines: &mut Vec<String>) -> Result<()> {
+    for (idx, child) in children.iter().enumerate() {
+        let path = format!("{}{}", prefix, idx + 1);
+        let mut parts = vec![child.description.clone()];
+        if let Some(project_id) = child.project_id {
+            let name = ProjectRepo::get_by_id(conn, project_id)?
+                .map(|p| p.name)
+                .unwrap_or_else(|| format!("[{}]", project_id));
+            parts.push(format!("project={}", name));
+        }
+        if let Some(offset) = &child.due_offset {
+            parts.push(format!("due={}", offset));
+        }
+        if let Some(alloc) = child.alloc_secs {
+            parts.push(format!("allocation={}", crate::cli::output::format_duration(alloc)));
+        }
+        parts.extend(child.tags.iter().map(|t| format!("+{}", t)));
+        let mut udas: Vec<_> = child.udas.iter().collect();
+        udas.sort();
+        parts.extend(udas.into_iter().map(|(k, v)| format!("uda.{}={}", k, v)));
+
+        let depth = path.matches('.').count();
+        lines.push(format!("{}{}  {}", "  ".repeat(depth), path, parts.join("  ")));
+        template_child_lines(conn, &child.children, &format!("{}.", path), lines)?;
+    }
+    Ok(())
+}
+
+fn handle_templates_list(conn: &Connection) -> Result<()> {
+    let templates = TemplateRepo::list(conn)?;
+    if templates.is_empty() {
+        println!("No templates. Use 'tatl templates add <name> ...' to create one.");
+        return Ok(());
+    }
+
+    let name_w = templates.iter().map(|t| t.name.chars().count()).max().unwrap_or(0).max(4);
+    println!("{:<name_w$} Attributes", "Name");
+    println!("{} {}", "─".repeat(name_w), "─".repeat(10));
+    for template in &templates {
+        let attrs: Vec<String> = template_attributes(conn, template)?
+            .into_iter()
+            .map(|(field, value)| match field.as_str() {
+                "description" => format!("\"{}\"", value),
+                "tags" => value,
+                "subtasks" => format!("({} subtasks)", value),
+                _ => format!("{}={}", field, value),
+            })
+            .collect();
+        println!("{:<name_w$} {}", template.name, attrs.join(" "));
+    }
+    Ok(())
+}
+
+fn handle_templates_show(conn: &Connection, name: &str) -> Result<()> {
+    let template = TemplateRepo::get_by_name(conn, name)?
+        .unwrap_or_else(|| user_error(&format!("Template '{}' not found", name)));
+
+    println!("Template: {}", template.name);
+    let attrs = template_attributes(conn, &template)?;
+    if attrs.is_empty() {
+        println!("  (no attributes)");
+    }
+    for (field, value) in attrs.into_iter().filter(|(field, _)| field != "subtasks") {
+        println!("  {:<12} {}", format!("{}:", field), value);
+    }
+    println!("  {:<12} {}", "Created:", crate::cli::output::format_date(template.created_ts));
+    println!("  {:<12} {}", "Modified:", crate::cli::output::format_date(template.modified_ts));
+
+    let mut lines = Vec::new();
+    template_child_lines(conn, &template.children()?, "", &mut lines)?;
+    if !lines.is_empty() {
+        println!();
+        println!("Subtasks:");
+        for line in lines {
+            println!("  {}", line);
+        }
+    }
+    Ok(())
+}
+
+/// Apply template add/modify arguments to a payload. Dates are validated but stored
+/// as the expressions given, so they are evaluated each time the template is used.
+fn apply_template_args(
+    conn: &Connection,
+    payload: &mut HashMap<String, serde_json::Value>,
+    args: Vec<String>,
+) -> Result<()> {
+    let parsed = match parse_task_args(args) {
+        Ok(p) => p,
+        Err(e) => user_error(&e.to_string()),
+    };
+    let is_clear = |value: &str| value == "none" || value.is_empty();
+
+    if parsed.template.is_some() {
+        user_error("template= cannot be set on a template");
+    }
+    if parsed.depends.is_some() {
+        user_error("depends= cannot be set on a template");
+    }
+    if !parsed.description.is_empty() {
+        payload.insert("description".to_string(), serde_json::json!(join_description(&parsed.description)));
+    }
+    if let Some(project_name) = &parsed.project {
+        if is_clear(project_name) {
+            payload.remove("project_id");
+        } else {
+            let project = ProjectRepo::get_by_name(conn, project_name)?
+                .unwrap_or_else(|| user_error(&format!(
+                    "Project '{}' not found. Create it with 'tatl projects add {}'", project_name, project_name
+                )));
+            payload.insert("project_id".to_string(), serde_json::json!(project.id));
+        }
+    }
+    for (field, value) in [("due", &parsed.due), ("scheduled", &parsed.scheduled), ("wait", &parsed.wait)] {
+        let Some(expr) = value else { continue };
+        payload.remove(&format!("{}_ts", field));
+        if is_clear(expr) {
+            payload.remove(field);
+        } else {
+            if let Err(e) = parse_date_expr(expr) {
+                user_error(&format!("Invalid {} expression '{}': {}", field, expr, e));
+            }
+            payload.insert(field.to_string(), serde_json::json!(expr));
+        }
+    }
+    if let Some(allocation) = &parsed.allocation {
+        if is_clear(allocation) {
+            payload.remove("alloc_secs");
+        } else {
+            let secs = parse_duration(allocation)
+                .unwrap_or_else(|e| user_error(&format!("Invalid allocation '{}': {}", allocation, e)));
+            payload.insert("alloc_secs".to_string(), serde_json::json!(secs));
+        }
+    }
+    if let Some(respawn) = &parsed.respawn {
+        if is_clear(respawn) {
+            payload.remove("respawn");
+        } else {
+            if let Err(e) = crate::respawn::parser::RespawnRule::parse(respawn) {
+                user_error(&format!("Invalid respawn rule '{}': {}", respawn, e));
+            }
+            payload.insert("respawn".to_string(), serde_json::json!(respawn));
+        }
+    }
+    if let Some(parent) = &parsed.parent {
+        if is_clear(parent) {
+            payload.remove("parent_id");
+        } else {
+            let parent_id = validate_task_id(parent).unwrap_or_else(|e| user_error(&e));
+            if TaskRepo::get_by_id(conn, parent_id)?.is_none() {
+                user_error(&format!("Parent task {} not found", parent_id));
+            }
+            payload.insert("parent_id".to_string(), serde_json::json!(parent_id));
+        }
+    }
+
+    if !parsed.tags_add.is_empty() || !parsed.tags_remove.is_empty() {
+        let mut tags: Vec<String> = payload.get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| tags.iter().filter_map(|t| t.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        for tag in &parsed.tags_add {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.retain(|t| !parsed.tags_remove.contains(t));
+        if tags.is_empty() {
+            payload.remove("tags");
+        } else {
+            payload.insert("tags".to_string(), serde_json::json!(tags));
+        }
+    }
+
+    if !parsed.udas.is_empty() {
+        let mut udas: serde_json::Map<String, serde_json::Value> = payload.get("udas")
+            .and_then(|v| v.as_object().cloned())
+            .unwrap_or_default();
+        for (key, value) in normalize_udas(conn, &parsed.udas)? {
+            if is_clear(&value) {
+                udas.remove(&key);
+            } else {
+                udas.insert(key, serde_json::Value::String(value));
+            }
+        }
+        if udas.is_empty() {
+            payload.remove("udas");
+        } else {
+            payload.insert("udas".to_string(), serde_json::Value::Object(udas));
+        }
+    }
+    Ok(())
+}
+
+fn handle_templates_add(conn: &Connection, name: &str, args: Vec<String>) -> Result<()> {
+    if name.trim().is_empty() || name.contains(char::is_whitespace) {
+        user_error(&format!("Invalid template name '{}': must be non-empty without whitespace", name));
+    }
+    if TemplateRepo::get_by_name(conn, name)?.is_some() {
+        user_error(&format!("Template '{}' already exists. Use 'tatl templates modify {}' to change it.", name, name));
+    }
+    let mut payload = HashMap::new();
+    apply_template_args(conn, &mut payload, args)?;
+    TemplateRepo::save(conn, name, &payload)?;
+    println!("Created template '{}'", name);
+    Ok(())
+}
+
+fn handle_templates_modify(conn: &Connection, name: &str, args: Vec<String>) -> Result<()> {
+    let template = TemplateRepo::get_by_name(conn, name)?
+        .unwrap_or_else(|| user_error(&format!("Template '{}' not found", name)));
+    if args.is_empty() {
+        user_error("Nothing to modify. Provide a description pattern or attributes.");
+    }
+    let mut payload = template.payload;
+    apply_template_args(conn, &mut payload, args)?;
+    TemplateRepo::save(conn, name, &payload)?;
+    println!("Modified template '{}'", name);
+    Ok(())
+}
+
+fn handle_templates_add_child(conn: &Connection, name: &str, under: Option<&str>, args: Vec<String>) -> Result<()> {
+    let template = TemplateRepo::get_by_name(conn, name)?
+        .unwrap_or_else(|| user_error(&format!("Template '{}' not found", name)));
+    let parsed = match parse_task_args(args) {
+        Ok(p) => p,
+        Err(e) => user_error(&e.to_string()),
+    };
+    if parsed.description.is_empty() {
+        user_error("Subtask description is required");
+    }
+    if parsed.scheduled.is_some() || parsed.wait.is_some() || parsed.respawn.is_some()
+        || parsed.parent.is_some() || parsed.template.is_some() || parsed.depends.is_some()
+        || !parsed.tags_remove.is_empty()
+    {
+        user_error("Template subtasks support only description, project=, due=<offset>, allocation=, +tag and uda.<key>=");
+    }
+
+    let project_id = match &parsed.project {
+        Some(project_name) if project_name != "none" => Some(
+            ProjectRepo::get_by_name(conn, project_name)?
+                .unwrap_or_else(|| user_error(&format!(
+                    "Project '{}' not found. Create it with 'tatl projects add {}'", project_name, project_name
+                )))
+                .id
+                .unwrap(),
+        ),
+        _ => None,
+    };
+    if let Some(offset) = &parsed.due {
+        if let Err(e) = TemplateChild::parse_offset(offset) {
+            user_error(&format!("{}. Subtask due dates are offsets from the parent's, e.g. due=-2d", e));
+        }
+    }
+    let alloc_secs = parsed.allocation.as_deref()
+        .map(|a| parse_duration(a).unwrap_or_else(|e| user_error(&format!("Invalid allocation '{}': {}", a, e))));
+    let mut udas = normalize_udas(conn, &parsed.udas)?;
+    udas.retain(|_, value| value != "none");
+
+    let child = TemplateChild {
+        description: join_description(&parsed.description),
+        project_id,
+        due_offset: parsed.due.clone(),
+        alloc_secs,
+        tags: parsed.tags_add.clone(),
+        udas,
+        children: Vec::new(),
+    };
+
+    let mut children = template.children()?;
+    let path = match under {
+        Some(path) => crate::repo::parse_child_path(path).unwrap_or_else(|e| user_error(&e.to_string())),
+        None => Vec::new(),
+    };
+    let siblings = crate::repo::children_at_mut(&mut children, &path)
+        .unwrap_or_else(|| user_error(&format!("Template '{}' has no subtask {}", name, under.unwrap_or(""))));
+    siblings.push(child.clone());
+    let new_path = match under {
+        Some(under) => format!("{}.{}", under, siblings.len()),
+        None => siblings.len().to_string(),
+    };
+
+    let mut payload = template.payload;
+    crate::repo::Template::set_children(&mut payload, &children)?;
+    TemplateRepo::save(conn, name, &payload)?;
+    println!("Added subtask {} to template '{}': {}", new_path, name, child.description);
+    Ok(())
+}
+
+fn handle_templates_remove_child(conn: &Connection, name: &str, path_str: &str) -> Result<()> {
+    let template = TemplateRepo::get_by_name(conn, name)?
+        .unwrap_or_else(|| user_error(&format!("Template '{}' not found", name)));
+    let path = crate::repo::parse_child_path(path_str).unwrap_or_else(|e| user_error(&e.to_string()));
+    let (index, parent_path) = path.split_last().expect("child path is never empty");
+
+    let mut children = template.children()?;
+    let siblings = crate::repo::children_at_mut(&mut children, parent_path)
+        .filter(|siblings| *index < siblings.len())
+        .unwrap_or_else(|| user_error(&format!("Template '{}' has no subtask {}", name, path_str)));
+    let removed = siblings.remove(*index);
+
+    let mut payload = template.payload;
+    crate::repo::Template::set_children(&mut payload, &children)?;
+    TemplateRepo::save(conn, name, &payload)?;
+    let nested = removed.count() - 1;
+    if nested > 0 {
+        println!("Removed subtask {} from template '{}': {} (and {} nested)", path_str, name, removed.description, nested);
+    } else {
+        println!("Removed subtask {} from template '{}': {}", path_str, name, removed.description);
+    }
+    Ok(())
+}
+
+fn handle_templates_delete(conn: &Connection, name: &str, yes: bool) -> Result<()> {
+    use std::io::{self, Write};
+
+    if TemplateRepo::get_by_name(conn, name)?.is_none() {
+        user_error(&format!("Template '{}' not found", name));
+    }
+    if !yes {
+        print!("Delete template '{}'? (y/n): ", name);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+        if input != "y" && input != "yes" {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+    TemplateRepo::delete(conn, name)?;
+    println!("Deleted template '{}'. Tasks created from it are unchanged.", name);
+    Ok(())
+}
+
 fn handle_send(task_id_str: String, recipient: String, request: Vec<String>) -> Result<()> {
     let conn = DbConnection::connect()?;
     let task_id = validate_task_id(&task_id_str)
@@ -2401,24 +3848,16 @@ fn handle_send(task_id_str: String, recipient: String, request: Vec<String>) ->
     let open_session = SessionRepo::get_open(&conn)?;
     if let Some(session) = open_session {
         if session.task_id == task_id {
-            let end_ts = chrono::Utc::now().timestamp();
-            let end_ts = std::cmp::max(end_ts, session.start_ts + 1);
-            SessionRepo::close_open(&conn, end_ts)
-                .context("Failed to close session")?;
+            let (end_ts, capped) = close_session_capped(&conn, &session, chrono::Utc::now().timestamp())?;
+            print_capped(capped);
             let duration = end_ts - session.start_ts;
             println!("Stopped timing task {}: {} ({}, {})",
                 task_id, task.description, format_time(end_ts), format_duration_human(duration));
         }
     }
 
-    // Remove from queue if present
-    let stack = StackRepo::get_or_create_default(&conn)?;
-    if let Some(stack_id) = stack.id {
-        let items = StackRepo::get_items(&conn, stack_id)?;
-        if items.iter().any(|item| item.task_id == task_id) {
-            StackRepo::remove_task(&conn, stack_id, task_id)?;
-        }
-    }
+    // Remove from all queues
+    StackRepo::remove_from_all(&conn, task_id)?;
 
     // Create external record
     let request_str = if request.is_empty() {
@@ -2452,7 +3891,7 @@ fn handle_collect(task_id_str: String) -> Result<()> {
     ExternalRepo::mark_all_returned_for_task(&conn, task_id)?;
 
     // Auto-enqueue at bottom (Plan 41: collect re-queues)
-    let stack = StackRepo::get_or_create_default(&conn)?;
+    let stack = StackRepo::get_active(&conn)?;
     StackRepo::enqueue(&conn, stack.id.unwrap(), task_id)?;
 
     println!("Collected task {}: {}", task_id, task.description);
@@ -2541,16 +3980,30 @@ fn handle_task_add(args: Vec<String>, auto_yes: bool) -> Result<i64> {
         }
     };
     
-    // Validate description
-    if parsed.description.is_empty() {
+    // Validate description (a template may supply it through its description pattern)
+    if parsed.description.is_empty() && parsed.template.is_none() {
         user_error("Task description is required");
     }
     
-    let description = join_description(&parsed.description);
-    
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
     
+    let template = match &parsed.template {
+        Some(name) => TemplateRepo::get_by_name(&conn, name)?,
+        None => None,
+    };
+    let description = match &template {
+        Some(tmpl) => tmpl.render_description(&join_description(&parsed.description)),
+        None => join_description(&parsed.description),
+    };
+    if parsed.description.is_empty() && description.is_empty() {
+        user_error("Task description is required");
+    }
+    
+    // Validate and normalise UDA values against their definitions
+    let mut task_udas = normalize_udas(&conn, &parsed.udas)?;
+    task_udas.retain(|_, value| value != "none");
+    
     // Resolve project (handle clearing with project=none or project=)
     let project_id = if let Some(project_name) = parsed.project {
         if project_name == "none" {
@@ -2602,20 +4055,20 @@ fn handle_task_add(args: Vec<String>, auto_yes: bool) -> Result<i64> {
     };
     
     // Parse dates (simplified for MVP)
-    let due_ts = if let Some(due) = parsed.due {
-        Some(parse_date_expr(&due).context("Failed to parse due date")?)
+    let due_ts = if let Some(due) = &parsed.due {
+        Some(parse_date_expr(due).context("Failed to parse due date")?)
     } else {
         None
     };
     
-    let scheduled_ts = if let Some(scheduled) = parsed.scheduled {
-        Some(parse_date_expr(&scheduled).context("Failed to parse scheduled date")?)
+    let scheduled_ts = if let Some(scheduled) = &parsed.scheduled {
+        Some(parse_date_expr(scheduled).context("Failed to parse scheduled date")?)
     } else {
         None
     };
     
-    let wait_ts = if let Some(wait) = parsed.wait {
-        Some(parse_date_expr(&wait).context("Failed to parse wait date")?)
+    let wait_ts = if let Some(wait) = &parsed.wait {
+        Some(parse_date_expr(wait).context("Failed to parse wait date")?)
     } else {
         None
     };
@@ -2628,23 +4081,21 @@ fn handle_task_add(args: Vec<String>, auto_yes: bool) -> Result<i64> {
     };
     
     // Load template if specified and merge attributes
-    let (final_project_id, final_due_ts, final_scheduled_ts, final_wait_ts, final_alloc_secs, final_udas, final_tags) = 
+    let (final_project_id, final_due_ts, final_scheduled_ts, final_wait_ts, final_alloc_secs, mut final_udas, final_tags) = 
         if let Some(template_name) = &parsed.template {
-            // Load template
-            let template = TemplateRepo::get_by_name(&conn, template_name)?;
-            if let Some(tmpl) = template {
+            if let Some(tmpl) = &template {
                 // Merge template with task attributes (task overrides template)
                 let (proj_id, due, scheduled, wait, alloc, udas, tags) = 
                     TemplateRepo::merge_attributes(
-                        &tmpl,
+                        tmpl,
                         project_id,
                         due_ts,
                         scheduled_ts,
                         wait_ts,
                         alloc_secs,
-                        &parsed.udas,
+                        &task_udas,
                         &parsed.tags_add,
-                    );
+                    )?;
                 (proj_id, due, scheduled, wait, alloc, udas, tags)
             } else {
                 // Template not found - create it from current task attributes
@@ -2652,23 +4103,31 @@ fn handle_task_add(args: Vec<String>, auto_yes: bool) -> Result<i64> {
                     &conn,
                     template_name,
                     project_id,
-                    due_ts,
-                    scheduled_ts,
-                    wait_ts,
+                    parsed.due.as_deref(),
+                    parsed.scheduled.as_deref(),
+                    parsed.wait.as_deref(),
                     alloc_secs,
-                    &parsed.udas,
+                    &task_udas,
                     &parsed.tags_add,
                 )?;
                 // Use task attributes as-is
-                (project_id, due_ts, scheduled_ts, wait_ts, alloc_secs, parsed.udas, parsed.tags_add)
+                (project_id, due_ts, scheduled_ts, wait_ts, alloc_secs, task_udas, parsed.tags_add)
             }
         } else {
             // No template - use task attributes as-is
-            (project_id, due_ts, scheduled_ts, wait_ts, alloc_secs, parsed.udas, parsed.tags_add)
+            (project_id, due_ts, scheduled_ts, wait_ts, alloc_secs, task_udas, parsed.tags_add)
         };
     
+    apply_uda_defaults(&conn, &mut final_udas)?;
+
+    // Respawn rule and parent fall back to the template's
+    let respawn = parsed.respawn.clone()
+        .or_else(|| template.as_ref().and_then(|t| t.respawn().map(str::to_string)));
+    let parent = parsed.parent.clone()
+        .or_else(|| template.as_ref().and_then(|t| t.parent_id().map(|id| id.to_string())));
+
     // Resolve parent
-    let parent_id = if let Some(parent_str) = &parsed.parent {
+    let parent_id = if let Some(parent_str) = &parent {
         if parent_str == "none" {
             None
         } else {
@@ -2685,6 +4144,11 @@ fn handle_task_add(args: Vec<String>, auto_yes: bool) -> Result<i64> {
         None
     };
 
+    let depends = match &parsed.depends {
+        Some(spec) => Some(parse_depends(&conn, None, spec)?),
+        None => None,
+    };
+
     // Create task
     let task = TaskRepo::create_full(
         &conn,
@@ -2695,7 +4159,7 @@ fn handle_task_add(args: Vec<String>, auto_yes: bool) -> Result<i64> {
         final_wait_ts,
         final_alloc_secs,
         parsed.template,
-        parsed.respawn,
+        respawn,
         &final_udas,
         &final_tags,
         parent_id,
@@ -2703,12 +4167,158 @@ fn handle_task_add(args: Vec<String>, auto_yes: bool) -> Result<i64> {
     .context("Failed to create task")?;
 
     let task_id = task.id.unwrap();
+    if let Some(depends) = &depends {
+        apply_depends(&conn, task_id, depends)?;
+    }
     println!("Created task {}: {}", task_id, description);
 
+    if let Some(tmpl) = &template {
+        create_template_subtasks(&conn, &tmpl.children()?, &task, 1)?;
+    }
+
     Ok(task_id)
 }
 
-fn handle_clone(task_id_str: String, args: Vec<String>, auto_yes: bool) -> Result<i64> {
+/// Create a template's subtask hierarchy under a task.
+/// Subtasks inherit the parent's project unless they set their own, and due offsets
+/// are applied to the parent's due date.
+fn create_template_subtasks(conn: &Connection, children: &[TemplateChild], parent: &crate::models::Task, depth: usize) -> Result<()> {
+    for child in children {
+        let mut udas = child.udas.clone();
+        apply_uda_defaults(conn, &mut udas)?;
+        let subtask = TaskRepo::create_full(
+            conn,
+            &child.description,
+            child.project_id.or(parent.project_id),
+            child.resolve_due(parent.due_ts)?,
+            None,
+            None,
+            child.alloc_secs,
+            None,
+            None,
+            &udas,
+            &child.tags,
+            parent.id,
+        )
+        .context("Failed to create subtask")?;
+        println!("{}Created subtask {}: {}", "  ".repeat(depth), subtask.id.unwrap(), child.description);
+        create_template_subtasks(conn, &child.children, &subtask, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// Validate UDA keys and normalise values against their typed definitions.
+/// "none" (clear) passes through; UDAs without a definition stay free strings.
+fn normalize_udas(conn: &Connection, udas: &HashMap<String, String>) -> Result<HashMap<String, String>> {
+    let definitions = UdaRepo::load_map(conn)?;
+    let mut normalized = HashMap::new();
+    for (key, value) in udas {
+        if let Err(e) = validate_uda_key(key) {
+            user_error(&e);
+        }
+        let value = match definitions.get(key) {
+            Some(def) if value != "none" => def.normalize(value).unwrap_or_else(|e| user_error(&e)),
+            _ => value.clone(),
+        };
+        normalized.insert(key.clone(), value);
+    }
+    Ok(normalized)
+}
+
+/// Fill in declared defaults for UDAs that were not set explicitly
+fn apply_uda_defaults(conn: &Connection, udas: &mut HashMap<String, String>) -> Result<()> {
+    for def in UdaRepo::list(conn)? {
+        if udas.contains_key(&def.name) {
+            continue;
+        }
+        if let Some(default) = &def.default_value {
+            let value = def.normalize(default)
+                .map_err(|e| anyhow::anyhow!("Invalid default for uda.{}: {}", def.name, e))?;
+            udas.insert(def.name.clone(), value);
+        }
+    }
+    Ok(())
+}
+
+/// A parsed depends= value
+enum DependsChange {
+    /// Replace the task's dependencies (depends=12,15; depends=none clears them)
+    Set(Vec<i64>),
+    /// Add and remove individual dependencies (depends=+12,-15)
+    Edit { add: Vec<i64>, remove: Vec<i64> },
+}
+
+/// Parse and validate a depends= value. For an existing task, dependencies that
+/// would form a cycle are rejected here, before anything is modified.
+fn parse_depends(conn: &Connection, task_id: Option<i64>, spec: &str) -> Result<DependsChange> {
+    let check = |id: i64| -> Result<()> {
+        if TaskRepo::get_by_id(conn, id)?.is_none() {
+            user_error(&format!("Dependency task {} not found", id));
+        }
+        if let Some(task_id) = task_id {
+            if task_id == id {
+                user_error(&format!("Task {} cannot depend on itself", id));
+            }
+            if let Err(e) = DependencyRepo::validate_no_cycle(conn, task_id, id) {
+                user_error(&e.to_string());
+            }
+        }
+        Ok(())
+    };
+
+    if spec == "none" || spec.is_empty() {
+        return Ok(DependsChange::Set(Vec::new()));
+    }
+
+    let items: Vec<&str> = spec.split(',').map(str::trim).collect();
+    let incremental = items.iter().filter(|i| i.starts_with('+') || i.starts_with('-')).count();
+    if incremental == 0 {
+        let ids = parse_task_id_spec(spec)
+            .unwrap_or_else(|e| user_error(&format!("Invalid depends '{}': {}", spec, e)));
+        for id in &ids {
+            check(*id)?;
+        }
+        return Ok(DependsChange::Set(ids));
+    }
+    if incremental != items.len() {
+        user_error(&format!(
+            "Invalid depends '{}': use either a list (depends=12,15) or +/- edits (depends=+12,-15), not both",
+            spec
+        ));
+    }
+
+    let mut add = Vec::new();
+    let mut remove = Vec::new();
+    for item in items {
+        let id = validate_task_id(&item[1..])
+            .unwrap_or_else(|e| user_error(&format!("Invalid depends '{}': {}", spec, e)));
+        if item.starts_with('+') {
+            check(id)?;
+            add.push(id);
+        } else {
+            remove.push(id);
+        }
+    }
+    Ok(DependsChange::Edit { add, remove })
+}
+
+/// Apply a parsed depends= value to a task
+fn apply_depends(conn: &Connection, task_id: i64, change: &DependsChange) -> Result<()> {
+    match change {
+        DependsChange::Set(ids) => DependencyRepo::set(conn, task_id, ids)?,
+        DependsChange::Edit { add, remove } => {
+            for id in remove {
+                DependencyRepo::remove(conn, task_id, *id)?;
+            }
+            for id in add {
+                DependencyRepo::add(conn, task_id, *id)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_clone(task_id_str: String, args: Vec<String>, auto_yes: bool, tree: bool) -> Result<i64> {
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
 
@@ -2827,11 +4437,11 @@ fn handle_clone(task_id_str: String, args: Vec<String>, auto_yes: bool) -> Resul
     // Merge UDAs: start with source, apply overrides
     let mut udas = source.udas.clone();
     if let Some(ref o) = overrides {
-        for (k, v) in &o.udas {
-            if v.is_empty() {
-                udas.remove(k);
+        for (k, v) in normalize_udas(&conn, &o.udas)? {
+            if v.is_empty() || v == "none" {
+                udas.remove(&k);
             } else {
-                udas.insert(k.clone(), v.clone());
+                udas.insert(k, v);
             }
         }
     }
@@ -2897,6 +4507,17 @@ fn handle_clone(task_id_str: String, args: Vec<String>, auto_yes: bool) -> Resul
     let new_id = new_task.id.unwrap();
     println!("Cloned task {} → new task {}: {}", source_id, new_id, description);
 
+    if tree {
+        // Subtask dates move with the root's due date
+        let shift = match (new_task.due_ts, source.due_ts) {
+            (Some(new_due), Some(old_due)) => new_due - old_due,
+            _ => 0,
+        };
+        for (child, clone_id, depth) in TaskRepo::clone_subtree(&conn, source_id, new_id, shift)? {
+            println!("{}Cloned task {} → new task {}: {}", "  ".repeat(depth), child.id.unwrap(), clone_id, child.description);
+        }
+    }
+
     Ok(new_id)
 }
 
@@ -2905,6 +4526,7 @@ struct ListRequest {
     sort_columns: Vec<String>,
     group_columns: Vec<String>,
     hide_columns: Vec<String>,
+    show_columns: Vec<String>,
     color_column: Option<String>,
     fill_column: Option<String>,
     save_alias: Option<String>,
@@ -2915,6 +4537,7 @@ fn parse_list_request(tokens: Vec<String>) -> ListRequest {
     let mut sort_columns = Vec::new();
     let mut group_columns = Vec::new();
     let mut hide_columns = Vec::new();
+    let mut show_columns = Vec::new();
     let mut color_column: Option<String> = None;
     let mut fill_column: Option<String> = None;
     let mut save_alias: Option<String> = None;
@@ -2926,6 +4549,8 @@ fn parse_list_request(tokens: Vec<String>) -> ListRequest {
             group_columns.extend(spec.split(',').filter(|s| !s.is_empty()).map(|s| s.to_string()));
         } else if let Some(spec) = token.strip_prefix("hide:") {
             hide_columns.extend(spec.split(',').filter(|s| !s.is_empty()).map(|s| s.to_string()));
+        } else if let Some(spec) = token.strip_prefix("show:") {
+            show_columns.extend(spec.split(',').filter(|s| !s.is_empty()).map(|s| s.to_string()));
         } else if let Some(spec) = token.strip_prefix("color:") {
             if color_column.is_none() && !spec.is_empty() {
                 color_column = Some(spec.to_lowercase());
@@ -2948,6 +4573,7 @@ fn parse_list_request(tokens: Vec<String>) -> ListRequest {
         sort_columns,
         group_columns,
         hide_columns,
+        show_columns,
         color_column,
         fill_column,
         save_alias,
@@ -2967,6 +4593,19 @@ fn handle_task_list(filter_args: Vec<String>, json: bool, relative: bool, full:
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
     
+    let config = Config::current();
+    // A bare 'tatl list' shows the configured default view, if any
+    let filter_args = match config.default_view() {
+        Some(view) if filter_args.is_empty() => {
+            if ViewRepo::get_by_name(&conn, "tasks", view)?.is_some() {
+                vec![view.to_string()]
+            } else {
+                eprintln!("Warning: list.default_view '{}' is not a saved view", view);
+                filter_args
+            }
+        }
+        _ => filter_args,
+    };
     let mut request = parse_list_request(filter_args);
     
     if request.sort_columns.is_empty()
@@ -2979,26 +4618,36 @@ fn handle_task_list(filter_args: Vec<String>, json: bool, relative: bool, full:
             request.sort_columns = view.sort_columns;
             request.group_columns = view.group_columns;
             request.hide_columns = view.hide_columns;
+            request.show_columns = view.show_columns;
             request.color_column = view.color_column;
             request.fill_column = view.fill_column;
         }
     }
     
     if let Some(alias) = request.save_alias.clone() {
-        ViewRepo::upsert(
-            &conn,
-            &alias,
-            "tasks",
-            &request.filter_tokens,
-            &request.sort_columns,
-            &request.group_columns,
-            &request.hide_columns,
-            &request.color_column,
-            &request.fill_column,
-        )?;
+        let options = ViewOptions {
+            filter_tokens: request.filter_tokens.clone(),
+            sort_columns: request.sort_columns.clone(),
+            group_columns: request.group_columns.clone(),
+            hide_columns: request.hide_columns.clone(),
+            show_columns: request.show_columns.clone(),
+            color_column: request.color_column.clone(),
+            fill_column: request.fill_column.clone(),
+        };
+        ViewRepo::upsert(&conn, &alias, "tasks", &options)?;
         println!("Saved view '{}'.", alias);
     }
+
+    if request.color_column.is_none() {
+        request.color_column = config.list_color().map(str::to_lowercase);
+    }
     
+    // A single queue=<name> filter shows positions in that queue instead of the active one
+    let list_queue = request.filter_tokens.iter()
+        .filter_map(|t| t.strip_prefix("queue="))
+        .find(|name| !name.contains(',') && *name != "any" && *name != "none")
+        .map(|name| name.to_string());
+
     // Parse filter if provided
     let tasks = if request.filter_tokens.is_empty() {
         TaskRepo::list_all(&conn)
@@ -3060,9 +4709,11 @@ fn handle_task_list(filter_args: Vec<String>, json: bool, relative: bool, full:
             sort_columns: request.sort_columns,
             group_columns: request.group_columns,
             hide_columns: request.hide_columns,
+            show_columns: request.show_columns,
             color_column: request.color_column,
             fill_column: request.fill_column,
             full_width: full,
+            queue: list_queue,
         };
         let table = format_task_list_table(&conn, &tasks, &options)?;
         print!("{}", table);
@@ -3316,11 +4967,16 @@ fn modify_single_task(conn: &Connection, task_id: i64, args: &[String], auto_cre
         None // Don't change
     };
 
+    let depends = match &parsed.depends {
+        Some(spec) => Some(parse_depends(conn, Some(task_id), spec)?),
+        None => None,
+    };
+
     // Separate UDAs to add and remove
     let mut udas_to_add = HashMap::new();
     let mut udas_to_remove = Vec::new();
 
-    for (key, value) in &parsed.udas {
+    for (key, value) in &normalize_udas(conn, &parsed.udas)? {
         if value == "none" {
             udas_to_remove.push(key.clone());
         } else {
@@ -3347,6 +5003,9 @@ fn modify_single_task(conn: &Connection, task_id: i64, args: &[String], auto_cre
         parent_id,
     )
     .with_context(|| format!("Failed to modify task {}", task_id))?;
+    if let Some(depends) = &depends {
+        apply_depends(conn, task_id, depends)?;
+    }
     
     println!("Modified task {}", task_id);
     
@@ -3364,9 +5023,23 @@ fn modify_single_task(conn: &Connection, task_id: i64, args: &[String], auto_cre
 }
 
 
-fn handle_task_enqueue(task_id_str: String) -> Result<()> {
+fn handle_task_enqueue(task_id_str: String, args: Vec<String>) -> Result<()> {
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
+
+    // Optional queue=<name>
+    let mut queue_name: Option<String> = None;
+    for arg in &args {
+        match arg.strip_prefix("queue=") {
+            Some(name) => {
+                if let Err(e) = validate_queue_name(name) {
+                    user_error(&e);
+                }
+                queue_name = Some(name.to_string());
+            }
+            None => user_error(&format!("Unexpected argument '{}'. Use queue=<name> to choose a queue.", arg)),
+        }
+    }
     
     // Parse comma-separated list of IDs (preserves order)
     let task_ids = match parse_task_id_list(&task_id_str) {
@@ -3413,40 +5086,237 @@ fn handle_task_enqueue(task_id_str: String) -> Result<()> {
     }
     
     // Enqueue all tasks in order
-    let stack = StackRepo::get_or_create_default(&conn)?;
+    let stack = match &queue_name {
+        Some(name) => StackRepo::get_or_create(&conn, name)?,
+        None => StackRepo::get_active(&conn)?,
+    };
     let stack_id = stack.id.unwrap();
     
     for task_id in eligible_ids {
         StackRepo::enqueue(&conn, stack_id, task_id)
             .context(format!("Failed to enqueue task {}", task_id))?;
-        println!("Enqueued task {}", task_id);
+        if queue_name.is_some() {
+            println!("Enqueued task {} to queue '{}'", task_id, stack.name);
+        } else {
+            println!("Enqueued task {}", task_id);
+        }
     }
     
     Ok(())
 }
 
-/// Handle `tatl on [<task_id>] [<time>]` - Start timing
-fn handle_on(task_id_opt: Option<String>, mut time_args: Vec<String>) -> Result<()> {
+/// Handle `tatl on [<task_id>] [<time>] [--pomodoro[=<work>/<break>]]` - Start timing
+fn handle_on(task_id_opt: Option<String>, mut time_args: Vec<String>, pomodoro: Option<String>) -> Result<()> {
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
+    let pomodoro = parse_pomodoro_arg(pomodoro.or_else(|| take_pomodoro_flag(&mut time_args)));
     
     if let Some(task_id_str) = task_id_opt {
         // Check if it's a valid task ID (numeric) or if it's actually a time expression
         if let Ok(_task_id) = task_id_str.parse::<i64>() {
             // Valid task ID - use it
-            handle_task_on(task_id_str, time_args)
+            handle_task_on(task_id_str, time_args, pomodoro)
         } else {
             // Not a valid task ID - treat as time expression, use queue[0]
             time_args.insert(0, task_id_str);
-            handle_on_queue_top(&conn, time_args)
+            handle_on_queue_top(&conn, time_args, pomodoro)
         }
     } else {
         // Use queue[0]
-        handle_on_queue_top(&conn, time_args)
+        handle_on_queue_top(&conn, time_args, pomodoro)
+    }
+}
+
+/// Remove `--pomodoro[=<spec>]` from `on` arguments that clap did not parse:
+/// time arguments after which it was given, or the arguments of a pipe stage
+fn take_pomodoro_flag(args: &mut Vec<String>) -> Option<String> {
+    let pos = args.iter().position(|arg| arg == "--pomodoro" || arg.starts_with("--pomodoro="))?;
+    let arg = args.remove(pos);
+    Some(arg.trim_start_matches("--pomodoro").trim_start_matches('=').to_string())
+}
+
+/// Parse the value of `--pomodoro` (empty for the configured defaults)
+fn parse_pomodoro_arg(spec: Option<String>) -> Option<Pomodoro> {
+    spec.map(|spec| match pomodoro::parse_pomodoro(&spec, &Config::current()) {
+        Ok(pomodoro) => pomodoro,
+        Err(e) => user_error(&e),
+    })
+}
+
+/// Mark a newly started session as a pomodoro work interval
+fn start_pomodoro(conn: &Connection, session: &crate::models::Session, pomodoro: &Pomodoro) -> Result<()> {
+    SessionRepo::set_pomodoro(conn, session.id.expect("new session has an id"), pomodoro)
+        .context("Failed to record pomodoro")
+}
+
+fn print_pomodoro_started(session: &crate::models::Session, pomodoro: &Pomodoro) {
+    println!("Pomodoro: {} work until {}, then {} break",
+        format_duration_human(pomodoro.work_secs),
+        format_time(session.start_ts + pomodoro.work_secs),
+        format_duration_human(pomodoro.break_secs));
+}
+
+/// End the running session at the end of its pomodoro work interval, once that is over.
+/// Only commands that change the timer (on, timer) call this; off caps the session
+/// through `capped_session_end`, and status and report leave the database alone.
+fn end_finished_pomodoro(conn: &Connection) -> Result<()> {
+    let Some(session) = SessionRepo::get_open(conn)? else {
+        return Ok(());
+    };
+    if let Some(pomodoro) = SessionRepo::get_pomodoro(conn, session.id.unwrap_or_default())? {
+        let work_end = session.start_ts + pomodoro.work_secs;
+        if work_end <= chrono::Utc::now().timestamp() {
+            SessionRepo::close_open(conn, work_end)
+                .context("Failed to close session")?;
+        }
+    }
+    Ok(())
+}
+
+/// The latest pomodoro session and where it is at `now`, unless another
+/// session has been started since
+fn current_pomodoro(conn: &Connection, now: i64) -> Result<Option<(crate::models::Session, Pomodoro, Phase)>> {
+    let Some((session, pomodoro)) = SessionRepo::get_last_pomodoro(conn)? else {
+        return Ok(None);
+    };
+    let Some(phase) = pomodoro::phase_at(&session, &pomodoro, now) else {
+        return Ok(None);
+    };
+    let started_since = SessionRepo::get_open(conn)?
+        .into_iter()
+        .chain(SessionRepo::get_recent_closed_after(conn, session.start_ts)?)
+        .any(|other| other.id != session.id && other.start_ts >= session.start_ts);
+    Ok((!started_since).then_some((session, pomodoro, phase)))
+}
+
+/// Handle `tatl status` - Summary, running session and pomodoro countdown
+fn handle_status() -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    let now = chrono::Utc::now().timestamp();
+    let pomodoro = current_pomodoro(&conn, now)?;
+
+    println!("{}", crate::cli::status::compute_root_status(&conn)?);
+    if let Some(session) = SessionRepo::get_open(&conn)? {
+        let desc = TaskRepo::get_by_id(&conn, session.task_id)?
+            .map(|task| task.description)
+            .unwrap_or_default();
+        match &pomodoro {
+            // The session is ended at the interval by the next on, off or timer
+            Some((_, _, Phase::Break { start, .. })) => println!("Task {}: {} (pomodoro work interval ended at {})",
+                session.task_id, desc, format_time(*start)),
+            _ => {
+                println!("Timing task {}: {} (since {}, {})",
+                    session.task_id, desc, format_time(session.start_ts), format_duration_human(now - session.start_ts));
+                warn_if_session_too_long(&session, now);
+            }
+        }
+    }
+    if let Some((_, _, phase)) = &pomodoro {
+        println!("{}", phase.line(now));
+    }
+    Ok(())
+}
+
+/// Handle `tatl timer [--once]` - Count down pomodoro intervals in the foreground
+fn handle_timer(once: bool) -> Result<()> {
+    use std::io::{IsTerminal, Write};
+
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    // Redraw in place on a terminal; otherwise print each interval once
+    let interactive = std::io::stdout().is_terminal();
+    let mut shown: Option<(crate::models::Session, Pomodoro, Phase)> = None;
+
+    loop {
+        end_finished_pomodoro(&conn)?;
+        let now = chrono::Utc::now().timestamp();
+        let mut current = current_pomodoro(&conn, now)?;
+
+        if let Some((session, pomodoro, phase)) = &shown {
+            let unchanged = current.as_ref().is_some_and(|(s, _, p)| s.id == session.id && p == phase);
+            if !unchanged && now >= phase.end() {
+                if interactive {
+                    println!();
+                }
+                match phase {
+                    Phase::Work { end, .. } => println!("Work interval over at {}. Break until {}.",
+                        format_time(*end), format_time(end + pomodoro.break_secs)),
+                    Phase::Break { .. } => println!("Break over."),
+                }
+                if let Some(hook) = Config::current().pomodoro_hook() {
+                    pomodoro::run_hook(hook, phase, session);
+                }
+                if once {
+                    return Ok(());
+                }
+                // Carry on with the next work interval unless something else was started
+                if matches!(phase, Phase::Break { .. }) && current.is_none() && SessionRepo::get_open(&conn)?.is_none() {
+                    let next = SessionRepo::create(&conn, session.task_id, now)
+                        .context("Failed to start session")?;
+                    start_pomodoro(&conn, &next, pomodoro)?;
+                    println!("Started timing task {} ({})", session.task_id, format_time(now));
+                    print_pomodoro_started(&next, pomodoro);
+                    current = current_pomodoro(&conn, now)?;
+                }
+            } else if !unchanged && interactive {
+                println!();
+            }
+        }
+
+        let Some((session, pomodoro, phase)) = current else {
+            if shown.is_none() {
+                user_error("No pomodoro is running. Start one with 'tatl on --pomodoro'.");
+            }
+            println!("Pomodoro stopped.");
+            return Ok(());
+        };
+        let desc = TaskRepo::get_by_id(&conn, session.task_id)?
+            .map(|task| task.description)
+            .unwrap_or_default();
+        let line = format!("{}  task {}: {}", phase.line(now), session.task_id, desc);
+        if interactive {
+            print!("\r\x1b[2K{}", line);
+            std::io::stdout().flush()?;
+        } else if shown.as_ref().map(|(_, _, p)| p) != Some(&phase) {
+            println!("{}", line);
+        }
+        shown = Some((session, pomodoro, phase));
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+}
+
+/// Split `off` arguments into the end time and session note/tags.
+/// The longest leading run of arguments that parses as a time is the end time;
+/// any text after it is the note (unless note= is given).
+fn parse_off_args(args: Vec<String>) -> Result<(Option<i64>, SessionAttributes)> {
+    let mut attributes = SessionAttributes::default();
+    let mut rest = Vec::new();
+    for arg in args {
+        if !attributes.parse_arg(&arg, false) {
+            rest.push(arg);
+        }
+    }
+
+    let time_len = (1..=rest.len()).rev()
+        .find(|&len| parse_date_expr(&rest[..len].join(" ")).is_ok())
+        .unwrap_or(0);
+    let end_ts = if time_len > 0 {
+        Some(parse_date_expr(&rest[..time_len].join(" ")).context("Invalid end time expression")?)
+    } else {
+        None
+    };
+    let text = rest[time_len..].join(" ");
+    if !text.trim().is_empty() {
+        if attributes.note.is_some() {
+            user_error(&format!("Unexpected argument '{}': the note is already set with note=", text));
+        }
+        attributes.note = Some(Some(text.trim().to_string()));
     }
+    Ok((end_ts, attributes))
 }
 
-/// Handle `tatl off [<time>]` - Stop timing
+/// Handle `tatl off [<time>] [<note>] [+tag...]` - Stop timing
 fn handle_off(time_args: Vec<String>) -> Result<()> {
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
@@ -3459,38 +5329,26 @@ fn handle_off(time_args: Vec<String>) -> Result<()> {
     }
     let session = session_opt.expect("checked above");
     
-    // Parse end time (defaults to "now")
-    let mut end_ts = if time_args.is_empty() {
-        chrono::Utc::now().timestamp()
-    } else {
-        let end_expr = time_args.join(" ");
-        parse_date_expr(&end_expr)
-            .context("Invalid end time expression")?
-    };
+    // Parse end time (defaults to "now"), note and tags
+    let (end_opt, attributes) = parse_off_args(time_args)?;
+    let (end_ts, capped) = close_session_capped(&conn, &session, end_opt.unwrap_or_else(|| chrono::Utc::now().timestamp()))?;
+    print_capped(capped);
 
-    // Ensure monotonicity (end must be after start). This can happen in tests (or if the user
-    // ends a session at a time-only expression that resolves before the session start).
-    if end_ts <= session.start_ts {
-        end_ts = session.start_ts + 1;
+    let task_id = session.task_id;
+    // Get task description for better message
+    let task = TaskRepo::get_by_id(&conn, task_id)?;
+    let desc = task.as_ref().map(|t| t.description.as_str()).unwrap_or("");
+    let duration = end_ts - session.start_ts;
+    println!("Stopped timing task {}: {} ({}, {})", task_id, desc, format_time(end_ts), format_duration_human(duration));
+    if !attributes.is_empty() {
+        let session_id = session.id.expect("open session has an id");
+        attributes.apply(&conn, session_id)?;
+        println!("Session {}: {}", session_id, attributes.describe());
     }
-    
-    // Close session
-    let closed = SessionRepo::close_open(&conn, end_ts)
-        .context("Failed to close session")?;
-    
-    if let Some(session) = closed {
-        let task_id = session.task_id;
-        // Get task description for better message
-        let task = TaskRepo::get_by_id(&conn, task_id)?;
-        let desc = task.as_ref().map(|t| t.description.as_str()).unwrap_or("");
-        let duration = end_ts - session.start_ts;
-        println!("Stopped timing task {}: {} ({}, {})", task_id, desc, format_time(end_ts), format_duration_human(duration));
 
-        // Invariant 3: external-waiting tasks are removed from queue when timer stops
-        if ExternalRepo::has_active_externals(&conn, task_id)? {
-            let stack = StackRepo::get_or_create_default(&conn)?;
-            StackRepo::remove_task(&conn, stack.id.unwrap(), task_id)?;
-        }
+    // Invariant 3: external-waiting tasks are removed from queue when timer stops
+    if ExternalRepo::has_active_externals(&conn, task_id)? {
+        StackRepo::remove_from_all(&conn, task_id)?;
     }
 
     Ok(())
@@ -3584,7 +5442,7 @@ fn handle_offon_current_session(conn: &Connection, time_args: Vec<String>) -> Re
     let resume_ts = start_ts_opt.unwrap_or_else(|| chrono::Utc::now().timestamp());
     
     // Get queue[0] for resume task (defaults to same task)
-    let stack = StackRepo::get_or_create_default(&tx)?;
+    let stack = StackRepo::get_active(&tx)?;
     let items = StackRepo::get_items(&tx, stack.id.unwrap())?;
     
     let resume_task_id = if items.is_empty() {
@@ -3689,7 +5547,7 @@ fn handle_offon_history(conn: &Connection, time_args: Vec<String>, yes: bool) ->
     Ok(())
 }
 
-/// Handle `tatl onoff <start>..<end> [<task_id>] [note:<text>]` - Add historical session
+/// Handle `tatl onoff <start>..<end> [<task_id>] [note=<text>] [+tag...]` - Add historical session
 fn handle_onoff(args: Vec<String>, mut yes: bool) -> Result<()> {
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
@@ -3712,14 +5570,14 @@ fn handle_onoff(args: Vec<String>, mut yes: bool) -> Result<()> {
         .collect();
     
     // Parse arguments
-    let (start_ts, end_ts, task_id_opt, note_opt) = parse_onoff_args(&filtered_args)?;
+    let OnoffArgs { start_ts, end_ts, task_id: task_id_opt, attributes, annotation } = parse_onoff_args(&filtered_args)?;
     
     // Determine task (task_id or queue[0])
     let task_id = if let Some(id) = task_id_opt {
         id
     } else {
         // Get queue[0]
-        let stack = StackRepo::get_or_create_default(&conn)?;
+        let stack = StackRepo::get_active(&conn)?;
         let items = StackRepo::get_items(&conn, stack.id.unwrap())?;
         
         if items.is_empty() {
@@ -3787,9 +5645,10 @@ fn handle_onoff(args: Vec<String>, mut yes: bool) -> Result<()> {
         // Create the new session
         let session = SessionRepo::create_closed(&tx, task_id, start_ts, end_ts)
             .context("Failed to create session")?;
+        attributes.apply(&tx, session.id.expect("new session has an id"))?;
         
         // Add annotation if note provided
-        if let Some(note_text) = note_opt {
+        if let Some(note_text) = annotation {
             if !note_text.trim().is_empty() {
                 AnnotationRepo::create(&tx, task_id, note_text, session.id)
                     .context("Failed to create annotation")?;
@@ -3804,9 +5663,10 @@ fn handle_onoff(args: Vec<String>, mut yes: bool) -> Result<()> {
         // Simple mode: just add the session
         let session = SessionRepo::create_closed(&conn, task_id, start_ts, end_ts)
             .context("Failed to create session")?;
+        attributes.apply(&conn, session.id.expect("new session has an id"))?;
         
         // Add annotation if note provided
-        if let Some(note_text) = note_opt {
+        if let Some(note_text) = annotation {
             if !note_text.trim().is_empty() {
                 AnnotationRepo::create(&conn, task_id, note_text, session.id)
                     .context("Failed to create annotation")?;
@@ -3845,39 +5705,9 @@ fn parse_offon_time_args(arg_str: &str) -> Result<(i64, Option<i64>)> {
     }
 }
 
-/// Detect simple time-only expressions (no explicit date or relative keywords)
-fn is_time_only_expression(expr: &str) -> bool {
-    let expr = expr.trim();
-    if expr.is_empty() {
-        return false;
-    }
-    
-    let lower = expr.to_lowercase();
-    let has_date_hint = lower.contains('-')
-        || lower.contains('t')
-        || lower.contains("today")
-        || lower.contains("tomorrow")
-        || lower.contains("eod")
-        || lower.contains("eow")
-        || lower.contains("eom")
-        || lower.starts_with('+')
-        || lower.starts_with("in ")
-        || lower.contains("next ");
-    
-    if has_date_hint {
-        return false;
-    }
-    
-    lower.contains(':')
-        || lower.ends_with("am")
-        || lower.ends_with("pm")
-        || lower == "noon"
-        || lower == "midnight"
-}
-
 /// Align a time-only timestamp to be after the provided anchor (by adding 24h if needed)
 fn align_time_only_to_anchor(ts: i64, expr: &str, anchor_ts: i64) -> i64 {
-    if !is_time_only_expression(expr) {
+    if parse_time_of_day(expr.trim()).is_none() {
         return ts;
     }
     
@@ -3888,16 +5718,31 @@ fn align_time_only_to_anchor(ts: i64, expr: &str, anchor_ts: i64) -> i64 {
     aligned
 }
 
-/// Parse onoff arguments: <start>..<end> [<task_id>] [note:<text>]
-fn parse_onoff_args(args: &[String]) -> Result<(i64, i64, Option<i64>, Option<String>)> {
+/// Parsed `tatl onoff` arguments
+struct OnoffArgs {
+    start_ts: i64,
+    end_ts: i64,
+    task_id: Option<i64>,
+    attributes: SessionAttributes,
+    /// `note:<text>`: task annotation linked to the new session
+    annotation: Option<String>,
+}
+
+/// Parse onoff arguments: <start>..<end> [<task_id>] [note=<text>] [+tag] [note:<text>]
+fn parse_onoff_args(args: &[String]) -> Result<OnoffArgs> {
     let mut start_ts: Option<i64> = None;
     let mut end_ts: Option<i64> = None;
     let mut task_id: Option<i64> = None;
-    let mut note: Option<String> = None;
+    let mut attributes = SessionAttributes::default();
+    let mut annotation: Option<String> = None;
 
     for arg in args {
-        if arg.starts_with("note:") {
-            note = Some(arg[5..].to_string());
+        if let Some(text) = arg.strip_prefix("note:") {
+            annotation = Some(text.to_string());
+        } else if arg.starts_with("note=") || arg.starts_with('+') {
+            if !attributes.parse_arg(arg, false) {
+                user_error(&format!("Invalid tag '{}'. Tags can only contain letters, numbers, underscores, hyphens, and dots.", arg));
+            }
         } else if arg.contains("..") {
             // Interval - parse both times coherently on the same day
             let sep_pos = arg.find("..").unwrap();
@@ -3916,10 +5761,10 @@ fn parse_onoff_args(args: &[String]) -> Result<(i64, i64, Option<i64>, Option<St
         }
     }
 
-    let start = start_ts.ok_or_else(|| anyhow::anyhow!("Interval required (use <start>..<end>)"))?;
-    let end = end_ts.ok_or_else(|| anyhow::anyhow!("Interval required (use <start>..<end>)"))?;
+    let start_ts = start_ts.ok_or_else(|| anyhow::anyhow!("Interval required (use <start>..<end>)"))?;
+    let end_ts = end_ts.ok_or_else(|| anyhow::anyhow!("Interval required (use <start>..<end>)"))?;
 
-    Ok((start, end, task_id, note))
+    Ok(OnoffArgs { start_ts, end_ts, task_id, attributes, annotation })
 }
 
 /// Parse a time interval ensuring both times are interpreted on the same day.
@@ -3929,19 +5774,11 @@ fn parse_time_interval(start_expr: &str, end_expr: &str) -> Result<(i64, i64)> {
     use chrono::Duration;
 
     // Check if both are time-only expressions (HH:MM format or keywords like "noon")
-    let start_time_only = is_time_only_expr(start_expr);
-    let end_time_only = is_time_only_expr(end_expr);
-
-    if start_time_only && end_time_only {
+    if let (Some(start_time), Some(end_time)) = (parse_time_of_day(start_expr), parse_time_of_day(end_expr)) {
         // Both are time-only: interpret on the same day
         let now = Local::now();
         let today = now.date_naive();
 
-        let start_time = parse_time_expr(start_expr)
-            .context("Invalid start time")?;
-        let end_time = parse_time_expr(end_expr)
-            .context("Invalid end time")?;
-
         // Try today first
         let start_today = Local.from_local_datetime(&today.and_time(start_time))
             .earliest()
@@ -3985,68 +5822,6 @@ fn parse_time_interval(start_expr: &str, end_expr: &str) -> Result<(i64, i64)> {
     Ok((start, end))
 }
 
-/// Check if an expression is a time-only format (no date component)
-fn is_time_only_expr(expr: &str) -> bool {
-    let expr_lower = expr.to_lowercase();
-
-    // Keywords
-    if matches!(expr_lower.as_str(), "noon" | "midnight") {
-        return true;
-    }
-
-    // HH:MM format
-    if expr.contains(':') && !expr.contains('-') && !expr.contains('T') {
-        return true;
-    }
-
-    // 12-hour format: 9am, 2pm, etc.
-    if expr_lower.ends_with("am") || expr_lower.ends_with("pm") {
-        return true;
-    }
-
-    false
-}
-
-/// Parse a time-only expression into a NaiveTime
-fn parse_time_expr(expr: &str) -> Result<chrono::NaiveTime> {
-    use chrono::NaiveTime;
-
-    let expr_lower = expr.to_lowercase();
-
-    match expr_lower.as_str() {
-        "noon" => return Ok(NaiveTime::from_hms_opt(12, 0, 0).unwrap()),
-        "midnight" => return Ok(NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
-        _ => {}
-    }
-
-    // Try HH:MM
-    if let Ok(time) = NaiveTime::parse_from_str(expr, "%H:%M") {
-        return Ok(time);
-    }
-
-    // Try 12-hour format
-    let (hour_str, is_pm) = if expr_lower.ends_with("pm") {
-        (&expr[..expr.len()-2], true)
-    } else if expr_lower.ends_with("am") {
-        (&expr[..expr.len()-2], false)
-    } else {
-        anyhow::bail!("Invalid time format: {}", expr);
-    };
-
-    let hour: u32 = hour_str.trim().parse()
-        .context("Invalid hour")?;
-
-    let hour_24 = match (hour, is_pm) {
-        (12, false) => 0,      // 12am = midnight
-        (12, true) => 12,      // 12pm = noon
-        (h, false) => h,       // 1am-11am
-        (h, true) => h + 12,   // 1pm-11pm
-    };
-
-    NaiveTime::from_hms_opt(hour_24, 0, 0)
-        .ok_or_else(|| anyhow::anyhow!("Invalid time: {}", expr))
-}
-
 /// Find all sessions overlapping with the given interval
 fn find_overlapping_sessions(conn: &Connection, start: i64, end: i64) -> Result<Vec<crate::models::Session>> {
     let all_sessions = SessionRepo::list_all(conn)?;
@@ -4065,7 +5840,7 @@ fn find_overlapping_sessions(conn: &Connection, start: i64, end: i64) -> Result<
 }
 
 /// Modify a session to remove the specified interval
-fn modify_session_for_removal(conn: &Connection, session: &crate::models::Session, remove_start: i64, remove_end: i64) -> Result<()> {
+pub(crate) fn modify_session_for_removal(conn: &Connection, session: &crate::models::Session, remove_start: i64, remove_end: i64) -> Result<()> {
     let s_start = session.start_ts;
     let is_open = session.end_ts.is_none();
     let s_end = session.end_ts.unwrap_or(i64::MAX);
@@ -4080,12 +5855,13 @@ fn modify_session_for_removal(conn: &Connection, session: &crate::models::Sessio
         // First part: s_start to remove_start (always closed)
         SessionRepo::update_times(conn, session_id, s_start, Some(remove_start))?;
         // Second part: remove_end to s_end
-        if is_open {
+        let second = if is_open {
             // Original was open - second part should remain open
-            SessionRepo::create(conn, session.task_id, remove_end)?;
-            } else {
-            SessionRepo::create_closed(conn, session.task_id, remove_end, s_end)?;
-        }
+            SessionRepo::create(conn, session.task_id, remove_end)?
+        } else {
+            SessionRepo::create_closed(conn, session.task_id, remove_end, s_end)?
+        };
+        SessionRepo::copy_attributes(conn, session_id, second.id.expect("new session has an id"))?;
         
     } else if remove_start <= s_start && remove_end < s_end {
         // Overlaps start: truncate at remove_end
@@ -4099,16 +5875,71 @@ fn modify_session_for_removal(conn: &Connection, session: &crate::models::Sessio
     } else if remove_start == remove_end {
         // Single time point: split at that point
         SessionRepo::update_times(conn, session_id, s_start, Some(remove_start))?;
-        if is_open {
+        let second = if is_open {
             // Original was open - second part should remain open
-            SessionRepo::create(conn, session.task_id, remove_start)?;
-                } else {
-            SessionRepo::create_closed(conn, session.task_id, remove_start, s_end)?;
-                }
-            }
-    
-            Ok(())
+            SessionRepo::create(conn, session.task_id, remove_start)?
+        } else {
+            SessionRepo::create_closed(conn, session.task_id, remove_start, s_end)?
+        };
+        SessionRepo::copy_attributes(conn, session_id, second.id.expect("new session has an id"))?;
+    }
+
+    Ok(())
+}
+
+/// End time for stopping a running session: the end of its pomodoro work
+/// interval if that is over, otherwise applying `session.auto_cap`.
+/// When the end is moved earlier, also returns a message saying so, for the
+/// caller to print once the session is closed.
+fn capped_session_end(conn: &Connection, session: &crate::models::Session, end_ts: i64) -> Result<(i64, Option<String>)> {
+    if let Some(pomodoro) = SessionRepo::get_pomodoro(conn, session.id.unwrap_or_default())? {
+        let work_end = session.start_ts + pomodoro.work_secs;
+        if work_end < end_ts {
+            let message = format!("Ended session {} at {}, the end of its pomodoro work interval.",
+                session.id.unwrap_or_default(), format_time(work_end));
+            return Ok((work_end, Some(message)));
+        }
+    }
+    let limits = SessionLimits::current();
+    match limits.capped_end(session.start_ts, end_ts) {
+        Some(capped) => {
+            let message = format!("Capped session {} at {} (session.auto_cap={}).",
+                session.id.unwrap_or_default(), format_time(capped), Config::current().session_auto_cap());
+            Ok((capped, Some(message)))
         }
+        None => Ok((end_ts, None)),
+    }
+}
+
+/// Close the running session at `end_ts`, or where `capped_session_end` moves it,
+/// keeping the end after the start (a time-only end can resolve before it).
+/// Returns the end time used and the capping message to print once committed.
+fn close_session_capped(conn: &Connection, session: &crate::models::Session, end_ts: i64) -> Result<(i64, Option<String>)> {
+    let (capped_end_ts, capped) = capped_session_end(conn, session, end_ts)?;
+    let end_ts = std::cmp::max(capped_end_ts, session.start_ts + 1);
+    SessionRepo::close_open(conn, end_ts)
+        .context("Failed to close session")?;
+    Ok((end_ts, capped))
+}
+
+/// Print the message from `close_session_capped`, if the end was capped
+fn print_capped(message: Option<String>) {
+    if let Some(message) = message {
+        println!("{}", message);
+    }
+}
+
+/// Warn when a running session has run longer than `session.max_duration`
+/// and stopping it now would not be capped
+fn warn_if_session_too_long(session: &crate::models::Session, now: i64) {
+    let limits = SessionLimits::current();
+    let running = now - session.start_ts;
+    if limits.is_too_long(running) && limits.capped_end(session.start_ts, now).is_none() {
+        eprintln!("Warning: session {} has been running for {} (since {}), longer than session.max_duration.",
+            session.id.unwrap_or_default(), format_duration_human(running), format_datetime(session.start_ts));
+        eprintln!("  If you forgot to stop it, use 'tatl offon <time>' or 'tatl sessions audit --fix'.");
+    }
+}
 
 /// Format a timestamp for display
 fn format_time(ts: i64) -> String {
@@ -4133,7 +5964,7 @@ fn format_datetime(ts: i64) -> String {
     use chrono::TimeZone;
     chrono::Local.timestamp_opt(ts, 0)
         .single()
-        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .map(|dt| dt.format(&format!("{} %H:%M", Config::current().date_format())).to_string())
         .unwrap_or_else(|| ts.to_string())
 }
 
@@ -4193,7 +6024,7 @@ fn handle_dequeue(task_id_opt: Option<String>) -> Result<()> {
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
 
-    let stack = StackRepo::get_or_create_default(&conn)?;
+    let stack = StackRepo::get_active(&conn)?;
     let stack_id = stack.id.unwrap();
     let items = StackRepo::get_items(&conn, stack_id)?;
 
@@ -4238,10 +6069,38 @@ fn handle_dequeue(task_id_opt: Option<String>) -> Result<()> {
     Ok(())
 }
 
+/// Report tasks that a just-closed or cancelled task was the last open blocker of
+fn print_unblocked(conn: &Connection, task_id: i64) -> Result<()> {
+    for id in DependencyRepo::get_unblocked_dependents(conn, task_id)? {
+        if let Some(task) = TaskRepo::get_by_id(conn, id)? {
+            println!("Unblocked task {}: {}", id, task.description);
+        }
+    }
+    Ok(())
+}
+
+/// Warn when starting a task that still depends on open tasks. Timing is not
+/// refused: the dependency may be soft, or the work on it already underway.
+fn warn_if_blocked(conn: &Connection, task_id: i64) -> Result<()> {
+    let blockers = DependencyRepo::get_open_blockers(conn, task_id)?;
+    if blockers.is_empty() {
+        return Ok(());
+    }
+    let mut names = Vec::new();
+    for id in blockers {
+        let description = TaskRepo::get_by_id(conn, id)?.map(|t| t.description).unwrap_or_default();
+        names.push(format!("{} ({})", id, description));
+    }
+    eprintln!("Warning: Task {} is blocked by open task(s): {}", task_id, names.join(", "));
+    Ok(())
+}
+
 /// Start timing queue[0]
-fn handle_on_queue_top(conn: &Connection, args: Vec<String>) -> Result<()> {
+fn handle_on_queue_top(conn: &Connection, args: Vec<String>, pomodoro: Option<Pomodoro>) -> Result<()> {
+    end_finished_pomodoro(conn)?;
+
     // Get stack and check if it's empty
-    let stack = StackRepo::get_or_create_default(conn)?;
+    let stack = StackRepo::get_active(conn)?;
     let stack_id = stack.id.unwrap();
     let items = StackRepo::get_items(conn, stack_id)?;
     
@@ -4262,6 +6121,7 @@ fn handle_on_queue_top(conn: &Connection, args: Vec<String>) -> Result<()> {
             task.status.as_str()
         ));
     }
+    warn_if_blocked(conn, task_id)?;
     
     // Parse arguments - check for interval syntax (start..end)
     let arg_str = args.join(" ");
@@ -4279,6 +6139,9 @@ fn handle_on_queue_top(conn: &Connection, args: Vec<String>) -> Result<()> {
         
         let end_ts = parse_date_expr(end_expr)
             .context("Invalid end time expression")?;
+        if pomodoro.is_some() {
+            user_error("--pomodoro starts a running session and cannot be used with an interval.");
+        }
         
         // Check for overlap prevention
         check_and_amend_overlaps(conn, start_ts)?;
@@ -4292,7 +6155,8 @@ fn handle_on_queue_top(conn: &Connection, args: Vec<String>) -> Result<()> {
     } else {
         // Single start time or "now" (creates open session)
         // Check if session is already running (only for open sessions)
-        if let Some(_) = SessionRepo::get_open(conn)? {
+        if let Some(session) = SessionRepo::get_open(conn)? {
+            warn_if_session_too_long(&session, chrono::Utc::now().timestamp());
             user_error("A session is already running. Please use 'tatl off' first.");
         }
         
@@ -4307,13 +6171,19 @@ fn handle_on_queue_top(conn: &Connection, args: Vec<String>) -> Result<()> {
         check_and_amend_overlaps(conn, start_ts)?;
         
         // Create open session
-        SessionRepo::create(conn, task_id, start_ts)
+        let session = SessionRepo::create(conn, task_id, start_ts)
             .context("Failed to start session")?;
+        if let Some(pomodoro) = &pomodoro {
+            start_pomodoro(conn, &session, pomodoro)?;
+        }
 
         // Get task description for better message
         let task = TaskRepo::get_by_id(conn, task_id)?;
         let desc = task.as_ref().map(|t| t.description.as_str()).unwrap_or("");
         println!("Started timing task {}: {} ({})", task_id, desc, format_time(start_ts));
+        if let Some(pomodoro) = &pomodoro {
+            print_pomodoro_started(&session, pomodoro);
+        }
         let alloc = task.as_ref().and_then(|t| t.alloc_secs);
         let context = crate::cli::output::format_on_context(conn, task_id, alloc)?;
         if !context.is_empty() {
@@ -4325,9 +6195,10 @@ fn handle_on_queue_top(conn: &Connection, args: Vec<String>) -> Result<()> {
 }
 
 /// Start timing a specific task (pushes to queue[0] and starts timing)
-fn handle_task_on(task_id_str: String, args: Vec<String>) -> Result<()> {
+fn handle_task_on(task_id_str: String, args: Vec<String>, pomodoro: Option<Pomodoro>) -> Result<()> {
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
+    end_finished_pomodoro(&conn)?;
     
     // Parse task ID
     let task_id = match validate_task_id(&task_id_str) {
@@ -4347,6 +6218,7 @@ fn handle_task_on(task_id_str: String, args: Vec<String>) -> Result<()> {
             task.status.as_str()
         ));
     }
+    warn_if_blocked(&conn, task_id)?;
     let task_desc = task.description.clone();
     
     // Parse arguments - check for interval syntax (start..end)
@@ -4365,6 +6237,9 @@ fn handle_task_on(task_id_str: String, args: Vec<String>) -> Result<()> {
         
         let end_ts = parse_date_expr(end_expr)
             .context("Invalid end time expression")?;
+        if pomodoro.is_some() {
+            user_error("--pomodoro starts a running session and cannot be used with an interval.");
+        }
         
         (start_ts, Some(end_ts))
     } else {
@@ -4405,9 +6280,10 @@ fn handle_task_on(task_id_str: String, args: Vec<String>) -> Result<()> {
     };
     
     // If session is running, close it at the effective start time
-    if existing_session.is_some() {
-        SessionRepo::close_open(&tx, effective_start_ts)
-            .context("Failed to close existing session")?;
+    let mut capped = None;
+    if let Some(session) = &existing_session {
+        warn_if_session_too_long(session, effective_start_ts);
+        capped = close_session_capped(&tx, session, effective_start_ts)?.1;
     }
     
     // Check for overlap prevention (before creating new session)
@@ -4416,7 +6292,7 @@ fn handle_task_on(task_id_str: String, args: Vec<String>) -> Result<()> {
     check_and_amend_overlaps_transactional(&tx, effective_start_ts)?;
     
     // Push task to stack[0]
-    let stack = StackRepo::get_or_create_default(&tx)?;
+    let stack = StackRepo::get_active(&tx)?;
     StackRepo::push_to_top(&tx, stack.id.unwrap(), task_id)
         .context("Failed to push task to stack")?;
     
@@ -4425,12 +6301,20 @@ fn handle_task_on(task_id_str: String, args: Vec<String>) -> Result<()> {
         SessionRepo::create_closed(&tx, task_id, start_ts, end_ts)
             .context("Failed to create closed session")?;
         tx.commit()?;
+        print_capped(capped);
         println!("Recorded session for task {} ({} to {})", task_id, start_ts, end_ts);
     } else {
-        SessionRepo::create(&tx, task_id, effective_start_ts)
+        let session = SessionRepo::create(&tx, task_id, effective_start_ts)
             .context("Failed to start session")?;
+        if let Some(pomodoro) = &pomodoro {
+            start_pomodoro(&tx, &session, pomodoro)?;
+        }
         tx.commit()?;
+        print_capped(capped);
         println!("Started timing task {}: {} ({})", task_id, task_desc, format_time(effective_start_ts));
+        if let Some(pomodoro) = &pomodoro {
+            print_pomodoro_started(&session, pomodoro);
+        }
         let context = crate::cli::output::format_on_context(&conn, task_id, task.alloc_secs)?;
         if !context.is_empty() {
             print!("{}", context);
@@ -4642,27 +6526,25 @@ fn handle_annotation_add_with_filter(id_or_filter: String, note_args: Vec<String
     Ok(())
 }
 
-fn handle_task_summary(id_or_filter: String) -> Result<()> {
-    let conn = DbConnection::connect()
-        .context("Failed to connect to database")?;
-    
+/// Task IDs for `show`: an ID spec (single ID, range, or list) or a filter
+fn resolve_summary_task_ids(conn: &Connection, id_or_filter: &str) -> Result<Vec<i64>> {
     // Parse task ID spec (single ID, range, or list)
-    let task_ids: Vec<i64> = match parse_task_id_spec(&id_or_filter) {
+    let task_ids: Vec<i64> = match parse_task_id_spec(id_or_filter) {
         Ok(ids) => {
             // Valid ID spec
             ids
         }
         Err(_) => {
             // Try single ID for backward compatibility
-            match validate_task_id(&id_or_filter) {
+            match validate_task_id(id_or_filter) {
                 Ok(id) => vec![id],
                 Err(_) => {
                     // Not an ID - treat as filter
-                    let filter_expr = match parse_filter(vec![id_or_filter.clone()]) {
+                    let filter_expr = match parse_filter(vec![id_or_filter.to_string()]) {
                         Ok(expr) => expr,
                         Err(e) => user_error(&format!("Filter parse error: {}", e)),
                     };
-                    let matching_tasks = filter_tasks(&conn, &filter_expr)
+                    let matching_tasks = filter_tasks(conn, &filter_expr)
                         .context("Failed to filter tasks")?;
                     
                     if matching_tasks.is_empty() {
@@ -4676,9 +6558,42 @@ fn handle_task_summary(id_or_filter: String) -> Result<()> {
             }
         }
     };
+    Ok(task_ids)
+}
+
+/// Print the per-term breakdown of each task's priority score
+fn handle_explain_priority(id_or_filter: String) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    let task_ids = resolve_summary_task_ids(&conn, &id_or_filter)?;
+
+    let mut found_any = false;
+    for task_id in task_ids {
+        let Some(task) = TaskRepo::get_by_id(&conn, task_id)? else {
+            eprintln!("Task {} not found", task_id);
+            continue;
+        };
+        if found_any {
+            println!();
+        }
+        found_any = true;
+        let terms = priority_terms(&task, &conn, UrgencyCoefficients::get())?;
+        print!("{}", format_priority_explanation(&task, &terms));
+    }
+
+    if !found_any {
+        user_error("No tasks found");
+    }
+    Ok(())
+}
+
+fn handle_task_summary(id_or_filter: String) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    let task_ids = resolve_summary_task_ids(&conn, &id_or_filter)?;
     
-    // Get default stack to check positions
-    let stack = StackRepo::get_or_create_default(&conn)?;
+    // Get active stack to check positions
+    let stack = StackRepo::get_active(&conn)?;
     let stack_id = stack.id.unwrap();
     let stack_items = StackRepo::get_items(&conn, stack_id)?;
     let stack_map: std::collections::HashMap<i64, i32> = stack_items.iter()
@@ -4760,6 +6675,37 @@ fn handle_annotation_delete(task_id_str: String, annotation_id_str: String) -> R
     Ok(())
 }
 
+fn handle_annotation_pin(task_id_str: String, annotation_id_str: String, pinned: bool) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+
+    let task_id = match validate_task_id(&task_id_str) {
+        Ok(id) => id,
+        Err(e) => user_error(&e),
+    };
+
+    let annotation_id: i64 = match annotation_id_str.parse() {
+        Ok(id) => id,
+        Err(_) => user_error(&format!("Invalid annotation ID: '{}'. Annotation ID must be a number.", annotation_id_str)),
+    };
+
+    if TaskRepo::get_by_id(&conn, task_id)?.is_none() {
+        user_error(&format!("Task {} not found", task_id));
+    }
+
+    // Verifies the annotation belongs to the task
+    if let Err(e) = AnnotationRepo::set_pinned(&conn, task_id, annotation_id, pinned) {
+        user_error(&e.to_string());
+    }
+
+    if pinned {
+        println!("Pinned annotation {} on task {}", annotation_id, task_id);
+    } else {
+        println!("Unpinned annotation {} on task {}", annotation_id, task_id);
+    }
+    Ok(())
+}
+
 fn handle_task_close(
     mut id_or_filter_opt: Option<String>,
     mut at_opt: Option<String>,
@@ -4854,7 +6800,7 @@ fn handle_task_close(
         }
     } else {
         // No ID provided - use stack[0]
-        let stack = StackRepo::get_or_create_default(&conn)?;
+        let stack = StackRepo::get_active(&conn)?;
         let stack_id = stack.id.unwrap();
         let items = StackRepo::get_items(&conn, stack_id)?;
         
@@ -4927,9 +6873,9 @@ fn handle_task_close(
         if let Some(session) = &open_session {
             if session.task_id == *task_id {
                 // Close the session
-                effective_end_ts = std::cmp::max(end_ts, session.start_ts + 1);
-                SessionRepo::close_open(&conn, effective_end_ts)
-                    .context("Failed to close session")?;
+                let (closed_end_ts, capped) = close_session_capped(&conn, session, end_ts)?;
+                effective_end_ts = closed_end_ts;
+                print_capped(capped);
                 let duration = effective_end_ts - session.start_ts;
                 let task_desc = TaskRepo::get_by_id(&conn, *task_id)?
                     .map(|t| t.description)
@@ -4950,6 +6896,7 @@ fn handle_task_close(
 
         // Invariant 4: terminal lifecycle cleanup - clear active externals
         ExternalRepo::mark_all_returned_for_task(&conn, *task_id)?;
+        print_unblocked(&conn, *task_id)?;
 
         // Handle respawn if task has respawn rule
         if let Some(new_task_id) = respawn_task(&conn, &task, effective_end_ts)? {
@@ -4964,16 +6911,8 @@ fn handle_task_close(
             }
         }
 
-        // Remove from stack
-        let stack = StackRepo::get_or_create_default(&conn)?;
-        let stack_id = stack.id.unwrap();
-        let items = StackRepo::get_items(&conn, stack_id)?;
-
-        // Find the task in the stack and remove it
-        if let Some(item) = items.iter().find(|item| item.task_id == *task_id) {
-            // Drop the task at this position using its ordinal
-            StackRepo::drop(&conn, stack_id, item.ordinal as i32)?;
-        }
+        // Remove from all queues
+        StackRepo::remove_from_all(&conn, *task_id)?;
 
         println!("Closed task {}: {}", task_id, task.description);
     }
@@ -5012,9 +6951,9 @@ fn handle_close_interactive(conn: &Connection, task_ids: &[i64], end_ts: i64) ->
         let mut effective_end_ts = end_ts;
         if let Some(session) = &open_session {
             if session.task_id == *task_id {
-                effective_end_ts = std::cmp::max(end_ts, session.start_ts + 1);
-                SessionRepo::close_open(conn, effective_end_ts)
-                    .context("Failed to close session")?;
+                let (closed_end_ts, capped) = close_session_capped(conn, session, end_ts)?;
+                effective_end_ts = closed_end_ts;
+                print_capped(capped);
                 let duration = effective_end_ts - session.start_ts;
                 println!("Stopped timing task {}: {} ({}, {})",
                     task_id, task.description, format_time(effective_end_ts), format_duration_human(duration));
@@ -5027,6 +6966,7 @@ fn handle_close_interactive(conn: &Connection, task_ids: &[i64], end_ts: i64) ->
 
         // Invariant 4: terminal lifecycle cleanup - clear active externals
         ExternalRepo::mark_all_returned_for_task(conn, *task_id)?;
+        print_unblocked(conn, *task_id)?;
 
         // Handle respawn if task has respawn rule
         if let Some(new_task_id) = respawn_task(conn, &task, effective_end_ts)? {
@@ -5041,16 +6981,8 @@ fn handle_close_interactive(conn: &Connection, task_ids: &[i64], end_ts: i64) ->
             }
         }
 
-        // Remove from stack
-        let stack = StackRepo::get_or_create_default(conn)?;
-        let stack_id = stack.id.unwrap();
-        let items = StackRepo::get_items(conn, stack_id)?;
-
-        // Find the task in the stack and remove it
-        if let Some(item) = items.iter().find(|item| item.task_id == *task_id) {
-            // Drop the task at this position using its ordinal
-            StackRepo::drop(conn, stack_id, item.ordinal as i32)?;
-        }
+        // Remove from all queues
+        StackRepo::remove_from_all(conn, *task_id)?;
 
         println!("Closed task {}: {}", task_id, task.description);
     }
@@ -5066,7 +6998,7 @@ fn handle_task_cancel_optional(target: Option<String>, yes: bool, interactive: b
         // Default to queue[0]
         let conn = DbConnection::connect()
             .context("Failed to connect to database")?;
-        let stack = StackRepo::get_or_create_default(&conn)?;
+        let stack = StackRepo::get_active(&conn)?;
         let items = StackRepo::get_items(&conn, stack.id.unwrap())?;
         
         if items.is_empty() {
@@ -5156,8 +7088,7 @@ fn handle_task_cancel(id_or_filter: String, yes: bool, interactive: bool) -> Res
         
         if let Some(session) = &open_session {
             if !closed_open_session && session.task_id == *task_id {
-                SessionRepo::close_open(&conn, end_ts)
-                    .context("Failed to close session")?;
+                print_capped(close_session_capped(&conn, session, end_ts)?.1);
                 closed_open_session = true;
             }
         }
@@ -5171,6 +7102,7 @@ fn handle_task_cancel(id_or_filter: String, yes: bool, interactive: bool) -> Res
 
         // Invariant 4: terminal lifecycle cleanup - clear active externals
         ExternalRepo::mark_all_returned_for_task(&conn, *task_id)?;
+        print_unblocked(&conn, *task_id)?;
 
         // Handle respawn if task has respawn rule
         if let Some(new_task_id) = respawn_task(&conn, &task, end_ts)? {
@@ -5185,12 +7117,7 @@ fn handle_task_cancel(id_or_filter: String, yes: bool, interactive: bool) -> Res
             }
         }
 
-        let stack = StackRepo::get_or_create_default(&conn)?;
-        let stack_id = stack.id.unwrap();
-        let items = StackRepo::get_items(&conn, stack_id)?;
-        if let Some(item) = items.iter().find(|item| item.task_id == *task_id) {
-            StackRepo::drop(&conn, stack_id, item.ordinal as i32)?;
-        }
+        StackRepo::remove_from_all(&conn, *task_id)?;
 
         println!("Cancelled task {}: {}", task_id, task.description);
     }
@@ -5232,8 +7159,7 @@ fn handle_cancel_interactive(conn: &Connection, task_ids: &[i64]) -> Result<()>
 
         if let Some(session) = &open_session {
             if !closed_open_session && session.task_id == *task_id {
-                SessionRepo::close_open(conn, end_ts)
-                    .context("Failed to close session")?;
+                print_capped(close_session_capped(conn, session, end_ts)?.1);
                 closed_open_session = true;
             }
         }
@@ -5243,6 +7169,7 @@ fn handle_cancel_interactive(conn: &Connection, task_ids: &[i64]) -> Result<()>
 
         // Invariant 4: terminal lifecycle cleanup - clear active externals
         ExternalRepo::mark_all_returned_for_task(conn, *task_id)?;
+        print_unblocked(conn, *task_id)?;
 
         // Handle respawn if task has respawn rule
         if let Some(new_task_id) = respawn_task(conn, &task, end_ts)? {
@@ -5257,12 +7184,7 @@ fn handle_cancel_interactive(conn: &Connection, task_ids: &[i64]) -> Result<()>
             }
         }
 
-        let stack = StackRepo::get_or_create_default(conn)?;
-        let stack_id = stack.id.unwrap();
-        let items = StackRepo::get_items(conn, stack_id)?;
-        if let Some(item) = items.iter().find(|item| item.task_id == *task_id) {
-            StackRepo::drop(conn, stack_id, item.ordinal as i32)?;
-        }
+        StackRepo::remove_from_all(conn, *task_id)?;
 
         println!("Cancelled task {}: {}", task_id, task.description);
     }
@@ -5271,6 +7193,43 @@ fn handle_cancel_interactive(conn: &Connection, task_ids: &[i64]) -> Result<()>
 }
 
 /// Handle task reopen (set status back to open)
+fn handle_skip(target: String) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+
+    let task_ids = match parse_task_id_spec(&target) {
+        Ok(ids) => ids,
+        Err(e) => user_error(&e),
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    for task_id in task_ids {
+        let task = match TaskRepo::get_by_id(&conn, task_id)? {
+            Some(task) => task,
+            None => user_error(&format!("Task {} not found", task_id)),
+        };
+        if task.status != TaskStatus::Open {
+            user_error(&format!("Task {} is {}. Only open tasks can be skipped.", task_id, task.status.as_str()));
+        }
+        if task.respawn.as_deref().is_none_or(str::is_empty) {
+            user_error(&format!("Task {} has no respawn rule. Use 'tatl cancel {}' instead.", task_id, task_id));
+        }
+
+        match skip_occurrence(&conn, &task, now)? {
+            Some(next_due) => println!(
+                "Skipped task {}: {} (next due: {})",
+                task_id, task.description, format_datetime(next_due)
+            ),
+            None => user_error(&format!(
+                "Task {} has no further occurrences: its respawn rule has ended. Use 'tatl close {}' or 'tatl cancel {}' instead.",
+                task_id, task_id, task_id
+            )),
+        }
+    }
+
+    Ok(())
+}
+
 fn handle_task_reopen(id_or_filter: String, yes: bool, interactive: bool) -> Result<()> {
     let conn = DbConnection::connect()
         .context("Failed to connect to database")?;
@@ -5466,8 +7425,6 @@ fn handle_delete_confirm(conn: &Connection, task_ids: &[i64]) -> Result<()> {
             return Ok(());
         }
 
-        // Orphan children before deleting
-        TaskRepo::orphan_children(conn, task_ids[0])?;
         TaskRepo::delete(conn, task_ids[0])
             .context("Failed to delete task")?;
         println!("Deleted task {}: {}", task_ids[0], task.description);
@@ -5498,8 +7455,6 @@ fn handle_delete_yes(conn: &Connection, task_ids: &[i64]) -> Result<()> {
     for task_id in task_ids {
         match TaskRepo::get_by_id(conn, *task_id) {
             Ok(Some(task)) => {
-                // Orphan children before deleting
-                TaskRepo::orphan_children(conn, *task_id)?;
                 TaskRepo::delete(conn, *task_id)
                     .context(format!("Failed to delete task {}", task_id))?;
                 println!("Deleted task {}: {}", task_id, task.description);
@@ -5552,8 +7507,6 @@ fn handle_delete_interactive(conn: &Connection, task_ids: &[i64]) -> Result<()>
             continue;
         }
 
-        // Orphan children before deleting
-        TaskRepo::orphan_children(conn, *task_id)?;
         TaskRepo::delete(conn, *task_id)
             .context(format!("Failed to delete task {}", task_id))?;
         println!("Deleted task {}: {}", task_id, task.description);
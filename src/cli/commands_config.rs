@@ -0,0 +1,108 @@
+// Config command handlers
+
+use crate::cli::commands::ConfigCommands;
+use crate::cli::error::user_error;
+use crate::config::{self, key_def, Config, KEYS};
+use anyhow::Result;
+
+pub fn handle_config(subcommand: Option<ConfigCommands>) -> Result<()> {
+    match subcommand {
+        None | Some(ConfigCommands::List) => handle_config_list(),
+        Some(ConfigCommands::Get { key }) => handle_config_get(&key),
+        Some(ConfigCommands::Set { key, value }) => handle_config_set(&key, &value.join(" ")),
+        Some(ConfigCommands::Unset { key }) => handle_config_unset(&key),
+    }
+}
+
+/// Reject keys that are not configuration settings
+fn check_key(key: &str) {
+    if key == "include" {
+        user_error("include= lines can only be edited in the rc file itself");
+    }
+    if key_def(key).is_none() {
+        user_error(&format!("Unknown config key '{}'. Run 'tatl config list' to see all keys.", key));
+    }
+}
+
+/// Effective value of a key and where it comes from
+fn value_and_source(config: &Config, key: &str) -> Option<(String, String)> {
+    if key == "data.location" {
+        let source = if std::env::var_os("TATL_DATA").is_some_and(|p| !p.is_empty()) {
+            "TATL_DATA".to_string()
+        } else {
+            config.entry(key).map_or("default".to_string(), |e| e.source.display().to_string())
+        };
+        return Some((config.data_location().display().to_string(), source));
+    }
+    match config.entry(key) {
+        Some(entry) => Some((entry.value.clone(), entry.source.display().to_string())),
+        None => config.get(key).map(|value| (value.to_string(), "default".to_string())),
+    }
+}
+
+fn handle_config_list() -> Result<()> {
+    let config = Config::current();
+    let mut rows = Vec::new();
+    for def in KEYS {
+        if def.name.ends_with('*') {
+            let prefix = def.name.trim_end_matches('*');
+            for (rest, _) in config.entries_with_prefix(prefix) {
+                let key = format!("{}{}", prefix, rest);
+                if let Some((value, source)) = value_and_source(&config, &key) {
+                    rows.push((key, def.value_type.as_str(), value, source));
+                }
+            }
+        } else {
+            let (value, source) = value_and_source(&config, def.name)
+                .unwrap_or_else(|| ("(unset)".to_string(), String::new()));
+            rows.push((def.name.to_string(), def.value_type.as_str(), value, source));
+        }
+    }
+
+    let key_w = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(3);
+    let type_w = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(4);
+    let value_w = rows.iter().map(|r| r.2.chars().count()).max().unwrap_or(0).clamp(5, 40);
+    println!("Config file: {}", Config::path().display());
+    println!();
+    println!("{:<key_w$} {:<type_w$} {:<value_w$} Source", "Key", "Type", "Value");
+    println!("{} {} {} {}", "─".repeat(key_w), "─".repeat(type_w), "─".repeat(value_w), "─".repeat(20));
+    for (key, value_type, value, source) in rows {
+        println!("{:<key_w$} {:<type_w$} {:<value_w$} {}", key, value_type, value, source);
+    }
+    Ok(())
+}
+
+fn handle_config_get(key: &str) -> Result<()> {
+    check_key(key);
+    match value_and_source(&Config::current(), key) {
+        Some((value, _)) => println!("{}", value),
+        None => user_error(&format!("{} is not set", key)),
+    }
+    Ok(())
+}
+
+fn handle_config_set(key: &str, value: &str) -> Result<()> {
+    check_key(key);
+    let path = Config::path();
+    let value = config::set_in_file(&path, key, value).unwrap_or_else(|e| user_error(&e.to_string()));
+    println!("Set {}={} in {}", key, value, path.display());
+    if key == "data.location" && std::env::var_os("TATL_DATA").is_some_and(|p| !p.is_empty()) {
+        eprintln!("Note: TATL_DATA is set and takes precedence over data.location");
+    }
+    Ok(())
+}
+
+fn handle_config_unset(key: &str) -> Result<()> {
+    check_key(key);
+    let path = Config::path();
+    if config::unset_in_file(&path, key)? {
+        println!("Unset {} in {}", key, path.display());
+        return Ok(());
+    }
+    match Config::current().entry(key) {
+        Some(entry) => user_error(&format!(
+            "{} is set in included file {}; edit that file to remove it", key, entry.source.display()
+        )),
+        None => user_error(&format!("{} is not set in {}", key, path.display())),
+    }
+}
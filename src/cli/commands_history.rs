@@ -0,0 +1,467 @@
+// History command handlers
+
+use crate::config::Config;
+use crate::db::DbConnection;
+use crate::repo::{EventRepo, TaskRepo, ProjectRepo, AnnotationRepo, UndoRepo};
+use crate::models::TaskEvent;
+use crate::cli::error::{user_error, validate_task_id, parse_task_id_spec};
+use crate::filter::{parse_filter, filter_tasks};
+use crate::utils::parse_date_expr;
+use anyhow::{Context, Result};
+use std::io::{self, Write};
+use chrono::{Local, TimeZone};
+use rusqlite::Connection;
+use serde_json::Value;
+
+/// Default window for the global history feed when no range is given
+const DEFAULT_FEED_RANGE: &str = "-7d";
+
+/// Format a timestamp with date for display
+fn format_datetime(ts: i64) -> String {
+    Local.timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format(&format!("{} %H:%M", Config::current().date_format())).to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+/// Format a duration in seconds as "2h 15m"
+fn format_duration_short(secs: i64) -> String {
+    let hours = secs / 3600;
+    let mins = (secs % 3600) / 60;
+    if hours > 0 && mins > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if hours > 0 {
+        format!("{}h", hours)
+    } else {
+        format!("{}m", mins)
+    }
+}
+
+/// Check if a token is a history range (e.g. "-7d", "-2w", "2024-01-01..now")
+fn is_range_token(token: &str) -> bool {
+    if token.contains("..") {
+        return true;
+    }
+    if let Some(rest) = token.strip_prefix('-') {
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let unit = &rest[digits.len()..];
+        return !digits.is_empty() && matches!(unit, "d" | "w" | "m" | "y");
+    }
+    false
+}
+
+/// Parse a range token into (start, optional end)
+fn parse_range(token: &str) -> Result<(i64, Option<i64>)> {
+    if let Some((start_str, end_str)) = token.split_once("..") {
+        let start = if start_str.is_empty() {
+            0
+        } else {
+            parse_date_expr(start_str)
+                .context(format!("Invalid start date: {}", start_str))?
+        };
+        let end = if end_str.is_empty() || end_str == "now" {
+            None
+        } else {
+            let ts = parse_date_expr(end_str)
+                .context(format!("Invalid end date: {}", end_str))?;
+            // A date (resolving to midnight) includes that whole day; a time is the exact end
+            let is_date = Local.timestamp_opt(ts, 0)
+                .single()
+                .is_some_and(|dt| dt.time() == chrono::NaiveTime::MIN);
+            Some(if is_date { ts + 86400 } else { ts })
+        };
+        return Ok((start, end));
+    }
+    let start = parse_date_expr(token)
+        .context(format!("Invalid date range: {}", token))?;
+    Ok((start, None))
+}
+
+/// Resolve a history target (ID, ID range/list, or filter) to task IDs
+fn resolve_target(conn: &Connection, tokens: Vec<String>) -> Result<Vec<i64>> {
+    if tokens.len() == 1 {
+        if let Ok(ids) = parse_task_id_spec(&tokens[0]) {
+            return Ok(ids);
+        }
+        if let Ok(id) = validate_task_id(&tokens[0]) {
+            return Ok(vec![id]);
+        }
+    }
+    let filter_expr = match parse_filter(tokens) {
+        Ok(expr) => expr,
+        Err(e) => user_error(&format!("Filter parse error: {}", e)),
+    };
+    let matching_tasks = filter_tasks(conn, &filter_expr)
+        .context("Failed to filter tasks")?;
+    Ok(matching_tasks.iter().filter_map(|(task, _)| task.id).collect())
+}
+
+/// Human-readable name for a modified field
+fn field_label(field: &str) -> &str {
+    match field {
+        "project_id" => "project",
+        "due_ts" => "due",
+        "scheduled_ts" => "scheduled",
+        "wait_ts" => "wait",
+        "alloc_secs" => "allocation",
+        "parent_id" => "parent",
+        other => other,
+    }
+}
+
+/// Format an old/new value from a modified event for display
+fn format_field_value(conn: &Connection, field: &str, value: &Value) -> String {
+    if value.is_null() {
+        return "none".to_string();
+    }
+    match (field, value.as_i64()) {
+        ("due_ts" | "scheduled_ts" | "wait_ts", Some(ts)) => format_datetime(ts),
+        ("alloc_secs", Some(secs)) => format_duration_short(secs),
+        ("project_id", Some(id)) => ProjectRepo::get_by_id(conn, id)
+            .ok()
+            .flatten()
+            .map(|p| p.name)
+            .unwrap_or_else(|| format!("#{}", id)),
+        ("parent_id", Some(id)) => id.to_string(),
+        _ => match value.as_str() {
+            Some(s) => s.to_string(),
+            None => value.to_string(),
+        },
+    }
+}
+
+/// Describe an event payload in one line
+pub fn describe_event(conn: &Connection, event: &TaskEvent) -> String {
+    match event.event_type.as_str() {
+        "created" => {
+            let desc = event.payload_str("description").unwrap_or("");
+            match event.payload_i64("project_id") {
+                Some(_) => format!(
+                    "{} (project: {})",
+                    desc,
+                    format_field_value(conn, "project_id", &event.payload["project_id"])
+                ),
+                None => desc.to_string(),
+            }
+        }
+        "modified" => {
+            let field = event.payload_str("field").unwrap_or("?");
+            let old_value = format_field_value(conn, field, &event.payload["old_value"]);
+            let new_value = format_field_value(conn, field, &event.payload["new_value"]);
+            format!("{}: {} → {}", field_label(field), old_value, new_value)
+        }
+        "status_changed" => format!(
+            "{} → {}",
+            event.payload_str("old_status").unwrap_or("?"),
+            event.payload_str("new_status").unwrap_or("?")
+        ),
+        "tag_added" => format!("+{}", event.payload_str("tag").unwrap_or("")),
+        "tag_removed" => format!("-{}", event.payload_str("tag").unwrap_or("")),
+        "annotation_added" => {
+            let annotation_id = event.payload_i64("annotation_id").unwrap_or(0);
+            match AnnotationRepo::get_by_id(conn, annotation_id).ok().flatten() {
+                Some(annotation) => annotation.note,
+                None => format!("annotation {} (deleted)", annotation_id),
+            }
+        }
+        "annotation_deleted" => format!(
+            "annotation {}",
+            event.payload_i64("annotation_id").unwrap_or(0)
+        ),
+        "stack_added" => {
+            let position = event.payload_i64("position").unwrap_or(0);
+            match event.payload_i64("old_position") {
+                Some(old_position) => format!("queue[{}] → queue[{}]", old_position, position),
+                None => format!("queue[{}]", position),
+            }
+        }
+        "stack_removed" => match event.payload_i64("position") {
+            Some(position) if position >= 0 => format!("removed from queue[{}]", position),
+            _ => "removed from queue".to_string(),
+        },
+        "session_started" => format!(
+            "started at {}",
+            format_datetime(event.payload_i64("start_ts").unwrap_or(event.ts))
+        ),
+        "session_ended" => {
+            let start = event.payload_i64("start_ts").unwrap_or(event.ts);
+            let end = event.payload_i64("end_ts").unwrap_or(event.ts);
+            format!("{} ({})", format_datetime(end), format_duration_short(end - start))
+        }
+        "session_modified" => {
+            let field = event.payload_str("field").unwrap_or("?");
+            let format_value = |value: &Value| match (field, value) {
+                (_, Value::Null) => "none".to_string(),
+                ("start_ts" | "end_ts", value) => value.as_i64().map(format_datetime).unwrap_or_default(),
+                ("tags", Value::Array(tags)) => tags.iter()
+                    .filter_map(|tag| tag.as_str())
+                    .map(|tag| format!("+{}", tag))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                (_, value) => value.as_str().map(|s| s.to_string()).unwrap_or_else(|| value.to_string()),
+            };
+            format!(
+                "session {} {}: {} → {}",
+                event.payload_i64("session_id").unwrap_or(0),
+                field.trim_end_matches("_ts"),
+                format_value(&event.payload["old_value"]),
+                format_value(&event.payload["new_value"])
+            )
+        }
+        "session_deleted" => {
+            let start = event.payload_i64("start_ts").unwrap_or(event.ts);
+            let session = format!("session {} deleted (started {}", event.payload_i64("session_id").unwrap_or(0), format_datetime(start));
+            match event.payload_i64("end_ts") {
+                Some(end) => format!("{}, {})", session, format_duration_short(end - start)),
+                None => format!("{})", session),
+            }
+        }
+        "skipped" => {
+            let to_due = format_datetime(event.payload_i64("to_due_ts").unwrap_or(event.ts));
+            match event.payload_i64("from_due_ts") {
+                Some(from_due) => format!("occurrence due {} skipped → {}", format_datetime(from_due), to_due),
+                None => format!("occurrence skipped → {}", to_due),
+            }
+        }
+        "dependency_added" => format!("depends on {}", event.payload_i64("depends_on").unwrap_or(0)),
+        "dependency_removed" => format!("no longer depends on {}", event.payload_i64("depends_on").unwrap_or(0)),
+        _ => event.payload.to_string(),
+    }
+}
+
+fn event_to_json(conn: &Connection, event: &TaskEvent) -> Value {
+    serde_json::json!({
+        "id": event.id,
+        "task_id": event.task_id,
+        "ts": event.ts,
+        "event_type": event.event_type,
+        "payload": event.payload,
+        "summary": describe_event(conn, event),
+    })
+}
+
+/// Handle `tatl history [<id|filter>] [<range>] [--json]`
+///
+/// With a target, prints a timeline of events for each matching task.
+/// Without a target, prints a feed of all events in the range (default: last 7 days).
+pub fn handle_history(args: Vec<String>, json: bool) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+
+    let mut json = json;
+    let mut target_tokens: Vec<String> = Vec::new();
+    let mut range_token: Option<String> = None;
+    for arg in args {
+        // --json after the target is captured by the trailing args
+        if arg == "--json" {
+            json = true;
+        } else if range_token.is_none() && is_range_token(&arg) {
+            range_token = Some(arg);
+        } else {
+            target_tokens.push(arg);
+        }
+    }
+
+    if target_tokens.is_empty() {
+        let range = range_token.unwrap_or_else(|| DEFAULT_FEED_RANGE.to_string());
+        let (start, end) = parse_range(&range)?;
+        let events = EventRepo::list_range(&conn, start, end)?;
+        return print_feed(&conn, &events, json);
+    }
+
+    let (start, end) = match range_token {
+        Some(range) => parse_range(&range)?,
+        None => (0, None),
+    };
+
+    let task_ids = resolve_target(&conn, target_tokens)?;
+    if task_ids.is_empty() {
+        user_error("No matching tasks found");
+    }
+
+    let mut timelines: Vec<(i64, String, Vec<TaskEvent>)> = Vec::new();
+    for task_id in task_ids {
+        // Deleted tasks keep their history
+        let task = match TaskRepo::get_by_id_with_deleted(&conn, task_id)? {
+            Some(t) => t,
+            None => {
+                eprintln!("Task {} not found", task_id);
+                continue;
+            }
+        };
+        let events: Vec<TaskEvent> = EventRepo::get_by_task(&conn, task_id)?
+            .into_iter()
+            .filter(|e| e.ts >= start && end.is_none_or(|end| e.ts < end))
+            .collect();
+        timelines.push((task_id, task.description, events));
+    }
+
+    if timelines.is_empty() {
+        user_error("No tasks found");
+    }
+
+    if json {
+        let json_tasks: Vec<Value> = timelines.iter().map(|(task_id, description, events)| {
+            serde_json::json!({
+                "task_id": task_id,
+                "description": description,
+                "events": events.iter().map(|e| event_to_json(&conn, e)).collect::<Vec<_>>(),
+            })
+        }).collect();
+        println!("{}", serde_json::to_string_pretty(&json_tasks)?);
+        return Ok(());
+    }
+
+    let count = timelines.len();
+    for (idx, (task_id, description, events)) in timelines.iter().enumerate() {
+        println!("Task {}: {}", task_id, description);
+        if events.is_empty() {
+            println!("  No events.");
+        }
+        for event in events {
+            println!(
+                "  {:<16}  {:<18}  {}",
+                format_datetime(event.ts),
+                event.event_type,
+                describe_event(&conn, event)
+            );
+        }
+        if idx + 1 < count {
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the global event feed
+fn print_feed(conn: &Connection, events: &[TaskEvent], json: bool) -> Result<()> {
+    if json {
+        let json_events: Vec<Value> = events.iter().map(|e| event_to_json(conn, e)).collect();
+        println!("{}", serde_json::to_string_pretty(&json_events)?);
+        return Ok(());
+    }
+
+    if events.is_empty() {
+        println!("No events found.");
+        return Ok(());
+    }
+
+    println!("{:<16}  {:>5}  {:<18}  Details", "Time", "Task", "Event");
+    println!("{}  {}  {}  {}", "─".repeat(16), "─".repeat(5), "─".repeat(18), "─".repeat(30));
+    for event in events {
+        println!(
+            "{:<16}  {:>5}  {:<18}  {}",
+            format_datetime(event.ts),
+            event.task_id,
+            event.event_type,
+            describe_event(conn, event)
+        );
+    }
+
+    Ok(())
+}
+
+/// Handle `tatl undo [n] [-y]`
+///
+/// Previews the last `n` command batches and reverts them after confirmation.
+pub fn handle_undo(n: usize, yes: bool) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+
+    let batches = UndoRepo::pending(&conn, n.max(1))?;
+    if batches.is_empty() {
+        println!("Nothing to undo.");
+        return Ok(());
+    }
+
+    println!("This will undo {} command(s):", batches.len());
+    for batch in &batches {
+        let events = batch.revertible_events();
+        println!();
+        println!("  {}  ({} change(s))", format_datetime(batch.ts()), events.len());
+        for event in events {
+            if event.event_type == "created" {
+                println!("    Task {:<5} delete task ({})", event.task_id, describe_event(&conn, event));
+            } else {
+                println!(
+                    "    Task {:<5} revert {:<18} {}",
+                    event.task_id,
+                    event.event_type,
+                    describe_event(&conn, event)
+                );
+            }
+        }
+    }
+    println!();
+
+    if !yes {
+        print!("Apply undo? (y/n): ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+        if input != "y" && input != "yes" {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    if let Err(e) = UndoRepo::apply(&conn, &batches) {
+        user_error(&format!("Undo failed: {}", e));
+    }
+    println!("Undid {} command(s).", batches.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DbConnection;
+
+    #[test]
+    fn test_is_range_token() {
+        assert!(is_range_token("-7d"));
+        assert!(is_range_token("-2w"));
+        assert!(is_range_token("2024-01-01..now"));
+        assert!(!is_range_token("-urgent"));
+        assert!(!is_range_token("5"));
+        assert!(!is_range_token("project=work"));
+    }
+
+    #[test]
+    fn test_parse_range_end() {
+        let day = parse_date_expr("2026-01-05").unwrap();
+        assert_eq!(parse_range("2026-01-01..2026-01-05").unwrap().1, Some(day + 86400));
+        assert_eq!(
+            parse_range("2026-01-01..2026-01-05T12:00").unwrap().1,
+            Some(parse_date_expr("2026-01-05T12:00").unwrap())
+        );
+        assert_eq!(parse_range("2026-01-01..now").unwrap().1, None);
+    }
+
+    #[test]
+    fn test_describe_modified_event() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let project = ProjectRepo::create(&conn, "work").unwrap();
+        let event = TaskEvent {
+            id: 1,
+            task_id: 1,
+            ts: 0,
+            event_type: "modified".to_string(),
+            payload: serde_json::json!({"field": "project_id", "old_value": null, "new_value": project.id}),
+            batch_id: None,
+        };
+        assert_eq!(describe_event(&conn, &event), "project: none → work");
+
+        let event = TaskEvent {
+            id: 2,
+            task_id: 1,
+            ts: 0,
+            event_type: "modified".to_string(),
+            payload: serde_json::json!({"field": "alloc_secs", "old_value": 3600, "new_value": 5400}),
+            batch_id: None,
+        };
+        assert_eq!(describe_event(&conn, &event), "allocation: 1h → 1h 30m");
+    }
+}
@@ -1,10 +1,16 @@
 // Sessions command handlers
 
+use crate::config::Config;
 use crate::db::DbConnection;
-use crate::repo::{SessionRepo, TaskRepo, AnnotationRepo, ViewRepo};
+use crate::repo::{SessionRepo, TaskRepo, AnnotationRepo, ViewRepo, ViewOptions, ProjectRepo};
 use crate::models::Session;
 use crate::cli::error::{user_error, validate_task_id};
 use crate::cli::output::is_tty;
+use crate::cli::parser::parse_tag_token;
+use crate::cli::billing::{bill, BillingRule, BillingRules, TrackedTime};
+use crate::cli::session_audit::{audit, Finding, Problem, SessionLimits};
+use crate::cli::commands::modify_session_for_removal;
+use crate::cli::timesheet::{round_to_increment, to_csv, to_ics, to_jsonl, ExportFormat, TimesheetEntry};
 use crate::filter::{parse_filter, filter_tasks};
 use crate::utils::{parse_date_expr, parse_duration};
 use anyhow::{Context, Result};
@@ -32,7 +38,7 @@ fn format_timestamp(ts: i64) -> String {
     let dt = Local.timestamp_opt(ts, 0)
         .single()
         .unwrap_or_else(|| Local.timestamp_opt(0, 0).single().unwrap());
-    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+    dt.format(&format!("{} %H:%M:%S", Config::current().date_format())).to_string()
 }
 
 /// Format duration for display
@@ -379,6 +385,18 @@ fn parse_session_filter_term(token: &str) -> Result<Option<SessionFilterTerm>, S
                 }
                 return Ok(Some(SessionFilterTerm::Description(op, value)));
             }
+            "tag" | "tags" => {
+                if op != SessionComparisonOp::Eq && op != SessionComparisonOp::Neq {
+                    return Err("Tag filter supports only '=' and '!='".to_string());
+                }
+                return Ok(Some(SessionFilterTerm::Tag(op, value)));
+            }
+            "note" => {
+                if op != SessionComparisonOp::Eq && op != SessionComparisonOp::Neq {
+                    return Err("Note filter supports only '=' and '!='".to_string());
+                }
+                return Ok(Some(SessionFilterTerm::Note(op, value)));
+            }
             _ => {}
         }
     }
@@ -396,7 +414,7 @@ fn match_int(op: SessionComparisonOp, left: i64, right: i64) -> bool {
     }
 }
 
-fn session_matches_filter(session: &Session, task_desc: &str, term: &SessionFilterTerm) -> bool {
+fn session_matches_filter(session: &Session, task_desc: &str, tags: &[String], term: &SessionFilterTerm) -> bool {
     match term {
         SessionFilterTerm::SessionId(op, id) => {
             if let Some(sid) = session.id {
@@ -428,6 +446,23 @@ fn session_matches_filter(session: &Session, task_desc: &str, term: &SessionFilt
                 _ => false,
             }
         }
+        SessionFilterTerm::Tag(op, tag) => {
+            let has_tag = tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+            match op {
+                SessionComparisonOp::Eq => has_tag,
+                SessionComparisonOp::Neq => !has_tag,
+                _ => false,
+            }
+        }
+        SessionFilterTerm::Note(op, pattern) => {
+            let contains = session.note.as_deref()
+                .is_some_and(|note| note.to_lowercase().contains(&pattern.to_lowercase()));
+            match op {
+                SessionComparisonOp::Eq => contains,
+                SessionComparisonOp::Neq => !contains,
+                _ => false,
+            }
+        }
     }
 }
 
@@ -439,6 +474,8 @@ enum SessionListColumn {
     Start,
     End,
     Duration,
+    Tags,
+    Note,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -471,6 +508,8 @@ enum SessionFilterTerm {
     End(SessionComparisonOp, i64),
     Duration(SessionComparisonOp, i64),
     Description(SessionComparisonOp, String), // contains match for Eq/Neq
+    Tag(SessionComparisonOp, String),         // session has (or lacks) the tag
+    Note(SessionComparisonOp, String),        // contains match for Eq/Neq
 }
 
 fn parse_session_column(name: &str) -> Option<SessionListColumn> {
@@ -481,6 +520,8 @@ fn parse_session_column(name: &str) -> Option<SessionListColumn> {
         "start" => Some(SessionListColumn::Start),
         "end" => Some(SessionListColumn::End),
         "duration" => Some(SessionListColumn::Duration),
+        "tags" | "tag" => Some(SessionListColumn::Tags),
+        "note" => Some(SessionListColumn::Note),
         _ => None,
     }
 }
@@ -493,6 +534,8 @@ fn session_column_label(column: SessionListColumn) -> &'static str {
         SessionListColumn::Start => "Start",
         SessionListColumn::End => "End",
         SessionListColumn::Duration => "Duration",
+        SessionListColumn::Tags => "Tags",
+        SessionListColumn::Note => "Note",
     }
 }
 
@@ -516,9 +559,22 @@ fn sort_value_as_string(value: &SortValue) -> String {
     }
 }
 
+/// Shorten a note to at most 50 bytes, cutting on a character boundary
+fn truncate_note(note: &str) -> String {
+    if note.len() <= 50 {
+        return note.to_string();
+    }
+    let mut end = 48;
+    while !note.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}..", &note[..end])
+}
+
 fn format_sessions_list_table(
     sessions: &[Session],
     tasks_by_id: &std::collections::HashMap<i64, String>,
+    tags_by_session: &std::collections::HashMap<i64, Vec<String>>,
     sort_columns: &[String],
     group_columns: &[String],
 ) -> String {
@@ -543,6 +599,11 @@ fn format_sessions_list_table(
         };
         let duration_secs = session.duration_secs().unwrap_or_else(|| chrono::Utc::now().timestamp() - session.start_ts);
         let duration_str = format_duration(duration_secs);
+        let tags = session.id
+            .and_then(|id| tags_by_session.get(&id))
+            .map(|tags| tags.join(" "))
+            .unwrap_or_default();
+        let note = session.note.clone().unwrap_or_default();
         
         let mut values = std::collections::HashMap::new();
         values.insert(SessionListColumn::SessionId, session.id.map(|id| id.to_string()).unwrap_or_else(|| "?".to_string()));
@@ -551,6 +612,8 @@ fn format_sessions_list_table(
         values.insert(SessionListColumn::Start, start_str.clone());
         values.insert(SessionListColumn::End, end_str.clone());
         values.insert(SessionListColumn::Duration, duration_str.clone());
+        values.insert(SessionListColumn::Tags, tags.clone());
+        values.insert(SessionListColumn::Note, truncate_note(&note));
         
         let mut sort_values = std::collections::HashMap::new();
         sort_values.insert(SessionListColumn::SessionId, session.id.map(SortValue::Int));
@@ -559,6 +622,8 @@ fn format_sessions_list_table(
         sort_values.insert(SessionListColumn::Start, Some(SortValue::Int(session.start_ts)));
         sort_values.insert(SessionListColumn::End, Some(SortValue::Int(session.end_ts.unwrap_or(0))));
         sort_values.insert(SessionListColumn::Duration, Some(SortValue::Int(duration_secs)));
+        sort_values.insert(SessionListColumn::Tags, Some(SortValue::Str(tags)));
+        sort_values.insert(SessionListColumn::Note, Some(SortValue::Str(note)));
         
         rows.push(SessionRow { values, sort_values });
     }
@@ -630,6 +695,13 @@ fn format_sessions_list_table(
             columns.push(column);
         }
     }
+    // Tags and Note are shown when any listed session has them
+    for column in [SessionListColumn::Tags, SessionListColumn::Note] {
+        let has_values = rows.iter().any(|row| row.values.get(&column).is_some_and(|v| !v.is_empty()));
+        if has_values && !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
     
     let mut column_widths: std::collections::HashMap<SessionListColumn, usize> = std::collections::HashMap::new();
     for column in &columns {
@@ -756,10 +828,21 @@ fn format_sessions_list_table(
     output
 }
 
-pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: bool) -> Result<()> {
-    let conn = DbConnection::connect()
-        .context("Failed to connect to database")?;
-    
+/// Sessions matched by `sessions list` filter arguments
+struct SessionSelection {
+    sessions: Vec<Session>,
+    /// Task descriptions by task ID
+    tasks_by_id: std::collections::HashMap<i64, String>,
+    /// Session tags by session ID
+    tags_by_session: std::collections::HashMap<i64, Vec<String>>,
+    request: ListRequest,
+    /// Filter tokens as given (before any saved view was expanded)
+    original_filter_tokens: Vec<String>,
+}
+
+/// Select sessions with `sessions list` filter arguments: `start:`/`end:` date filters,
+/// session column filters, task filters and saved session views
+fn select_sessions(conn: &Connection, filter_args: Vec<String>) -> Result<SessionSelection> {
     // Separate session date filters from task filters
     let mut task_filters = Vec::new();
     let mut session_start_filter: Option<(i64, Option<i64>)> = None; // (from, optional to)
@@ -784,10 +867,10 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
     // Split filters into session filters and task filters
     let mut session_filters: Vec<SessionFilterTerm> = Vec::new();
     let mut task_filter_tokens: Vec<String> = Vec::new();
-    for token in request.filter_tokens.into_iter() {
-        match parse_session_filter_term(&token) {
+    for token in &request.filter_tokens {
+        match parse_session_filter_term(token) {
             Ok(Some(term)) => session_filters.push(term),
-            Ok(None) => task_filter_tokens.push(token),
+            Ok(None) => task_filter_tokens.push(token.clone()),
             Err(e) => user_error(&format!("Session filter error: {}", e)),
         }
     }
@@ -797,7 +880,7 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
         && task_filter_tokens.len() == 1
         && is_view_name_token(&task_filter_tokens[0])
     {
-        if let Some(view) = ViewRepo::get_by_name(&conn, "sessions", &task_filter_tokens[0])? {
+        if let Some(view) = ViewRepo::get_by_name(conn, "sessions", &task_filter_tokens[0])? {
             request.filter_tokens = view.filter_tokens.clone();
             request.sort_columns = view.sort_columns;
             request.group_columns = view.group_columns;
@@ -815,33 +898,18 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
         }
     }
     
-    if let Some(alias) = request.save_alias.clone() {
-        ViewRepo::upsert(
-            &conn,
-            &alias,
-            "sessions",
-            &original_filter_tokens,
-            &request.sort_columns,
-            &request.group_columns,
-            &request.hide_columns,
-            &None, // color_column not used for sessions
-            &None, // fill_column not used for sessions
-        )?;
-        println!("Saved view '{}'.", alias);
-    }
-    
     let sessions = if task_filter_tokens.is_empty() {
         // List all sessions
-        SessionRepo::list_all(&conn)?
+        SessionRepo::list_all(conn)?
     } else if task_filter_tokens.len() == 1 {
         // Single argument - try to parse as task ID first, otherwise treat as filter
         match validate_task_id(&task_filter_tokens[0]) {
             Ok(task_id) => {
                 // Single task ID
-                if TaskRepo::get_by_id(&conn, task_id)?.is_none() {
+                if TaskRepo::get_by_id(conn, task_id)?.is_none() {
                     user_error(&format!("Task {} not found", task_id));
                 }
-                SessionRepo::get_by_task(&conn, task_id)?
+                SessionRepo::get_by_task(conn, task_id)?
             }
             Err(_) => {
                 // Treat as filter - aggregate sessions across all matching tasks
@@ -849,16 +917,10 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
                     Ok(expr) => expr,
                     Err(e) => user_error(&format!("Filter parse error: {}", e)),
                 };
-                let matching_tasks = filter_tasks(&conn, &filter_expr)
+                let matching_tasks = filter_tasks(conn, &filter_expr)
                     .context("Failed to filter tasks")?;
                 
-                if matching_tasks.is_empty() {
-                    if !json {
-                        println!("No sessions found.");
-                    }
-                    return Ok(()); // No tasks, no sessions
-                }
-                
+
                 let task_ids: Vec<i64> = matching_tasks.iter()
                     .filter_map(|(task, _)| task.id)
                     .collect();
@@ -866,7 +928,7 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
                 // Aggregate sessions from all matching tasks
                 let mut all_sessions = Vec::new();
                 for task_id in task_ids {
-                    let mut task_sessions = SessionRepo::get_by_task(&conn, task_id)?;
+                    let mut task_sessions = SessionRepo::get_by_task(conn, task_id)?;
                     all_sessions.append(&mut task_sessions);
                 }
                 
@@ -881,16 +943,10 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
             Ok(expr) => expr,
             Err(e) => user_error(&format!("Filter parse error: {}", e)),
         };
-        let matching_tasks = filter_tasks(&conn, &filter_expr)
+        let matching_tasks = filter_tasks(conn, &filter_expr)
             .context("Failed to filter tasks")?;
         
-        if matching_tasks.is_empty() {
-            if !json {
-                println!("No sessions found.");
-            }
-            return Ok(()); // No tasks, no sessions
-        }
-        
+
         let task_ids: Vec<i64> = matching_tasks.iter()
             .filter_map(|(task, _)| task.id)
             .collect();
@@ -898,7 +954,7 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
         // Aggregate sessions from all matching tasks
         let mut all_sessions = Vec::new();
         for task_id in task_ids {
-            let mut task_sessions = SessionRepo::get_by_task(&conn, task_id)?;
+            let mut task_sessions = SessionRepo::get_by_task(conn, task_id)?;
             all_sessions.append(&mut task_sessions);
         }
         
@@ -911,12 +967,15 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
     let mut tasks_by_id = std::collections::HashMap::new();
     for session in &sessions {
         if !tasks_by_id.contains_key(&session.task_id) {
-            if let Ok(Some(task)) = TaskRepo::get_by_id(&conn, session.task_id) {
+            if let Ok(Some(task)) = TaskRepo::get_by_id(conn, session.task_id) {
                 tasks_by_id.insert(session.task_id, task.description);
             }
         }
     }
 
+    let tags_by_session = SessionRepo::tags_by_session(conn)?;
+    let no_tags = Vec::new();
+
     // Apply session date filtering if specified, then session column filters
     let sessions: Vec<_> = sessions.into_iter()
         .filter(|session| {
@@ -962,14 +1021,38 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
             // Apply column-style session filters
             for term in &session_filters {
                 let desc = tasks_by_id.get(&session.task_id).cloned().unwrap_or_default();
-                if !session_matches_filter(session, &desc, term) {
+                let tags = session.id.and_then(|id| tags_by_session.get(&id)).unwrap_or(&no_tags);
+                if !session_matches_filter(session, &desc, tags, term) {
                     return false;
                 }
             }
             true
         })
         .collect();
+
+    Ok(SessionSelection { sessions, tasks_by_id, tags_by_session, request, original_filter_tokens })
+}
+
+pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: bool) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    let selection = select_sessions(&conn, filter_args)?;
+
+    if let Some(alias) = selection.request.save_alias.clone() {
+        // show, color and fill columns are not used for sessions
+        let options = ViewOptions {
+            filter_tokens: selection.original_filter_tokens.clone(),
+            sort_columns: selection.request.sort_columns.clone(),
+            group_columns: selection.request.group_columns.clone(),
+            hide_columns: selection.request.hide_columns.clone(),
+            ..Default::default()
+        };
+        ViewRepo::upsert(&conn, &alias, "sessions", &options)?;
+        println!("Saved view '{}'.", alias);
+    }
     
+    let SessionSelection { sessions, tasks_by_id, tags_by_session, request, .. } = selection;
+
     if json {
         // JSON output
         let mut json_sessions = Vec::new();
@@ -985,6 +1068,8 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
                 "end_ts": session.end_ts,
                 "duration_secs": session.duration_secs(),
                 "is_open": session.is_open(),
+                "note": session.note,
+                "tags": session.id.and_then(|id| tags_by_session.get(&id)).cloned().unwrap_or_default(),
             });
             
             json_sessions.push(json_session);
@@ -994,6 +1079,7 @@ pub fn handle_task_sessions_list_with_filter(filter_args: Vec<String>, json: boo
         let table = format_sessions_list_table(
             &sessions,
             &tasks_by_id,
+            &tags_by_session,
             &request.sort_columns,
             &request.group_columns,
         );
@@ -1101,16 +1187,95 @@ pub fn handle_task_sessions_show_with_filter(id_or_filter_opt: Option<String>) -
     Ok(())
 }
 
-/// Parse session modification arguments
-/// Returns (start_ts, end_ts) where:
+/// Note and tag changes for a session, given as `note=<text>`, `+tag` and `-tag`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionAttributes {
+    /// `Some(None)` clears the note
+    pub note: Option<Option<String>>,
+    pub tags_add: Vec<String>,
+    pub tags_remove: Vec<String>,
+}
+
+impl SessionAttributes {
+    /// Take an argument if it sets a session attribute. `-tag` is only accepted with
+    /// `allow_remove`, since elsewhere it may be a relative time such as `-1h`.
+    /// `note=none` (or an empty note) clears the note.
+    pub fn parse_arg(&mut self, arg: &str, allow_remove: bool) -> bool {
+        if let Some(text) = arg.strip_prefix("note=") {
+            let text = text.trim();
+            self.note = Some(if text.is_empty() || text == "none" { None } else { Some(text.to_string()) });
+            return true;
+        }
+        if !(arg.starts_with('+') || (allow_remove && arg.starts_with('-'))) {
+            return false;
+        }
+        match parse_tag_token(arg) {
+            Some(token) => {
+                let (sign, tag) = token.split_at(1);
+                let (add, remove) = if sign == "+" {
+                    (&mut self.tags_add, &mut self.tags_remove)
+                } else {
+                    (&mut self.tags_remove, &mut self.tags_add)
+                };
+                remove.retain(|t| t != tag);
+                if !add.iter().any(|t| t == tag) {
+                    add.push(tag.to_string());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.note.is_none() && self.tags_add.is_empty() && self.tags_remove.is_empty()
+    }
+
+    /// Write the changes to a session
+    pub fn apply(&self, conn: &Connection, session_id: i64) -> Result<()> {
+        if let Some(note) = &self.note {
+            SessionRepo::set_note(conn, session_id, note.as_deref())?;
+        }
+        SessionRepo::add_tags(conn, session_id, &self.tags_add)?;
+        SessionRepo::remove_tags(conn, session_id, &self.tags_remove)?;
+        Ok(())
+    }
+
+    /// Short description of the changes, e.g. `+meeting "fixed the retry loop"`
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = self.tags_add.iter().map(|t| format!("+{}", t)).collect();
+        parts.extend(self.tags_remove.iter().map(|t| format!("-{}", t)));
+        match &self.note {
+            Some(Some(note)) => parts.push(format!("\"{}\"", note)),
+            Some(None) => parts.push("(note cleared)".to_string()),
+            None => {}
+        }
+        parts.join(" ")
+    }
+}
+
+/// A requested change to a session time:
 /// - Some(Some(ts)) = set to timestamp
 /// - Some(None) = clear (for end only)
 /// - None = no change
-fn parse_session_modify_args(args: Vec<String>) -> Result<(Option<Option<i64>>, Option<Option<i64>>)> {
-    let mut start: Option<Option<i64>> = None;
-    let mut end: Option<Option<i64>> = None;
+type TimeChange = Option<Option<i64>>;
+
+type SessionModification = (TimeChange, TimeChange, SessionAttributes);
+
+/// Parse session modification arguments
+/// Returns (start_ts, end_ts, attributes)
+fn parse_session_modify_args(args: Vec<String>) -> Result<SessionModification> {
+    let mut start: TimeChange = None;
+    let mut end: TimeChange = None;
+    let mut attributes = SessionAttributes::default();
     
     for arg in args {
+        // Notes may contain "..", so take them before intervals
+        if arg.starts_with("note=") {
+            attributes.parse_arg(&arg, true);
+            continue;
+        }
+
         // Check for interval syntax first: <start>..<end>, <start>.., ..<end>
         if arg.contains("..") {
             let (start_parsed, end_parsed) = parse_session_interval(&arg)?;
@@ -1154,20 +1319,20 @@ fn parse_session_modify_args(args: Vec<String>) -> Result<(Option<Option<i64>>,
         } else if arg == "-y" || arg == "--yes" || arg == "--force" {
             // Flags are handled separately
             continue;
-        } else {
+        } else if !attributes.parse_arg(&arg, true) {
             return Err(anyhow::anyhow!(
-                "Invalid argument: {}. Use interval syntax: <start>..<end>, <start>.., or ..<end>",
+                "Invalid argument: {}. Use interval syntax: <start>..<end>, <start>.., or ..<end>, note=<text>, +tag or -tag",
                 arg
             ));
         }
     }
     
-    Ok((start, end))
+    Ok((start, end, attributes))
 }
 
 /// Parse interval syntax for session modification
 /// Formats: <start>..<end>, <start>.., ..<end>
-fn parse_session_interval(arg: &str) -> Result<(Option<Option<i64>>, Option<Option<i64>>)> {
+fn parse_session_interval(arg: &str) -> Result<(TimeChange, TimeChange)> {
     let parts: Vec<&str> = arg.splitn(2, "..").collect();
     if parts.len() != 2 {
         return Err(anyhow::anyhow!("Invalid interval syntax: {}", arg));
@@ -1259,7 +1424,7 @@ pub fn handle_sessions_modify(session_id: i64, args: Vec<String>, yes: bool, for
         .ok_or_else(|| anyhow::anyhow!("Session {} not found", session_id))?;
     
     // Parse modification arguments
-    let (start_opt, end_opt) = parse_session_modify_args(args)?;
+    let (start_opt, end_opt, attributes) = parse_session_modify_args(args)?;
     
     // Determine new values
     let new_start_ts = start_opt.map(|s| s.unwrap());
@@ -1304,6 +1469,22 @@ pub fn handle_sessions_modify(session_id: i64, args: Vec<String>, yes: bool, for
             _ => {}
         }
     }
+    if let Some(note) = &attributes.note {
+        if *note != session.note {
+            changes.push(format!("Note: {} -> {}",
+                session.note.as_deref().unwrap_or("(none)"),
+                note.as_deref().unwrap_or("(none)")));
+        }
+    }
+    let old_tags = SessionRepo::get_tags(&conn, session_id)?;
+    let tags_add: Vec<&String> = attributes.tags_add.iter().filter(|t| !old_tags.contains(t)).collect();
+    let tags_remove: Vec<&String> = attributes.tags_remove.iter().filter(|t| old_tags.contains(t)).collect();
+    if !tags_add.is_empty() || !tags_remove.is_empty() {
+        let tag_changes: Vec<String> = tags_add.iter().map(|t| format!("+{}", t))
+            .chain(tags_remove.iter().map(|t| format!("-{}", t)))
+            .collect();
+        changes.push(format!("Tags: {}", tag_changes.join(" ")));
+    }
     
     if changes.is_empty() {
         println!("No changes specified.");
@@ -1340,6 +1521,7 @@ pub fn handle_sessions_modify(session_id: i64, args: Vec<String>, yes: bool, for
     if let Some(new_end) = new_end_ts {
         SessionRepo::modify_end_time(&conn, session_id, new_end)?;
     }
+    attributes.apply(&conn, session_id)?;
     
     println!("Modified session {}.", session_id);
     Ok(())
@@ -1520,6 +1702,10 @@ struct ProjectNode {
     full_path: String,      // Full path (e.g., "client.projectA.frontend")
     direct_secs: i64,       // Time from sessions directly on this project
     total_secs: i64,        // Direct + all children (computed after tree is built)
+    direct_billed_secs: i64, // Billed time directly on this project (--billing)
+    total_billed_secs: i64,
+    direct_amount: f64,     // Billed amount directly on this project (--billing)
+    total_amount: f64,
     children: BTreeMap<String, ProjectNode>,
 }
 
@@ -1530,17 +1716,23 @@ impl ProjectNode {
             full_path: full_path.to_string(),
             direct_secs: 0,
             total_secs: 0,
+            direct_billed_secs: 0,
+            total_billed_secs: 0,
+            direct_amount: 0.0,
+            total_amount: 0.0,
             children: BTreeMap::new(),
         }
     }
     
-    /// Recursively compute total_secs from direct_secs + children totals
+    /// Recursively compute totals from direct values + children totals
     fn compute_totals(&mut self) {
         for child in self.children.values_mut() {
             child.compute_totals();
         }
         let children_sum: i64 = self.children.values().map(|c| c.total_secs).sum();
         self.total_secs = self.direct_secs + children_sum;
+        self.total_billed_secs = self.direct_billed_secs + self.children.values().map(|c| c.total_billed_secs).sum::<i64>();
+        self.total_amount = self.direct_amount + self.children.values().map(|c| c.total_amount).sum::<f64>();
     }
 }
 
@@ -1574,6 +1766,32 @@ fn format_percentage(part_secs: i64, total_secs: i64) -> String {
     }
 }
 
+/// Format billed time as decimal hours
+fn format_hours(secs: i64) -> String {
+    format!("{:.2}", secs as f64 / 3600.0)
+}
+
+/// Value columns of a report row: time and percentage, or with --billing
+/// time, billed hours and amount
+fn format_report_cells(node: &ProjectNode, grand_total: i64, billing: bool) -> String {
+    let time_str = format_duration_hm(node.total_secs);
+    if billing {
+        format!("{:>12} {:>8} {:>10.2}", time_str, format_hours(node.total_billed_secs), node.total_amount)
+    } else {
+        format!("{:>12} {:>8}", time_str, format_percentage(node.total_secs, grand_total))
+    }
+}
+
+/// Horizontal rule under a report header
+fn report_rule(label_width: usize, billing: bool) -> String {
+    let mut rule = format!("{} {} {}", "─".repeat(label_width), "─".repeat(12), "─".repeat(8));
+    if billing {
+        rule.push(' ');
+        rule.push_str(&"─".repeat(10));
+    }
+    rule
+}
+
 /// Build project hierarchy tree from session data
 fn build_project_tree(
     conn: &Connection,
@@ -1611,7 +1829,9 @@ fn build_project_tree(
             Some(proj_name) => {
                 // Insert into hierarchy
                 let parts: Vec<&str> = proj_name.split('.').collect();
-                insert_into_tree(&mut roots, &parts, duration);
+                if let Some(node) = tree_node(&mut roots, &parts) {
+                    node.direct_secs += duration;
+                }
             }
             None => {
                 no_project_secs += duration;
@@ -1627,54 +1847,35 @@ fn build_project_tree(
     (roots, no_project_secs)
 }
 
-/// Insert time into the project hierarchy tree
-fn insert_into_tree(roots: &mut BTreeMap<String, ProjectNode>, parts: &[&str], duration: i64) {
-    if parts.is_empty() {
-        return;
-    }
-    
-    let first = parts[0];
-    let full_path = parts.join(".");
-    
+/// Find or create the node for a project path in the hierarchy tree
+fn tree_node<'a>(roots: &'a mut BTreeMap<String, ProjectNode>, parts: &[&str]) -> Option<&'a mut ProjectNode> {
+    let (first, rest) = parts.split_first()?;
+
     // Get or create root node
     let root = roots.entry(first.to_string()).or_insert_with(|| {
         ProjectNode::new(first, first)
     });
-    
-    if parts.len() == 1 {
-        // This is the target node - add direct time
-        root.direct_secs += duration;
-    } else {
-        // Recurse into children
-        insert_into_children(root, &parts[1..], &full_path, duration);
-    }
+    Some(child_node(root, rest))
 }
 
-/// Recursively insert into child nodes
-fn insert_into_children(parent: &mut ProjectNode, parts: &[&str], full_path: &str, duration: i64) {
-    if parts.is_empty() {
-        return;
-    }
-    
-    let first = parts[0];
+/// Recursively find or create a descendant node
+fn child_node<'a>(parent: &'a mut ProjectNode, parts: &[&str]) -> &'a mut ProjectNode {
+    let Some((first, rest)) = parts.split_first() else {
+        // This is the target node
+        return parent;
+    };
     // Build the full path for this node
     let child_path = if parent.full_path.is_empty() {
         first.to_string()
     } else {
         format!("{}.{}", parent.full_path, first)
     };
-    
+
     let child = parent.children.entry(first.to_string()).or_insert_with(|| {
         ProjectNode::new(first, &child_path)
     });
-    
-    if parts.len() == 1 {
-        // This is the target node
-        child.direct_secs += duration;
-    } else {
-        // Keep going deeper
-        insert_into_children(child, &parts[1..], full_path, duration);
-    }
+    // Keep going deeper
+    child_node(child, rest)
 }
 
 fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
@@ -1758,10 +1959,9 @@ fn print_project_tree(
     prefix_flags: &[bool],
     is_last: bool,
     tty_mode: bool,
+    billing: bool,
 ) {
     let name_display = render_tree_label(prefix_flags, is_last, &node.name, project_width);
-    let time_str = format_duration_hm(node.total_secs);
-    let pct_str = format_percentage(node.total_secs, total_secs);
     
     let padded = format!("{:<width$}", name_display, width = project_width);
     let project_cell = if prefix_flags.is_empty() {
@@ -1771,7 +1971,7 @@ fn print_project_tree(
         padded
     };
 
-    println!("{project_cell} {:>12} {:>8}", time_str, pct_str);
+    println!("{project_cell} {}", format_report_cells(node, total_secs, billing));
     
     let child_count = node.children.len();
     let mut children: Vec<&ProjectNode> = node.children.values().collect();
@@ -1784,13 +1984,79 @@ fn print_project_tree(
         let is_child_last = idx == child_count - 1;
         let mut child_prefix = prefix_flags.to_vec();
         child_prefix.push(!is_child_last);
-        print_project_tree(child, total_secs, project_width, &child_prefix, is_child_last, tty_mode);
+        print_project_tree(child, total_secs, project_width, &child_prefix, is_child_last, tty_mode, billing);
     }
 }
 
+/// Billed time of a report: the project tree, time without a project, and totals by client
+type BillingTree = (BTreeMap<String, ProjectNode>, ProjectNode, BTreeMap<Option<String>, ProjectNode>);
+
+/// Build the project tree from session data with billing rules applied
+fn build_billing_tree(
+    conn: &Connection,
+    sessions: &[Session],
+    period_start: i64,
+    period_end: i64,
+) -> Result<BillingTree> {
+    let rules = BillingRules::from_config(&Config::current());
+
+    // Project, client and billing rule of each task
+    let mut task_billing: std::collections::HashMap<i64, (Option<String>, Option<String>, BillingRule)> = std::collections::HashMap::new();
+    let mut tracked = Vec::new();
+    for session in sessions {
+        let duration = session_duration_in_period(session, period_start, period_end);
+        if duration == 0 {
+            continue;
+        }
+        if let std::collections::hash_map::Entry::Vacant(slot) = task_billing.entry(session.task_id) {
+            let task = TaskRepo::get_by_id(conn, session.task_id)?
+                .ok_or_else(|| anyhow::anyhow!("Task {} not found", session.task_id))?;
+            let project = match task.project_id {
+                Some(pid) => ProjectRepo::get_by_id(conn, pid)?.map(|p| p.name),
+                None => None,
+            };
+            let client = task.udas.get(&rules.client_uda).cloned();
+            let rule = rules.rule_for(project.as_deref(), &task.udas);
+            slot.insert((project, client, rule));
+        }
+        let (project, client, rule) = &task_billing[&session.task_id];
+        let day = Local.timestamp_opt(session.start_ts.max(period_start), 0)
+            .single()
+            .map(|dt| dt.date_naive())
+            .unwrap_or_default();
+        tracked.push(TrackedTime {
+            project: project.clone(),
+            client: client.clone(),
+            day,
+            secs: duration,
+            rule: rule.clone(),
+        });
+    }
+
+    let mut roots: BTreeMap<String, ProjectNode> = BTreeMap::new();
+    let mut no_project = ProjectNode::new("(no project)", "");
+    let mut clients: BTreeMap<Option<String>, ProjectNode> = BTreeMap::new();
+    for line in bill(tracked) {
+        let parts: Vec<&str> = line.project.as_deref().map(|p| p.split('.').collect()).unwrap_or_default();
+        let client_name = line.client.clone().unwrap_or_else(|| "(no client)".to_string());
+        let client = clients.entry(line.client).or_insert_with(|| ProjectNode::new(&client_name, ""));
+        let project = tree_node(&mut roots, &parts).unwrap_or(&mut no_project);
+        for node in [project, client] {
+            node.direct_secs += line.secs;
+            node.direct_billed_secs += line.billed_secs;
+            node.direct_amount += line.amount;
+        }
+    }
+    for node in roots.values_mut().chain(std::iter::once(&mut no_project)).chain(clients.values_mut()) {
+        node.compute_totals();
+    }
+    Ok((roots, no_project, clients))
+}
+
 /// Handle the sessions report command
 /// Args format: [start] [end] [filter...] or [start..end] [filter...]
-pub fn handle_sessions_report(args: Vec<String>) -> Result<()> {
+/// With `billing`, time is rounded and priced by the `billing.*` settings.
+pub fn handle_sessions_report(args: Vec<String>, billing: bool) -> Result<()> {
     let conn = DbConnection::connect()?;
     let now = chrono::Utc::now().timestamp();
     
@@ -1816,6 +2082,18 @@ pub fn handle_sessions_report(args: Vec<String>) -> Result<()> {
         }
     }
     
+    // Session tag and note filters apply to sessions; the rest select tasks
+    let mut session_filters: Vec<SessionFilterTerm> = Vec::new();
+    let mut task_filter_tokens: Vec<String> = Vec::new();
+    for token in filter_tokens {
+        match parse_session_filter_term(&token) {
+            Ok(Some(term @ (SessionFilterTerm::Tag(..) | SessionFilterTerm::Note(..)))) => session_filters.push(term),
+            Ok(_) => task_filter_tokens.push(token),
+            Err(e) => user_error(&format!("Session filter error: {}", e)),
+        }
+    }
+    let filter_tokens = task_filter_tokens;
+
     // Parse date arguments
     let (period_start, period_end) = parse_report_date_args(&conn, &date_args, now)?;
     
@@ -1844,6 +2122,15 @@ pub fn handle_sessions_report(args: Vec<String>) -> Result<()> {
         
         sessions.retain(|s| matching_task_ids.contains(&s.task_id));
     }
+
+    if !session_filters.is_empty() {
+        let tags_by_session = SessionRepo::tags_by_session(&conn)?;
+        let no_tags = Vec::new();
+        sessions.retain(|s| {
+            let tags = s.id.and_then(|id| tags_by_session.get(&id)).unwrap_or(&no_tags);
+            session_filters.iter().all(|term| session_matches_filter(s, "", tags, term))
+        });
+    }
     
     if sessions.is_empty() {
         println!("No sessions found for this period.");
@@ -1851,11 +2138,25 @@ pub fn handle_sessions_report(args: Vec<String>) -> Result<()> {
     }
     
     // Build project hierarchy tree
-    let (roots, no_project_secs) = build_project_tree(&conn, &sessions, period_start, period_end);
+    let (roots, no_project, clients) = if billing {
+        build_billing_tree(&conn, &sessions, period_start, period_end)?
+    } else {
+        let (roots, no_project_secs) = build_project_tree(&conn, &sessions, period_start, period_end);
+        let mut no_project = ProjectNode::new("(no project)", "");
+        no_project.direct_secs = no_project_secs;
+        no_project.compute_totals();
+        (roots, no_project, BTreeMap::new())
+    };
     
     // Calculate grand total
-    let project_total: i64 = roots.values().map(|n| n.total_secs).sum();
-    let grand_total = project_total + no_project_secs;
+    let mut total = ProjectNode::new("TOTAL", "");
+    for node in roots.values().chain(std::iter::once(&no_project)) {
+        total.direct_secs += node.total_secs;
+        total.direct_billed_secs += node.total_billed_secs;
+        total.direct_amount += node.total_amount;
+    }
+    total.compute_totals();
+    let grand_total = total.total_secs;
     
     // Format date range
     let start_date = Local.timestamp_opt(period_start, 0)
@@ -1878,34 +2179,234 @@ pub fn handle_sessions_report(args: Vec<String>) -> Result<()> {
     }
     let tty_mode = is_tty();
     
-    println!("{:<width$} {:>12} {:>8}", "Project", "Time", "%", width = project_width);
-    println!("{} {} {}", "─".repeat(project_width), "─".repeat(12), "─".repeat(8));
+    if billing {
+        println!("{:<width$} {:>12} {:>8} {:>10}", "Project", "Time", "Hours", "Amount", width = project_width);
+    } else {
+        println!("{:<width$} {:>12} {:>8}", "Project", "Time", "%", width = project_width);
+    }
+    println!("{}", report_rule(project_width, billing));
     
     // Print project hierarchy
     for (idx, node) in roots.values().enumerate() {
         let is_last = idx == root_count - 1;
-        print_project_tree(node, grand_total, project_width, &[], is_last, tty_mode);
+        print_project_tree(node, grand_total, project_width, &[], is_last, tty_mode, billing);
     }
     
     // Print no-project time if any
-    if no_project_secs > 0 {
-        let time_str = format_duration_hm(no_project_secs);
-        let pct_str = format_percentage(no_project_secs, grand_total);
-        println!("{:<width$} {:>12} {:>8}", "(no project)", time_str, pct_str, width = project_width);
+    if no_project.total_secs > 0 {
+        println!("{:<width$} {}", "(no project)", format_report_cells(&no_project, grand_total, billing), width = project_width);
     }
     
-    println!("{} {} {}", "─".repeat(project_width), "─".repeat(12), "─".repeat(8));
+    println!("{}", report_rule(project_width, billing));
     
     // Print grand total
-    let total_time_str = format_duration_hm(grand_total);
-    println!("{:<width$} {:>12} {:>8}", "TOTAL", total_time_str, "100.0%", width = project_width);
+    println!("{:<width$} {}", "TOTAL", format_report_cells(&total, grand_total, billing), width = project_width);
     println!();
+
+    // Print totals by client, with clients that have no name last
+    if billing {
+        let client_width = clients.values().map(|c| c.name.chars().count()).max().unwrap_or(0).max("Client".len());
+        println!("{:<width$} {:>12} {:>8} {:>10}", "Client", "Time", "Hours", "Amount", width = client_width);
+        println!("{}", report_rule(client_width, billing));
+        let mut client_nodes: Vec<&ProjectNode> = clients.iter().filter(|(c, _)| c.is_some()).map(|(_, n)| n).collect();
+        client_nodes.extend(clients.get(&None));
+        for node in client_nodes {
+            println!("{:<width$} {}", node.name, format_report_cells(node, grand_total, billing), width = client_width);
+        }
+        println!("{}", report_rule(client_width, billing));
+        println!("{:<width$} {}", "TOTAL", format_report_cells(&total, grand_total, billing), width = client_width);
+        println!();
+    }
     println!("Sessions: {} | Period: {} days | {}..{}", sessions.len(), period_days, start_date, end_date);
     println!();
     
     Ok(())
 }
 
+/// Whether an export argument is the date range rather than a filter
+fn is_export_range_arg(arg: &str) -> bool {
+    if arg.starts_with('+') || arg.contains(['=', '<', '>']) || arg.starts_with("start:") || arg.starts_with("end:") {
+        return false;
+    }
+    // Bare numbers are task IDs
+    arg.contains("..") || (arg.parse::<i64>().is_err() && parse_date_expr(arg).is_ok())
+}
+
+/// Handle `sessions export`: write closed sessions as a timesheet
+/// Args format: [<start>..<end> | <start>] [filter...]
+pub fn handle_sessions_export(format: String, round: Option<String>, args: Vec<String>) -> Result<()> {
+    let format = ExportFormat::parse(&format)
+        .unwrap_or_else(|| user_error(&format!("Unknown export format '{}'. Use csv, ics or jsonl.", format)));
+    let increment = match round {
+        Some(expr) => parse_duration(&expr)
+            .unwrap_or_else(|e| user_error(&format!("Invalid rounding increment '{}': {}", expr, e))),
+        None => 0,
+    };
+
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    let now = chrono::Utc::now().timestamp();
+
+    let mut date_args = Vec::new();
+    let mut filter_args = Vec::new();
+    for arg in args {
+        if date_args.is_empty() && is_export_range_arg(&arg) {
+            date_args.push(arg);
+        } else {
+            filter_args.push(arg);
+        }
+    }
+    let (period_start, period_end) = parse_report_date_args(&conn, &date_args, now)
+        .unwrap_or_else(|e| user_error(&format!("{:#}", e)));
+
+    let mut sessions = select_sessions(&conn, filter_args)?.sessions;
+    let open = sessions.iter().filter(|s| s.is_open()).count();
+    if open > 0 {
+        eprintln!("Note: skipping {} running session(s)", open);
+    }
+    sessions.retain(|s| s.end_ts.is_some_and(|end| s.start_ts < period_end && end > period_start));
+    sessions.sort_by_key(|s| (s.start_ts, s.id));
+
+    let mut entries = Vec::new();
+    let mut task_cache = std::collections::HashMap::new();
+    for session in sessions {
+        if let std::collections::hash_map::Entry::Vacant(slot) = task_cache.entry(session.task_id) {
+            let task = TaskRepo::get_by_id(&conn, session.task_id)?
+                .ok_or_else(|| anyhow::anyhow!("Task {} not found", session.task_id))?;
+            let project = match task.project_id {
+                Some(pid) => ProjectRepo::get_by_id(&conn, pid)?.map(|p| p.name),
+                None => None,
+            };
+            let tags = TaskRepo::get_tags(&conn, session.task_id)?;
+            slot.insert((task, project, tags));
+        }
+        let (task, project, tags) = &task_cache[&session.task_id];
+
+        // Clip to the requested range, then round the boundaries
+        let start_ts = round_to_increment(session.start_ts.max(period_start), increment);
+        let end_ts = round_to_increment(session.end_ts.unwrap_or(now).min(period_end), increment);
+        entries.push(TimesheetEntry {
+            session_id: session.id.unwrap_or(0),
+            task_id: session.task_id,
+            task_uuid: task.uuid.clone(),
+            description: task.description.clone(),
+            project: project.clone(),
+            tags: tags.clone(),
+            udas: task.udas.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            start_ts,
+            end_ts: end_ts.max(start_ts),
+        });
+    }
+
+    let output = match format {
+        ExportFormat::Csv => to_csv(&entries),
+        ExportFormat::Ics => to_ics(&entries, now),
+        ExportFormat::Jsonl => to_jsonl(&entries),
+    };
+    print!("{}", output);
+    Ok(())
+}
+
+/// Handle `tatl sessions audit [--fix] [--yes] [<session_id>...]`
+pub fn handle_sessions_audit(session_ids: Vec<i64>, fix: bool, yes: bool) -> Result<()> {
+    let conn = DbConnection::connect()
+        .context("Failed to connect to database")?;
+    let now = chrono::Utc::now().timestamp();
+
+    let sessions = SessionRepo::list_all(&conn)?;
+    let findings: Vec<Finding> = audit(&sessions, &SessionLimits::current(), now)
+        .into_iter()
+        .filter(|f| session_ids.is_empty() || f.session.id.is_some_and(|id| session_ids.contains(&id)))
+        .collect();
+    if findings.is_empty() {
+        println!("No suspicious sessions found.");
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 7]> = findings.iter()
+        .map(|f| [
+            f.session.id.map(|id| id.to_string()).unwrap_or_default(),
+            f.session.task_id.to_string(),
+            format_timestamp(f.session.start_ts),
+            f.session.end_ts.map(format_timestamp).unwrap_or_else(|| "(running)".to_string()),
+            format_duration(f.session.end_ts.unwrap_or(now) - f.session.start_ts),
+            f.problem.description(),
+            f.problem.fix_description(),
+        ])
+        .collect();
+    let header = ["Session", "Task", "Start", "End", "Duration", "Problem", "Fix"];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| rows.iter().map(|row| row[col].chars().count()).chain([header[col].len()]).max().unwrap_or(0))
+        .collect();
+    let format_row = |cells: &[&str]| {
+        cells.iter().zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", format_row(&header));
+    println!("{}", widths.iter().map(|w| "─".repeat(*w)).collect::<Vec<_>>().join(" "));
+    for row in &rows {
+        println!("{}", format_row(&row.iter().map(String::as_str).collect::<Vec<_>>()));
+    }
+
+    if !fix {
+        println!("\nFix with: tatl sessions audit --fix [<session_id>...]");
+        return Ok(());
+    }
+
+    // One fix per session; an earlier fix can change what else is wrong with it
+    let mut seen = std::collections::HashSet::new();
+    let to_fix: Vec<&Finding> = findings.iter()
+        .filter(|f| f.session.id.is_some_and(|id| seen.insert(id)))
+        .collect();
+
+    if !yes {
+        print!("\nApply {} fix(es)? (y/n): ", to_fix.len());
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if input.trim().to_lowercase() != "y" {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for finding in &to_fix {
+        let session_id = finding.session.id.expect("filtered above");
+        // Earlier fixes may have changed or removed this session
+        let Some(session) = SessionRepo::get_by_id(&tx, session_id)? else {
+            continue;
+        };
+        apply_audit_fix(&tx, &session, &finding.problem)?;
+        println!("Session {}: {}.", session_id, finding.problem.fixed_description());
+    }
+    tx.commit()?;
+
+    if findings.len() > to_fix.len() {
+        println!("Run 'tatl sessions audit' again to check the remaining {} problem(s).", findings.len() - to_fix.len());
+    }
+    Ok(())
+}
+
+/// Fix a suspicious session
+fn apply_audit_fix(conn: &Connection, session: &Session, problem: &Problem) -> Result<()> {
+    let session_id = session.id.expect("stored session has an id");
+    match problem {
+        Problem::ZeroLength => SessionRepo::delete(conn, session_id),
+        Problem::Overlap { start, end, .. } => modify_session_for_removal(conn, session, *start, *end),
+        Problem::TooLong { max_duration } => {
+            let cap = session.start_ts + max_duration;
+            modify_session_for_removal(conn, session, cap, session.end_ts.unwrap_or(i64::MAX))
+        }
+        // Removing an empty interval splits the session there
+        Problem::CrossesMidnight { midnight } => modify_session_for_removal(conn, session, *midnight, *midnight),
+    }
+}
+
 /// Check if a string looks like a time expression (not a filter token)
 fn is_time_like(s: &str) -> bool {
     // Times look like: "14:30", "09:00", but not "project=work"
@@ -269,6 +269,25 @@ pub fn validate_template_name(name: &str) -> Result<(), String> {
     }
 }
 
+/// Validate queue name format
+pub fn validate_queue_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Queue name cannot be empty".to_string());
+    }
+
+    // "any" and "none" are reserved for the queue= filter
+    if name == "any" || name == "none" {
+        return Err(format!("Invalid queue name: '{}' is reserved.", name));
+    }
+
+    // Queue names: alphanumeric, dots, underscores, hyphens
+    if name.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '_' || c == '-') {
+        Ok(())
+    } else {
+        Err(format!("Invalid queue name: '{}'. Queue names can only contain letters, numbers, dots, underscores, and hyphens.", name))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,6 +328,16 @@ mod tests {
         assert!(validate_project_name("work home").is_err());
     }
 
+    #[test]
+    fn test_validate_queue_name() {
+        assert!(validate_queue_name("oncall").is_ok());
+        assert!(validate_queue_name("work.personal").is_ok());
+        assert!(validate_queue_name("").is_err());
+        assert!(validate_queue_name("none").is_err());
+        assert!(validate_queue_name("on call").is_err());
+        assert!(validate_queue_name("a,b").is_err());
+    }
+
     #[test]
     fn test_validate_tag() {
         assert!(validate_tag("urgent").is_ok());
@@ -1,11 +1,17 @@
 pub mod commands;
 pub mod commands_sessions;
+pub mod commands_history;
+pub mod commands_config;
 pub mod error;
 pub mod output;
 pub mod parser;
 pub mod status;
 pub mod abbrev;
 pub mod priority;
+pub mod timesheet;
+pub mod billing;
+pub mod session_audit;
+pub mod pomodoro;
 
 pub use commands::*;
 pub use parser::*;
@@ -1,8 +1,10 @@
 // Output formatting utilities
 
-use crate::models::{Task, TaskStatus, StageMapping};
-use crate::repo::{AnnotationRepo, ProjectRepo, SessionRepo, StackRepo, TaskRepo, ExternalRepo, StageRepo};
-use crate::cli::priority::calculate_priority;
+use crate::config::Config;
+use crate::models::{Task, TaskStatus, StageMapping, UdaDefinition, UdaType};
+use crate::repo::{AnnotationRepo, ProjectRepo, SessionRepo, StackRepo, TaskRepo, ExternalRepo, StageRepo, UdaRepo, DependencyRepo};
+use crate::cli::priority::{calculate_priority, UrgencyTerm};
+use crate::respawn::{project_occurrences, remaining_occurrences, RespawnPattern, RespawnRule};
 use chrono::Local;
 use rusqlite::Connection;
 use anyhow::Result;
@@ -438,22 +440,23 @@ fn bold_if_tty(text: &str, is_tty: bool) -> String {
 /// of task state booleans. Falls back to hardcoded defaults if no stage map
 /// is provided.
 ///
-/// Note: Q column shows exact queue position (0, 1, 2, etc. or @ for external)
+/// Note: Q column shows exact queue position (0, 1, 2, etc. or @ for external);
+/// in_queue reflects membership in any named queue.
 pub fn calculate_stage_status(
     task: &Task,
-    stack_position: Option<usize>,
+    in_queue: bool,
     has_sessions: bool,
     open_session_task_id: Option<i64>,
     has_externals: bool,
+    is_blocked: bool,
     stage_map: Option<&[StageMapping]>,
 ) -> String {
     let status = task.status.as_str();
-    let in_queue = stack_position.is_some();
     let has_open_session = open_session_task_id.map_or(false, |tid| task.id == Some(tid));
 
     if let Some(mappings) = stage_map {
         if let Some(mapping) = StageRepo::lookup_from_cache(
-            mappings, status, in_queue, has_sessions, has_open_session, has_externals,
+            mappings, status, in_queue, has_sessions, has_open_session, has_externals, is_blocked,
         ) {
             return mapping.stage.clone();
         }
@@ -472,6 +475,9 @@ pub fn calculate_stage_status(
     if has_externals {
         return "external".to_string();
     }
+    if is_blocked {
+        return "blocked".to_string();
+    }
     match (in_queue, has_sessions) {
         (true, true) => "in progress".to_string(),
         (true, false) => "planned".to_string(),
@@ -481,8 +487,15 @@ pub fn calculate_stage_status(
 }
 
 /// Get stack positions for all task IDs as a map (task_id -> position)
-fn get_stack_positions(conn: &Connection) -> Result<HashMap<i64, usize>> {
-    let stack = StackRepo::get_or_create_default(conn)?;
+/// Uses the named queue if given, otherwise the active queue.
+fn get_stack_positions(conn: &Connection, queue: Option<&str>) -> Result<HashMap<i64, usize>> {
+    let stack = match queue {
+        Some(name) => match StackRepo::get_by_name(conn, name)? {
+            Some(stack) => stack,
+            None => return Ok(HashMap::new()),
+        },
+        None => StackRepo::get_active(conn)?,
+    };
     let items = StackRepo::get_items(conn, stack.id.unwrap())?;
     
     let mut positions = HashMap::new();
@@ -513,7 +526,7 @@ pub fn format_timestamp(ts: i64) -> String {
     let dt = Local.timestamp_opt(ts, 0)
         .single()
         .unwrap_or_else(|| Local.timestamp_opt(0, 0).single().unwrap());
-    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+    dt.format(&format!("{} %H:%M:%S", Config::current().date_format())).to_string()
 }
 
 /// Format date for display (date only, no time)
@@ -522,7 +535,21 @@ pub fn format_date(ts: i64) -> String {
     let dt = Local.timestamp_opt(ts, 0)
         .single()
         .unwrap_or_else(|| Local.timestamp_opt(0, 0).single().unwrap());
-    dt.format("%Y-%m-%d").to_string()
+    dt.format(Config::current().date_format()).to_string()
+}
+
+/// Format a respawn occurrence: date and weekday, plus the time for cron rules
+/// (other patterns keep the time of day of the task's due date)
+pub fn format_occurrence(rule: &RespawnRule, ts: i64) -> String {
+    use chrono::TimeZone;
+    let dt = Local.timestamp_opt(ts, 0)
+        .single()
+        .unwrap_or_else(|| Local.timestamp_opt(0, 0).single().unwrap());
+    let date_format = Config::current().date_format().to_string();
+    match rule.pattern {
+        RespawnPattern::Cron(_) => dt.format(&format!("{} %a %H:%M", date_format)).to_string(),
+        _ => dt.format(&format!("{} %a", date_format)).to_string(),
+    }
 }
 
 /// Format date as relative time (e.g., "2 days ago", "in 3 days", "today", "overdue")
@@ -585,9 +612,11 @@ pub struct TaskListOptions {
     pub sort_columns: Vec<String>,
     pub group_columns: Vec<String>,
     pub hide_columns: Vec<String>,
+    pub show_columns: Vec<String>, // Extra columns to show (e.g. uda.<key>)
     pub color_column: Option<String>,  // Column for text color
     pub fill_column: Option<String>,   // Column for background color
     pub full_width: bool, // Show all columns regardless of terminal width
+    pub queue: Option<String>, // Queue shown in the Q column (None = active queue)
 }
 
 /// Parse a sort specification, detecting negation prefix for descending order
@@ -751,7 +780,7 @@ fn get_row_colors(
     (fg_color, bg_color, reset_needed)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum TaskListColumn {
     Id,
     Queue,
@@ -767,6 +796,7 @@ enum TaskListColumn {
     Modified,
     Activity,
     Status,
+    Uda(String), // User-defined attribute, shown as `uda.<key>`
 }
 
 /// Column display priority for adaptive width (lower = more important)
@@ -775,7 +805,7 @@ enum TaskListColumn {
 /// Priority 4+: Secondary/Optional (hide first)
 /// 
 /// Hide order (first to last): Status -> Modified -> Tags -> Priority -> Alloc -> Timer -> Activity -> Created -> Stage -> Due
-fn column_priority(column: TaskListColumn) -> u8 {
+fn column_priority(column: &TaskListColumn) -> u8 {
     match column {
         TaskListColumn::Id => 1,          // Never hide
         TaskListColumn::Queue => 1,       // Never hide
@@ -789,13 +819,14 @@ fn column_priority(column: TaskListColumn) -> u8 {
         TaskListColumn::Alloc => 9,
         TaskListColumn::Priority => 10,
         TaskListColumn::Tags => 11,
+        TaskListColumn::Uda(_) => 11,
         TaskListColumn::Modified => 12,   // Hidden before Created
         TaskListColumn::Status => 13,     // Hidden first
     }
 }
 
 /// Minimum column width before hiding
-fn column_min_width(column: TaskListColumn) -> usize {
+fn column_min_width(column: &TaskListColumn) -> usize {
     match column {
         TaskListColumn::Id => 4,
         TaskListColumn::Queue => 4,
@@ -811,6 +842,7 @@ fn column_min_width(column: TaskListColumn) -> usize {
         TaskListColumn::Created => 10,
         TaskListColumn::Modified => 10,
         TaskListColumn::Activity => 10,
+        TaskListColumn::Uda(_) => 6,
     }
 }
 
@@ -830,6 +862,9 @@ struct TaskRow {
 }
 
 fn parse_task_column(name: &str) -> Option<TaskListColumn> {
+    if let Some(key) = name.strip_prefix("uda.") {
+        return (!key.is_empty()).then(|| TaskListColumn::Uda(key.to_string()));
+    }
     match name.to_lowercase().as_str() {
         "id" => Some(TaskListColumn::Id),
         "q" | "queue" => Some(TaskListColumn::Queue),
@@ -849,7 +884,8 @@ fn parse_task_column(name: &str) -> Option<TaskListColumn> {
     }
 }
 
-fn column_label(column: TaskListColumn) -> &'static str {
+/// Header label for a column; UDA columns use the definition's display label if any
+fn column_label<'a>(column: &'a TaskListColumn, udas: &'a HashMap<String, UdaDefinition>) -> &'a str {
     match column {
         TaskListColumn::Queue => "Q",
         TaskListColumn::Id => "ID",
@@ -865,6 +901,7 @@ fn column_label(column: TaskListColumn) -> &'static str {
         TaskListColumn::Status => "Status",
         TaskListColumn::Stage => "Stage",
         TaskListColumn::Priority => "Priority",
+        TaskListColumn::Uda(key) => udas.get(key).map(|def| def.display_label()).unwrap_or(key),
     }
 }
 
@@ -1004,6 +1041,16 @@ fn apply_tree_ordering(rows: &mut Vec<TaskRow>) {
     *rows = new_rows;
 }
 
+/// Display form of a stored UDA value: dates and durations are stored as seconds
+fn format_uda_value(value: &str, definition: Option<&UdaDefinition>) -> String {
+    let secs = value.parse::<i64>().ok();
+    match (definition.map(|def| def.uda_type), secs) {
+        (Some(UdaType::Date), Some(ts)) => format_date(ts),
+        (Some(UdaType::Duration), Some(secs)) => format_duration(secs),
+        _ => value.to_string(),
+    }
+}
+
 /// Format task list as a table
 pub fn format_task_list_table(
     conn: &Connection,
@@ -1015,11 +1062,14 @@ pub fn format_task_list_table(
     }
     
     // Pre-compute stage-related data for all tasks (batch queries for performance)
-    let stack_positions = get_stack_positions(conn)?;
+    let stack_positions = get_stack_positions(conn, options.queue.as_deref())?;
+    let queued_task_ids = StackRepo::get_queued_task_ids(conn)?;
     let tasks_with_sessions = get_tasks_with_sessions(conn)?;
     let tasks_with_externals = get_tasks_with_externals(conn)?;
     let open_session_task_id = SessionRepo::get_open(conn)?.map(|s| s.task_id);
+    let blocked_task_ids = DependencyRepo::get_blocked_task_ids(conn)?;
     let stage_map = StageRepo::load_map(conn).unwrap_or_default();
+    let uda_definitions = UdaRepo::load_map(conn)?;
     
     let mut rows: Vec<TaskRow> = Vec::new();
     for (task, tags) in tasks {
@@ -1029,10 +1079,11 @@ pub fn format_task_list_table(
         let has_externals = tasks_with_externals.contains(&task_id);
         let stage = calculate_stage_status(
             task,
-            stack_pos,
+            queued_task_ids.contains(&task_id),
             has_sessions,
             open_session_task_id,
             has_externals,
+            blocked_task_ids.contains(&task_id),
             Some(&stage_map),
         );
         
@@ -1139,6 +1190,9 @@ pub fn format_task_list_table(
         values.insert(TaskListColumn::Priority, priority.clone());
         values.insert(TaskListColumn::Timer, clock.clone());
         values.insert(TaskListColumn::Status, task.status.as_str().to_string());
+        for (key, value) in &task.udas {
+            values.insert(TaskListColumn::Uda(key.clone()), format_uda_value(value, uda_definitions.get(key)));
+        }
         
         let mut sort_values = HashMap::new();
         sort_values.insert(TaskListColumn::Id, task.id.map(SortValue::Int));
@@ -1164,6 +1218,19 @@ pub fn format_task_list_table(
             None
         });
         sort_values.insert(TaskListColumn::Status, Some(SortValue::Int(status_sort_order(task.status.as_str()))));
+        // UDA values sort numerically when they parse as numbers and enums by declared
+        // order; missing values sort last
+        for (key, value) in &task.udas {
+            let enum_position = uda_definitions.get(key)
+                .filter(|def| def.uda_type == UdaType::Enum)
+                .and_then(|def| def.enum_position(value));
+            let sort_value = match (enum_position, value.parse::<f64>()) {
+                (Some(pos), _) => SortValue::Int(pos as i64),
+                (None, Ok(n)) => SortValue::Float(n),
+                (None, Err(_)) => SortValue::Str(value.clone()),
+            };
+            sort_values.insert(TaskListColumn::Uda(key.clone()), Some(sort_value));
+        }
         
         rows.push(TaskRow {
             task_id,
@@ -1215,6 +1282,13 @@ pub fn format_task_list_table(
             columns.push(column);
         }
     }
+    for col in &options.show_columns {
+        let column = parse_task_column(col)
+            .ok_or_else(|| anyhow::anyhow!("Unknown column: {}", col))?;
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
     
     // Remove hidden columns
     let hidden_columns: Vec<TaskListColumn> = options.hide_columns.iter()
@@ -1229,8 +1303,8 @@ pub fn format_task_list_table(
     let mut column_widths: HashMap<TaskListColumn, usize> = HashMap::new();
     for column in &columns {
         // Use character count for header labels too (though they're ASCII, this is consistent)
-        let label = column_label(*column);
-        column_widths.insert(*column, label.chars().count().max(4));
+        let label = column_label(column, &uda_definitions);
+        column_widths.insert(column.clone(), label.chars().count().max(4));
     }
 
     for row in &rows {
@@ -1243,7 +1317,7 @@ pub fn format_task_list_table(
                 } else {
                     char_count
                 };
-                let entry = column_widths.entry(*column).or_insert(4);
+                let entry = column_widths.entry(column.clone()).or_insert(4);
                 *entry = (*entry).max(max_len);
             }
         }
@@ -1279,7 +1353,7 @@ pub fn format_task_list_table(
         if current_total > target_width && columns.contains(&TaskListColumn::Description) {
             if let Some(width) = column_widths.get_mut(&TaskListColumn::Description) {
                 let excess = current_total.saturating_sub(target_width);
-                let new_width = (*width).saturating_sub(excess).max(column_min_width(TaskListColumn::Description));
+                let new_width = (*width).saturating_sub(excess).max(column_min_width(&TaskListColumn::Description));
                 *width = new_width;
                 current_total = calc_total_width(&columns, &column_widths);
             }
@@ -1289,7 +1363,7 @@ pub fn format_task_list_table(
         if current_total > target_width && columns.contains(&TaskListColumn::Project) {
             if let Some(width) = column_widths.get_mut(&TaskListColumn::Project) {
                 let excess = current_total.saturating_sub(target_width);
-                let new_width = (*width).saturating_sub(excess).max(column_min_width(TaskListColumn::Project));
+                let new_width = (*width).saturating_sub(excess).max(column_min_width(&TaskListColumn::Project));
                 *width = new_width;
             }
         }
@@ -1305,9 +1379,9 @@ pub fn format_task_list_table(
             
             // Find the lowest priority column (highest priority number) that can be hidden
             let hide_candidate = columns.iter()
-                .filter(|c| column_priority(**c) > 3) // Never hide priority 1-3 columns (ID, Queue, Description, Project)
-                .max_by_key(|c| column_priority(**c))
-                .copied();
+                .filter(|c| column_priority(c) > 3) // Never hide priority 1-3 columns (ID, Queue, Description, Project)
+                .max_by_key(|c| column_priority(c))
+                .cloned();
 
             if let Some(col_to_hide) = hide_candidate {
                 columns.retain(|c| *c != col_to_hide);
@@ -1324,9 +1398,9 @@ pub fn format_task_list_table(
     for (idx, column) in columns.iter().enumerate() {
         let width = *column_widths.get(column).unwrap_or(&4);
         if idx == columns.len() - 1 {
-            header_line.push_str(&format!("{:<width$}", column_label(*column), width = width));
+            header_line.push_str(&format!("{:<width$}", column_label(column, &uda_definitions), width = width));
         } else {
-            header_line.push_str(&format!("{:<width$} ", column_label(*column), width = width));
+            header_line.push_str(&format!("{:<width$} ", column_label(column, &uda_definitions), width = width));
         }
     }
     let mut separator_line = String::new();
@@ -1512,8 +1586,8 @@ pub fn format_task_list_table(
         let fill_column_enum = options.fill_column.as_deref()
             .and_then(|name| parse_task_column(name));
         
-        let color_matches_group = color_column_enum.map(|col| group_columns_parsed.contains(&col)).unwrap_or(false);
-        let fill_matches_group = fill_column_enum.map(|col| group_columns_parsed.contains(&col)).unwrap_or(false);
+        let color_matches_group = color_column_enum.as_ref().map(|col| group_columns_parsed.contains(col)).unwrap_or(false);
+        let fill_matches_group = fill_column_enum.as_ref().map(|col| group_columns_parsed.contains(col)).unwrap_or(false);
         let color_or_fill_matches_group = color_matches_group || fill_matches_group;
         
         // Use the group_columns_parsed from earlier (already handles negation prefix)
@@ -1523,7 +1597,7 @@ pub fn format_task_list_table(
             let group_values: Vec<String> = group_columns_parsed.iter()
                 .map(|column| {
                     let value = row.values.get(column).cloned().unwrap_or_default();
-                    normalize_group_value(*column, &value)
+                    normalize_group_value(column, &value)
                 })
                 .collect();
             let group_key = group_values.join("\u{1f}");
@@ -1552,7 +1626,7 @@ pub fn format_task_list_table(
                 if color_matches_group {
                     // Find which group column matches the color_column
                     let color_col_idx = group_columns_parsed.iter()
-                        .position(|&col| Some(col) == color_column_enum);
+                        .position(|col| color_column_enum.as_ref() == Some(col));
                     let group_value = color_col_idx
                         .and_then(|idx| group_values.get(idx))
                         .unwrap_or(&group_values[0]); // Fallback to first if not found
@@ -1566,7 +1640,7 @@ pub fn format_task_list_table(
                 if fill_matches_group {
                     // Find which group column matches the fill_column
                     let fill_col_idx = group_columns_parsed.iter()
-                        .position(|&col| Some(col) == fill_column_enum);
+                        .position(|col| fill_column_enum.as_ref() == Some(col));
                     let group_value = fill_col_idx
                         .and_then(|idx| group_values.get(idx))
                         .unwrap_or(&group_values[0]); // Fallback to first if not found
@@ -1670,7 +1744,7 @@ pub fn format_task_list_table(
     Ok(output)
 }
 
-fn normalize_group_value(column: TaskListColumn, value: &str) -> String {
+fn normalize_group_value(column: &TaskListColumn, value: &str) -> String {
     let trimmed = value.trim();
     match column {
         TaskListColumn::Status | TaskListColumn::Stage => trimmed.to_lowercase(),
@@ -1839,6 +1913,16 @@ pub fn format_clock_transition(
     }
 }
 
+/// Format a progress bar such as `[=====---------------] 25%`
+pub fn format_progress_bar(done: i64, total: i64) -> String {
+    let ratio = if total > 0 { done as f64 / total as f64 } else { 0.0 };
+    let pct = (ratio * 100.0).round() as i64;
+    let bar_width = 20;
+    let filled = ((ratio * bar_width as f64).round() as usize).min(bar_width);
+    let empty = bar_width - filled;
+    format!("[{}{}] {}%", "=".repeat(filled), "-".repeat(empty), pct)
+}
+
 /// Format brief context shown when starting timing (`tatl on`).
 /// Shows annotations (bulleted, no timestamps) and a timer progress bar.
 pub fn format_on_context(
@@ -1859,18 +1943,11 @@ pub fn format_on_context(
 
     match alloc_secs {
         Some(alloc) if alloc > 0 => {
-            let pct = (logged as f64 / alloc as f64 * 100.0).round() as i64;
-            let bar_width = 20;
-            let filled = ((logged as f64 / alloc as f64) * bar_width as f64).round() as usize;
-            let filled = filled.min(bar_width);
-            let empty = bar_width - filled;
-            let bar: String = "=".repeat(filled) + &"-".repeat(empty);
             output.push_str(&format!(
-                "  Timer: {} / {} [{}] {}%\n",
+                "  Timer: {} / {} {}\n",
                 format_duration(logged),
                 format_duration(alloc),
-                bar,
-                pct,
+                format_progress_bar(logged, alloc),
             ));
         }
         _ => {
@@ -2000,15 +2077,34 @@ pub fn format_task_summary(
             }
             output.push_str("\n");
         }
+
+        // Dependencies, in both directions
+        let sections = [
+            ("Depends On", DependencyRepo::get_dependencies(conn, task_id)?),
+            ("Blocks", DependencyRepo::get_dependents(conn, task_id)?),
+        ];
+        for (title, ids) in sections {
+            if ids.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("{} ({}):\n", title, ids.len()));
+            for id in ids {
+                if let Some(other) = TaskRepo::get_by_id(conn, id)? {
+                    output.push_str(&format!("  {:<6} {:<10} {}\n", id, other.status.as_str(), other.description));
+                }
+            }
+            output.push('\n');
+        }
     }
 
     // User-Defined Attributes
     if !task.udas.is_empty() {
         output.push_str("User-Defined Attributes:\n");
+        let definitions = UdaRepo::load_map(conn)?;
         let mut udas: Vec<_> = task.udas.iter().collect();
         udas.sort_by_key(|(k, _)| *k);
         for (key, value) in udas {
-            output.push_str(&format!("  {}:    {}\n", key, value));
+            output.push_str(&format!("  {}:    {}\n", key, format_uda_value(value, definitions.get(key))));
         }
         output.push_str("\n");
     }
@@ -2024,17 +2120,58 @@ pub fn format_task_summary(
         if let Ok(priority) = calculate_priority(task, conn) {
             output.push_str("Priority:\n");
             output.push_str(&format!("  Score:       {:.1}\n", priority));
-            output.push_str("  (Auto-calculated based on due date, allocation remaining, and task age)\n\n");
+            output.push_str("  (Weighted sum of urgency terms; see tatl show --explain-priority)\n\n");
         }
     }
 
     // Respawn details (if respawning)
-    if task.respawn.is_some() {
+    if let Some(ref respawn) = task.respawn {
         output.push_str("Respawn:\n");
-        output.push_str(&format!("  Type:        {}\n", task.respawn.as_ref().unwrap()));
-        // TODO: Add more respawn details if needed (next occurrence, etc.)
+        output.push_str(&format!("  Type:        {}\n", respawn));
+        if let Ok(rule) = RespawnRule::parse(respawn) {
+            output.push_str(&format!("  Rule:        {}\n", rule.describe()));
+            if task.status == TaskStatus::Open {
+                let remaining = match task.id {
+                    Some(task_id) => remaining_occurrences(conn, &rule, task_id)?,
+                    None => None,
+                };
+                if let Some(remaining) = remaining {
+                    output.push_str(&format!("  Remaining:   {} more occurrence{}\n", remaining, if remaining == 1 { "" } else { "s" }));
+                }
+                // Projected as if each instance is completed on its due date
+                let from_ts = task.due_ts.unwrap_or_else(|| chrono::Utc::now().timestamp());
+                let limit = remaining.map_or(3, |r| (r as usize).min(3));
+                let upcoming = project_occurrences(&rule, from_ts, task.due_ts, limit);
+                if upcoming.is_empty() {
+                    output.push_str("  Next:        (none, the rule ends with this instance)\n");
+                }
+                for (idx, ts) in upcoming.iter().enumerate() {
+                    let label = if idx == 0 { "  Next:        " } else { "               " };
+                    output.push_str(&format!("{}{}\n", label, format_occurrence(&rule, *ts)));
+                }
+            }
+        }
         output.push_str("\n");
     }
+
+    // Respawn chain: every instance this one was generated from, with its logged time
+    if let Some(task_id) = task.id {
+        let chain = TaskRepo::get_respawn_chain(conn, task_id)?;
+        if chain.len() > 1 {
+            output.push_str(&format!("Respawn Chain ({} instances):\n", chain.len()));
+            let mut chain_total = 0;
+            for instance_id in &chain {
+                let Some(instance) = TaskRepo::get_by_id(conn, *instance_id)? else { continue };
+                let logged = TaskRepo::get_total_logged_time(conn, *instance_id)?;
+                chain_total += logged;
+                let due = instance.due_ts.map(format_date).unwrap_or_else(|| "-".to_string());
+                let marker = if *instance_id == task_id { "  (this task)" } else { "" };
+                output.push_str(&format!("  {:<6} {:<10} {:<10} {}{}\n",
+                    instance_id, instance.status.as_str(), due, format_duration(logged), marker));
+            }
+            output.push_str(&format!("  Total:      {}\n\n", format_duration(chain_total)));
+        }
+    }
     
     // Annotations
     output.push_str(&format!("Annotations ({}):\n", annotations.len()));
@@ -2042,7 +2179,8 @@ pub fn format_task_summary(
         output.push_str("  (none)\n");
     } else {
         for (idx, annotation) in annotations.iter().enumerate() {
-            output.push_str(&format!("  {}. {}\n", idx + 1, format_timestamp(annotation.entry_ts)));
+            let pinned = if annotation.pinned { " (pinned)" } else { "" };
+            output.push_str(&format!("  {}. {}{}\n", idx + 1, format_timestamp(annotation.entry_ts), pinned));
             // Format note with indentation for multi-line notes
             for line in annotation.note.lines() {
                 output.push_str(&format!("     {}\n", line));
@@ -2083,6 +2221,34 @@ pub fn format_task_summary(
     Ok(output)
 }
 
+/// Format the per-term breakdown of a task's priority score
+pub fn format_priority_explanation(task: &Task, terms: &[UrgencyTerm]) -> String {
+    let mut output = format!("Priority of task {}: {}\n\n",
+        task.id.map(|id| id.to_string()).unwrap_or_else(|| "?".to_string()),
+        task.description);
+
+    let name_width = terms.iter().map(|t| t.name.len()).max().unwrap_or(0).max("Term".len());
+    let detail_width = terms.iter().map(|t| t.detail.len()).max().unwrap_or(0).max("Reason".len());
+    output.push_str(&format!("{:<nw$}  {:<dw$}  {:>8}  {:>11}  {:>12}\n",
+        "Term", "Reason", "Value", "Coefficient", "Contribution", nw = name_width, dw = detail_width));
+    let rule_width = name_width + detail_width + 8 + 11 + 12 + 8;
+    output.push_str(&format!("{}\n", "─".repeat(rule_width)));
+
+    if terms.is_empty() {
+        output.push_str("(no terms apply)\n");
+    }
+    for term in terms {
+        output.push_str(&format!("{:<nw$}  {:<dw$}  {:>8.2}  {:>11.2}  {:>12.2}\n",
+            term.name, term.detail, term.value, term.coefficient, term.contribution(),
+            nw = name_width, dw = detail_width));
+    }
+
+    output.push_str(&format!("{}\n", "─".repeat(rule_width)));
+    let total: f64 = terms.iter().map(UrgencyTerm::contribution).sum();
+    output.push_str(&format!("{:<w$}{:>12.2}\n", "Total", total, w = rule_width - 12));
+    output
+}
+
 /// Format dashboard output
 pub fn format_dashboard(
     conn: &Connection,
@@ -15,6 +15,7 @@ pub struct ParsedTaskArgs {
     pub template: Option<String>,
     pub respawn: Option<String>,
     pub parent: Option<String>,
+    pub depends: Option<String>,
     pub tags_add: Vec<String>,
     pub tags_remove: Vec<String>,
     pub udas: HashMap<String, String>,
@@ -76,6 +77,7 @@ const FIELD_NAMES: &[&str] = &[
     "template",
     "respawn",
     "parent",
+    "depends",
 ];
 
 /// Fields that are read-only (cannot be modified via modify command)
@@ -242,15 +244,13 @@ pub fn parse_task_args(args: Vec<String>) -> Result<ParsedTaskArgs, FieldParseEr
                     "template" => parsed.template = Some(value),
                     "respawn" => parsed.respawn = Some(value),
                     "parent" => parsed.parent = Some(value),
+                    "depends" => parsed.depends = Some(value),
                     _ => {
                         // Check if it's a UDA (uda.<key>=<value>)
                         if field.starts_with("uda.") {
                             let key = field.strip_prefix("uda.").unwrap().to_string();
-                            if value == "none" {
-                                // UDA clearing - handled separately
-                            } else {
-                                parsed.udas.insert(key, value);
-                            }
+                            // "none" is kept so modify can clear the UDA
+                            parsed.udas.insert(key, value);
                         } else {
                             // Unknown field - treat as description
                             description_parts.push(arg.clone());
@@ -267,11 +267,9 @@ pub fn parse_task_args(args: Vec<String>) -> Result<ParsedTaskArgs, FieldParseEr
                         let field = &arg[..eq_pos];
                         let value = &arg[eq_pos + 1..];
                         let key = field.strip_prefix("uda.").unwrap().to_string();
-                        if value == "none" {
-                            // UDA clearing - handled separately
-                        } else {
-                            parsed.udas.insert(key, value.to_string());
-                        }
+                        // "none" (or empty) is kept so modify can clear the UDA
+                        let value = if value.is_empty() { "none" } else { value };
+                        parsed.udas.insert(key, value.to_string());
                         handled = true;
                     }
                 } else if arg.contains('=') && !arg.starts_with('+') && !arg.starts_with('-') {
@@ -339,7 +337,7 @@ pub fn parse_task_args(args: Vec<String>) -> Result<ParsedTaskArgs, FieldParseEr
 /// - Token doesn't start with + or -
 /// - Tag name is empty (just "+" or "-")
 /// - Tag name contains invalid characters
-fn parse_tag_token(token: &str) -> Option<String> {
+pub fn parse_tag_token(token: &str) -> Option<String> {
     if token.starts_with('+') || token.starts_with('-') {
         // Validate tag charset: [A-Za-z0-9_\-\.]+
         let tag_part = &token[1..];
@@ -0,0 +1,157 @@
+// Pomodoro focus intervals for `tatl on --pomodoro`, `tatl status` and `tatl timer`
+
+use crate::cli::output::format_progress_bar;
+use crate::config::Config;
+use crate::models::{Pomodoro, Session};
+use crate::utils::parse_duration;
+use chrono::{Local, TimeZone};
+
+/// Parse a `--pomodoro` value: `<work>/<break>` (e.g. `25m/5m`), just `<work>`,
+/// or empty for `pomodoro.work` and `pomodoro.break`
+pub fn parse_pomodoro(spec: &str, config: &Config) -> Result<Pomodoro, String> {
+    let spec = spec.trim();
+    let (work, brk) = match spec.split_once('/') {
+        Some((work, brk)) => (work.trim(), Some(brk.trim())),
+        None => (spec, None),
+    };
+    let parse = |value: &str| match parse_duration(value) {
+        Ok(secs) if secs > 0 => Ok(secs),
+        _ => Err(format!("Invalid pomodoro '{}': '{}' is not a duration (e.g. 25m/5m)", spec, value)),
+    };
+    let work_secs = if work.is_empty() { config.pomodoro_work_secs() } else { parse(work)? };
+    let break_secs = match brk {
+        Some(brk) => parse(brk)?,
+        None => config.pomodoro_break_secs(),
+    };
+    Ok(Pomodoro { work_secs, break_secs })
+}
+
+/// Where a pomodoro is at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    /// Working from `start` until `end`
+    Work { start: i64, end: i64 },
+    /// On a break from `start` until `end`
+    Break { start: i64, end: i64 },
+}
+
+impl Phase {
+    /// Value of `TATL_INTERVAL` for the hook run when this phase ends
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Work { .. } => "work",
+            Phase::Break { .. } => "break",
+        }
+    }
+
+    pub fn end(&self) -> i64 {
+        match self {
+            Phase::Work { end, .. } | Phase::Break { end, .. } => *end,
+        }
+    }
+
+    /// Countdown line, e.g. `Focus: 12:30 left (until 14:55) [==========----------] 50%`
+    pub fn line(&self, now: i64) -> String {
+        let (label, start, end) = match *self {
+            Phase::Work { start, end } => ("Focus", start, end),
+            Phase::Break { start, end } => ("Break", start, end),
+        };
+        let remaining = (end - now).max(0);
+        format!("{}: {} left (until {}) {}",
+            label, format_countdown(remaining), format_clock(end), format_progress_bar(end - start - remaining, end - start))
+    }
+}
+
+/// The phase of a pomodoro session at `now`. The session is the work interval
+/// while it is open and within `work_secs`; once the interval is over (whether or
+/// not the session has been closed yet) the break runs for `break_secs`. A session
+/// stopped before its interval was over has no break.
+pub fn phase_at(session: &Session, pomodoro: &Pomodoro, now: i64) -> Option<Phase> {
+    let work_end = session.start_ts + pomodoro.work_secs;
+    match session.end_ts {
+        None if now < work_end => Some(Phase::Work { start: session.start_ts, end: work_end }),
+        Some(end) if end < work_end => None,
+        _ => {
+            let break_end = work_end + pomodoro.break_secs;
+            (now < break_end).then_some(Phase::Break { start: work_end, end: break_end })
+        }
+    }
+}
+
+/// Format remaining time as a countdown: `4:05`, `1:02:03`
+pub fn format_countdown(secs: i64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+fn format_clock(ts: i64) -> String {
+    Local.timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format("%H:%M").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+/// Run `pomodoro.hook` after a phase of a pomodoro session ended. The command
+/// runs through `sh -c` with `TATL_INTERVAL` (`work` or `break`), `TATL_TASK_ID`
+/// and `TATL_SESSION_ID` set. Failures are reported on stderr.
+pub fn run_hook(hook: &str, phase: &Phase, session: &Session) {
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(hook)
+        .env("TATL_INTERVAL", phase.name())
+        .env("TATL_TASK_ID", session.task_id.to_string())
+        .env("TATL_SESSION_ID", session.id.unwrap_or_default().to_string())
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("Warning: pomodoro.hook exited with {}", status),
+        Err(e) => eprintln!("Warning: failed to run pomodoro.hook: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_pomodoro() {
+        let config = Config::parse("pomodoro.work=50m\npomodoro.break=10m\n", Path::new("rc"));
+        assert_eq!(parse_pomodoro("25m/5m", &config), Ok(Pomodoro { work_secs: 1500, break_secs: 300 }));
+        assert_eq!(parse_pomodoro("45m", &config), Ok(Pomodoro { work_secs: 2700, break_secs: 600 }));
+        assert_eq!(parse_pomodoro("", &config), Ok(Pomodoro { work_secs: 3000, break_secs: 600 }));
+        assert!(parse_pomodoro("25m/soon", &config).is_err());
+        assert!(parse_pomodoro("0s/5m", &config).is_err());
+        let defaults = Config::parse("", Path::new("rc"));
+        assert_eq!(parse_pomodoro("", &defaults), Ok(Pomodoro { work_secs: 1500, break_secs: 300 }));
+    }
+
+    #[test]
+    fn test_phase_at() {
+        let pomodoro = Pomodoro { work_secs: 1500, break_secs: 300 };
+        let mut session = Session::new(1, 10_000);
+        assert_eq!(phase_at(&session, &pomodoro, 10_100), Some(Phase::Work { start: 10_000, end: 11_500 }));
+        // Still open after the interval: already on the break
+        assert_eq!(phase_at(&session, &pomodoro, 11_600), Some(Phase::Break { start: 11_500, end: 11_800 }));
+        assert_eq!(phase_at(&session, &pomodoro, 11_800), None);
+        session.end_ts = Some(11_500);
+        assert_eq!(phase_at(&session, &pomodoro, 11_500), Some(Phase::Break { start: 11_500, end: 11_800 }));
+        // Stopped early: no break
+        session.end_ts = Some(11_000);
+        assert_eq!(phase_at(&session, &pomodoro, 11_100), None);
+    }
+
+    #[test]
+    fn test_countdown_line() {
+        let phase = Phase::Work { start: 10_000, end: 11_500 };
+        assert_eq!(format_countdown(750), "12:30");
+        assert_eq!(format_countdown(3723), "1:02:03");
+        let line = phase.line(10_750);
+        assert!(line.starts_with("Focus: 12:30 left (until "));
+        assert!(line.ends_with("[==========----------] 50%"));
+    }
+}
@@ -1,52 +1,212 @@
 // Priority/Urgency calculation modeled on Taskwarrior
 
+use crate::config::Config;
+use crate::filter::evaluator::calculate_task_stage;
 use crate::models::Task;
-use crate::repo::TaskRepo;
+use crate::repo::{DependencyRepo, ProjectRepo, StackRepo, TaskRepo};
 use rusqlite::Connection;
 use anyhow::Result;
 use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Urgency coefficients, read from `urgency.*` configuration keys
+///
+/// Each term of the urgency score is a value (usually 0 or 1, or a curve for due
+/// date, allocation and age) multiplied by its coefficient. The defaults reproduce
+/// the built-in Taskwarrior-like weights; terms without a default count for nothing
+/// until configured:
+///
+/// ```text
+/// urgency.due=1.0                 # multiplier on the due-date curve
+/// urgency.tag.urgent=6.0          # task has tag +urgent
+/// urgency.project.work=1.0        # project work (or work.*)
+/// urgency.scheduled=4.0           # scheduled date has passed
+/// urgency.uda.severity.high=5.0   # uda.severity=high
+/// urgency.uda.customer=1.0        # uda.customer is set
+/// urgency.stage.suspended=2.0     # derived stage
+/// urgency.queue=3.0               # task is in a queue
+/// urgency.blocking=8.0            # an open task depends on this one
+/// urgency.blocked=-5.0            # this task depends on an open task
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrgencyCoefficients {
+    pub open: f64,
+    pub due: f64,
+    pub allocation: f64,
+    pub age: f64,
+    pub scheduled: f64,
+    pub queue: f64,
+    pub blocking: f64,
+    pub blocked: f64,
+    pub tags: HashMap<String, f64>,
+    pub projects: HashMap<String, f64>,
+    /// Keyed by `name` (attribute set) or `name.value` (attribute equals value)
+    pub udas: HashMap<String, f64>,
+    pub stages: HashMap<String, f64>,
+}
+
+impl Default for UrgencyCoefficients {
+    fn default() -> Self {
+        UrgencyCoefficients {
+            open: 1.0,
+            due: 1.0,
+            allocation: 1.0,
+            age: 1.0,
+            scheduled: 0.0,
+            queue: 0.0,
+            blocking: 8.0,
+            blocked: -5.0,
+            tags: HashMap::new(),
+            projects: HashMap::new(),
+            udas: HashMap::new(),
+            stages: HashMap::new(),
+        }
+    }
+}
+
+impl UrgencyCoefficients {
+    /// Coefficients from the configuration, loaded once per process
+    pub fn get() -> &'static UrgencyCoefficients {
+        static COEFFICIENTS: OnceLock<UrgencyCoefficients> = OnceLock::new();
+        COEFFICIENTS.get_or_init(|| {
+            let (coefficients, warnings) = Self::from_config(&Config::current());
+            for warning in warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            coefficients
+        })
+    }
+
+    /// Coefficients from the `urgency.*` keys of a configuration.
+    /// Returns the coefficients and a warning for each key that names no urgency term.
+    pub fn from_config(config: &Config) -> (Self, Vec<String>) {
+        let mut coefficients = UrgencyCoefficients::default();
+        let mut warnings = Vec::new();
+        // Values were checked to be numbers when the configuration was loaded
+        for (key, value) in config.entries_with_prefix("urgency.") {
+            if let Err(e) = coefficients.set(key, value) {
+                warnings.push(format!("ignoring 'urgency.{}': {}", key, e));
+            }
+        }
+        (coefficients, warnings)
+    }
+
+    /// Set one coefficient from a key without the `urgency.` prefix
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let weight = match value.parse::<f64>() {
+            Ok(w) if w.is_finite() => w,
+            _ => return Err(format!("'{}' is not a number", value)),
+        };
+
+        let (kind, name) = key.split_once('.').unwrap_or((key, ""));
+        match (kind, name) {
+            ("open", "") => self.open = weight,
+            ("due", "") => self.due = weight,
+            ("allocation", "") | ("alloc", "") => self.allocation = weight,
+            ("age", "") => self.age = weight,
+            ("scheduled", "") => self.scheduled = weight,
+            ("queue", "") => self.queue = weight,
+            ("blocking", "") => self.blocking = weight,
+            ("blocked", "") => self.blocked = weight,
+            ("tag", name) if !name.is_empty() => { self.tags.insert(name.to_string(), weight); }
+            ("project", name) if !name.is_empty() => { self.projects.insert(name.to_string(), weight); }
+            ("uda", name) if !name.is_empty() => { self.udas.insert(name.to_string(), weight); }
+            ("stage", name) if !name.is_empty() => { self.stages.insert(name.to_lowercase(), weight); }
+            _ => return Err(format!("unknown urgency term 'urgency.{}'", key)),
+        }
+        Ok(())
+    }
+
+    /// Coefficient for a project, using the most specific configured ancestor
+    /// (`urgency.project.work` applies to `work.email` unless that has its own)
+    fn project_weight(&self, project: &str) -> Option<(&str, f64)> {
+        self.projects
+            .iter()
+            .filter(|(name, _)| project == name.as_str() || project.starts_with(&format!("{}.", name)))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(name, weight)| (name.as_str(), *weight))
+    }
+}
+
+/// One term of a task's urgency score
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrgencyTerm {
+    /// Coefficient key without the `urgency.` prefix, e.g. `due` or `tag.urgent`
+    pub name: String,
+    /// Why the term applies, e.g. "due in 2.0 days"
+    pub detail: String,
+    pub value: f64,
+    pub coefficient: f64,
+}
+
+impl UrgencyTerm {
+    pub fn contribution(&self) -> f64 {
+        self.value * self.coefficient
+    }
+}
 
 /// Calculate priority/urgency score for a task (Taskwarrior-style)
-/// 
-/// Priority is calculated using a polynomial with configurable coefficients:
+///
+/// Priority is the weighted sum of the terms from `priority_terms`, using the
+/// coefficients configured in the rc file (see `UrgencyCoefficients`).
+///
+/// Returns a floating-point urgency score (higher = more urgent)
+pub fn calculate_priority(task: &Task, conn: &Connection) -> Result<f64> {
+    Ok(priority_terms(task, conn, UrgencyCoefficients::get())?
+        .iter()
+        .map(UrgencyTerm::contribution)
+        .sum())
+}
+
+/// The terms that make up a task's urgency score:
+/// - Status (open tasks only)
 /// - Due date proximity (higher urgency for tasks due soon or overdue)
 /// - Allocation (tasks with less allocation remaining get higher urgency)
 /// - Age (older tasks get slightly higher urgency)
-/// - Status (open tasks only)
+/// - Dependencies (blocking other open tasks raises urgency, being blocked lowers it)
+/// - Scheduled date passed, queue membership, stage, tags, project and UDAs
+///   (only when a coefficient is configured)
 ///
-/// Returns a floating-point urgency score (higher = more urgent)
-pub fn calculate_priority(task: &Task, conn: &Connection) -> Result<f64> {
-    let mut urgency = 0.0;
+/// Terms with a zero value or coefficient are left out.
+pub fn priority_terms(task: &Task, conn: &Connection, coefficients: &UrgencyCoefficients) -> Result<Vec<UrgencyTerm>> {
+    let mut terms = Vec::new();
+    let mut add = |name: &str, detail: String, value: f64, coefficient: f64| {
+        if value != 0.0 && coefficient != 0.0 {
+            terms.push(UrgencyTerm { name: name.to_string(), detail, value, coefficient });
+        }
+    };
     let now = Utc::now().timestamp();
 
     // Base urgency for open tasks
     if task.status == crate::models::TaskStatus::Open {
-        urgency += 1.0;
+        add("open", "task is open".to_string(), 1.0, coefficients.open);
     }
-    
+
     // Due date urgency (Taskwarrior-style)
     if let Some(due_ts) = task.due_ts {
         let days_until_due = (due_ts - now) as f64 / 86400.0;
-        
-        if days_until_due < 0.0 {
+
+        let (value, detail) = if days_until_due < 0.0 {
             // Overdue - high urgency that increases with lateness
             // Formula: 15.0 - (days overdue * 0.5), minimum 1.0
-            urgency += (15.0 - (days_until_due.abs() * 0.5)).max(1.0);
+            ((15.0 - (days_until_due.abs() * 0.5)).max(1.0), format!("overdue by {:.1} days", -days_until_due))
         } else if days_until_due <= 7.0 {
             // Due within a week - urgency increases as deadline approaches
             // Formula: 12.0 - days_until_due
-            urgency += (12.0 - days_until_due).max(1.0);
+            ((12.0 - days_until_due).max(1.0), format!("due in {:.1} days", days_until_due))
         } else if days_until_due <= 30.0 {
             // Due within a month - moderate urgency
             // Formula: 5.0 - (days_until_due / 10.0)
-            urgency += (5.0 - (days_until_due / 10.0)).max(0.5);
+            ((5.0 - (days_until_due / 10.0)).max(0.5), format!("due in {:.1} days", days_until_due))
         } else {
             // Due far in the future - low urgency
             // Formula: 2.0 / (1.0 + days_until_due / 30.0)
-            urgency += 2.0 / (1.0 + days_until_due / 30.0);
-        }
+            (2.0 / (1.0 + days_until_due / 30.0), format!("due in {:.0} days", days_until_due))
+        };
+        add("due", detail, value, coefficients.due);
     }
-    
+
     // Allocation urgency (tasks with less time remaining get higher urgency)
     if let Some(alloc_secs) = task.alloc_secs {
         if alloc_secs > 0 {
@@ -54,30 +214,101 @@ pub fn calculate_priority(task: &Task, conn: &Connection) -> Result<f64> {
             if let Some(task_id) = task.id {
                 if let Ok(total_logged) = TaskRepo::get_total_logged_time(conn, task_id) {
                     let remaining_secs = alloc_secs.saturating_sub(total_logged);
-                    
-                    if remaining_secs < alloc_secs / 4 {
+
+                    let value = if remaining_secs < alloc_secs / 4 {
                         // Less than 25% allocation remaining - high urgency
-                        urgency += 3.0;
+                        3.0
                     } else if remaining_secs < alloc_secs / 2 {
                         // Less than 50% allocation remaining - moderate urgency
-                        urgency += 1.5;
+                        1.5
                     } else {
                         // More than 50% remaining - low urgency
-                        urgency += 0.5;
-                    }
+                        0.5
+                    };
+                    let percent = remaining_secs.max(0) * 100 / alloc_secs;
+                    add("allocation", format!("{}% of allocation remaining", percent), value, coefficients.allocation);
                 }
             }
         }
     }
-    
+
     // Age urgency (older tasks get slightly higher urgency)
     let age_days = (now - task.created_ts) as f64 / 86400.0;
     if age_days > 30.0 {
         // Tasks older than 30 days get a small boost
-        urgency += (age_days / 30.0).min(2.0) * 0.1;
+        add("age", format!("{:.0} days old", age_days), (age_days / 30.0).min(2.0) * 0.1, coefficients.age);
     }
-    
-    Ok(urgency)
+
+    // Scheduled date has passed (the task is ready to work on)
+    if let Some(scheduled_ts) = task.scheduled_ts {
+        if scheduled_ts <= now {
+            add("scheduled", "scheduled date has passed".to_string(), 1.0, coefficients.scheduled);
+        }
+    }
+
+    // The lookups below are skipped unless a coefficient could use them
+    if let Some(task_id) = task.id {
+        if task.status == crate::models::TaskStatus::Open {
+            let blockers = DependencyRepo::get_open_blockers(conn, task_id)?;
+            if !blockers.is_empty() {
+                add("blocked", format!("waiting on {}", format_ids(&blockers)), 1.0, coefficients.blocked);
+            }
+            let blocks: Vec<i64> = DependencyRepo::get_dependents(conn, task_id)?
+                .into_iter()
+                .filter(|id| matches!(TaskRepo::get_by_id(conn, *id), Ok(Some(t)) if t.status == crate::models::TaskStatus::Open))
+                .collect();
+            if !blocks.is_empty() {
+                add("blocking", format!("blocks {}", format_ids(&blocks)), 1.0, coefficients.blocking);
+            }
+        }
+        if coefficients.queue != 0.0 && StackRepo::is_queued(conn, task_id)? {
+            add("queue", "task is in a queue".to_string(), 1.0, coefficients.queue);
+        }
+        if !coefficients.tags.is_empty() {
+            for tag in TaskRepo::get_tags(conn, task_id)? {
+                if let Some(weight) = coefficients.tags.get(&tag) {
+                    add(&format!("tag.{}", tag), format!("tagged +{}", tag), 1.0, *weight);
+                }
+            }
+        }
+    }
+
+    if !coefficients.stages.is_empty() {
+        let stage = calculate_task_stage(task, conn)?;
+        if let Some(weight) = coefficients.stages.get(&stage.to_lowercase()) {
+            add(&format!("stage.{}", stage), format!("stage is {}", stage), 1.0, *weight);
+        }
+    }
+
+    if !coefficients.projects.is_empty() {
+        if let Some(project) = task.project_id.map(|id| ProjectRepo::get_by_id(conn, id)).transpose()?.flatten() {
+            if let Some((name, weight)) = coefficients.project_weight(&project.name) {
+                add(&format!("project.{}", name), format!("project is {}", project.name), 1.0, weight);
+            }
+        }
+    }
+
+    let mut udas: Vec<_> = task.udas.iter().collect();
+    udas.sort();
+    for (key, value) in udas {
+        if let Some(weight) = coefficients.udas.get(key) {
+            add(&format!("uda.{}", key), format!("uda.{} is set", key), 1.0, *weight);
+        }
+        let key_value = format!("{}.{}", key, value);
+        if let Some(weight) = coefficients.udas.get(&key_value)
+            .or_else(|| coefficients.udas.get(&key_value.to_lowercase()))
+        {
+            add(&format!("uda.{}", key_value), format!("uda.{}={}", key, value), 1.0, *weight);
+        }
+    }
+
+    Ok(terms)
+}
+
+/// "task 3" or "tasks 3, 5"
+fn format_ids(ids: &[i64]) -> String {
+    let list: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    format!("task{} {}", if ids.len() == 1 { "" } else { "s" }, list.join(", "))
 }
 
 /// Get top N priority tasks (not in clock stack)
@@ -87,7 +318,7 @@ pub fn get_top_priority_tasks(
     limit: usize,
 ) -> Result<Vec<(Task, Vec<String>, f64)>> {
     let all_tasks = TaskRepo::list_all(conn)?;
-    
+
     // Filter out excluded tasks and calculate priority
     let mut tasks_with_priority: Vec<(Task, Vec<String>, f64)> = all_tasks
         .into_iter()
@@ -103,10 +334,97 @@ pub fn get_top_priority_tasks(
             }
         })
         .collect();
-    
+
     // Sort by priority (descending) and take top N
     tasks_with_priority.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
     tasks_with_priority.truncate(limit);
-    
+
     Ok(tasks_with_priority)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DbConnection;
+    use std::path::Path;
+
+    fn parse_coefficients(text: &str) -> (UrgencyCoefficients, Vec<String>) {
+        UrgencyCoefficients::from_config(&Config::parse(text, Path::new("rc")))
+    }
+
+    #[test]
+    fn test_parse_coefficients() {
+        let config = "data.location=/tmp/x.db\n\
+                      urgency.tag.urgent=6.0\n\
+                      urgency.project.work=1\n\
+                      urgency.scheduled = 4\n\
+                      urgency.uda.severity.high=5.0\n\
+                      urgency.due=0.5\n\
+                      urgency.color=2\n\
+                      urgency.queue=lots\n";
+        let config = Config::parse(config, Path::new("rc"));
+        assert_eq!(config.warnings.len(), 1);
+        assert!(config.warnings[0].contains("urgency.queue=lots"));
+        let (coefficients, warnings) = UrgencyCoefficients::from_config(&config);
+        assert_eq!(coefficients.tags.get("urgent"), Some(&6.0));
+        assert_eq!(coefficients.projects.get("work"), Some(&1.0));
+        assert_eq!(coefficients.scheduled, 4.0);
+        assert_eq!(coefficients.udas.get("severity.high"), Some(&5.0));
+        assert_eq!(coefficients.due, 0.5);
+        assert_eq!(coefficients.open, 1.0);
+        assert_eq!(coefficients.queue, 0.0);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("unknown urgency term 'urgency.color'"));
+
+        let (coefficients, _) = parse_coefficients("urgency.project.work=1\nurgency.project.work.email=3\n");
+        assert_eq!(coefficients.project_weight("work.email.inbox"), Some(("work.email", 3.0)));
+        assert_eq!(coefficients.project_weight("work.admin"), Some(("work", 1.0)));
+        assert_eq!(coefficients.project_weight("workshop"), None);
+    }
+
+    #[test]
+    fn test_priority_terms_weighted_sum() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let project = ProjectRepo::create(&conn, "work.email").unwrap();
+        let mut udas = HashMap::new();
+        udas.insert("severity".to_string(), "high".to_string());
+        let now = Utc::now().timestamp();
+        let task = TaskRepo::create_full(
+            &conn, "Reply", project.id, None, Some(now - 3600), None, None, None, None,
+            &udas, &["urgent".to_string(), "later".to_string()], None,
+        ).unwrap();
+
+        // Defaults: only the open term applies to a task without due date or allocation
+        let terms = priority_terms(&task, &conn, &UrgencyCoefficients::default()).unwrap();
+        assert_eq!(terms.len(), 1);
+        assert_eq!(terms[0].name, "open");
+
+        let (coefficients, _) = parse_coefficients(
+            "urgency.tag.urgent=6\nurgency.project.work=1.5\nurgency.scheduled=4\nurgency.uda.severity.high=5\nurgency.open=0\n",
+        );
+        let terms = priority_terms(&task, &conn, &coefficients).unwrap();
+        let names: Vec<&str> = terms.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["scheduled", "tag.urgent", "project.work", "uda.severity.high"]);
+        let total: f64 = terms.iter().map(UrgencyTerm::contribution).sum();
+        assert_eq!(total, 4.0 + 6.0 + 1.5 + 5.0);
+    }
+
+    #[test]
+    fn test_dependency_terms() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let first = TaskRepo::create(&conn, "Design", None).unwrap();
+        let second = TaskRepo::create(&conn, "Build", None).unwrap();
+        DependencyRepo::add(&conn, second.id.unwrap(), first.id.unwrap()).unwrap();
+
+        let coefficients = UrgencyCoefficients::default();
+        let score = |task: &Task| -> f64 {
+            priority_terms(task, &conn, &coefficients).unwrap().iter().map(UrgencyTerm::contribution).sum()
+        };
+        assert_eq!(score(&first), 1.0 + 8.0);
+        assert_eq!(score(&second), 1.0 - 5.0);
+
+        // Once the blocker closes neither term applies
+        TaskRepo::close(&conn, first.id.unwrap()).unwrap();
+        assert_eq!(score(&second), 1.0);
+    }
+}
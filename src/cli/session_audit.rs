@@ -0,0 +1,206 @@
+// Implausible session detection for `tatl sessions audit`, and automatic capping
+
+use crate::config::Config;
+use crate::models::Session;
+use chrono::{Local, NaiveTime, TimeZone};
+
+/// How sessions are capped when they are stopped (`session.auto_cap`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutoCap {
+    Off,
+    /// End at start + `session.max_duration`
+    MaxDuration,
+    /// End at the first midnight after the start
+    Midnight,
+}
+
+/// Limits on how long a session can plausibly run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionLimits {
+    /// `session.max_duration` in seconds
+    pub max_duration: Option<i64>,
+    pub auto_cap: AutoCap,
+}
+
+impl SessionLimits {
+    pub fn from_config(config: &Config) -> SessionLimits {
+        let auto_cap = match config.session_auto_cap() {
+            "max_duration" => AutoCap::MaxDuration,
+            "midnight" => AutoCap::Midnight,
+            _ => AutoCap::Off,
+        };
+        SessionLimits { max_duration: config.session_max_duration(), auto_cap }
+    }
+
+    pub fn current() -> SessionLimits {
+        Self::from_config(&Config::current())
+    }
+
+    /// Whether a session of this length is longer than `session.max_duration`
+    pub fn is_too_long(&self, secs: i64) -> bool {
+        self.max_duration.is_some_and(|max| secs > max)
+    }
+
+    /// End time for a session started at `start_ts` and stopped at `end_ts`,
+    /// when `session.auto_cap` moves it earlier
+    pub fn capped_end(&self, start_ts: i64, end_ts: i64) -> Option<i64> {
+        let cap = match self.auto_cap {
+            AutoCap::Off => return None,
+            AutoCap::MaxDuration => start_ts + self.max_duration?,
+            AutoCap::Midnight => next_midnight(start_ts),
+        };
+        (cap < end_ts).then_some(cap)
+    }
+}
+
+/// The first local midnight after a timestamp
+pub fn next_midnight(ts: i64) -> i64 {
+    let date = Local.timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default();
+    let next = date.succ_opt().unwrap_or(date).and_time(NaiveTime::MIN);
+    Local.from_local_datetime(&next)
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or(ts + 86400)
+}
+
+/// Why a session looks wrong
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// Ends at or before its start
+    ZeroLength,
+    /// Overlaps a later-starting session between these times
+    Overlap { other_id: i64, start: i64, end: i64 },
+    /// Longer than `session.max_duration`
+    TooLong { max_duration: i64 },
+    /// Runs past the midnight at this time
+    CrossesMidnight { midnight: i64 },
+}
+
+impl Problem {
+    pub fn description(&self) -> String {
+        match self {
+            Problem::ZeroLength => "zero length".to_string(),
+            Problem::Overlap { other_id, .. } => format!("overlaps session {}", other_id),
+            Problem::TooLong { .. } => "longer than session.max_duration".to_string(),
+            Problem::CrossesMidnight { .. } => "crosses midnight".to_string(),
+        }
+    }
+
+    /// What `sessions audit --fix` does about it
+    pub fn fix_description(&self) -> String {
+        match self {
+            Problem::ZeroLength => "delete it".to_string(),
+            Problem::Overlap { .. } => "remove the overlapping time".to_string(),
+            Problem::TooLong { max_duration } => format!("end it after {}", crate::cli::output::format_duration(*max_duration)),
+            Problem::CrossesMidnight { .. } => "split it at midnight".to_string(),
+        }
+    }
+
+    /// What `sessions audit --fix` did about it
+    pub fn fixed_description(&self) -> String {
+        match self {
+            Problem::ZeroLength => "deleted".to_string(),
+            Problem::Overlap { other_id, .. } => format!("removed the time overlapping session {}", other_id),
+            Problem::TooLong { max_duration } => format!("ended after {}", crate::cli::output::format_duration(*max_duration)),
+            Problem::CrossesMidnight { .. } => "split at midnight".to_string(),
+        }
+    }
+}
+
+/// A suspicious session
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub session: Session,
+    pub problem: Problem,
+}
+
+/// Find suspicious sessions. Open sessions are measured up to `now`. A session
+/// may have several findings; they are ordered by session start, then with
+/// zero-length first, overlaps, too long, and crossing midnight last.
+pub fn audit(sessions: &[Session], limits: &SessionLimits, now: i64) -> Vec<Finding> {
+    let mut sorted: Vec<&Session> = sessions.iter().collect();
+    sorted.sort_by_key(|s| (s.start_ts, s.id));
+
+    let mut findings = Vec::new();
+    for (idx, session) in sorted.iter().enumerate() {
+        let end = session.end_ts.unwrap_or(now);
+        let mut add = |problem| findings.push(Finding { session: (*session).clone(), problem });
+
+        if session.end_ts.is_some_and(|end| end <= session.start_ts) {
+            add(Problem::ZeroLength);
+            continue;
+        }
+        for other in sorted[idx + 1..].iter().take_while(|other| other.start_ts < end) {
+            let other_end = other.end_ts.unwrap_or(now);
+            if other_end <= other.start_ts {
+                continue;
+            }
+            add(Problem::Overlap {
+                other_id: other.id.unwrap_or_default(),
+                start: other.start_ts,
+                end: other_end.min(end),
+            });
+        }
+        if let Some(max_duration) = limits.max_duration.filter(|_| limits.is_too_long(end - session.start_ts)) {
+            add(Problem::TooLong { max_duration });
+        }
+        let midnight = next_midnight(session.start_ts);
+        if midnight < end {
+            add(Problem::CrossesMidnight { midnight });
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: i64, start_ts: i64, end_ts: Option<i64>) -> Session {
+        let mut session = Session::new(1, start_ts);
+        session.id = Some(id);
+        session.end_ts = end_ts;
+        session
+    }
+
+    fn local(hour: u32, min: u32) -> i64 {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        Local.from_local_datetime(&date.and_hms_opt(hour, min, 0).unwrap()).single().unwrap().timestamp()
+    }
+
+    #[test]
+    fn test_capped_end() {
+        let mut limits = SessionLimits { max_duration: Some(4 * 3600), auto_cap: AutoCap::Off };
+        assert_eq!(limits.capped_end(local(9, 0), local(18, 0)), None);
+        limits.auto_cap = AutoCap::MaxDuration;
+        assert_eq!(limits.capped_end(local(9, 0), local(18, 0)), Some(local(13, 0)));
+        assert_eq!(limits.capped_end(local(9, 0), local(12, 0)), None);
+        limits.auto_cap = AutoCap::Midnight;
+        assert_eq!(limits.capped_end(local(22, 0), local(23, 0)), None);
+        assert_eq!(limits.capped_end(local(22, 0), local(22, 0) + 8 * 3600), Some(next_midnight(local(22, 0))));
+    }
+
+    #[test]
+    fn test_audit() {
+        let limits = SessionLimits { max_duration: Some(4 * 3600), auto_cap: AutoCap::Off };
+        let sessions = vec![
+            session(1, local(8, 0), Some(local(13, 0))),
+            session(2, local(12, 0), Some(local(12, 30))),
+            session(3, local(14, 0), Some(local(14, 0))),
+            session(4, local(23, 0), Some(local(23, 0) + 2 * 3600)),
+        ];
+        let problems: Vec<(i64, Problem)> = audit(&sessions, &limits, local(23, 59))
+            .into_iter()
+            .map(|f| (f.session.id.unwrap(), f.problem))
+            .collect();
+        assert_eq!(problems, vec![
+            (1, Problem::Overlap { other_id: 2, start: local(12, 0), end: local(12, 30) }),
+            (1, Problem::TooLong { max_duration: 4 * 3600 }),
+            (3, Problem::ZeroLength),
+            (4, Problem::CrossesMidnight { midnight: next_midnight(local(23, 0)) }),
+        ]);
+    }
+}
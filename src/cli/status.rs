@@ -39,7 +39,7 @@ fn format_relative_time(ts: i64) -> String {
 /// Compute status for root command (`task`)
 pub fn compute_root_status(conn: &rusqlite::Connection) -> Result<String> {
     // Tasks in stack
-    let stack = StackRepo::get_or_create_default(conn)?;
+    let stack = StackRepo::get_active(conn)?;
     let stack_items = StackRepo::get_items(conn, stack.id.unwrap())?;
     let tasks_in_stack = stack_items.len();
     
@@ -79,7 +79,7 @@ pub fn compute_root_status(conn: &rusqlite::Connection) -> Result<String> {
 
 /// Compute status for `task clock`
 pub fn compute_clock_status(conn: &rusqlite::Connection) -> Result<String> {
-    let stack = StackRepo::get_or_create_default(conn)?;
+    let stack = StackRepo::get_active(conn)?;
     let stack_items = StackRepo::get_items(conn, stack.id.unwrap())?;
     
     let (task_id, clock_state, duration_str) = if let Some(top_task) = stack_items.first() {
@@ -161,7 +161,7 @@ pub fn compute_projects_status(conn: &rusqlite::Connection) -> Result<String> {
 
 /// Compute status for `task stack`
 pub fn compute_stack_status(conn: &rusqlite::Connection) -> Result<String> {
-    let stack = StackRepo::get_or_create_default(conn)?;
+    let stack = StackRepo::get_active(conn)?;
     let stack_items = StackRepo::get_items(conn, stack.id.unwrap())?;
     
     if stack_items.is_empty() {
@@ -184,15 +184,26 @@ pub fn compute_stack_status(conn: &rusqlite::Connection) -> Result<String> {
     ))
 }
 
-/// Compute status for respawning tasks
+/// Compute status for respawning tasks: how many are open and which is due next
 pub fn compute_respawn_status(conn: &rusqlite::Connection) -> Result<String> {
     let all_tasks = TaskRepo::list_all(conn)?;
     let respawn_tasks: Vec<_> = all_tasks.iter()
-        .filter(|(task, _)| task.respawn.is_some())
+        .filter(|(task, _)| task.status == TaskStatus::Open)
+        .filter(|(task, _)| task.respawn.as_deref().is_some_and(|r| !r.is_empty()))
         .collect();
     
     let count = respawn_tasks.len();
-    Ok(format!("{} respawning task{}", count, if count == 1 { "" } else { "s" }))
+    let mut status = format!("{} respawning task{}", count, if count == 1 { "" } else { "s" });
+    let next_due = respawn_tasks.iter()
+        .filter_map(|(task, _)| task.due_ts.map(|due| (due, task)))
+        .min_by_key(|(due, _)| *due);
+    if let Some((due_ts, task)) = next_due {
+        status.push_str(&format!(
+            ", next due: {} {} ({})",
+            task.id.unwrap_or(0), task.description, crate::cli::output::format_date(due_ts)
+        ));
+    }
+    Ok(status)
 }
 
 /// Compute status for `task sessions`
@@ -238,6 +249,22 @@ mod tests {
         assert_eq!(format_duration_short(45), "45s");
     }
     
+    #[test]
+    fn test_compute_respawn_status() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        assert_eq!(compute_respawn_status(&conn).unwrap(), "0 respawning tasks");
+
+        let respawn = Some("weekly".to_string());
+        let tags: Vec<String> = Vec::new();
+        let udas = std::collections::HashMap::new();
+        TaskRepo::create_full(&conn, "Later", None, Some(2_000_000_000), None, None, None, None, respawn.clone(), &udas, &tags, None).unwrap();
+        TaskRepo::create_full(&conn, "Sooner", None, Some(1_900_000_000), None, None, None, None, respawn, &udas, &tags, None).unwrap();
+        TaskRepo::create(&conn, "One-off", None).unwrap();
+
+        let status = compute_respawn_status(&conn).unwrap();
+        assert!(status.starts_with("2 respawning tasks, next due: 2 Sooner"));
+    }
+
     #[test]
     fn test_compute_projects_status() {
         let conn = DbConnection::connect_in_memory().unwrap();
@@ -0,0 +1,280 @@
+// Timesheet export formats for `tatl sessions export`
+
+use chrono::{Local, TimeZone, Utc};
+use std::collections::BTreeMap;
+
+/// Output format for `sessions export`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Ics,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn parse(name: &str) -> Option<ExportFormat> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "ics" | "ical" | "icalendar" => Some(ExportFormat::Ics),
+            "jsonl" | "ndjson" => Some(ExportFormat::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+/// One closed session with the task details a timesheet needs
+#[derive(Debug, Clone)]
+pub struct TimesheetEntry {
+    pub session_id: i64,
+    pub task_id: i64,
+    pub task_uuid: String,
+    pub description: String,
+    /// Full project path (e.g. `work.client.api`)
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    pub udas: BTreeMap<String, String>,
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+impl TimesheetEntry {
+    pub fn duration_secs(&self) -> i64 {
+        self.end_ts - self.start_ts
+    }
+}
+
+/// Round a timestamp to the nearest multiple of `increment` seconds, counted from local midnight
+pub fn round_to_increment(ts: i64, increment: i64) -> i64 {
+    if increment <= 1 {
+        return ts;
+    }
+    let offset = Local.timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.offset().local_minus_utc() as i64)
+        .unwrap_or(0);
+    let local = ts + offset;
+    (local + increment / 2).div_euclid(increment) * increment - offset
+}
+
+fn local_rfc3339(ts: i64) -> String {
+    Local.timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%:z").to_string())
+        .unwrap_or_default()
+}
+
+fn local_date(ts: i64) -> String {
+    Local.timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn utc_stamp(ts: i64) -> String {
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_default()
+}
+
+fn hours(secs: i64) -> String {
+    format!("{:.2}", secs as f64 / 3600.0)
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// CSV with a header row; UDAs get one `uda.<key>` column each
+pub fn to_csv(entries: &[TimesheetEntry]) -> String {
+    let mut uda_keys: Vec<&String> = entries.iter().flat_map(|e| e.udas.keys()).collect();
+    uda_keys.sort();
+    uda_keys.dedup();
+
+    let mut header: Vec<String> = ["session_id", "task_id", "date", "start", "end", "duration_secs", "hours", "description", "project", "tags"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    header.extend(uda_keys.iter().map(|key| format!("uda.{}", key)));
+
+    let mut output = header.join(",");
+    output.push('\n');
+    for entry in entries {
+        let mut fields = vec![
+            entry.session_id.to_string(),
+            entry.task_id.to_string(),
+            local_date(entry.start_ts),
+            local_rfc3339(entry.start_ts),
+            local_rfc3339(entry.end_ts),
+            entry.duration_secs().to_string(),
+            hours(entry.duration_secs()),
+            csv_field(&entry.description),
+            csv_field(entry.project.as_deref().unwrap_or("")),
+            csv_field(&entry.tags.join(" ")),
+        ];
+        for key in &uda_keys {
+            fields.push(csv_field(entry.udas.get(*key).map(|v| v.as_str()).unwrap_or("")));
+        }
+        output.push_str(&fields.join(","));
+        output.push('\n');
+    }
+    output
+}
+
+/// Escape an iCalendar TEXT value (RFC 5545 section 3.3.11)
+fn ics_text(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Append a content line, folded at 75 octets, with a CRLF line ending
+fn push_ics_line(output: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            output.push_str("\r\n ");
+            width = 1;
+        }
+        output.push(c);
+        width += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+/// iCalendar with one VEVENT per session. `now` is used for DTSTAMP.
+pub fn to_ics(entries: &[TimesheetEntry], now: i64) -> String {
+    let mut output = String::new();
+    push_ics_line(&mut output, "BEGIN:VCALENDAR");
+    push_ics_line(&mut output, "VERSION:2.0");
+    push_ics_line(&mut output, "PRODID:-//tatl//sessions export//EN");
+    push_ics_line(&mut output, "CALSCALE:GREGORIAN");
+    for entry in entries {
+        let mut details = vec![format!("Task {}", entry.task_id)];
+        if let Some(project) = &entry.project {
+            details.push(format!("Project: {}", project));
+        }
+        if !entry.tags.is_empty() {
+            details.push(format!("Tags: {}", entry.tags.join(" ")));
+        }
+        for (key, value) in &entry.udas {
+            details.push(format!("{}: {}", key, value));
+        }
+        let categories: Vec<String> = entry.project.iter()
+            .chain(entry.tags.iter())
+            .map(|c| ics_text(c))
+            .collect();
+
+        push_ics_line(&mut output, "BEGIN:VEVENT");
+        push_ics_line(&mut output, &format!("UID:{}-{}@tatl", entry.task_uuid, entry.session_id));
+        push_ics_line(&mut output, &format!("DTSTAMP:{}", utc_stamp(now)));
+        push_ics_line(&mut output, &format!("DTSTART:{}", utc_stamp(entry.start_ts)));
+        push_ics_line(&mut output, &format!("DTEND:{}", utc_stamp(entry.end_ts)));
+        push_ics_line(&mut output, &format!("SUMMARY:{}", ics_text(&entry.description)));
+        push_ics_line(&mut output, &format!("DESCRIPTION:{}", ics_text(&details.join("\n"))));
+        if !categories.is_empty() {
+            push_ics_line(&mut output, &format!("CATEGORIES:{}", categories.join(",")));
+        }
+        push_ics_line(&mut output, "END:VEVENT");
+    }
+    push_ics_line(&mut output, "END:VCALENDAR");
+    output
+}
+
+/// JSON Lines: one object per session
+pub fn to_jsonl(entries: &[TimesheetEntry]) -> String {
+    let mut output = String::new();
+    for entry in entries {
+        let line = serde_json::json!({
+            "session_id": entry.session_id,
+            "task_id": entry.task_id,
+            "task_uuid": entry.task_uuid,
+            "description": entry.description,
+            "project": entry.project,
+            "tags": entry.tags,
+            "udas": entry.udas,
+            "start": local_rfc3339(entry.start_ts),
+            "end": local_rfc3339(entry.end_ts),
+            "start_ts": entry.start_ts,
+            "end_ts": entry.end_ts,
+            "duration_secs": entry.duration_secs(),
+        });
+        output.push_str(&line.to_string());
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> TimesheetEntry {
+        TimesheetEntry {
+            session_id: 7,
+            task_id: 3,
+            task_uuid: "abc".to_string(),
+            description: "Review, \"final\" draft; part 2".to_string(),
+            project: Some("work.client".to_string()),
+            tags: vec!["billable".to_string(), "review".to_string()],
+            udas: [("client".to_string(), "Acme".to_string())].into_iter().collect(),
+            start_ts: 1_773_216_000, // 2026-03-11 08:00:00 UTC
+            end_ts: 1_773_221_400,   // 09:30:00 UTC
+        }
+    }
+
+    #[test]
+    fn test_round_to_increment() {
+        let start = entry().start_ts;
+        assert_eq!(round_to_increment(start + 7 * 60, 900), start);
+        assert_eq!(round_to_increment(start + 8 * 60, 900), start + 900);
+        assert_eq!(round_to_increment(start + 8 * 60, 0), start + 8 * 60);
+    }
+
+    #[test]
+    fn test_csv_and_jsonl() {
+        let csv = to_csv(&[entry()]);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "session_id,task_id,date,start,end,duration_secs,hours,description,project,tags,uda.client"
+        );
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("7,3,"));
+        assert!(row.ends_with(",5400,1.50,\"Review, \"\"final\"\" draft; part 2\",work.client,billable review,Acme"));
+
+        let jsonl = to_jsonl(&[entry(), entry()]);
+        assert_eq!(jsonl.lines().count(), 2);
+        let value: serde_json::Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(value["project"], "work.client");
+        assert_eq!(value["udas"]["client"], "Acme");
+        assert_eq!(value["duration_secs"], 5400);
+    }
+
+    #[test]
+    fn test_ics() {
+        let mut long = entry();
+        long.description = "x".repeat(100);
+        let ics = to_ics(&[entry(), long], 1_773_216_000);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("UID:abc-7@tatl\r\n"));
+        assert!(ics.contains("DTSTART:20260311T080000Z\r\nDTEND:20260311T093000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Review\\, \"final\" draft\\; part 2\r\n"));
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains("DESCRIPTION:Task 3\\nProject: work.client\\nTags: billable review\\nclient: Acme\r\n"));
+        assert!(ics.contains("CATEGORIES:work.client,billable,review\r\n"));
+        // Long lines are folded
+        assert!(ics.lines().all(|line| line.trim_end_matches('\r').len() <= 75));
+        assert!(ics.contains(&format!("SUMMARY:{}\r\n {}\r\n", "x".repeat(67), "x".repeat(33))));
+    }
+}
@@ -0,0 +1,713 @@
+//! Configuration file
+//!
+//! Settings are read from `~/.tatl/rc` (or the file named by `TATL_RC`) as
+//! `key=value` lines. Blank lines and lines starting with `#` are ignored, and
+//! `include=<path>` reads another file in place, with relative paths resolved
+//! against the including file. Later lines override earlier ones.
+//!
+//! Every key has a type and most have a default; see [`KEYS`]. Invalid values
+//! are reported as warnings and the default is used instead. `TATL_DATA`
+//! overrides `data.location`.
+
+use anyhow::{Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Month, Weekday};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// How deeply `include=` directives may nest
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// The type of a configuration value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueType {
+    /// File path; relative paths resolve against the rc file's directory
+    Path,
+    /// Free text
+    Text,
+    /// true/false (also yes/no, on/off, 1/0)
+    Bool,
+    /// Whole number, zero or more
+    Count,
+    /// Any number
+    Number,
+    /// Day of the week
+    Weekday,
+    /// Comma-separated days of the week, e.g. `mon,tue,wed,thu,fri`
+    Weekdays,
+    /// Month of the year, by name or number
+    Month,
+    /// strftime-style date format, e.g. `%d.%m.%Y`
+    DateFormat,
+    /// Duration such as `15m` or `1h30m`
+    Duration,
+    /// One of a fixed set of words
+    Choice(&'static [&'static str]),
+}
+
+impl ValueType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValueType::Path => "path",
+            ValueType::Text => "text",
+            ValueType::Bool => "bool",
+            ValueType::Count => "count",
+            ValueType::Number => "number",
+            ValueType::Weekday => "weekday",
+            ValueType::Weekdays => "weekdays",
+            ValueType::Month => "month",
+            ValueType::DateFormat => "date format",
+            ValueType::Duration => "duration",
+            ValueType::Choice(_) => "choice",
+        }
+    }
+
+    /// Validate a value, returning it in normalized form
+    pub fn validate(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        match self {
+            ValueType::Path | ValueType::Text => {
+                if value.is_empty() {
+                    return Err("value is empty".to_string());
+                }
+                Ok(value.to_string())
+            }
+            ValueType::Bool => match value.to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+                "false" | "no" | "off" | "0" => Ok("false".to_string()),
+                _ => Err(format!("'{}' is not a boolean (use true or false)", value)),
+            },
+            ValueType::Count => value
+                .parse::<u32>()
+                .map(|n| n.to_string())
+                .map_err(|_| format!("'{}' is not a whole number", value)),
+            ValueType::Number => match value.parse::<f64>() {
+                Ok(n) if n.is_finite() => Ok(value.to_string()),
+                _ => Err(format!("'{}' is not a number", value)),
+            },
+            ValueType::Weekday => value
+                .parse::<Weekday>()
+                .map(|day| weekday_name(day).to_string())
+                .map_err(|_| format!("'{}' is not a day of the week", value)),
+            ValueType::Weekdays => {
+                let mut days = Vec::new();
+                for day in value.split(',').map(str::trim) {
+                    let day = day.parse::<Weekday>()
+                        .map_err(|_| format!("'{}' is not a day of the week", day))?;
+                    if !days.contains(&day) {
+                        days.push(day);
+                    }
+                }
+                days.sort_by_key(|day| day.num_days_from_monday());
+                Ok(days.iter().map(|day| &weekday_name(*day)[..3]).collect::<Vec<_>>().join(","))
+            }
+            ValueType::Month => {
+                let month = match value.parse::<u32>() {
+                    Ok(n) => Month::try_from(u8::try_from(n).unwrap_or(0)).ok(),
+                    Err(_) => value.parse::<Month>().ok(),
+                };
+                month
+                    .map(|month| month.name().to_lowercase())
+                    .ok_or_else(|| format!("'{}' is not a month", value))
+            }
+            ValueType::DateFormat => {
+                if value.is_empty() || StrftimeItems::new(value).any(|item| matches!(item, Item::Error)) {
+                    Err(format!("'{}' is not a valid date format", value))
+                } else {
+                    Ok(value.to_string())
+                }
+            }
+            ValueType::Duration => crate::utils::parse_duration(value)
+                .map(|_| value.to_string())
+                .map_err(|_| format!("'{}' is not a duration (e.g. 15m, 1h30m)", value)),
+            ValueType::Choice(choices) => {
+                let lower = value.to_lowercase();
+                if choices.contains(&lower.as_str()) {
+                    Ok(lower)
+                } else {
+                    Err(format!("'{}' is not one of {}", value, choices.join(", ")))
+                }
+            }
+        }
+    }
+}
+
+/// A known configuration key
+#[derive(Debug)]
+pub struct KeyDef {
+    /// Key name; a trailing `.*` accepts any suffix (e.g. `urgency.*`)
+    pub name: &'static str,
+    pub value_type: ValueType,
+    pub default: Option<&'static str>,
+    pub description: &'static str,
+}
+
+impl KeyDef {
+    fn matches(&self, key: &str) -> bool {
+        match self.name.strip_suffix('*') {
+            Some(prefix) => key.len() > prefix.len() && key.starts_with(prefix),
+            None => key == self.name,
+        }
+    }
+}
+
+/// All configuration keys
+pub const KEYS: &[KeyDef] = &[
+    KeyDef {
+        name: "data.location",
+        value_type: ValueType::Path,
+        default: None,
+        description: "Database file (default ~/.tatl/ledger.db)",
+    },
+    KeyDef {
+        name: "list.default_view",
+        value_type: ValueType::Text,
+        default: None,
+        description: "Saved view shown by a bare 'tatl list'",
+    },
+    KeyDef {
+        name: "list.color",
+        value_type: ValueType::Text,
+        default: None,
+        description: "Column to color 'tatl list' rows by when no color: is given",
+    },
+    KeyDef {
+        name: "date.format",
+        value_type: ValueType::DateFormat,
+        default: Some("%Y-%m-%d"),
+        description: "Format of dates in output",
+    },
+    KeyDef {
+        name: "week.start",
+        value_type: ValueType::Weekday,
+        default: Some("monday"),
+        description: "First day of the week (sow/eow, weekly reports and bins)",
+    },
+    KeyDef {
+        name: "week.workdays",
+        value_type: ValueType::Weekdays,
+        default: Some("mon,tue,wed,thu,fri"),
+        description: "Working days (business-day respawn patterns)",
+    },
+    KeyDef {
+        name: "fiscal.year_start",
+        value_type: ValueType::Month,
+        default: Some("january"),
+        description: "First month of the fiscal year (fiscal-quarter, fiscal-year periods)",
+    },
+    KeyDef {
+        name: "abbrev.verbose",
+        value_type: ValueType::Bool,
+        default: Some("false"),
+        description: "Print what abbreviated commands expand to",
+    },
+    KeyDef {
+        name: "session.micro",
+        value_type: ValueType::Count,
+        default: Some("30"),
+        description: "Seconds below which a session is a micro-session",
+    },
+    KeyDef {
+        name: "session.max_duration",
+        value_type: ValueType::Duration,
+        default: None,
+        description: "Longest plausible session; longer ones are warned about and flagged by sessions audit",
+    },
+    KeyDef {
+        name: "session.auto_cap",
+        value_type: ValueType::Choice(AUTO_CAP_MODES),
+        default: Some("off"),
+        description: "End stopped sessions at session.max_duration or at midnight (off, max_duration, midnight)",
+    },
+    KeyDef {
+        name: "pomodoro.work",
+        value_type: ValueType::Duration,
+        default: Some("25m"),
+        description: "Work interval of 'tatl on --pomodoro'",
+    },
+    KeyDef {
+        name: "pomodoro.break",
+        value_type: ValueType::Duration,
+        default: Some("5m"),
+        description: "Break after each pomodoro work interval",
+    },
+    KeyDef {
+        name: "pomodoro.hook",
+        value_type: ValueType::Text,
+        default: None,
+        description: "Shell command 'tatl timer' runs when a work interval or break ends",
+    },
+    KeyDef {
+        name: "urgency.*",
+        value_type: ValueType::Number,
+        default: None,
+        description: "Urgency coefficient (see tatl show --explain-priority)",
+    },
+    KeyDef {
+        name: "billing.increment",
+        value_type: ValueType::Duration,
+        default: None,
+        description: "Billing increment for sessions report --billing (unset: exact time)",
+    },
+    KeyDef {
+        name: "billing.increment.*",
+        value_type: ValueType::Duration,
+        default: None,
+        description: "Billing increment for a project.<path> or uda.<key>.<value>",
+    },
+    KeyDef {
+        name: "billing.rounding",
+        value_type: ValueType::Choice(ROUNDING_MODES),
+        default: Some("up"),
+        description: "Round billed time up, nearest or down to the increment",
+    },
+    KeyDef {
+        name: "billing.rounding.*",
+        value_type: ValueType::Choice(ROUNDING_MODES),
+        default: None,
+        description: "Rounding mode for a project.<path> or uda.<key>.<value>",
+    },
+    KeyDef {
+        name: "billing.round_per",
+        value_type: ValueType::Choice(ROUNDING_SCOPES),
+        default: Some("session"),
+        description: "Round each session, or each day's total per project and client",
+    },
+    KeyDef {
+        name: "billing.round_per.*",
+        value_type: ValueType::Choice(ROUNDING_SCOPES),
+        default: None,
+        description: "Rounding scope for a project.<path> or uda.<key>.<value>",
+    },
+    KeyDef {
+        name: "billing.rate",
+        value_type: ValueType::Number,
+        default: None,
+        description: "Hourly rate for billed time",
+    },
+    KeyDef {
+        name: "billing.rate.*",
+        value_type: ValueType::Number,
+        default: None,
+        description: "Hourly rate for a project.<path> or uda.<key>.<value>",
+    },
+    KeyDef {
+        name: "billing.billable",
+        value_type: ValueType::Bool,
+        default: Some("true"),
+        description: "Whether tracked time is billable",
+    },
+    KeyDef {
+        name: "billing.billable.*",
+        value_type: ValueType::Bool,
+        default: None,
+        description: "Whether time on a project.<path> or uda.<key>.<value> is billable",
+    },
+    KeyDef {
+        name: "billing.client_uda",
+        value_type: ValueType::Text,
+        default: Some("client"),
+        description: "UDA that names the client in sessions report --billing",
+    },
+];
+
+/// Values of `session.auto_cap`
+pub const AUTO_CAP_MODES: &[&str] = &["off", "max_duration", "midnight"];
+
+/// Values of `billing.rounding`
+pub const ROUNDING_MODES: &[&str] = &["up", "nearest", "down"];
+
+/// Values of `billing.round_per`
+pub const ROUNDING_SCOPES: &[&str] = &["session", "day"];
+
+/// Look up the definition of a key
+pub fn key_def(key: &str) -> Option<&'static KeyDef> {
+    KEYS.iter().find(|def| def.matches(key))
+}
+
+/// A value set in a configuration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigEntry {
+    pub value: String,
+    /// File the value was read from
+    pub source: PathBuf,
+}
+
+/// Loaded configuration
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    entries: BTreeMap<String, ConfigEntry>,
+    /// Lines that could not be used
+    pub warnings: Vec<String>,
+}
+
+impl Config {
+    /// Path of the rc file: `TATL_RC` if set, else `~/.tatl/rc`
+    pub fn path() -> PathBuf {
+        match std::env::var_os("TATL_RC") {
+            Some(path) if !path.is_empty() => PathBuf::from(path),
+            _ => dirs::home_dir()
+                .expect("Could not determine home directory")
+                .join(".tatl")
+                .join("rc"),
+        }
+    }
+
+    /// The configuration from the current rc file.
+    /// Loaded once per rc path; warnings are printed to stderr when it is loaded.
+    pub fn current() -> Arc<Config> {
+        static CURRENT: Mutex<Option<(PathBuf, Arc<Config>)>> = Mutex::new(None);
+        let path = Self::path();
+        let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((loaded_path, config)) = current.as_ref() {
+            if *loaded_path == path {
+                return config.clone();
+            }
+        }
+        let config = Arc::new(Self::load(&path));
+        for warning in &config.warnings {
+            eprintln!("Warning: {}", warning);
+        }
+        *current = Some((path, config.clone()));
+        config
+    }
+
+    /// Load a configuration file and its includes. A missing file is an empty configuration.
+    pub fn load(path: &Path) -> Config {
+        let mut config = Config::default();
+        if path.exists() {
+            config.read_file(path, 0);
+        }
+        config
+    }
+
+    /// Parse configuration text as if it were read from `source`
+    pub fn parse(text: &str, source: &Path) -> Config {
+        let mut config = Config::default();
+        config.read_text(text, source, 0);
+        config
+    }
+
+    fn read_file(&mut self, path: &Path, depth: usize) {
+        match std::fs::read_to_string(path) {
+            Ok(text) => self.read_text(&text, path, depth),
+            Err(e) => self.warnings.push(format!("cannot read {}: {}", path.display(), e)),
+        }
+    }
+
+    fn read_text(&mut self, text: &str, source: &Path, depth: usize) {
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let location = format!("{} line {}", source.display(), idx + 1);
+            let Some((key, value)) = line.split_once('=') else {
+                self.warnings.push(format!("{}: ignoring '{}': expected key=value", location, line));
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            if key == "include" {
+                if depth >= MAX_INCLUDE_DEPTH {
+                    self.warnings.push(format!("{}: ignoring include of '{}': includes nested too deeply", location, value));
+                } else {
+                    self.read_file(&resolve_path(value, source), depth + 1);
+                }
+                continue;
+            }
+
+            let Some(def) = key_def(key) else {
+                self.warnings.push(format!("{}: ignoring unknown key '{}'", location, key));
+                continue;
+            };
+            match def.value_type.validate(value) {
+                Ok(value) => {
+                    self.entries.insert(key.to_string(), ConfigEntry { value, source: source.to_path_buf() });
+                }
+                Err(e) => self.warnings.push(format!("{}: ignoring '{}': {}", location, line, e)),
+            }
+        }
+    }
+
+    /// The value a file sets for a key, if any
+    pub fn entry(&self, key: &str) -> Option<&ConfigEntry> {
+        self.entries.get(key)
+    }
+
+    /// All values set in configuration files, by key
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &ConfigEntry)> {
+        self.entries.iter().map(|(key, entry)| (key.as_str(), entry))
+    }
+
+    /// Set values whose key starts with `prefix`, with the prefix removed
+    pub fn entries_with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.entries
+            .iter()
+            .filter_map(move |(key, entry)| key.strip_prefix(prefix).map(|rest| (rest, entry.value.as_str())))
+    }
+
+    /// Effective value of a key: the configured value, else its default
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .get(key)
+            .map(|entry| entry.value.as_str())
+            .or_else(|| key_def(key).and_then(|def| def.default))
+    }
+
+    fn get_bool(&self, key: &str) -> bool {
+        self.get(key) == Some("true")
+    }
+
+    /// Database path: `TATL_DATA`, else `data.location`, else `~/.tatl/ledger.db`
+    pub fn data_location(&self) -> PathBuf {
+        if let Some(path) = std::env::var_os("TATL_DATA").filter(|p| !p.is_empty()) {
+            return PathBuf::from(path);
+        }
+        match self.entries.get("data.location") {
+            Some(entry) => resolve_path(&entry.value, &entry.source),
+            None => dirs::home_dir()
+                .expect("Could not determine home directory")
+                .join(".tatl")
+                .join("ledger.db"),
+        }
+    }
+
+    pub fn default_view(&self) -> Option<&str> {
+        self.get("list.default_view")
+    }
+
+    pub fn list_color(&self) -> Option<&str> {
+        self.get("list.color")
+    }
+
+    pub fn date_format(&self) -> &str {
+        self.get("date.format").unwrap_or("%Y-%m-%d")
+    }
+
+    pub fn week_start(&self) -> Weekday {
+        self.get("week.start").and_then(|day| day.parse().ok()).unwrap_or(Weekday::Mon)
+    }
+
+    pub fn workdays(&self) -> Vec<Weekday> {
+        self.get("week.workdays")
+            .map(|days| days.split(',').filter_map(|day| day.parse().ok()).collect())
+            .unwrap_or_default()
+    }
+
+    /// First month of the fiscal year (1-12)
+    pub fn fiscal_year_start(&self) -> u32 {
+        self.get("fiscal.year_start")
+            .and_then(|month| month.parse::<Month>().ok())
+            .map_or(1, |month| month.number_from_month())
+    }
+
+    pub fn abbrev_verbose(&self) -> bool {
+        self.get_bool("abbrev.verbose")
+    }
+
+    /// Micro-session threshold in seconds
+    pub fn micro_session_secs(&self) -> i64 {
+        self.get("session.micro").and_then(|secs| secs.parse().ok()).unwrap_or(30)
+    }
+
+    /// Longest plausible session in seconds, if set
+    pub fn session_max_duration(&self) -> Option<i64> {
+        self.get("session.max_duration").and_then(|value| crate::utils::parse_duration(value).ok())
+    }
+
+    /// How stopped sessions are capped: `off`, `max_duration` or `midnight`
+    pub fn session_auto_cap(&self) -> &str {
+        self.get("session.auto_cap").unwrap_or("off")
+    }
+
+    /// Pomodoro work interval in seconds
+    pub fn pomodoro_work_secs(&self) -> i64 {
+        self.get("pomodoro.work").and_then(|value| crate::utils::parse_duration(value).ok()).unwrap_or(25 * 60)
+    }
+
+    /// Pomodoro break in seconds
+    pub fn pomodoro_break_secs(&self) -> i64 {
+        self.get("pomodoro.break").and_then(|value| crate::utils::parse_duration(value).ok()).unwrap_or(5 * 60)
+    }
+
+    /// Shell command run by `tatl timer` when an interval ends
+    pub fn pomodoro_hook(&self) -> Option<&str> {
+        self.get("pomodoro.hook").filter(|hook| !hook.trim().is_empty())
+    }
+}
+
+/// Set a key in an rc file, replacing any lines that already set it.
+/// The value is validated first; comments and other lines are kept.
+pub fn set_in_file(path: &Path, key: &str, value: &str) -> Result<String> {
+    let def = key_def(key).ok_or_else(|| anyhow::anyhow!("Unknown config key '{}'", key))?;
+    let value = def.value_type.validate(value)
+        .map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", key, e))?;
+
+    let text = if path.exists() {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let new_line = format!("{}={}", key, value);
+    let mut lines = Vec::new();
+    let mut replaced = false;
+    for line in text.lines() {
+        if line_key(line) == Some(key) {
+            if !replaced {
+                lines.push(new_line.clone());
+                replaced = true;
+            }
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    if !replaced {
+        lines.push(new_line);
+    }
+
+    write_lines(path, &lines)?;
+    Ok(value)
+}
+
+/// Remove a key from an rc file. Returns false if the file did not set it.
+pub fn unset_in_file(path: &Path, key: &str) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let lines: Vec<String> = text.lines()
+        .filter(|line| line_key(line) != Some(key))
+        .map(str::to_string)
+        .collect();
+    if lines.len() == text.lines().count() {
+        return Ok(false);
+    }
+    write_lines(path, &lines)?;
+    Ok(true)
+}
+
+fn write_lines(path: &Path, lines: &[String]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The key a `key=value` line sets, if it is one
+fn line_key(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    line.split_once('=').map(|(key, _)| key.trim())
+}
+
+/// Resolve a path from a config file: `~/` is the home directory and relative
+/// paths are relative to the file's directory
+fn resolve_path(value: &str, source: &Path) -> PathBuf {
+    if let Some(rest) = value.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    let path = PathBuf::from(value);
+    if path.is_relative() {
+        source.parent().map(|dir| dir.join(&path)).unwrap_or(path)
+    } else {
+        path
+    }
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_typed_values() {
+        let config = Config::parse(
+            "# comment\n\
+             data.location=./ledger.db\n\
+             week.start=Sun\n\
+             week.workdays=sun,mon,tue,wed,thu\n\
+             fiscal.year_start=apr\n\
+             abbrev.verbose=yes\n\
+             session.micro=-5\n\
+             date.format=%d.%m.%Y\n\
+             urgency.tag.urgent=6\n\
+             colour=red\n\
+             nonsense\n",
+            Path::new("/home/me/.tatl/rc"),
+        );
+        assert_eq!(config.data_location(), PathBuf::from("/home/me/.tatl/./ledger.db"));
+        assert_eq!(config.week_start(), Weekday::Sun);
+        assert_eq!(config.workdays(), vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Sun]);
+        assert_eq!(config.fiscal_year_start(), 4);
+        assert!(config.abbrev_verbose());
+        assert_eq!(config.micro_session_secs(), 30);
+        assert_eq!(config.date_format(), "%d.%m.%Y");
+        assert_eq!(config.entries_with_prefix("urgency.").collect::<Vec<_>>(), vec![("tag.urgent", "6")]);
+        assert_eq!(config.get("list.default_view"), None);
+        assert_eq!(config.warnings.len(), 3);
+        assert!(config.warnings[0].contains("not a whole number"));
+        assert!(config.warnings[1].contains("unknown key 'colour'"));
+        assert!(config.warnings[2].contains("line 11"));
+
+        assert!(ValueType::DateFormat.validate("%Y-%Q").is_err());
+        assert_eq!(ValueType::Weekdays.validate("Fri, mon,sun,mon"), Ok("mon,fri,sun".to_string()));
+        assert!(ValueType::Weekdays.validate("mon,funday").is_err());
+        assert_eq!(ValueType::Month.validate("4"), Ok("april".to_string()));
+        assert_eq!(ValueType::Month.validate("Oct"), Ok("october".to_string()));
+        assert!(ValueType::Month.validate("13").is_err());
+        assert_eq!(ValueType::Bool.validate("Off"), Ok("false".to_string()));
+        assert_eq!(ValueType::Duration.validate("1h30m"), Ok("1h30m".to_string()));
+        assert!(ValueType::Duration.validate("soon").is_err());
+        assert_eq!(ValueType::Choice(ROUNDING_MODES).validate("Nearest"), Ok("nearest".to_string()));
+        assert!(ValueType::Choice(ROUNDING_MODES).validate("sideways").is_err());
+        assert_eq!(key_def("billing.rate.project.work.acme").unwrap().name, "billing.rate.*");
+    }
+
+    #[test]
+    fn test_include_and_file_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let rc = temp_dir.path().join("rc");
+        fs::write(temp_dir.path().join("work.rc"), "week.start=sunday\nsession.micro=10\n").unwrap();
+        fs::write(&rc, "# settings\ninclude=work.rc\nsession.micro=20\ninclude=rc\n").unwrap();
+
+        // Later lines override included ones; a self-include stops at the depth limit
+        let config = Config::load(&rc);
+        assert_eq!(config.week_start(), Weekday::Sun);
+        assert_eq!(config.micro_session_secs(), 20);
+        assert_eq!(config.entry("week.start").unwrap().source, temp_dir.path().join("work.rc"));
+        assert!(config.warnings.iter().any(|w| w.contains("nested too deeply")));
+
+        assert_eq!(set_in_file(&rc, "abbrev.verbose", "on").unwrap(), "true");
+        set_in_file(&rc, "session.micro", "45").unwrap();
+        assert!(set_in_file(&rc, "session.micro", "soon").is_err());
+        assert!(set_in_file(&rc, "no.such.key", "1").is_err());
+        let text = fs::read_to_string(&rc).unwrap();
+        assert_eq!(text, "# settings\ninclude=work.rc\nsession.micro=45\ninclude=rc\nabbrev.verbose=true\n");
+
+        assert!(unset_in_file(&rc, "session.micro").unwrap());
+        assert!(!unset_in_file(&rc, "session.micro").unwrap());
+        assert_eq!(Config::load(&rc).micro_session_secs(), 10);
+    }
+}
@@ -1,6 +1,7 @@
 use rusqlite::Connection;
 use std::path::PathBuf;
 use anyhow::{Context, Result};
+use crate::config::Config;
 use crate::db::migrations::MigrationManager;
 
 /// Database connection manager
@@ -12,30 +13,9 @@ impl DbConnection {
         Self::home_dir().join(".tatl").join("ledger.db")
     }
 
-    /// Get database path from configuration file or default
+    /// Get database path from the configuration (`TATL_DATA`, `data.location`) or default
     pub fn resolve_path() -> Result<PathBuf> {
-        let config_path = Self::config_path();
-        
-        if config_path.exists() {
-            if let Ok(config) = std::fs::read_to_string(&config_path) {
-                for line in config.lines() {
-                    let line = line.trim();
-                    if line.starts_with("data.location=") {
-                        let path_str = line.strip_prefix("data.location=").unwrap().trim();
-                        let path = PathBuf::from(path_str);
-                        
-                        // If path is relative, resolve relative to config file directory
-                        if path.is_relative() {
-                            return Ok(config_path.parent().unwrap().join(path));
-                        } else {
-                            return Ok(path);
-                        }
-                    }
-                }
-            }
-        }
-        
-        Ok(Self::default_path())
+        Ok(Config::current().data_location())
     }
 
     /// Get the home directory (cross-platform)
@@ -43,11 +23,6 @@ impl DbConnection {
         dirs::home_dir().expect("Could not determine home directory")
     }
 
-    /// Get the configuration file path
-    pub fn config_path() -> PathBuf {
-        Self::home_dir().join(".tatl").join("rc")
-    }
-
     /// Connect to the database, creating it and parent directories if needed
     pub fn connect() -> Result<Connection> {
         let db_path = Self::resolve_path()?;
@@ -126,6 +101,14 @@ mod tests {
 
         // Verify schema was initialized
         let version = MigrationManager::get_version(&conn).unwrap();
-        assert_eq!(version, 11);
+        assert_eq!(version, crate::db::migrations::CURRENT_VERSION);
+
+        // Latest tables are present (v21: pomodoro intervals)
+        let tables: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'session_pomodoro'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(tables, 1);
     }
 }
@@ -2,7 +2,7 @@ use rusqlite::{Connection, Result};
 use std::collections::HashMap;
 
 /// Current database schema version
-const CURRENT_VERSION: u32 = 11;
+pub(crate) const CURRENT_VERSION: u32 = 21;
 
 /// Migration system for managing database schema versions
 pub struct MigrationManager;
@@ -93,6 +93,16 @@ fn get_migrations() -> HashMap<u32, fn(&rusqlite::Transaction) -> Result<(), rus
     migrations.insert(9, migration_v9);
     migrations.insert(10, migration_v10);
     migrations.insert(11, migration_v11);
+    migrations.insert(12, migration_v12);
+    migrations.insert(13, migration_v13);
+    migrations.insert(14, migration_v14);
+    migrations.insert(15, migration_v15);
+    migrations.insert(16, migration_v16);
+    migrations.insert(17, migration_v17);
+    migrations.insert(18, migration_v18);
+    migrations.insert(19, migration_v19);
+    migrations.insert(20, migration_v20);
+    migrations.insert(21, migration_v21);
     migrations
 }
 
@@ -697,6 +707,194 @@ fn migration_v11(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
     Ok(())
 }
 
+/// Migration v12: Group events into undoable batches
+/// Each CLI invocation (including pipe chains) writes its events under one batch_id.
+/// undone_batches records which batches were reverted, and by which undo batch,
+/// so task_events itself stays append-only.
+fn migration_v12(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "ALTER TABLE task_events ADD COLUMN batch_id TEXT",
+        [],
+    )?;
+    tx.execute(
+        "CREATE INDEX idx_task_events_batch_id ON task_events(batch_id)",
+        [],
+    )?;
+    tx.execute(
+        "CREATE TABLE undone_batches (
+            batch_id TEXT PRIMARY KEY,
+            undo_batch_id TEXT NOT NULL,
+            ts INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v13: Named queues
+/// Queue names become unique, and one queue can be marked active. Commands that
+/// act on "the queue" (on, close, dequeue, ...) use the active queue, falling
+/// back to 'default' when none is marked.
+fn migration_v13(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "ALTER TABLE stacks ADD COLUMN active INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    tx.execute(
+        "CREATE UNIQUE INDEX idx_stacks_name ON stacks(name)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v14: Index stack_items by task
+/// Filters are compiled to SQL with correlated subqueries on stack_items.task_id
+/// (stage and queue terms), which otherwise scan the whole table per task.
+fn migration_v14(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "CREATE INDEX idx_stack_items_task ON stack_items(task_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v15: Add show_json column to list_views
+fn migration_v15(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "ALTER TABLE list_views ADD COLUMN show_json TEXT NOT NULL DEFAULT '[]'",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v16: Typed UDA definitions
+/// Declares a type (string, number, date, duration, enum), allowed enum values,
+/// default and display label per UDA key. Undeclared UDAs remain free strings.
+fn migration_v16(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "CREATE TABLE uda_definitions (
+            name TEXT PRIMARY KEY,
+            type TEXT NOT NULL,
+            values_json TEXT NOT NULL DEFAULT '[]',
+            default_value TEXT,
+            label TEXT,
+            created_ts INTEGER NOT NULL,
+            modified_ts INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v17: Link respawned tasks to the instance they were generated from
+/// Following respawned_from back gives the chain of generated task ids, which the
+/// `count=` end condition of a respawn rule is checked against.
+fn migration_v17(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "ALTER TABLE tasks ADD COLUMN respawned_from INTEGER REFERENCES tasks(id) ON DELETE SET NULL",
+        [],
+    )?;
+    tx.execute(
+        "CREATE INDEX idx_tasks_respawned_from ON tasks(respawned_from)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v18: Pinned annotations
+/// Pinned annotations (checklists, links) are copied to the next instance when a
+/// respawning task has the carry=annotations option.
+fn migration_v18(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "ALTER TABLE task_annotations ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v19: Task dependencies and the blocked stage
+///
+/// A row in task_dependencies means task_id cannot start until depends_on_id is
+/// closed. stage_map gains an is_blocked input: open tasks with an open dependency
+/// map to "blocked" unless they are being timed or are out with an external.
+fn migration_v19(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "CREATE TABLE task_dependencies (
+            task_id       INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            depends_on_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            created_ts    INTEGER NOT NULL,
+            PRIMARY KEY (task_id, depends_on_id),
+            CHECK (task_id != depends_on_id)
+        )",
+        [],
+    )?;
+    tx.execute(
+        "CREATE INDEX idx_task_dependencies_depends_on ON task_dependencies(depends_on_id)",
+        [],
+    )?;
+
+    tx.execute(
+        "ALTER TABLE stage_map ADD COLUMN is_blocked INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    tx.execute(
+        "UPDATE stage_map SET is_blocked = -1 WHERE in_queue = -1",
+        [],
+    )?;
+
+    // One blocked row per open combination; timed and external tasks keep their stage
+    tx.execute(
+        "INSERT INTO stage_map (status, in_queue, has_sessions, has_open_session, has_externals, is_blocked, stage, sort_order, color)
+         SELECT status, in_queue, has_sessions, has_open_session, has_externals, 1,
+                CASE WHEN has_open_session = 1 OR has_externals = 1 THEN stage ELSE 'blocked' END,
+                CASE WHEN has_open_session = 1 OR has_externals = 1 THEN sort_order ELSE 3 END,
+                CASE WHEN has_open_session = 1 OR has_externals = 1 THEN color ELSE 'red' END
+         FROM stage_map
+         WHERE status = 'open' AND is_blocked = 0
+         ORDER BY id",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v20: Session notes and tags
+/// A session can say what happened during it without going through an annotation
+/// linked by task_annotations.session_id.
+fn migration_v20(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "ALTER TABLE sessions ADD COLUMN note TEXT",
+        [],
+    )?;
+    tx.execute(
+        "CREATE TABLE session_tags (
+            session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+            tag TEXT NOT NULL,
+            PRIMARY KEY(session_id, tag)
+        )",
+        [],
+    )?;
+    tx.execute(
+        "CREATE INDEX idx_session_tags_tag ON session_tags(tag)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v21: Pomodoro focus intervals
+/// A session started with `tatl on --pomodoro` is one work interval; the break
+/// after it is the gap before the next session.
+fn migration_v21(tx: &rusqlite::Transaction) -> Result<(), rusqlite::Error> {
+    tx.execute(
+        "CREATE TABLE session_pomodoro (
+            session_id INTEGER PRIMARY KEY REFERENCES sessions(id) ON DELETE CASCADE,
+            work_secs INTEGER NOT NULL,
+            break_secs INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,9 +4,10 @@
 //!
 //! # Evaluation Process
 //!
-//! 1. Load all tasks from the database
-//! 2. For each task, evaluate the filter expression
-//! 3. Return tasks that match
+//! 1. Compile the filter expression into a SQL condition (see `filter::sql`)
+//! 2. Load only the tasks matching that condition from the database
+//! 3. Evaluate any terms that could not be translated against each candidate in Rust
+//! 4. Return tasks that match
 //!
 //! # Filter Terms
 //!
@@ -14,14 +15,18 @@
 //! - `status=<status>` - Match by status (open, closed, cancelled, deleted)
 //! - `project=<name>` - Match by project (supports prefix matching for nested projects)
 //! - `+tag` / `-tag` - Match by tag presence/absence
+//! - `+blocked` / `+blocking` - Derived: open tasks waiting on an open dependency / that an open task depends on
+//!   (a literal `blocked` or `blocking` tag also matches)
 //! - `due=<expr>` - Match by due date (supports =, >, <, >=, <=, !=)
 //! - `scheduled=<expr>` - Match by scheduled date
 //! - `wait=<expr>` - Match by wait date
 //! - `waiting` - Derived: matches tasks with wait_ts in the future
-//! - `stage=<stage>` - Derived: matches tasks by stage (proposed, planned, in progress, suspended, active, external, completed, cancelled)
+//! - `queue=<name>` - Match by membership in a named queue (`any`/`none` for any queue)
+//! - `uda.<key>=<value>` - Match by user-defined attribute (numeric comparison when both sides are numbers; `none`/`any` for presence)
+//! - `stage=<stage>` - Derived: matches tasks by stage (proposed, planned, in progress, suspended, blocked, active, external, completed, cancelled)
 
-use crate::models::{Task, TaskStatus};
-use crate::repo::{TaskRepo, SessionRepo, StackRepo, ExternalRepo, StageRepo};
+use crate::models::{Task, TaskStatus, UdaDefinition, UdaType};
+use crate::repo::{TaskRepo, SessionRepo, StackRepo, ExternalRepo, StageRepo, UdaRepo, DependencyRepo};
 use crate::filter::parser::{FilterTerm, ComparisonOp};
 use rusqlite::Connection;
 use anyhow::Result;
@@ -281,6 +286,18 @@ impl FilterTerm {
             FilterTerm::Waiting => {
                 Ok(task.is_waiting())
             }
+            FilterTerm::Blocked(is_positive) => {
+                let task_id = task.id.unwrap_or(0);
+                let blocked = DependencyRepo::is_blocked(conn, task_id)?
+                    || TaskRepo::get_tags(conn, task_id)?.iter().any(|t| t == "blocked");
+                Ok(blocked == *is_positive)
+            }
+            FilterTerm::Blocking(is_positive) => {
+                let task_id = task.id.unwrap_or(0);
+                let blocking = DependencyRepo::is_blocking(conn, task_id)?
+                    || TaskRepo::get_tags(conn, task_id)?.iter().any(|t| t == "blocking");
+                Ok(blocking == *is_positive)
+            }
             FilterTerm::Stage(op, statuses) => {
                 // Multi-value stage filter: stage=planned,suspended matches if task stage is any of the values
                 let task_stage = calculate_task_stage(task, conn)?;
@@ -314,6 +331,20 @@ impl FilterTerm {
                     _ => Err(anyhow::anyhow!("External filter supports only '=' and '!='")),
                 }
             }
+            FilterTerm::Queue(op, names) => {
+                // Multi-value queue filter: queue=work,oncall matches tasks in either queue
+                let task_queues = StackRepo::get_names_for_task(conn, task.id.unwrap_or(0))?;
+                let matches_any = names.iter().any(|name| match name.as_str() {
+                    "any" => !task_queues.is_empty(),
+                    "none" => task_queues.is_empty(),
+                    _ => task_queues.iter().any(|q| q == name),
+                });
+                match op {
+                    ComparisonOp::Eq => Ok(matches_any),
+                    ComparisonOp::Neq => Ok(!matches_any),
+                    _ => Err(anyhow::anyhow!("Queue filter supports only '=' and '!='")),
+                }
+            }
             FilterTerm::Created(op, expr) => {
                 match_date_field(Some(task.created_ts), op, expr)
             }
@@ -323,6 +354,15 @@ impl FilterTerm {
             FilterTerm::Activity(op, expr) => {
                 match_date_field(Some(task.activity_ts), op, expr)
             }
+            FilterTerm::Uda(key, op, value) => {
+                let task_value = task.udas.get(key);
+                match UdaRepo::get(conn, key)? {
+                    Some(def) if def.uda_type == UdaType::Date => {
+                        match_date_field(task_value.and_then(|v| v.parse::<i64>().ok()), op, value)
+                    }
+                    definition => Ok(match_uda_value(task_value, op, value, definition.as_ref())),
+                }
+            }
             FilterTerm::Parent(op, value) => {
                 let value_lower = value.to_lowercase();
                 if value_lower == "none" {
@@ -354,16 +394,60 @@ impl FilterTerm {
     }
 }
 
+/// Compare a UDA value against a filter value.
+/// Typed attributes compare by their definition (numbers and durations numerically,
+/// enums by declared order) once the filter value normalises. Otherwise both sides are
+/// compared as numbers when they parse, and as strings when they don't.
+/// `=none`/`!=any` match a missing attribute, `=any`/`!=none` a present one.
+fn match_uda_value(
+    task_value: Option<&String>,
+    op: &ComparisonOp,
+    expr: &str,
+    definition: Option<&UdaDefinition>,
+) -> bool {
+    match (op, expr) {
+        (ComparisonOp::Eq, "any") | (ComparisonOp::Neq, "none") => return task_value.is_some(),
+        (ComparisonOp::Eq, "none") | (ComparisonOp::Neq, "any") => return task_value.is_none(),
+        _ => {}
+    }
+
+    let Some(value) = task_value else {
+        // Missing attribute is only "not equal" to a concrete value
+        return *op == ComparisonOp::Neq;
+    };
+
+    let typed = definition
+        .filter(|def| def.uda_type != UdaType::String)
+        .and_then(|def| def.normalize(expr).ok().map(|normalized| (def, normalized)));
+    let ordering = match (typed, value.parse::<f64>(), expr.parse::<f64>()) {
+        (Some((def, normalized)), _, _) => def.compare(value, &normalized),
+        (None, Ok(a), Ok(b)) => a.partial_cmp(&b),
+        _ => Some(value.as_str().cmp(expr)),
+    };
+    let Some(ordering) = ordering else {
+        return *op == ComparisonOp::Neq;
+    };
+
+    match op {
+        ComparisonOp::Eq => ordering.is_eq(),
+        ComparisonOp::Neq => ordering.is_ne(),
+        ComparisonOp::Gt => ordering.is_gt(),
+        ComparisonOp::Lt => ordering.is_lt(),
+        ComparisonOp::Gte => ordering.is_ge(),
+        ComparisonOp::Lte => ordering.is_le(),
+    }
+}
+
 /// Calculate the derived stage for a task using the stage_map table.
 ///
-/// Computes five booleans (status, in_queue, has_sessions, has_open_session,
-/// has_externals) and looks up the matching row in stage_map.
+/// Computes six inputs (status, in_queue, has_sessions, has_open_session,
+/// has_externals, is_blocked) and looks up the matching row in stage_map.
 pub fn calculate_task_stage(task: &Task, conn: &Connection) -> Result<String> {
     let status = task.status.as_str();
 
     // Terminal statuses: lookup directly
     if task.status == TaskStatus::Closed || task.status == TaskStatus::Cancelled {
-        let mapping = StageRepo::lookup(conn, status, false, false, false, false)?;
+        let mapping = StageRepo::lookup(conn, status, false, false, false, false, false)?;
         return Ok(mapping.stage);
     }
 
@@ -375,24 +459,33 @@ pub fn calculate_task_stage(task: &Task, conn: &Connection) -> Result<String> {
 
     let has_externals = ExternalRepo::has_active_externals(conn, task_id)?;
 
-    let stack = StackRepo::get_or_create_default(conn)?;
-    let items = StackRepo::get_items(conn, stack.id.unwrap())?;
-    let in_queue = items.iter().any(|item| item.task_id == task_id);
+    // Membership in any named queue counts as in_queue
+    let in_queue = StackRepo::is_queued(conn, task_id)?;
 
-    let all_sessions = SessionRepo::list_all(conn)?;
-    let has_sessions = all_sessions.iter().any(|s| s.task_id == task_id);
+    let has_sessions = has_open_session || !SessionRepo::get_by_task(conn, task_id)?.is_empty();
 
-    let mapping = StageRepo::lookup(conn, status, in_queue, has_sessions, has_open_session, has_externals)?;
+    let is_blocked = DependencyRepo::is_blocked(conn, task_id)?;
+
+    let mapping = StageRepo::lookup(conn, status, in_queue, has_sessions, has_open_session, has_externals, is_blocked)?;
     Ok(mapping.stage)
 }
 
 /// Get tasks matching a filter expression
 pub fn filter_tasks(conn: &Connection, filter: &FilterExpr) -> Result<Vec<(Task, Vec<String>)>> {
-    let all_tasks = TaskRepo::list_all(conn)?;
-    let mut matching = Vec::new();
+    let udas = UdaRepo::load_map(conn)?;
+    let (sql, residual) = crate::filter::sql::plan_filter(filter, &udas);
+    let candidates = match sql {
+        Some(sql) => TaskRepo::list_matching(conn, &sql.condition, &sql.params)?,
+        None => TaskRepo::list_all(conn)?,
+    };
 
-    for (task, tags) in all_tasks {
-        if filter.matches(&task, conn)? {
+    let Some(residual) = residual else {
+        return Ok(candidates);
+    };
+
+    let mut matching = Vec::new();
+    for (task, tags) in candidates {
+        if residual.matches(&task, conn)? {
             matching.push((task, tags));
         }
     }
@@ -574,4 +667,32 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].0.id, task1.id);
     }
+
+    #[test]
+    fn test_filter_uda() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+
+        let udas = |customer: &str, estimate: &str| {
+            std::collections::HashMap::from([
+                ("customer".to_string(), customer.to_string()),
+                ("estimate".to_string(), estimate.to_string()),
+            ])
+        };
+        let acme = TaskRepo::create_full(&conn, "Acme", None, None, None, None, None, None, None, &udas("acme", "10"), &[], None).unwrap();
+        let globex = TaskRepo::create_full(&conn, "Globex", None, None, None, None, None, None, None, &udas("globex", "3"), &[], None).unwrap();
+        let plain = TaskRepo::create(&conn, "Plain", None).unwrap();
+
+        let ids = |tokens: &[&str]| -> Vec<Option<i64>> {
+            let filter = parse_filter(tokens.iter().map(|t| t.to_string()).collect()).unwrap();
+            filter_tasks(&conn, &filter).unwrap().into_iter().map(|(t, _)| t.id).collect()
+        };
+
+        assert_eq!(ids(&["uda.customer=acme"]), vec![acme.id]);
+        assert_eq!(ids(&["uda.customer=none"]), vec![plain.id]);
+        assert_eq!(ids(&["uda.customer!=acme"]), vec![globex.id, plain.id]);
+        // Numeric comparison: 10 > 3 even though "10" < "3" as strings
+        assert_eq!(ids(&["uda.estimate>3"]), vec![acme.id]);
+        assert_eq!(ids(&["uda.estimate<=3.0"]), vec![globex.id]);
+        assert_eq!(ids(&["uda.estimate=10.0"]), vec![acme.id]);
+    }
 }
@@ -1,5 +1,6 @@
 pub mod parser;
 pub mod evaluator;
+pub mod sql;
 
 pub use parser::*;
 pub use evaluator::*;
@@ -5,15 +5,21 @@
 //! # Grammar
 //!
 //! ```text
-//! filter := term | filter "or" term | "not" term
-//! term := id | status=<status> | project=<name> | +tag | -tag | due=<expr> | due>expr | ...
+//! filter  := and_expr ("or" and_expr)*
+//! and_expr := unary unary*
+//! unary   := "not" unary | "(" filter ")" | term
+//! term    := id | status=<status> | project=<name> | +tag | -tag | due=<expr> | due>expr | uda.<key>=<value> | ...
 //! ```
 //!
 //! # Precedence
 //!
-//! 1. `not` (highest)
-//! 2. Implicit `and` (between adjacent terms)
-//! 3. `or` (lowest)
+//! 1. `( ... )` grouping
+//! 2. `not`
+//! 3. Implicit `and` (between adjacent terms)
+//! 4. `or` (lowest)
+//!
+//! Parentheses may be separate tokens or attached to a term (`(+urgent`, `eod)`).
+//! A whole group may also be passed as one quoted argument, e.g. `"(+a or +b)"`.
 //!
 //! # Examples
 //!
@@ -32,6 +38,9 @@
 //!
 //! // Comparison operators
 //! due>tomorrow due<=eod
+//!
+//! // Grouping
+//! project=work (+urgent or due<eod) not +blocked
 //! ```
 
 use crate::filter::evaluator::FilterExpr;
@@ -67,39 +76,86 @@ pub fn parse_filter(tokens: Vec<String>) -> Result<FilterExpr, String> {
         return Ok(FilterExpr::All); // No filter = match all
     }
 
-    // First pass: parse tokens into filter terms and operators
+    // First pass: parse tokens into filter terms, operators and parentheses
     let mut parsed: Vec<FilterToken> = Vec::new();
-    let mut i = 0;
 
-    while i < tokens.len() {
-        let token = &tokens[i];
-
-        // Check for operators
-        if token == "or" {
-            parsed.push(FilterToken::Or);
-            i += 1;
-            continue;
+    for token in split_grouped_tokens(&tokens) {
+        match token.as_str() {
+            "or" => parsed.push(FilterToken::Or),
+            "not" => parsed.push(FilterToken::Not),
+            "(" => parsed.push(FilterToken::LParen),
+            ")" => parsed.push(FilterToken::RParen),
+            _ => match parse_filter_term(&token) {
+                Ok(Some(term)) => parsed.push(FilterToken::Term(term)),
+                Ok(None) => return Err(format!("Invalid filter token: {}", token)),
+                Err(err) => return Err(err),
+            },
         }
+    }
 
-        if token == "not" {
-            parsed.push(FilterToken::Not);
-            i += 1;
-            continue;
+    // Second pass: build expression tree respecting precedence
+    // Precedence: () > not > and > or
+    build_expression(parsed)
+}
+
+/// Split raw filter arguments into tokens, detaching parentheses from terms.
+///
+/// An argument with whitespace is a quoted group (e.g. `"(+a or +b)"`) and is
+/// split on whitespace first when it has parentheses or `or`/`not` and all its
+/// other words are filter terms; any other argument with whitespace is a single
+/// term with a quoted value (e.g. `"desc=foo (bar)"`) and is kept whole. Leading `(` are always
+/// detached; trailing `)` are detached only while they are unbalanced within the
+/// term, so values like `desc=fix(parser)` stay intact.
+fn split_grouped_tokens(tokens: &[String]) -> Vec<String> {
+    let mut result = Vec::new();
+    for token in tokens {
+        if !token.contains(char::is_whitespace) {
+            split_grouped_word(token, &mut result);
+        } else if is_quoted_group(token) {
+            for word in token.split_whitespace() {
+                split_grouped_word(word, &mut result);
+            }
+        } else {
+            result.push(token.clone());
         }
+    }
+    result
+}
 
-        // Parse as filter term
-        match parse_filter_term(token) {
-            Ok(Some(term)) => parsed.push(FilterToken::Term(term)),
-            Ok(None) => return Err(format!("Invalid filter token: {}", token)),
-            Err(err) => return Err(err),
+fn is_quoted_group(token: &str) -> bool {
+    let mut words = Vec::new();
+    for word in token.split_whitespace() {
+        split_grouped_word(word, &mut words);
+    }
+    let mut grouped = false;
+    for word in &words {
+        match word.as_str() {
+            "(" | ")" | "or" | "not" => grouped = true,
+            _ if matches!(parse_filter_term(word), Ok(Some(_))) => {}
+            _ => return false,
         }
+    }
+    grouped
+}
 
-        i += 1;
+/// Detach grouping parentheses from one whitespace-free word
+fn split_grouped_word(word: &str, result: &mut Vec<String>) {
+    let mut rest = word;
+    while let Some(stripped) = rest.strip_prefix('(') {
+        result.push("(".to_string());
+        rest = stripped;
     }
 
-    // Second pass: build expression tree respecting precedence
-    // Precedence: not > and > or
-    build_expression(parsed)
+    let mut closing = 0;
+    while rest.ends_with(')') && rest.matches(')').count() > rest.matches('(').count() {
+        rest = &rest[..rest.len() - 1];
+        closing += 1;
+    }
+
+    if !rest.is_empty() {
+        result.push(rest.to_string());
+    }
+    result.extend(std::iter::repeat_n(")".to_string(), closing));
 }
 
 #[derive(Debug, Clone)]
@@ -107,6 +163,8 @@ enum FilterToken {
     Term(FilterTerm),
     Not,
     Or,
+    LParen,
+    RParen,
 }
 
 #[derive(Debug, Clone)]
@@ -120,12 +178,16 @@ pub enum FilterTerm {
     Wait(ComparisonOp, String),
     Waiting,
     Stage(ComparisonOp, Vec<String>), // Supports comma-separated values
+    Queue(ComparisonOp, Vec<String>), // Named queue membership, comma-separated values (OR logic)
     Desc(ComparisonOp, String), // Description substring search (case-insensitive)
     External(ComparisonOp, String), // External recipient filter
     Created(ComparisonOp, String),
     Modified(ComparisonOp, String),
     Activity(ComparisonOp, String),
     Parent(ComparisonOp, String),
+    Blocked(bool),  // +blocked / -blocked: waiting on an open dependency
+    Blocking(bool), // +blocking / -blocking: an open task depends on it
+    Uda(String, ComparisonOp, String), // (key, op, value); numeric compare when both sides parse
 }
 
 /// Split a token into (key, operator, value) using operator detection.
@@ -167,7 +229,7 @@ fn split_on_operator(token: &str) -> Option<(String, ComparisonOp, String)> {
 /// Known filter keys (exact match only)
 const FILTER_KEYS: &[&str] = &[
     "id", "status", "project", "due", "scheduled", "wait",
-    "stage", "desc", "description", "external",
+    "stage", "queue", "desc", "description", "external",
     "created", "modified", "activity", "parent",
 ];
 
@@ -220,6 +282,14 @@ fn parse_filter_term(token: &str) -> Result<Option<FilterTerm>, String> {
 
     // Try to split on operator (=, >, <, >=, <=, !=, <>)
     if let Some((key, op, value)) = split_on_operator(token) {
+        // User-defined attributes: uda.<key>=<value>, uda.<key>>3, uda.<key>=none
+        if let Some(uda_key) = key.strip_prefix("uda.") {
+            if uda_key.is_empty() {
+                return Err("UDA filter requires a key, e.g. uda.customer=acme".to_string());
+            }
+            return Ok(Some(FilterTerm::Uda(uda_key.to_string(), op, value)));
+        }
+
         let key_resolved = resolve_filter_key(&key)?;
         return match key_resolved.as_str() {
             "id" => {
@@ -259,6 +329,15 @@ fn parse_filter_term(token: &str) -> Result<Option<FilterTerm>, String> {
                     .collect();
                 Ok(Some(FilterTerm::Stage(op, values)))
             },
+            "queue" => {
+                if op != ComparisonOp::Eq && op != ComparisonOp::Neq {
+                    return Err(format!("Queue filter only supports '=' and '!=' operators, got '{}'", format_op(&op)));
+                }
+                let values: Vec<String> = value.split(',')
+                    .map(|v| v.trim().to_string())
+                    .collect();
+                Ok(Some(FilterTerm::Queue(op, values)))
+            },
             "desc" | "description" => {
                 if op != ComparisonOp::Eq && op != ComparisonOp::Neq {
                     return Err(format!("Description filter only supports '=' and '!=' operators, got '{}'", format_op(&op)));
@@ -284,6 +363,15 @@ fn parse_filter_term(token: &str) -> Result<Option<FilterTerm>, String> {
         };
     }
 
+    // Dependency state (virtual tags)
+    match token {
+        "+blocked" => return Ok(Some(FilterTerm::Blocked(true))),
+        "-blocked" => return Ok(Some(FilterTerm::Blocked(false))),
+        "+blocking" => return Ok(Some(FilterTerm::Blocking(true))),
+        "-blocking" => return Ok(Some(FilterTerm::Blocking(false))),
+        _ => {}
+    }
+
     // +tag or -tag
     if let Some(tag) = token.strip_prefix('+') {
         return Ok(Some(FilterTerm::Tag(tag.to_string(), true)));
@@ -313,100 +401,93 @@ fn format_op(op: &ComparisonOp) -> &'static str {
 }
 
 /// Build expression tree from parsed tokens
-/// Precedence: not > and > or
+/// Precedence: () > not > and > or
 fn build_expression(tokens: Vec<FilterToken>) -> Result<FilterExpr, String> {
-    if tokens.is_empty() {
-        return Ok(FilterExpr::All);
-    }
-
-    // First, apply NOT operators (highest precedence)
-    let mut after_not = Vec::new();
-    let mut i = 0;
-    while i < tokens.len() {
-        if let FilterToken::Not = tokens[i] {
-            if i + 1 >= tokens.len() {
-                return Err("NOT operator requires a following term".to_string());
-            }
-            if let FilterToken::Term(term) = &tokens[i + 1] {
-                after_not.push(FilterToken::Term(term.clone()));
-                after_not.push(FilterToken::Not); // Mark as negated
-                i += 2;
-            } else {
-                return Err("NOT operator must be followed by a term".to_string());
-            }
-        } else {
-            after_not.push(tokens[i].clone());
-            i += 1;
-        }
-    }
+    let mut builder = ExprBuilder { tokens, pos: 0 };
+    let expr = builder.parse_or(0)?;
+    Ok(expr.unwrap_or(FilterExpr::All))
+}
 
-    // Now handle OR operators (lowest precedence)
-    // Split by OR to get AND groups
-    let mut or_groups: Vec<Vec<FilterToken>> = Vec::new();
-    let mut current_group = Vec::new();
+/// Recursive-descent builder over parsed filter tokens
+struct ExprBuilder {
+    tokens: Vec<FilterToken>,
+    pos: usize,
+}
 
-    for token in after_not {
-        if let FilterToken::Or = token {
-            if !current_group.is_empty() {
-                or_groups.push(current_group);
-                current_group = Vec::new();
-            }
-        } else {
-            current_group.push(token);
-        }
-    }
-    if !current_group.is_empty() {
-        or_groups.push(current_group);
+impl ExprBuilder {
+    fn peek(&self) -> Option<&FilterToken> {
+        self.tokens.get(self.pos)
     }
 
-    // Convert each group (AND group) to expression
-    let mut or_exprs = Vec::new();
-    for group in or_groups {
-        let and_expr = build_and_expression(group)?;
-        or_exprs.push(and_expr);
+    fn next(&mut self) -> Option<FilterToken> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
     }
 
-    // Combine OR expressions
-    if or_exprs.len() == 1 {
-        Ok(or_exprs.remove(0))
-    } else {
-        Ok(FilterExpr::Or(or_exprs))
-    }
-}
+    /// OR of AND groups. Empty groups (leading, trailing or repeated `or`) are skipped.
+    fn parse_or(&mut self, depth: usize) -> Result<Option<FilterExpr>, String> {
+        let mut or_exprs = Vec::new();
+        loop {
+            if let Some(and_expr) = self.parse_and()? {
+                or_exprs.push(and_expr);
+            }
+            match self.peek() {
+                Some(FilterToken::Or) => {
+                    self.pos += 1;
+                }
+                Some(FilterToken::RParen) if depth == 0 => {
+                    return Err("Unbalanced parentheses: unexpected ')'".to_string());
+                }
+                _ => break,
+            }
+        }
 
-/// Build AND expression from a group of terms (implicit AND)
-fn build_and_expression(tokens: Vec<FilterToken>) -> Result<FilterExpr, String> {
-    if tokens.is_empty() {
-        return Ok(FilterExpr::All);
+        Ok(match or_exprs.len() {
+            0 => None,
+            1 => or_exprs.pop(),
+            _ => Some(FilterExpr::Or(or_exprs)),
+        })
     }
 
-    let mut and_terms = Vec::new();
+    /// Implicit AND of adjacent unary expressions
+    fn parse_and(&mut self) -> Result<Option<FilterExpr>, String> {
+        let mut and_terms = Vec::new();
+        while !matches!(self.peek(), None | Some(FilterToken::Or) | Some(FilterToken::RParen)) {
+            and_terms.push(self.parse_unary()?);
+        }
 
-    for token in tokens {
-        match token {
-            FilterToken::Term(term) => {
-                and_terms.push(FilterExpr::Term(term));
-            }
-            FilterToken::Not => {
-                // Apply NOT to the last term
-                if let Some(last) = and_terms.pop() {
-                    and_terms.push(FilterExpr::Not(Box::new(last)));
-                } else {
-                    return Err("NOT operator without preceding term".to_string());
+        Ok(match and_terms.len() {
+            0 => None,
+            1 => and_terms.pop(),
+            _ => Some(FilterExpr::And(and_terms)),
+        })
+    }
+
+    /// A term, a negation, or a parenthesised group
+    fn parse_unary(&mut self) -> Result<FilterExpr, String> {
+        match self.next() {
+            Some(FilterToken::Term(term)) => Ok(FilterExpr::Term(term)),
+            Some(FilterToken::Not) => match self.peek() {
+                None => Err("NOT operator requires a following term".to_string()),
+                Some(FilterToken::Or) | Some(FilterToken::RParen) => {
+                    Err("NOT operator must be followed by a term or group".to_string())
+                }
+                _ => Ok(FilterExpr::Not(Box::new(self.parse_unary()?))),
+            },
+            Some(FilterToken::LParen) => {
+                let inner = self.parse_or(1)?;
+                match self.next() {
+                    Some(FilterToken::RParen) => {}
+                    _ => return Err("Unbalanced parentheses: missing ')'".to_string()),
                 }
+                inner.ok_or_else(|| "Empty parentheses in filter".to_string())
             }
-            FilterToken::Or => {
-                // Should not happen in AND group, but handle gracefully
-                return Err("Unexpected OR in AND group".to_string());
+            Some(FilterToken::Or) | Some(FilterToken::RParen) | None => {
+                Err("Expected a filter term".to_string())
             }
         }
     }
-
-    if and_terms.len() == 1 {
-        Ok(and_terms.remove(0))
-    } else {
-        Ok(FilterExpr::And(and_terms))
-    }
 }
 
 #[cfg(test)]
@@ -566,4 +647,91 @@ mod tests {
         let result = parse_filter(vec!["status>open".to_string()]);
         assert!(result.is_err());
     }
+
+    fn tokens(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_grouping() {
+        // project=work AND (+urgent OR due<eod) AND NOT +blocked
+        let expr = parse_filter(tokens("project=work ( +urgent or due<eod ) not +blocked")).unwrap();
+        match expr {
+            FilterExpr::And(terms) => {
+                assert_eq!(terms.len(), 3);
+                assert!(matches!(&terms[1], FilterExpr::Or(inner) if inner.len() == 2));
+                assert!(matches!(&terms[2], FilterExpr::Not(_)));
+            }
+            _ => panic!("Expected And expression"),
+        }
+    }
+
+    #[test]
+    fn test_parse_attached_parentheses() {
+        let expr = parse_filter(tokens("project=work (+urgent or due<eod) not +blocked")).unwrap();
+        assert!(matches!(expr, FilterExpr::And(ref terms) if terms.len() == 3));
+
+        // Nested and negated groups
+        let expr = parse_filter(tokens("not ((+a or +b) +c)")).unwrap();
+        match expr {
+            FilterExpr::Not(inner) => assert!(matches!(*inner, FilterExpr::And(_))),
+            _ => panic!("Expected Not expression"),
+        }
+
+        // Balanced parentheses inside a value are kept
+        let expr = parse_filter(tokens("(desc=fix(parser))")).unwrap();
+        match expr {
+            FilterExpr::Term(FilterTerm::Desc(_, value)) => assert_eq!(value, "fix(parser)"),
+            _ => panic!("Expected Desc term"),
+        }
+    }
+
+    #[test]
+    fn test_parse_quoted_group() {
+        // A group passed as a single argument (as modify/close/show receive it)
+        let expr = parse_filter(vec!["(+urgent or +important) project=work".to_string()]).unwrap();
+        assert!(matches!(expr, FilterExpr::And(ref terms) if terms.len() == 2));
+
+        // A quoted group may also start with an operator
+        let expr = parse_filter(vec!["not (+a or +b)".to_string()]).unwrap();
+        assert!(matches!(expr, FilterExpr::Not(_)));
+
+        // A quoted value containing parentheses is one term
+        let expr = parse_filter(vec!["desc=foo (bar)".to_string()]).unwrap();
+        match expr {
+            FilterExpr::Term(FilterTerm::Desc(_, value)) => assert_eq!(value, "foo (bar)"),
+            _ => panic!("Expected Desc term"),
+        }
+
+        // A phrase with a lone "and" is a value, not a group
+        let expr = parse_filter(vec!["desc=salt and pepper".to_string()]).unwrap();
+        match expr {
+            FilterExpr::Term(FilterTerm::Desc(_, value)) => assert_eq!(value, "salt and pepper"),
+            _ => panic!("Expected Desc term"),
+        }
+        let expr = parse_filter(vec!["desc=rock or roll".to_string()]).unwrap();
+        assert!(matches!(expr, FilterExpr::Term(FilterTerm::Desc(_, ref value)) if value == "rock or roll"));
+
+        // Whitespace in values without parentheses is preserved
+        let expr = parse_filter(vec!["stage=in progress".to_string()]).unwrap();
+        match expr {
+            FilterExpr::Term(FilterTerm::Stage(_, values)) => assert_eq!(values, vec!["in progress".to_string()]),
+            _ => panic!("Expected Stage term"),
+        }
+    }
+
+    #[test]
+    fn test_parse_unbalanced_parentheses() {
+        let err = parse_filter(tokens("(+urgent or +important")).unwrap_err();
+        assert!(err.contains("missing ')'"));
+
+        let err = parse_filter(tokens("+urgent)")).unwrap_err();
+        assert!(err.contains("unexpected ')'"));
+
+        let err = parse_filter(tokens("project=work ()")).unwrap_err();
+        assert!(err.contains("Empty parentheses"));
+
+        let err = parse_filter(tokens("not )")).unwrap_err();
+        assert!(err.contains("NOT operator"));
+    }
 }
@@ -0,0 +1,511 @@
+//! SQL pushdown for filter expressions
+//!
+//! Compiles `FilterExpr` trees into a SQL condition on the tasks table (aliased `t`)
+//! so filtering happens in SQLite instead of loading every task and evaluating it in Rust.
+//! Derived terms (stage, waiting, queue, external) are translated into correlated
+//! `EXISTS` subqueries against sessions, stack_items, externals and stage_map.
+//!
+//! Terms that cannot be translated exactly stay in Rust. For a top-level AND, the
+//! translatable children are still pushed down and the rest is evaluated on the
+//! reduced candidate set (see `plan_filter`).
+//!
+//! UDA terms are compiled against the UDA definitions: date-typed attributes use the
+//! same date semantics as built-in date fields, other typed attributes stay in Rust.
+
+use crate::filter::evaluator::FilterExpr;
+use crate::filter::parser::{ComparisonOp, FilterTerm};
+use crate::models::{UdaDefinition, UdaType};
+use rusqlite::types::Value;
+use std::collections::HashMap;
+
+/// A compiled SQL condition with its positional parameters
+#[derive(Debug, Clone)]
+pub struct SqlFilter {
+    pub condition: String,
+    pub params: Vec<Value>,
+}
+
+impl SqlFilter {
+    fn new(condition: impl Into<String>, params: Vec<Value>) -> Self {
+        Self { condition: condition.into(), params }
+    }
+
+    fn constant(matches: bool) -> Self {
+        Self::new(if matches { "1" } else { "0" }, Vec::new())
+    }
+
+    fn negate(self) -> Self {
+        Self::new(format!("NOT ({})", self.condition), self.params)
+    }
+
+    fn join(parts: Vec<SqlFilter>, separator: &str) -> Self {
+        if parts.is_empty() {
+            // Empty AND matches everything, empty OR matches nothing
+            return Self::constant(separator.trim() == "AND");
+        }
+        let mut params = Vec::new();
+        let mut conditions = Vec::new();
+        for part in parts {
+            conditions.push(format!("({})", part.condition));
+            params.extend(part.params);
+        }
+        Self::new(conditions.join(separator), params)
+    }
+}
+
+/// Split a filter into a SQL condition and a residual expression for Rust evaluation
+///
+/// Returns `(sql, residual)`. When the whole expression translates, the residual is `None`.
+/// When nothing translates, the SQL part is `None` and the caller evaluates everything in Rust.
+pub fn plan_filter(
+    expr: &FilterExpr,
+    udas: &HashMap<String, UdaDefinition>,
+) -> (Option<SqlFilter>, Option<FilterExpr>) {
+    if let Some(sql) = compile_expr(expr, udas) {
+        return (Some(sql), None);
+    }
+
+    if let FilterExpr::And(children) = expr {
+        let mut pushed = Vec::new();
+        let mut residual = Vec::new();
+        for child in children {
+            match compile_expr(child, udas) {
+                Some(sql) => pushed.push(sql),
+                None => residual.push(child.clone()),
+            }
+        }
+        let sql = if pushed.is_empty() { None } else { Some(SqlFilter::join(pushed, " AND ")) };
+        let residual = if residual.len() == 1 {
+            residual.pop()
+        } else {
+            Some(FilterExpr::And(residual))
+        };
+        return (sql, residual);
+    }
+
+    (None, Some(expr.clone()))
+}
+
+/// Compile an expression to SQL, or `None` if any part of it cannot be translated
+pub fn compile_expr(expr: &FilterExpr, udas: &HashMap<String, UdaDefinition>) -> Option<SqlFilter> {
+    match expr {
+        FilterExpr::All => Some(SqlFilter::constant(true)),
+        FilterExpr::Term(term) => compile_term(term, udas),
+        FilterExpr::And(children) => {
+            let parts = children.iter().map(|c| compile_expr(c, udas)).collect::<Option<Vec<_>>>()?;
+            Some(SqlFilter::join(parts, " AND "))
+        }
+        FilterExpr::Or(children) => {
+            let parts = children.iter().map(|c| compile_expr(c, udas)).collect::<Option<Vec<_>>>()?;
+            Some(SqlFilter::join(parts, " OR "))
+        }
+        FilterExpr::Not(inner) => compile_expr(inner, udas).map(SqlFilter::negate),
+    }
+}
+
+fn sql_op(op: &ComparisonOp) -> &'static str {
+    match op {
+        ComparisonOp::Eq => "=",
+        ComparisonOp::Neq => "!=",
+        ComparisonOp::Gt => ">",
+        ComparisonOp::Lt => "<",
+        ComparisonOp::Gte => ">=",
+        ComparisonOp::Lte => "<=",
+    }
+}
+
+/// Wrap a membership condition for '=' / '!=' operators; other operators are not translated
+fn eq_or_neq(op: &ComparisonOp, matches: SqlFilter) -> Option<SqlFilter> {
+    match op {
+        ComparisonOp::Eq => Some(matches),
+        ComparisonOp::Neq => Some(matches.negate()),
+        _ => None,
+    }
+}
+
+/// Build "?, ?, ?" placeholders and text params for an IN list
+fn in_list(values: &[String]) -> (String, Vec<Value>) {
+    let placeholders = vec!["?"; values.len()].join(", ");
+    let params = values.iter().map(|v| Value::Text(v.clone())).collect();
+    (placeholders, params)
+}
+
+fn compile_term(term: &FilterTerm, udas: &HashMap<String, UdaDefinition>) -> Option<SqlFilter> {
+    match term {
+        FilterTerm::Id(op, id) => Some(SqlFilter::new(
+            format!("t.id {} ?", sql_op(op)),
+            vec![Value::Integer(*id)],
+        )),
+        FilterTerm::Status(op, statuses) => {
+            let (placeholders, params) = in_list(statuses);
+            eq_or_neq(op, SqlFilter::new(format!("t.status IN ({})", placeholders), params))
+        }
+        FilterTerm::Project(op, names) => eq_or_neq(op, project_condition(names)),
+        FilterTerm::Tag(tag, is_positive) => {
+            let exists = SqlFilter::new(
+                "EXISTS (SELECT 1 FROM task_tags tt WHERE tt.task_id = t.id AND tt.tag = ?)",
+                vec![Value::Text(tag.clone())],
+            );
+            Some(if *is_positive { exists } else { exists.negate() })
+        }
+        FilterTerm::Due(op, expr) => Some(date_condition("t.due_ts", op, expr)),
+        FilterTerm::Scheduled(op, expr) => Some(date_condition("t.scheduled_ts", op, expr)),
+        FilterTerm::Wait(op, expr) => Some(date_condition("t.wait_ts", op, expr)),
+        FilterTerm::Created(op, expr) => Some(date_condition("t.created_ts", op, expr)),
+        FilterTerm::Modified(op, expr) => Some(date_condition("t.modified_ts", op, expr)),
+        FilterTerm::Activity(op, expr) => Some(date_condition("COALESCE(t.activity_ts, t.modified_ts)", op, expr)),
+        FilterTerm::Waiting => Some(SqlFilter::new(
+            "t.wait_ts IS NOT NULL AND t.wait_ts > ?",
+            vec![Value::Integer(chrono::Utc::now().timestamp())],
+        )),
+        FilterTerm::Blocked(is_positive) => {
+            let blocked = SqlFilter::new(
+                "(t.status = 'open' AND EXISTS (SELECT 1 FROM task_dependencies d JOIN tasks b ON b.id = d.depends_on_id
+                                              WHERE d.task_id = t.id AND b.status = 'open'))
+                 OR EXISTS (SELECT 1 FROM task_tags tt WHERE tt.task_id = t.id AND tt.tag = 'blocked')",
+                Vec::new(),
+            );
+            Some(if *is_positive { blocked } else { blocked.negate() })
+        }
+        FilterTerm::Blocking(is_positive) => {
+            let blocking = SqlFilter::new(
+                "(t.status = 'open' AND EXISTS (SELECT 1 FROM task_dependencies d JOIN tasks w ON w.id = d.task_id
+                                              WHERE d.depends_on_id = t.id AND w.status = 'open'))
+                 OR EXISTS (SELECT 1 FROM task_tags tt WHERE tt.task_id = t.id AND tt.tag = 'blocking')",
+                Vec::new(),
+            );
+            Some(if *is_positive { blocking } else { blocking.negate() })
+        }
+        FilterTerm::Stage(op, stages) => {
+            let stages: Vec<String> = stages.iter().map(|s| s.to_lowercase()).collect();
+            let (placeholders, params) = in_list(&stages);
+            eq_or_neq(op, SqlFilter::new(
+                format!("lower(COALESCE(({}), '')) IN ({})", STAGE_SUBQUERY, placeholders),
+                params,
+            ))
+        }
+        FilterTerm::Desc(op, pattern) => {
+            // SQLite's lower() only folds ASCII; keep non-ASCII patterns in Rust
+            if !pattern.is_ascii() {
+                return None;
+            }
+            eq_or_neq(op, SqlFilter::new(
+                "instr(lower(t.description), lower(?)) > 0",
+                vec![Value::Text(pattern.clone())],
+            ))
+        }
+        FilterTerm::External(op, recipient) => eq_or_neq(op, SqlFilter::new(
+            "EXISTS (SELECT 1 FROM externals e WHERE e.task_id = t.id AND e.returned_ts IS NULL AND e.recipient = ?)",
+            vec![Value::Text(recipient.clone())],
+        )),
+        FilterTerm::Parent(op, value) => {
+            let matches = match value.to_lowercase().as_str() {
+                "none" => SqlFilter::new("t.parent_id IS NULL", Vec::new()),
+                "any" => SqlFilter::new("t.parent_id IS NOT NULL", Vec::new()),
+                // Invalid values are reported by the Rust evaluator
+                _ => SqlFilter::new("t.parent_id IS ?", vec![Value::Integer(value.parse().ok()?)]),
+            };
+            eq_or_neq(op, matches)
+        }
+        FilterTerm::Uda(key, op, value) => uda_condition(key, op, value, udas.get(key)),
+        FilterTerm::Queue(op, names) => {
+            let parts = names.iter().map(|name| match name.as_str() {
+                "any" => SqlFilter::new("EXISTS (SELECT 1 FROM stack_items si WHERE si.task_id = t.id)", Vec::new()),
+                "none" => SqlFilter::new("NOT EXISTS (SELECT 1 FROM stack_items si WHERE si.task_id = t.id)", Vec::new()),
+                _ => SqlFilter::new(
+                    "EXISTS (SELECT 1 FROM stack_items si JOIN stacks s ON s.id = si.stack_id
+                             WHERE si.task_id = t.id AND s.name = ?)",
+                    vec![Value::Text(name.clone())],
+                ),
+            }).collect();
+            eq_or_neq(op, SqlFilter::join(parts, " OR "))
+        }
+    }
+}
+
+/// Stage lookup for the current row, mirroring `StageRepo::lookup`
+/// Terminal statuses match their wildcard row; open tasks match on all derived booleans.
+const STAGE_SUBQUERY: &str = "SELECT m.stage FROM stage_map m
+     WHERE m.status = t.status AND (
+         (t.status IN ('closed', 'cancelled') AND m.in_queue = -1)
+         OR (t.status NOT IN ('closed', 'cancelled')
+             AND m.in_queue = EXISTS (SELECT 1 FROM stack_items si WHERE si.task_id = t.id)
+             AND m.has_sessions = EXISTS (SELECT 1 FROM sessions s WHERE s.task_id = t.id)
+             AND m.has_open_session = EXISTS (SELECT 1 FROM sessions s WHERE s.task_id = t.id AND s.end_ts IS NULL)
+             AND m.has_externals = EXISTS (SELECT 1 FROM externals e WHERE e.task_id = t.id AND e.returned_ts IS NULL)
+             AND m.is_blocked = EXISTS (SELECT 1 FROM task_dependencies d JOIN tasks b ON b.id = d.depends_on_id
+                                        WHERE d.task_id = t.id AND b.status = 'open'))
+     )
+     ORDER BY m.id LIMIT 1";
+
+/// Project membership: exact name or nested under it; "none" or empty matches tasks without a project
+fn project_condition(names: &[String]) -> SqlFilter {
+    let mut parts = Vec::new();
+    for name in names {
+        if name.is_empty() || name.eq_ignore_ascii_case("none") {
+            parts.push(SqlFilter::new("t.project_id IS NULL", Vec::new()));
+        } else {
+            // Prefix comparison instead of LIKE, which would treat '_' in names as a wildcard
+            parts.push(SqlFilter::new(
+                "EXISTS (SELECT 1 FROM projects p WHERE p.id = t.project_id
+                         AND (p.name = ? OR substr(p.name, 1, length(?) + 1) = ? || '.'))",
+                vec![Value::Text(name.clone()), Value::Text(name.clone()), Value::Text(name.clone())],
+            ));
+        }
+    }
+    SqlFilter::join(parts, " OR ")
+}
+
+/// UDA comparison on the JSON-encoded attribute map
+///
+/// Numeric filter values are left to Rust, which compares numerically only when the
+/// task's value also parses; string comparisons and presence checks translate directly.
+/// Date-typed attributes hold epoch seconds and compare like built-in date fields.
+fn uda_condition(
+    key: &str,
+    op: &ComparisonOp,
+    value: &str,
+    definition: Option<&UdaDefinition>,
+) -> Option<SqlFilter> {
+    if key.contains('"') || key.contains('\\') {
+        return None;
+    }
+    let column = format!("json_extract(t.udas_json, '$.\"{}\"')", key.replace('\'', "''"));
+
+    match definition.map(|d| d.uda_type) {
+        None | Some(UdaType::String) => {}
+        Some(UdaType::Date) => {
+            // Only values that round-trip as integers are timestamps, matching `parse::<i64>()`
+            let timestamp = format!(
+                "(CASE WHEN CAST({c} AS INTEGER) || '' = {c} THEN CAST({c} AS INTEGER) END)",
+                c = column
+            );
+            return Some(date_condition(&timestamp, op, value));
+        }
+        // Typed comparisons (numeric, duration, enum order) are evaluated in Rust
+        Some(_) if value != "any" && value != "none" => return None,
+        Some(_) => {}
+    }
+
+    match (op, value) {
+        (ComparisonOp::Eq, "any") | (ComparisonOp::Neq, "none") => {
+            return Some(SqlFilter::new(format!("{} IS NOT NULL", column), Vec::new()));
+        }
+        (ComparisonOp::Eq, "none") | (ComparisonOp::Neq, "any") => {
+            return Some(SqlFilter::new(format!("{} IS NULL", column), Vec::new()));
+        }
+        _ => {}
+    }
+    if value.parse::<f64>().is_ok() {
+        return None;
+    }
+
+    let params = vec![Value::Text(value.to_string())];
+    Some(match op {
+        ComparisonOp::Neq => SqlFilter::new(format!("{} IS NULL OR {} != ?", column, column), params),
+        _ => SqlFilter::new(format!("{} IS NOT NULL AND {} {} ?", column, column, sql_op(op)), params),
+    })
+}
+
+/// Date comparison with the same semantics as the Rust evaluator:
+/// '=' and '!=' compare calendar dates, 'any'/'none' test presence, and
+/// unparseable expressions match nothing.
+fn date_condition(column: &str, op: &ComparisonOp, expr: &str) -> SqlFilter {
+    match (op, expr) {
+        (ComparisonOp::Eq, "any") | (ComparisonOp::Neq, "none") => {
+            return SqlFilter::new(format!("{} IS NOT NULL", column), Vec::new());
+        }
+        (ComparisonOp::Eq, "none") | (ComparisonOp::Neq, "any") => {
+            return SqlFilter::new(format!("{} IS NULL", column), Vec::new());
+        }
+        _ => {}
+    }
+
+    let ts = match crate::utils::parse_date_expr(expr) {
+        Ok(ts) => ts,
+        Err(_) => return SqlFilter::constant(false),
+    };
+
+    match op {
+        ComparisonOp::Eq => SqlFilter::new(
+            format!("{} IS NOT NULL AND date({}, 'unixepoch') = date(?, 'unixepoch')", column, column),
+            vec![Value::Integer(ts)],
+        ),
+        ComparisonOp::Neq => SqlFilter::new(
+            format!("{} IS NULL OR date({}, 'unixepoch') != date(?, 'unixepoch')", column, column),
+            vec![Value::Integer(ts)],
+        ),
+        _ => SqlFilter::new(
+            format!("{} IS NOT NULL AND {} {} ?", column, column, sql_op(op)),
+            vec![Value::Integer(ts)],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DbConnection;
+    use crate::filter::{parse_filter, filter_tasks};
+    use crate::repo::{DependencyRepo, ExternalRepo, ProjectRepo, SessionRepo, StackRepo, TaskRepo, UdaRepo};
+    use rusqlite::Connection;
+    use std::collections::HashMap;
+
+    /// Task IDs matched by the SQL path and by the Rust evaluator
+    fn both_paths(conn: &Connection, tokens: &[&str]) -> (Vec<i64>, Vec<i64>) {
+        let expr = parse_filter(tokens.iter().map(|t| t.to_string()).collect()).unwrap();
+        let sql = compile_expr(&expr, &UdaRepo::load_map(conn).unwrap()).expect("filter should compile to SQL");
+        let via_sql: Vec<i64> = TaskRepo::list_matching(conn, &sql.condition, &sql.params).unwrap()
+            .iter().map(|(t, _)| t.id.unwrap()).collect();
+        let via_rust: Vec<i64> = TaskRepo::list_all(conn).unwrap().into_iter()
+            .filter(|(t, _)| expr.matches(t, conn).unwrap())
+            .map(|(t, _)| t.id.unwrap())
+            .collect();
+        (via_sql, via_rust)
+    }
+
+    fn setup(conn: &Connection) {
+        let work = ProjectRepo::create(conn, "work").unwrap().id;
+        let email = ProjectRepo::create(conn, "work.email").unwrap().id;
+        let home = ProjectRepo::create(conn, "home_x").unwrap().id;
+        let stack_id = StackRepo::get_or_create_default(conn).unwrap().id.unwrap();
+        let no_udas = HashMap::new();
+
+        // 1: work, +urgent, queued with sessions (in progress)
+        let acme = HashMap::from([("customer".to_string(), "acme".to_string()), ("estimate".to_string(), "10".to_string())]);
+        let t1 = TaskRepo::create_full(conn, "Write report", work, Some(1_800_000_000), None, None, None, None, None,
+            &acme, &["urgent".to_string()], None).unwrap().id.unwrap();
+        StackRepo::enqueue(conn, stack_id, t1).unwrap();
+        SessionRepo::create_closed(conn, t1, 1000, 2000).unwrap();
+        // 2: work.email, queued (planned)
+        let globex = HashMap::from([("customer".to_string(), "globex".to_string()), ("estimate".to_string(), "3".to_string())]);
+        let t2 = TaskRepo::create_full(conn, "Inbox zero", email, None, None, None, None, None, None,
+            &globex, &[], None).unwrap().id.unwrap();
+        StackRepo::enqueue(conn, stack_id, t2).unwrap();
+        // 3: home_x, sessions only (suspended), child of 1
+        let t3 = TaskRepo::create_full(conn, "Fix sink", home, None, None, Some(4_000_000_000), None, None, None,
+            &no_udas, &[], Some(t1)).unwrap().id.unwrap();
+        SessionRepo::create_closed(conn, t3, 3000, 4000).unwrap();
+        // 4: no project, external
+        let t4 = TaskRepo::create(conn, "Ask Bob", None).unwrap().id.unwrap();
+        ExternalRepo::create(conn, t4, "bob".to_string(), None).unwrap();
+        // 5: closed
+        let t5 = TaskRepo::create(conn, "Done thing", work).unwrap().id.unwrap();
+        TaskRepo::close(conn, t5).unwrap();
+        // 6: running
+        let t6 = TaskRepo::create(conn, "Now", None).unwrap().id.unwrap();
+        StackRepo::push_to_top(conn, stack_id, t6).unwrap();
+        SessionRepo::create(conn, t6, 5000).unwrap();
+        // 7: waits on 1 (blocked) and on closed 5; 8: tagged +blocked by hand
+        let t7 = TaskRepo::create(conn, "After report", None).unwrap().id.unwrap();
+        DependencyRepo::add(conn, t7, t1).unwrap();
+        DependencyRepo::add(conn, t7, t5).unwrap();
+        TaskRepo::create_full(conn, "Stuck", None, None, None, None, None, None, None,
+            &no_udas, &["blocked".to_string()], None).unwrap();
+        // The running task keeps its stage while blocked
+        DependencyRepo::add(conn, t6, t2).unwrap();
+    }
+
+    #[test]
+    fn test_sql_matches_rust_evaluator() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        setup(&conn);
+
+        let filters: &[&[&str]] = &[
+            &["project=work"], &["project!=work"], &["project=none"], &["project=work.email,none"],
+            &["project=home"], &["+urgent"], &["-urgent"], &["status=closed"], &["status!=open"],
+            &["stage=planned"], &["stage=in progress"], &["stage=suspended,external"], &["stage!=proposed"],
+            &["stage=active"], &["stage=completed"], &["due=any"], &["due=none"], &["due>2020-01-01"],
+            &["due!=2027-01-15"], &["wait=none"], &["waiting"], &["desc=report"], &["desc!=INBOX"],
+            &["external=bob"], &["external!=bob"], &["parent=1"], &["parent=none"], &["parent!=any"],
+            &["queue=default"], &["queue=none"], &["queue!=any"], &["id>2"], &["3"],
+            &["project=work", "or", "+urgent"], &["not", "+urgent", "project=work"],
+            &["due=bogus"], &["created>-1d"], &["activity<=now"], &["modified!=none"],
+            &["uda.customer=acme"], &["uda.customer!=acme"], &["uda.customer=none"], &["uda.customer!=none"],
+            &["uda.customer>b"], &["uda.customer<=acme"], &["uda.missing=any"],
+            &["+blocked"], &["-blocked"], &["+blocking"], &["-blocking"], &["stage=blocked"],
+            &["+blocked", "or", "+blocking"],
+        ];
+        for tokens in filters {
+            let (via_sql, via_rust) = both_paths(&conn, tokens);
+            assert_eq!(via_sql, via_rust, "filter {:?}", tokens);
+        }
+    }
+
+    #[test]
+    fn test_dependency_filters_and_stage() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        setup(&conn);
+        let ids = |tokens: &[&str]| both_paths(&conn, tokens).0;
+        assert_eq!(ids(&["+blocked"]), vec![6, 7, 8]);
+        assert_eq!(ids(&["+blocking"]), vec![1, 2]);
+        assert_eq!(ids(&["stage=blocked"]), vec![7]);
+        assert_eq!(ids(&["stage=active"]), vec![6]);
+    }
+
+    #[test]
+    fn test_plan_filter_splits_untranslatable_terms() {
+        let expr = parse_filter(vec!["project=work".to_string(), "desc=café".to_string()]).unwrap();
+        let (sql, residual) = plan_filter(&expr, &HashMap::new());
+        assert!(sql.unwrap().condition.contains("projects"));
+        assert!(matches!(residual, Some(FilterExpr::Term(FilterTerm::Desc(_, _)))));
+
+        // Numeric UDA comparisons stay in Rust
+        let expr = parse_filter(vec!["uda.customer=acme".to_string(), "uda.estimate>3".to_string()]).unwrap();
+        let (sql, residual) = plan_filter(&expr, &HashMap::new());
+        assert!(sql.unwrap().condition.contains("json_extract"));
+        assert!(matches!(residual, Some(FilterExpr::Term(FilterTerm::Uda(_, ComparisonOp::Gt, _)))));
+
+        let expr = parse_filter(vec!["desc=café".to_string(), "or".to_string(), "+urgent".to_string()]).unwrap();
+        let (sql, residual) = plan_filter(&expr, &HashMap::new());
+        assert!(sql.is_none());
+        assert!(residual.is_some());
+    }
+
+    #[test]
+    fn test_filter_tasks_with_residual() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        setup(&conn);
+        TaskRepo::create(&conn, "Café order", None).unwrap();
+
+        let expr = parse_filter(vec!["project=none".to_string(), "desc=CAFÉ".to_string()]).unwrap();
+        let matching = filter_tasks(&conn, &expr).unwrap();
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].0.description, "Café order");
+    }
+
+    #[test]
+    fn test_typed_uda_filters() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        setup(&conn);
+        for (name, uda_type) in [("customer", UdaType::Date), ("estimate", UdaType::Number)] {
+            UdaRepo::upsert(&conn, &UdaDefinition {
+                name: name.to_string(),
+                uda_type,
+                values: Vec::new(),
+                default_value: None,
+                label: None,
+            }).unwrap();
+        }
+        let review = HashMap::from([("customer".to_string(), "1800000000".to_string())]);
+        TaskRepo::create_full(&conn, "Review", None, None, None, None, None, None, None,
+            &review, &[], None).unwrap();
+
+        // Date-typed attributes compile to date comparisons
+        let filters: &[&[&str]] = &[
+            &["uda.customer>2020-01-01"], &["uda.customer=2027-01-15"], &["uda.customer!=2027-01-15"],
+            &["uda.customer=none"], &["uda.customer<bogus"],
+        ];
+        for tokens in filters {
+            let (via_sql, via_rust) = both_paths(&conn, tokens);
+            assert_eq!(via_sql, via_rust, "filter {:?}", tokens);
+        }
+
+        // Other typed comparisons stay in Rust; presence checks still translate
+        let udas = UdaRepo::load_map(&conn).unwrap();
+        let expr = parse_filter(vec!["uda.estimate=abc".to_string()]).unwrap();
+        assert!(compile_expr(&expr, &udas).is_none());
+        let expr = parse_filter(vec!["uda.estimate=any".to_string()]).unwrap();
+        assert!(compile_expr(&expr, &udas).is_some());
+    }
+}
@@ -1,6 +1,7 @@
 //! Tatl (Task and Time Ledger) - A powerful command-line task and time tracking tool
 //!
 //! This library provides the core functionality for Tatl, including:
+//! - Configuration file loading
 //! - Database operations and migrations
 //! - Data models for tasks, projects, sessions, and more
 //! - Repository layer for data access
@@ -22,6 +23,7 @@
 //! }
 //! ```
 
+pub mod config;
 pub mod db;
 pub mod models;
 pub mod repo;
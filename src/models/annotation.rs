@@ -9,6 +9,7 @@ pub struct Annotation {
     pub note: String,
     pub entry_ts: i64,
     pub created_ts: i64,
+    pub pinned: bool, // Carried forward to respawned instances (respawn option carry=annotations)
 }
 
 impl Annotation {
@@ -22,6 +23,7 @@ impl Annotation {
             note,
             entry_ts: now,
             created_ts: now,
+            pinned: false,
         }
     }
 }
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Task event model
+/// Represents a row in the append-only task_events log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub id: i64,
+    pub task_id: i64,
+    pub ts: i64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub batch_id: Option<String>,
+}
+
+impl TaskEvent {
+    /// Get a string field from the payload, if present
+    pub fn payload_str(&self, key: &str) -> Option<&str> {
+        self.payload.get(key).and_then(|v| v.as_str())
+    }
+
+    /// Get an integer field from the payload, if present
+    pub fn payload_i64(&self, key: &str) -> Option<i64> {
+        self.payload.get(key).and_then(|v| v.as_i64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_accessors() {
+        let event = TaskEvent {
+            id: 1,
+            task_id: 1,
+            ts: 0,
+            event_type: "modified".to_string(),
+            payload: serde_json::json!({"field": "due_ts", "new_value": 42}),
+            batch_id: None,
+        };
+        assert_eq!(event.payload_str("field"), Some("due_ts"));
+        assert_eq!(event.payload_i64("new_value"), Some(42));
+        assert_eq!(event.payload_str("missing"), None);
+    }
+}
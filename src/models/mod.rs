@@ -8,6 +8,8 @@ pub mod stack;
 pub mod annotation;
 pub mod external;
 pub mod stage;
+pub mod event;
+pub mod uda;
 
 pub use task::*;
 pub use project::*;
@@ -15,4 +17,6 @@ pub use session::*;
 pub use stack::*;
 pub use annotation::*;
 pub use external::*;
-pub use stage::*;
\ No newline at end of file
+pub use stage::*;
+pub use event::*;
+pub use uda::*;
\ No newline at end of file
@@ -8,6 +8,8 @@ pub struct Session {
     pub start_ts: i64,
     pub end_ts: Option<i64>,
     pub created_ts: i64,
+    /// What happened during the session
+    pub note: Option<String>,
 }
 
 impl Session {
@@ -19,6 +21,7 @@ impl Session {
             start_ts,
             end_ts: None,
             created_ts: chrono::Utc::now().timestamp(),
+            note: None,
         }
     }
 
@@ -38,6 +41,14 @@ impl Session {
     }
 }
 
+/// Pomodoro settings of a session started with `tatl on --pomodoro`.
+/// The session is one work interval; the break is the gap after it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pomodoro {
+    pub work_secs: i64,
+    pub break_secs: i64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -8,6 +8,7 @@ pub struct StageMapping {
     pub has_sessions: i8,
     pub has_open_session: i8,
     pub has_externals: i8,
+    pub is_blocked: i8,    // Has an open dependency (-1 wildcard for terminal statuses)
     pub stage: String,
     pub sort_order: i64,
     pub color: Option<String>,
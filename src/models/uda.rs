@@ -0,0 +1,154 @@
+use crate::utils::fuzzy::levenshtein_distance;
+use std::cmp::Ordering;
+
+/// Value type of a user-defined attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UdaType {
+    String,
+    Number,
+    Date,
+    Duration,
+    Enum,
+}
+
+impl UdaType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UdaType::String => "string",
+            UdaType::Number => "number",
+            UdaType::Date => "date",
+            UdaType::Duration => "duration",
+            UdaType::Enum => "enum",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "string" => Some(UdaType::String),
+            "number" => Some(UdaType::Number),
+            "date" => Some(UdaType::Date),
+            "duration" => Some(UdaType::Duration),
+            "enum" => Some(UdaType::Enum),
+            _ => None,
+        }
+    }
+}
+
+/// UDA definition model
+/// Represents a row in the uda_definitions table. Values are stored on tasks in
+/// normalised form: numbers canonicalised, dates as epoch seconds, durations as
+/// seconds, and enum values in their declared spelling.
+#[derive(Debug, Clone)]
+pub struct UdaDefinition {
+    pub name: String,
+    pub uda_type: UdaType,
+    pub values: Vec<String>,           // Allowed values (enum only), in sort order
+    pub default_value: Option<String>, // Raw default, normalised when applied (e.g. "eow" for dates)
+    pub label: Option<String>,         // Display label for list columns
+}
+
+impl UdaDefinition {
+    /// Column label: the display label if set, otherwise the attribute name
+    pub fn display_label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Validate and normalise a raw value for storage
+    pub fn normalize(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        match self.uda_type {
+            UdaType::String => Ok(value.to_string()),
+            UdaType::Number => value
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .map(|n| n.to_string())
+                .ok_or_else(|| format!("Invalid value '{}' for uda.{}: expected a number", value, self.name)),
+            UdaType::Date => crate::utils::parse_date_expr(value)
+                .map(|ts| ts.to_string())
+                .map_err(|e| format!("Invalid value '{}' for uda.{}: {}", value, self.name, e)),
+            UdaType::Duration => crate::utils::parse_duration(value)
+                .map(|secs| secs.to_string())
+                .map_err(|e| format!("Invalid value '{}' for uda.{}: {}", value, self.name, e)),
+            UdaType::Enum => {
+                if let Some(allowed) = self.values.iter().find(|v| v.eq_ignore_ascii_case(value)) {
+                    return Ok(allowed.clone());
+                }
+                let mut message = format!("Invalid value '{}' for uda.{}", value, self.name);
+                if let Some(suggestion) = self.suggest(value) {
+                    message.push_str(&format!("\n  Did you mean '{}'?", suggestion));
+                }
+                message.push_str(&format!("\n  Allowed values: {}", self.values.join(", ")));
+                Err(message)
+            }
+        }
+    }
+
+    /// Closest allowed enum value within edit distance 3
+    fn suggest(&self, value: &str) -> Option<&str> {
+        let value_lower = value.to_lowercase();
+        self.values
+            .iter()
+            .map(|v| (v, levenshtein_distance(&value_lower, &v.to_lowercase())))
+            .filter(|(_, distance)| *distance <= 3)
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(v, _)| v.as_str())
+    }
+
+    /// Position of an enum value in the declared order (case-insensitive)
+    pub fn enum_position(&self, value: &str) -> Option<usize> {
+        self.values.iter().position(|v| v.eq_ignore_ascii_case(value))
+    }
+
+    /// Compare two stored values according to the attribute type.
+    /// Returns None when either side cannot be interpreted as the type.
+    pub fn compare(&self, a: &str, b: &str) -> Option<Ordering> {
+        match self.uda_type {
+            UdaType::String => Some(a.cmp(b)),
+            UdaType::Enum => Some(self.enum_position(a)?.cmp(&self.enum_position(b)?)),
+            UdaType::Number | UdaType::Date | UdaType::Duration => {
+                a.parse::<f64>().ok()?.partial_cmp(&b.parse::<f64>().ok()?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn severity() -> UdaDefinition {
+        UdaDefinition {
+            name: "severity".to_string(),
+            uda_type: UdaType::Enum,
+            values: vec!["low".to_string(), "medium".to_string(), "high".to_string()],
+            default_value: None,
+            label: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_enum() {
+        let def = severity();
+        assert_eq!(def.normalize("HIGH").unwrap(), "high");
+        let err = def.normalize("hgih").unwrap_err();
+        assert!(err.contains("Did you mean 'high'?"));
+        assert!(err.contains("Allowed values: low, medium, high"));
+        assert_eq!(def.compare("low", "high"), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn test_normalize_number_and_duration() {
+        let mut def = severity();
+        def.uda_type = UdaType::Number;
+        def.values.clear();
+        assert_eq!(def.normalize("3.50").unwrap(), "3.5");
+        assert_eq!(def.normalize("10").unwrap(), "10");
+        assert!(def.normalize("ten").is_err());
+        assert_eq!(def.compare("10", "3"), Some(Ordering::Greater));
+
+        def.uda_type = UdaType::Duration;
+        assert_eq!(def.normalize("1h30m").unwrap(), "5400");
+        assert!(def.normalize("soon").is_err());
+    }
+}
@@ -59,13 +59,14 @@ impl AnnotationRepo {
             note,
             entry_ts: now,
             created_ts: now,
+            pinned: false,
         })
     }
 
     /// Get all annotations for a session, ordered by entry_ts (oldest first)
     pub fn get_by_session(conn: &Connection, session_id: i64) -> Result<Vec<Annotation>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, session_id, note, entry_ts, created_ts 
+            "SELECT id, task_id, session_id, note, entry_ts, created_ts, pinned
              FROM task_annotations 
              WHERE session_id = ?1 
              ORDER BY entry_ts ASC"
@@ -79,6 +80,7 @@ impl AnnotationRepo {
                 note: row.get(3)?,
                 entry_ts: row.get(4)?,
                 created_ts: row.get(5)?,
+                pinned: row.get::<_, i64>(6)? != 0,
             })
         })?;
         
@@ -92,7 +94,7 @@ impl AnnotationRepo {
     /// Get all annotations for a task, ordered by entry_ts (oldest first)
     pub fn get_by_task(conn: &Connection, task_id: i64) -> Result<Vec<Annotation>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, session_id, note, entry_ts, created_ts 
+            "SELECT id, task_id, session_id, note, entry_ts, created_ts, pinned
              FROM task_annotations 
              WHERE task_id = ?1 
              ORDER BY entry_ts ASC"
@@ -106,6 +108,7 @@ impl AnnotationRepo {
                 note: row.get(3)?,
                 entry_ts: row.get(4)?,
                 created_ts: row.get(5)?,
+                pinned: row.get::<_, i64>(6)? != 0,
             })
         })?;
         
@@ -119,7 +122,7 @@ impl AnnotationRepo {
     /// Get annotation by ID
     pub fn get_by_id(conn: &Connection, annotation_id: i64) -> Result<Option<Annotation>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, session_id, note, entry_ts, created_ts 
+            "SELECT id, task_id, session_id, note, entry_ts, created_ts, pinned
              FROM task_annotations 
              WHERE id = ?1"
         )?;
@@ -132,12 +135,31 @@ impl AnnotationRepo {
                 note: row.get(3)?,
                 entry_ts: row.get(4)?,
                 created_ts: row.get(5)?,
+                pinned: row.get::<_, i64>(6)? != 0,
             })
         })
         .optional()
         .context("Failed to query annotation")
     }
 
+    /// Pin or unpin an annotation, verifying it belongs to the specified task.
+    /// Pinned annotations (checklists, links) can be carried forward on respawn.
+    pub fn set_pinned(conn: &Connection, task_id: i64, annotation_id: i64, pinned: bool) -> Result<()> {
+        let rows_affected = conn.execute(
+            "UPDATE task_annotations SET pinned = ?1 WHERE id = ?2 AND task_id = ?3",
+            rusqlite::params![pinned as i64, annotation_id, task_id],
+        )?;
+
+        if rows_affected == 0 {
+            if Self::get_by_id(conn, annotation_id)?.is_some() {
+                anyhow::bail!("Annotation {} does not belong to task {}", annotation_id, task_id);
+            } else {
+                anyhow::bail!("Annotation {} not found", annotation_id);
+            }
+        }
+        Ok(())
+    }
+
     /// Delete an annotation
     pub fn delete(conn: &Connection, annotation_id: i64) -> Result<()> {
         let rows_affected = conn.execute(
@@ -154,8 +176,12 @@ impl AnnotationRepo {
 
     /// Delete an annotation, verifying it belongs to the specified task
     pub fn delete_for_task(conn: &Connection, task_id: i64, annotation_id: i64) -> Result<()> {
-        // Record event before deletion
-        EventRepo::record_annotation_deleted(conn, task_id, annotation_id)?;
+        // Record event before deletion (keeping the note so it can be restored)
+        let existing = Self::get_by_id(conn, annotation_id)?
+            .filter(|a| a.task_id == task_id);
+        if let Some(annotation) = &existing {
+            EventRepo::record_annotation_deleted(conn, task_id, annotation_id, &annotation.note, annotation.session_id)?;
+        }
         
         let rows_affected = conn.execute(
             "DELETE FROM task_annotations WHERE id = ?1 AND task_id = ?2",
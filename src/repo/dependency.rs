@@ -0,0 +1,224 @@
+use rusqlite::Connection;
+use crate::repo::{EventRepo, TaskRepo};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+
+/// Repository for task dependencies ("B can't start until A closes")
+///
+/// A dependency is blocking while the task depended on is open. Closed,
+/// cancelled and deleted tasks no longer block anything.
+pub struct DependencyRepo;
+
+impl DependencyRepo {
+    /// Make `task_id` depend on `depends_on_id`. Returns false if it already did.
+    pub fn add(conn: &Connection, task_id: i64, depends_on_id: i64) -> Result<bool> {
+        if task_id == depends_on_id {
+            anyhow::bail!("Task {} cannot depend on itself", task_id);
+        }
+        if TaskRepo::get_by_id(conn, depends_on_id)?.is_none() {
+            anyhow::bail!("Dependency task {} not found", depends_on_id);
+        }
+        Self::validate_no_cycle(conn, task_id, depends_on_id)?;
+
+        let now = chrono::Utc::now().timestamp();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id, created_ts) VALUES (?1, ?2, ?3)",
+            rusqlite::params![task_id, depends_on_id, now],
+        )
+        .with_context(|| format!("Failed to add dependency {} -> {}", task_id, depends_on_id))?;
+
+        if inserted > 0 {
+            EventRepo::record_dependency_added(conn, task_id, depends_on_id)?;
+        }
+        Ok(inserted > 0)
+    }
+
+    /// Remove a dependency. Returns false if there was none.
+    pub fn remove(conn: &Connection, task_id: i64, depends_on_id: i64) -> Result<bool> {
+        let deleted = conn.execute(
+            "DELETE FROM task_dependencies WHERE task_id = ?1 AND depends_on_id = ?2",
+            [task_id, depends_on_id],
+        )?;
+        if deleted > 0 {
+            EventRepo::record_dependency_removed(conn, task_id, depends_on_id)?;
+        }
+        Ok(deleted > 0)
+    }
+
+    /// Replace a task's dependencies with the given set
+    pub fn set(conn: &Connection, task_id: i64, depends_on_ids: &[i64]) -> Result<()> {
+        for existing in Self::get_dependencies(conn, task_id)? {
+            if !depends_on_ids.contains(&existing) {
+                Self::remove(conn, task_id, existing)?;
+            }
+        }
+        for depends_on_id in depends_on_ids {
+            Self::add(conn, task_id, *depends_on_id)?;
+        }
+        Ok(())
+    }
+
+    /// Tasks that `task_id` depends on
+    pub fn get_dependencies(conn: &Connection, task_id: i64) -> Result<Vec<i64>> {
+        Self::query_ids(
+            conn,
+            "SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1 ORDER BY depends_on_id",
+            task_id,
+        )
+    }
+
+    /// Tasks that depend on `task_id` (what it blocks while open)
+    pub fn get_dependents(conn: &Connection, task_id: i64) -> Result<Vec<i64>> {
+        Self::query_ids(
+            conn,
+            "SELECT d.task_id FROM task_dependencies d
+             JOIN tasks t ON t.id = d.task_id
+             WHERE d.depends_on_id = ?1 AND t.status != 'deleted'
+             ORDER BY d.task_id",
+            task_id,
+        )
+    }
+
+    /// Open tasks that `task_id` is still waiting on
+    pub fn get_open_blockers(conn: &Connection, task_id: i64) -> Result<Vec<i64>> {
+        Self::query_ids(
+            conn,
+            "SELECT d.depends_on_id FROM task_dependencies d
+             JOIN tasks b ON b.id = d.depends_on_id
+             WHERE d.task_id = ?1 AND b.status = 'open'
+             ORDER BY d.depends_on_id",
+            task_id,
+        )
+    }
+
+    /// Whether an open task is waiting on an open dependency
+    pub fn is_blocked(conn: &Connection, task_id: i64) -> Result<bool> {
+        let blocked = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM task_dependencies d
+                            JOIN tasks t ON t.id = d.task_id
+                            JOIN tasks b ON b.id = d.depends_on_id
+                            WHERE d.task_id = ?1 AND t.status = 'open' AND b.status = 'open')",
+            [task_id],
+            |row| row.get(0),
+        )?;
+        Ok(blocked)
+    }
+
+    /// Whether an open task is a dependency of another open task
+    pub fn is_blocking(conn: &Connection, task_id: i64) -> Result<bool> {
+        let blocking = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM task_dependencies d
+                            JOIN tasks t ON t.id = d.task_id
+                            JOIN tasks b ON b.id = d.depends_on_id
+                            WHERE d.depends_on_id = ?1 AND t.status = 'open' AND b.status = 'open')",
+            [task_id],
+            |row| row.get(0),
+        )?;
+        Ok(blocking)
+    }
+
+    /// IDs of all open tasks with at least one open dependency
+    pub fn get_blocked_task_ids(conn: &Connection) -> Result<HashSet<i64>> {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT d.task_id FROM task_dependencies d
+             JOIN tasks t ON t.id = d.task_id
+             JOIN tasks b ON b.id = d.depends_on_id
+             WHERE t.status = 'open' AND b.status = 'open'"
+        )?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        let mut ids = HashSet::new();
+        for row in rows {
+            ids.insert(row?);
+        }
+        Ok(ids)
+    }
+
+    /// Open dependents of `task_id` that have no open blockers left.
+    /// Call after `task_id` leaves the open status to find the tasks it just unblocked.
+    pub fn get_unblocked_dependents(conn: &Connection, task_id: i64) -> Result<Vec<i64>> {
+        let mut unblocked = Vec::new();
+        for dependent in Self::get_dependents(conn, task_id)? {
+            let is_open = TaskRepo::get_by_id(conn, dependent)?
+                .is_some_and(|t| t.status == crate::models::TaskStatus::Open);
+            if is_open && !Self::is_blocked(conn, dependent)? {
+                unblocked.push(dependent);
+            }
+        }
+        Ok(unblocked)
+    }
+
+    /// Validate that making task_id depend on depends_on_id won't create a cycle.
+    /// Walks the dependencies of depends_on_id; if task_id is reachable, it's a cycle.
+    pub fn validate_no_cycle(conn: &Connection, task_id: i64, depends_on_id: i64) -> Result<()> {
+        let mut stack = vec![depends_on_id];
+        let mut seen = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == task_id {
+                anyhow::bail!(
+                    "Circular dependency: task {} already depends on task {}",
+                    depends_on_id, task_id
+                );
+            }
+            if seen.insert(id) {
+                stack.extend(Self::get_dependencies(conn, id)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn query_ids(conn: &Connection, sql: &str, task_id: i64) -> Result<Vec<i64>> {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([task_id], |row| row.get(0))?;
+        let mut ids = Vec::new();
+        for row in rows {
+            ids.push(row?);
+        }
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DbConnection;
+    use crate::models::TaskStatus;
+
+    #[test]
+    fn test_dependencies_and_cycles() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let a = TaskRepo::create(&conn, "A", None).unwrap().id.unwrap();
+        let b = TaskRepo::create(&conn, "B", None).unwrap().id.unwrap();
+        let c = TaskRepo::create(&conn, "C", None).unwrap().id.unwrap();
+
+        // C depends on B, B depends on A
+        assert!(DependencyRepo::add(&conn, c, b).unwrap());
+        assert!(DependencyRepo::add(&conn, b, a).unwrap());
+        assert!(!DependencyRepo::add(&conn, b, a).unwrap());
+        assert_eq!(DependencyRepo::get_dependents(&conn, a).unwrap(), vec![b]);
+
+        // A -> C would close the loop
+        let err = DependencyRepo::add(&conn, a, c).unwrap_err();
+        assert!(err.to_string().contains("Circular dependency"));
+        assert!(DependencyRepo::add(&conn, a, a).is_err());
+        assert!(DependencyRepo::add(&conn, a, 999).is_err());
+
+        let blocked = DependencyRepo::get_blocked_task_ids(&conn).unwrap();
+        assert_eq!(blocked, [b, c].into_iter().collect());
+        assert!(DependencyRepo::is_blocked(&conn, c).unwrap());
+        assert!(!DependencyRepo::is_blocked(&conn, a).unwrap());
+        assert!(DependencyRepo::is_blocking(&conn, a).unwrap());
+        assert!(DependencyRepo::is_blocking(&conn, b).unwrap());
+        assert!(!DependencyRepo::is_blocking(&conn, c).unwrap());
+
+        // Closing A unblocks B but not C
+        TaskRepo::set_status(&conn, a, TaskStatus::Closed).unwrap();
+        assert_eq!(DependencyRepo::get_unblocked_dependents(&conn, a).unwrap(), vec![b]);
+        assert!(!DependencyRepo::is_blocked(&conn, b).unwrap());
+        assert!(DependencyRepo::is_blocked(&conn, c).unwrap());
+
+        DependencyRepo::set(&conn, c, &[]).unwrap();
+        assert!(DependencyRepo::get_dependencies(&conn, c).unwrap().is_empty());
+        assert!(DependencyRepo::remove(&conn, b, a).unwrap());
+        assert!(!DependencyRepo::remove(&conn, b, a).unwrap());
+    }
+}
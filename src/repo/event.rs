@@ -1,10 +1,16 @@
 use rusqlite::Connection;
 use serde_json;
 use anyhow::Result;
+use crate::models::TaskEvent;
 
 /// Event repository for recording immutable task events
 pub struct EventRepo;
 
+thread_local! {
+    /// Batch that events recorded on this thread are grouped under (one per CLI invocation)
+    static CURRENT_BATCH: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
 /// Event types
 #[derive(Debug, Clone)]
 pub enum EventType {
@@ -19,6 +25,11 @@ pub enum EventType {
     StackRemoved,
     SessionStarted,
     SessionEnded,
+    SessionModified,
+    SessionDeleted,
+    Skipped,
+    DependencyAdded,
+    DependencyRemoved,
 }
 
 impl EventType {
@@ -35,11 +46,29 @@ impl EventType {
             EventType::StackRemoved => "stack_removed",
             EventType::SessionStarted => "session_started",
             EventType::SessionEnded => "session_ended",
+            EventType::SessionModified => "session_modified",
+            EventType::SessionDeleted => "session_deleted",
+            EventType::Skipped => "skipped",
+            EventType::DependencyAdded => "dependency_added",
+            EventType::DependencyRemoved => "dependency_removed",
         }
     }
 }
 
 impl EventRepo {
+    /// Start a new event batch. All events recorded afterwards (until the next call)
+    /// share the returned batch ID and are undone together.
+    pub fn begin_batch() -> String {
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        CURRENT_BATCH.with(|b| *b.borrow_mut() = Some(batch_id.clone()));
+        batch_id
+    }
+
+    /// Get the current batch ID, if a batch has been started
+    pub fn current_batch() -> Option<String> {
+        CURRENT_BATCH.with(|b| b.borrow().clone())
+    }
+
     /// Record an event (immutable - never modified or deleted)
     pub fn record(
         conn: &Connection,
@@ -49,10 +78,11 @@ impl EventRepo {
     ) -> Result<()> {
         let ts = chrono::Utc::now().timestamp();
         let payload_json = serde_json::to_string(&payload)?;
+        let batch_id = Self::current_batch();
         
         conn.execute(
-            "INSERT INTO task_events (task_id, ts, event_type, payload_json) VALUES (?1, ?2, ?3, ?4)",
-            rusqlite::params![task_id, ts, event_type.as_str(), payload_json],
+            "INSERT INTO task_events (task_id, ts, event_type, payload_json, batch_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![task_id, ts, event_type.as_str(), payload_json, batch_id],
         )?;
         
         Ok(())
@@ -141,39 +171,50 @@ impl EventRepo {
     }
 
     /// Record annotation deleted event
+    /// The note and session link are kept so the annotation can be restored by undo.
     pub fn record_annotation_deleted(
         conn: &Connection,
         task_id: i64,
         annotation_id: i64,
+        note: &str,
+        session_id: Option<i64>,
     ) -> Result<()> {
         let payload = serde_json::json!({
             "annotation_id": annotation_id,
+            "note": note,
+            "session_id": session_id,
         });
         Self::record(conn, task_id, EventType::AnnotationDeleted, payload)
     }
 
     /// Record stack added event
+    /// `old_position` is the task's position before the change (None if it was not queued).
     pub fn record_stack_added(
         conn: &Connection,
         task_id: i64,
         stack_id: i64,
         position: i32,
+        old_position: Option<i32>,
     ) -> Result<()> {
         let payload = serde_json::json!({
             "stack_id": stack_id,
             "position": position,
+            "old_position": old_position,
         });
         Self::record(conn, task_id, EventType::StackAdded, payload)
     }
 
     /// Record stack removed event
+    /// `position` is where the task was before removal.
     pub fn record_stack_removed(
         conn: &Connection,
         task_id: i64,
         stack_id: i64,
+        position: i32,
     ) -> Result<()> {
         let payload = serde_json::json!({
             "stack_id": stack_id,
+            "position": position,
         });
         Self::record(conn, task_id, EventType::StackRemoved, payload)
     }
@@ -207,6 +248,183 @@ impl EventRepo {
         });
         Self::record(conn, task_id, EventType::SessionEnded, payload)
     }
+
+    /// Record a change to a session's start, end, note or tags
+    pub fn record_session_modified(
+        conn: &Connection,
+        task_id: i64,
+        session_id: i64,
+        field: &str,
+        old_value: serde_json::Value,
+        new_value: serde_json::Value,
+    ) -> Result<()> {
+        let payload = serde_json::json!({
+            "session_id": session_id,
+            "field": field,
+            "old_value": old_value,
+            "new_value": new_value,
+        });
+        Self::record(conn, task_id, EventType::SessionModified, payload)
+    }
+
+    /// Record session deleted event, with enough of the session to restore it
+    pub fn record_session_deleted(
+        conn: &Connection,
+        task_id: i64,
+        session_id: i64,
+        start_ts: i64,
+        end_ts: Option<i64>,
+        note: Option<&str>,
+        tags: &[String],
+    ) -> Result<()> {
+        let payload = serde_json::json!({
+            "session_id": session_id,
+            "start_ts": start_ts,
+            "end_ts": end_ts,
+            "note": note,
+            "tags": tags,
+        });
+        Self::record(conn, task_id, EventType::SessionDeleted, payload)
+    }
+
+    /// Record a skipped respawn occurrence (the task moved to its next due date
+    /// without being closed)
+    pub fn record_skipped(
+        conn: &Connection,
+        task_id: i64,
+        from_due_ts: Option<i64>,
+        to_due_ts: i64,
+    ) -> Result<()> {
+        let payload = serde_json::json!({
+            "from_due_ts": from_due_ts,
+            "to_due_ts": to_due_ts,
+        });
+        Self::record(conn, task_id, EventType::Skipped, payload)
+    }
+
+    /// Record dependency added event
+    pub fn record_dependency_added(
+        conn: &Connection,
+        task_id: i64,
+        depends_on_id: i64,
+    ) -> Result<()> {
+        let payload = serde_json::json!({
+            "depends_on": depends_on_id,
+        });
+        Self::record(conn, task_id, EventType::DependencyAdded, payload)
+    }
+
+    /// Record dependency removed event
+    pub fn record_dependency_removed(
+        conn: &Connection,
+        task_id: i64,
+        depends_on_id: i64,
+    ) -> Result<()> {
+        let payload = serde_json::json!({
+            "depends_on": depends_on_id,
+        });
+        Self::record(conn, task_id, EventType::DependencyRemoved, payload)
+    }
+
+    /// Count skipped occurrences across a set of tasks, ignoring undone skips
+    pub fn count_skipped(conn: &Connection, task_ids: &[i64]) -> Result<i64> {
+        let mut total = 0;
+        for task_id in task_ids {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM task_events
+                 WHERE task_id = ?1 AND event_type = 'skipped'
+                   AND (batch_id IS NULL OR batch_id NOT IN (SELECT batch_id FROM undone_batches))",
+                [task_id],
+                |row| row.get(0),
+            )?;
+            total += count;
+        }
+        Ok(total)
+    }
+
+    /// Get all events for a task, oldest first
+    pub fn get_by_task(conn: &Connection, task_id: i64) -> Result<Vec<TaskEvent>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, task_id, ts, event_type, payload_json, batch_id FROM task_events
+             WHERE task_id = ?1 ORDER BY ts, id"
+        )?;
+        let rows = stmt.query_map([task_id], Self::row_to_event)?;
+        let mut events = Vec::new();
+        for row in rows {
+            events.push(row?);
+        }
+        Ok(events)
+    }
+
+    /// Get all events in a time range (inclusive start, exclusive end), oldest first
+    pub fn list_range(conn: &Connection, start_ts: i64, end_ts: Option<i64>) -> Result<Vec<TaskEvent>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, task_id, ts, event_type, payload_json, batch_id FROM task_events
+             WHERE ts >= ?1 AND (?2 IS NULL OR ts < ?2) ORDER BY ts, id"
+        )?;
+        let rows = stmt.query_map(rusqlite::params![start_ts, end_ts], Self::row_to_event)?;
+        let mut events = Vec::new();
+        for row in rows {
+            events.push(row?);
+        }
+        Ok(events)
+    }
+
+    /// Get the IDs of the most recent batches that can still be undone, newest first.
+    /// Excludes batches that were already undone and the batches written by undo itself.
+    pub fn list_undoable_batches(conn: &Connection, limit: usize) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare(
+            "SELECT batch_id FROM task_events
+             WHERE batch_id IS NOT NULL
+               AND batch_id NOT IN (SELECT batch_id FROM undone_batches)
+               AND batch_id NOT IN (SELECT undo_batch_id FROM undone_batches)
+             GROUP BY batch_id
+             ORDER BY MAX(id) DESC
+             LIMIT ?1"
+        )?;
+        let rows = stmt.query_map([limit as i64], |row| row.get(0))?;
+        let mut batches = Vec::new();
+        for row in rows {
+            batches.push(row?);
+        }
+        Ok(batches)
+    }
+
+    /// Get all events in a batch, oldest first
+    pub fn get_by_batch(conn: &Connection, batch_id: &str) -> Result<Vec<TaskEvent>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, task_id, ts, event_type, payload_json, batch_id FROM task_events
+             WHERE batch_id = ?1 ORDER BY id"
+        )?;
+        let rows = stmt.query_map([batch_id], Self::row_to_event)?;
+        let mut events = Vec::new();
+        for row in rows {
+            events.push(row?);
+        }
+        Ok(events)
+    }
+
+    /// Record that a batch was reverted by an undo batch
+    pub fn mark_batch_undone(conn: &Connection, batch_id: &str, undo_batch_id: &str) -> Result<()> {
+        let ts = chrono::Utc::now().timestamp();
+        conn.execute(
+            "INSERT INTO undone_batches (batch_id, undo_batch_id, ts) VALUES (?1, ?2, ?3)",
+            rusqlite::params![batch_id, undo_batch_id, ts],
+        )?;
+        Ok(())
+    }
+
+    fn row_to_event(row: &rusqlite::Row) -> rusqlite::Result<TaskEvent> {
+        let payload_json: String = row.get(4)?;
+        Ok(TaskEvent {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            ts: row.get(2)?,
+            event_type: row.get(3)?,
+            payload: serde_json::from_str(&payload_json).unwrap_or(serde_json::Value::Null),
+            batch_id: row.get(5)?,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -242,4 +460,30 @@ mod tests {
         assert_eq!(payload_value["old_status"], "pending");
         assert_eq!(payload_value["new_status"], "completed");
     }
+
+    #[test]
+    fn test_get_by_task_returns_events_in_order() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let task = TaskRepo::create(&conn, "Test task", None).unwrap();
+        let task_id = task.id.unwrap();
+        TaskRepo::close(&conn, task_id).unwrap();
+
+        let events = EventRepo::get_by_task(&conn, task_id).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "created");
+        assert_eq!(events[1].event_type, "status_changed");
+        assert_eq!(events[1].payload_str("new_status"), Some("closed"));
+    }
+
+    #[test]
+    fn test_list_range() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        TaskRepo::create(&conn, "Task 1", None).unwrap();
+        TaskRepo::create(&conn, "Task 2", None).unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        assert_eq!(EventRepo::list_range(&conn, now - 60, None).unwrap().len(), 2);
+        assert!(EventRepo::list_range(&conn, now + 60, None).unwrap().is_empty());
+        assert!(EventRepo::list_range(&conn, 0, Some(now - 60)).unwrap().is_empty());
+    }
 }
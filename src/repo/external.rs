@@ -196,4 +196,30 @@ impl ExternalRepo {
         
         Ok(external)
     }
+
+    /// Get the most recently sent external for a task, returned or not
+    pub fn get_latest_for_task(conn: &Connection, task_id: i64) -> Result<Option<External>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, task_id, recipient, request, sent_ts, returned_ts, created_ts, modified_ts
+             FROM externals
+             WHERE task_id = ?1
+             ORDER BY sent_ts DESC, id DESC
+             LIMIT 1"
+        )?;
+
+        let external = stmt.query_row([task_id], |row| {
+            Ok(External {
+                id: Some(row.get(0)?),
+                task_id: row.get(1)?,
+                recipient: row.get(2)?,
+                request: row.get(3)?,
+                sent_ts: row.get(4)?,
+                returned_ts: row.get(5)?,
+                created_ts: row.get(6)?,
+                modified_ts: row.get(7)?,
+            })
+        }).optional()?;
+
+        Ok(external)
+    }
 }
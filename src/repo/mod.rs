@@ -8,6 +8,9 @@ pub mod template;
 pub mod view;
 pub mod external;
 pub mod stage;
+pub mod undo;
+pub mod uda;
+pub mod dependency;
 
 pub use project::*;
 pub use task::*;
@@ -18,4 +21,7 @@ pub use event::*;
 pub use template::*;
 pub use view::*;
 pub use external::*;
-pub use stage::*;
\ No newline at end of file
+pub use stage::*;
+pub use undo::*;
+pub use uda::*;
+pub use dependency::*;
\ No newline at end of file
@@ -1,11 +1,15 @@
 use rusqlite::{Connection, OptionalExtension};
-use crate::models::Session;
+use crate::config::Config;
+use crate::models::{Pomodoro, Session};
 use crate::repo::{EventRepo, TaskRepo};
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 
-/// Micro-session threshold (30 seconds)
+/// Micro-session threshold (`session.micro` in the rc file, 30 seconds by default)
 /// Sessions shorter than this duration may be merged or purged based on subsequent activity
-const MICRO_SECONDS: i64 = 30;
+fn micro_seconds() -> i64 {
+    Config::current().micro_session_secs()
+}
 
 /// Session repository for database operations
 ///
@@ -33,14 +37,15 @@ impl SessionRepo {
     /// # Micro-Session Rules
     ///
     /// When creating a new session, the system checks for recent micro-sessions (closed sessions
-    /// shorter than 30 seconds that ended within the last 30 seconds):
+    /// shorter than the micro-session threshold, 30 seconds by default, that ended within
+    /// the threshold):
     ///
     /// 1. **Merge**: If a micro-session exists for the same task, it is merged into the new session
     ///    (the new session's start time is set to the micro-session's original start time)
     ///
     /// 2. **Purge**: If a micro-session exists for a different task, it is deleted
     ///
-    /// 3. **Preserve**: If no micro-session exists or it's outside the 30-second window, the
+    /// 3. **Preserve**: If no micro-session exists or it's outside the threshold window, the
     ///    micro-session is preserved
     ///
     /// # Example
@@ -57,6 +62,7 @@ impl SessionRepo {
     /// ```
     pub fn create(conn: &Connection, task_id: i64, start_ts: i64) -> Result<Session> {
         let now = chrono::Utc::now().timestamp();
+        let micro_seconds = micro_seconds();
         
         // Check for recent micro-session that might need merge/purge
         if let Some(micro_session) = Self::get_recent_micro_session(conn, start_ts)? {
@@ -64,7 +70,7 @@ impl SessionRepo {
             let time_since_micro_end = start_ts - micro_end_ts;
             
             // Check if within MICRO seconds of micro-session end
-            if time_since_micro_end >= 0 && time_since_micro_end <= MICRO_SECONDS {
+            if time_since_micro_end >= 0 && time_since_micro_end <= micro_seconds {
                 if micro_session.task_id == task_id {
                     // Merge: same task - merge micro-session into new session
                     let new_session_id = {
@@ -85,6 +91,7 @@ impl SessionRepo {
                     
                     // Merge: update new session to start at micro-session's start time
                     Self::merge_micro_session(conn, &micro_session, new_session_id)?;
+                    EventRepo::record_session_started(conn, task_id, new_session_id, micro_session.start_ts)?;
                     
                     println!("Merged micro-session (task {}, {}s) into new session (task {}, started at {}).", 
                         micro_session.task_id, 
@@ -101,6 +108,7 @@ impl SessionRepo {
                         start_ts: micro_session.start_ts, // Merged start time
                         end_ts: None,
                         created_ts: now,
+                        note: None,
                     });
                 } else {
                     // Purge: different task - delete micro-session
@@ -110,7 +118,7 @@ impl SessionRepo {
                         micro_session.task_id,
                         micro_end_ts - micro_session.start_ts,
                         task_id,
-                        MICRO_SECONDS);
+                        micro_seconds);
                 }
             }
         }
@@ -142,6 +150,7 @@ impl SessionRepo {
             start_ts,
             end_ts: None,
             created_ts: now,
+            note: None,
         })
     }
 
@@ -166,6 +175,9 @@ impl SessionRepo {
         
         let id = conn.last_insert_rowid();
 
+        EventRepo::record_session_started(conn, task_id, id, start_ts)?;
+        EventRepo::record_session_ended(conn, task_id, id, start_ts, end_ts)?;
+
         // Touch activity_ts on the task
         TaskRepo::touch_activity(conn, task_id)?;
 
@@ -175,13 +187,14 @@ impl SessionRepo {
             start_ts,
             end_ts: Some(end_ts),
             created_ts: now,
+            note: None,
         })
     }
 
     /// Get the currently open session (if any)
     pub fn get_open(conn: &Connection) -> Result<Option<Session>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, start_ts, end_ts, created_ts FROM sessions WHERE end_ts IS NULL"
+            "SELECT id, task_id, start_ts, end_ts, created_ts, note FROM sessions WHERE end_ts IS NULL"
         )?;
         
         stmt.query_row([], |row| {
@@ -191,6 +204,7 @@ impl SessionRepo {
                 start_ts: row.get(2)?,
                 end_ts: row.get(3)?,
                 created_ts: row.get(4)?,
+                note: row.get(5)?,
             })
         })
         .optional()
@@ -223,6 +237,7 @@ impl SessionRepo {
                 "UPDATE sessions SET end_ts = ?1 WHERE id = ?2",
                 rusqlite::params![end_ts, session_id],
             )?;
+            EventRepo::record_session_ended(conn, session.task_id, session_id, session.start_ts, end_ts)?;
             
             let closed_session = Session {
                 id: Some(session_id),
@@ -230,11 +245,13 @@ impl SessionRepo {
                 start_ts: session.start_ts,
                 end_ts: Some(end_ts),
                 created_ts: session.created_ts,
+                note: session.note,
             };
             
             // Check if this is a micro-session and warn
-            if duration < MICRO_SECONDS {
-                eprintln!("Warning: Micro-session detected ({}s). This session may be merged or purged if another session starts within {} seconds.", duration, MICRO_SECONDS);
+            let micro_seconds = micro_seconds();
+            if duration < micro_seconds {
+                eprintln!("Warning: Micro-session detected ({}s). This session may be merged or purged if another session starts within {} seconds.", duration, micro_seconds);
             }
 
             // Touch activity_ts on the task
@@ -251,10 +268,11 @@ impl SessionRepo {
     /// Returns the most recent closed session that ended within MICRO seconds of the given timestamp
     pub fn get_recent_micro_session(conn: &Connection, before_ts: i64) -> Result<Option<Session>> {
         // Look for sessions that ended within MICRO seconds before before_ts
-        let cutoff_ts = before_ts - MICRO_SECONDS;
+        let micro_seconds = micro_seconds();
+        let cutoff_ts = before_ts - micro_seconds;
         
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, start_ts, end_ts, created_ts 
+            "SELECT id, task_id, start_ts, end_ts, created_ts, note 
              FROM sessions 
              WHERE end_ts IS NOT NULL 
              AND end_ts >= ?1 
@@ -264,13 +282,14 @@ impl SessionRepo {
              LIMIT 1"
         )?;
         
-        stmt.query_row(rusqlite::params![cutoff_ts, before_ts, MICRO_SECONDS], |row| {
+        stmt.query_row(rusqlite::params![cutoff_ts, before_ts, micro_seconds], |row| {
             Ok(Session {
                 id: Some(row.get(0)?),
                 task_id: row.get(1)?,
                 start_ts: row.get(2)?,
                 end_ts: Some(row.get(3)?),
                 created_ts: row.get(4)?,
+                note: row.get(5)?,
             })
         })
         .optional()
@@ -287,27 +306,18 @@ impl SessionRepo {
         )?;
         
         // Delete the micro-session
-        conn.execute(
-            "DELETE FROM sessions WHERE id = ?1",
-            rusqlite::params![micro_session.id.unwrap()],
-        )?;
-        
-        Ok(())
+        Self::delete(conn, micro_session.id.unwrap())
     }
     
     /// Purge (delete) a micro-session
     pub fn purge_micro_session(conn: &Connection, micro_session_id: i64) -> Result<()> {
-        conn.execute(
-            "DELETE FROM sessions WHERE id = ?1",
-            rusqlite::params![micro_session_id],
-        )?;
-        Ok(())
+        Self::delete(conn, micro_session_id)
     }
 
     /// Get all sessions for a task, ordered by start time (newest first)
     pub fn get_by_task(conn: &Connection, task_id: i64) -> Result<Vec<Session>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, start_ts, end_ts, created_ts 
+            "SELECT id, task_id, start_ts, end_ts, created_ts, note 
              FROM sessions 
              WHERE task_id = ?1 
              ORDER BY start_ts DESC"
@@ -320,6 +330,7 @@ impl SessionRepo {
                 start_ts: row.get(2)?,
                 end_ts: row.get(3)?,
                 created_ts: row.get(4)?,
+                note: row.get(5)?,
             })
         })?;
         
@@ -332,19 +343,20 @@ impl SessionRepo {
 
     /// Amend the end time of a closed session (for overlap prevention)
     pub fn amend_end_time(conn: &Connection, session_id: i64, new_end_ts: i64) -> Result<()> {
-        conn.execute(
-            "UPDATE sessions SET end_ts = ?1 WHERE id = ?2 AND end_ts IS NOT NULL",
-            rusqlite::params![new_end_ts, session_id],
-        )?;
-        Ok(())
+        match Self::get_by_id(conn, session_id)? {
+            Some(session) if session.end_ts.is_some() => Self::modify_end_time(conn, session_id, Some(new_end_ts)),
+            _ => Ok(()),
+        }
     }
 
     /// Get the most recent closed session that ends at or after the given timestamp
     /// Used for overlap prevention - find sessions that might need end time amendment
-    /// Get all sessions, ordered by start time (newest first)
+    /// Get all sessions, ordered by start time (newest first). Sessions of deleted tasks are left out.
     pub fn list_all(conn: &Connection) -> Result<Vec<Session>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, start_ts, end_ts, created_ts FROM sessions ORDER BY start_ts DESC"
+            "SELECT id, task_id, start_ts, end_ts, created_ts, note FROM sessions
+             WHERE task_id NOT IN (SELECT id FROM tasks WHERE status = 'deleted')
+             ORDER BY start_ts DESC"
         )?;
         
         let rows = stmt.query_map([], |row| {
@@ -354,6 +366,7 @@ impl SessionRepo {
                 start_ts: row.get(2)?,
                 end_ts: row.get(3)?,
                 created_ts: row.get(4)?,
+                note: row.get(5)?,
             })
         })?;
         
@@ -367,7 +380,7 @@ impl SessionRepo {
     /// Get the most recent session for a task (open or closed)
     pub fn get_most_recent_for_task(conn: &Connection, task_id: i64) -> Result<Option<Session>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, start_ts, end_ts, created_ts FROM sessions 
+            "SELECT id, task_id, start_ts, end_ts, created_ts, note FROM sessions 
              WHERE task_id = ?1 ORDER BY start_ts DESC LIMIT 1"
         )?;
         
@@ -378,6 +391,7 @@ impl SessionRepo {
                 start_ts: row.get(2)?,
                 end_ts: row.get(3)?,
                 created_ts: row.get(4)?,
+                note: row.get(5)?,
             })
         }).optional()?;
         
@@ -386,7 +400,7 @@ impl SessionRepo {
     
     pub fn get_recent_closed_after(conn: &Connection, before_ts: i64) -> Result<Vec<Session>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, start_ts, end_ts, created_ts 
+            "SELECT id, task_id, start_ts, end_ts, created_ts, note 
              FROM sessions 
              WHERE end_ts IS NOT NULL AND end_ts >= ?1 
              ORDER BY end_ts DESC 
@@ -400,6 +414,7 @@ impl SessionRepo {
                 start_ts: row.get(2)?,
                 end_ts: row.get(3)?,
                 created_ts: row.get(4)?,
+                note: row.get(5)?,
             })
         })?;
         
@@ -413,7 +428,7 @@ impl SessionRepo {
     /// Get session by ID
     pub fn get_by_id(conn: &Connection, session_id: i64) -> Result<Option<Session>> {
         let mut stmt = conn.prepare(
-            "SELECT id, task_id, start_ts, end_ts, created_ts FROM sessions WHERE id = ?1"
+            "SELECT id, task_id, start_ts, end_ts, created_ts, note FROM sessions WHERE id = ?1"
         )?;
         
         stmt.query_row([session_id], |row| {
@@ -423,6 +438,7 @@ impl SessionRepo {
                 start_ts: row.get(2)?,
                 end_ts: row.get(3)?,
                 created_ts: row.get(4)?,
+                note: row.get(5)?,
             })
         })
         .optional()
@@ -431,30 +447,77 @@ impl SessionRepo {
 
     /// Modify session start time
     pub fn modify_start_time(conn: &Connection, session_id: i64, new_start_ts: i64) -> Result<()> {
+        let Some(session) = Self::get_by_id(conn, session_id)? else { return Ok(()) };
         conn.execute(
             "UPDATE sessions SET start_ts = ?1 WHERE id = ?2",
             rusqlite::params![new_start_ts, session_id],
         )?;
-        Ok(())
+        Self::record_times(conn, &session, new_start_ts, session.end_ts)
     }
 
     /// Modify session end time
     /// Can set to None (make open) or Some(timestamp) (set end time)
     pub fn modify_end_time(conn: &Connection, session_id: i64, new_end_ts: Option<i64>) -> Result<()> {
+        let Some(session) = Self::get_by_id(conn, session_id)? else { return Ok(()) };
         conn.execute(
             "UPDATE sessions SET end_ts = ?1 WHERE id = ?2",
             rusqlite::params![new_end_ts, session_id],
         )?;
+        Self::record_times(conn, &session, session.start_ts, new_end_ts)
+    }
+
+    /// Record session_modified events for changed start and end times
+    fn record_times(conn: &Connection, session: &Session, start_ts: i64, end_ts: Option<i64>) -> Result<()> {
+        let session_id = session.id.unwrap_or(0);
+        if start_ts != session.start_ts {
+            EventRepo::record_session_modified(conn, session.task_id, session_id, "start_ts",
+                session.start_ts.into(), start_ts.into())?;
+        }
+        if end_ts != session.end_ts {
+            EventRepo::record_session_modified(conn, session.task_id, session_id, "end_ts",
+                session.end_ts.into(), end_ts.into())?;
+        }
         Ok(())
     }
 
     /// Delete a session
     /// Annotations linked to this session will have their session_id set to NULL (via ON DELETE SET NULL)
     pub fn delete(conn: &Connection, session_id: i64) -> Result<()> {
+        let Some(session) = Self::get_by_id(conn, session_id)? else { return Ok(()) };
+        let tags = Self::get_tags(conn, session_id)?;
         conn.execute(
             "DELETE FROM sessions WHERE id = ?1",
             rusqlite::params![session_id],
         )?;
+        EventRepo::record_session_deleted(conn, session.task_id, session_id, session.start_ts,
+            session.end_ts, session.note.as_deref(), &tags)
+    }
+
+    /// Recreate a deleted session with its original ID, note and tags (used by undo)
+    pub fn restore(
+        conn: &Connection,
+        task_id: i64,
+        session_id: i64,
+        start_ts: i64,
+        end_ts: Option<i64>,
+        note: Option<&str>,
+        tags: &[String],
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "INSERT INTO sessions (id, task_id, start_ts, end_ts, created_ts, note) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![session_id, task_id, start_ts, end_ts, now, note],
+        )?;
+        for tag in tags {
+            conn.execute(
+                "INSERT OR IGNORE INTO session_tags (session_id, tag) VALUES (?1, ?2)",
+                rusqlite::params![session_id, tag],
+            )?;
+        }
+        EventRepo::record_session_started(conn, task_id, session_id, start_ts)?;
+        if let Some(end_ts) = end_ts {
+            EventRepo::record_session_ended(conn, task_id, session_id, start_ts, end_ts)?;
+        }
         Ok(())
     }
 
@@ -479,13 +542,131 @@ impl SessionRepo {
             }
         }
 
+        let Some(session) = Self::get_by_id(conn, session_id)? else { return Ok(()) };
         conn.execute(
             "UPDATE sessions SET start_ts = ?1, end_ts = ?2 WHERE id = ?3",
             rusqlite::params![start_ts, end_ts, session_id],
         )?;
+        Self::record_times(conn, &session, start_ts, end_ts)
+    }
+
+    /// Set or clear the note of a session
+    pub fn set_note(conn: &Connection, session_id: i64, note: Option<&str>) -> Result<()> {
+        let Some(session) = Self::get_by_id(conn, session_id)? else { return Ok(()) };
+        conn.execute(
+            "UPDATE sessions SET note = ?1 WHERE id = ?2",
+            rusqlite::params![note, session_id],
+        )?;
+        if session.note.as_deref() != note {
+            EventRepo::record_session_modified(conn, session.task_id, session_id, "note",
+                session.note.into(), note.into())?;
+        }
+        Ok(())
+    }
+
+    /// Get tags for a session
+    pub fn get_tags(conn: &Connection, session_id: i64) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare("SELECT tag FROM session_tags WHERE session_id = ?1 ORDER BY tag")?;
+        let rows = stmt.query_map([session_id], |row| row.get::<_, String>(0))?;
+
+        let mut tags = Vec::new();
+        for row in rows {
+            tags.push(row?);
+        }
+        Ok(tags)
+    }
+
+    /// Tags of all sessions that have any, by session ID
+    pub fn tags_by_session(conn: &Connection) -> Result<HashMap<i64, Vec<String>>> {
+        let mut stmt = conn.prepare("SELECT session_id, tag FROM session_tags ORDER BY session_id, tag")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (session_id, tag) = row?;
+            tags.entry(session_id).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+
+    /// Add tags to a session (tags it already has are left alone)
+    pub fn add_tags(conn: &Connection, session_id: i64, tags: &[String]) -> Result<()> {
+        Self::change_tags(conn, session_id, tags, "INSERT OR IGNORE INTO session_tags (session_id, tag) VALUES (?1, ?2)")
+    }
+
+    /// Remove tags from a session
+    pub fn remove_tags(conn: &Connection, session_id: i64, tags: &[String]) -> Result<()> {
+        Self::change_tags(conn, session_id, tags, "DELETE FROM session_tags WHERE session_id = ?1 AND tag = ?2")
+    }
+
+    /// Run `sql` for each tag and record the change as one session_modified event
+    fn change_tags(conn: &Connection, session_id: i64, tags: &[String], sql: &str) -> Result<()> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+        let Some(session) = Self::get_by_id(conn, session_id)? else { return Ok(()) };
+        let old_tags = Self::get_tags(conn, session_id)?;
+        for tag in tags {
+            conn.execute(sql, rusqlite::params![session_id, tag])?;
+        }
+        let new_tags = Self::get_tags(conn, session_id)?;
+        if new_tags != old_tags {
+            EventRepo::record_session_modified(conn, session.task_id, session_id, "tags",
+                old_tags.into(), new_tags.into())?;
+        }
+        Ok(())
+    }
+
+    /// Copy the note and tags of one session to another (used when a session is split)
+    pub fn copy_attributes(conn: &Connection, from_session_id: i64, to_session_id: i64) -> Result<()> {
+        conn.execute(
+            "UPDATE sessions SET note = (SELECT note FROM sessions WHERE id = ?1) WHERE id = ?2",
+            rusqlite::params![from_session_id, to_session_id],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO session_tags (session_id, tag)
+             SELECT ?2, tag FROM session_tags WHERE session_id = ?1",
+            rusqlite::params![from_session_id, to_session_id],
+        )?;
+        Ok(())
+    }
+
+    /// Record that a session is a pomodoro work interval
+    pub fn set_pomodoro(conn: &Connection, session_id: i64, pomodoro: &Pomodoro) -> Result<()> {
+        conn.execute(
+            "INSERT OR REPLACE INTO session_pomodoro (session_id, work_secs, break_secs) VALUES (?1, ?2, ?3)",
+            rusqlite::params![session_id, pomodoro.work_secs, pomodoro.break_secs],
+        )?;
         Ok(())
     }
 
+    /// Pomodoro settings of a session, if it was started with `--pomodoro`
+    pub fn get_pomodoro(conn: &Connection, session_id: i64) -> Result<Option<Pomodoro>> {
+        let pomodoro = conn.query_row(
+            "SELECT work_secs, break_secs FROM session_pomodoro WHERE session_id = ?1",
+            [session_id],
+            |row| Ok(Pomodoro { work_secs: row.get(0)?, break_secs: row.get(1)? }),
+        ).optional()?;
+        Ok(pomodoro)
+    }
+
+    /// The most recent pomodoro session and its settings
+    pub fn get_last_pomodoro(conn: &Connection) -> Result<Option<(Session, Pomodoro)>> {
+        let session_id: Option<i64> = conn.query_row(
+            "SELECT s.id FROM sessions s JOIN session_pomodoro p ON p.session_id = s.id
+             ORDER BY s.start_ts DESC, s.id DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        let Some(session_id) = session_id else {
+            return Ok(None);
+        };
+        match (Self::get_by_id(conn, session_id)?, Self::get_pomodoro(conn, session_id)?) {
+            (Some(session), Some(pomodoro)) => Ok(Some((session, pomodoro))),
+            _ => Ok(None),
+        }
+    }
+
     /// Find sessions that overlap with the given time range
     /// 
     /// # Arguments
@@ -514,7 +695,7 @@ impl SessionRepo {
         // Get all sessions (excluding the one being modified if specified)
         let all_sessions = if let Some(exclude_id) = exclude_session_id {
             let mut stmt = conn.prepare(
-                "SELECT id, task_id, start_ts, end_ts, created_ts FROM sessions WHERE id != ?1"
+                "SELECT id, task_id, start_ts, end_ts, created_ts, note FROM sessions WHERE id != ?1"
             )?;
             let rows = stmt.query_map([exclude_id], |row| {
                 Ok(Session {
@@ -523,6 +704,7 @@ impl SessionRepo {
                     start_ts: row.get(2)?,
                     end_ts: row.get(3)?,
                     created_ts: row.get(4)?,
+                    note: row.get(5)?,
                 })
             })?;
             let mut sessions = Vec::new();
@@ -694,6 +876,51 @@ mod tests {
         assert_eq!(sessions[0].end_ts, Some(new_end_ts));
     }
 
+    #[test]
+    fn test_session_note_and_tags() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let task = TaskRepo::create(&conn, "Test task", None).unwrap();
+        let session = SessionRepo::create_closed(&conn, task.id.unwrap(), 1000, 2000).unwrap();
+        let session_id = session.id.unwrap();
+
+        SessionRepo::set_note(&conn, session_id, Some("pairing")).unwrap();
+        SessionRepo::add_tags(&conn, session_id, &["review".to_string(), "meeting".to_string()]).unwrap();
+        SessionRepo::add_tags(&conn, session_id, &["review".to_string()]).unwrap();
+        assert_eq!(SessionRepo::get_by_id(&conn, session_id).unwrap().unwrap().note.as_deref(), Some("pairing"));
+        assert_eq!(SessionRepo::get_tags(&conn, session_id).unwrap(), vec!["meeting", "review"]);
+
+        let split = SessionRepo::create_closed(&conn, task.id.unwrap(), 3000, 4000).unwrap();
+        SessionRepo::copy_attributes(&conn, session_id, split.id.unwrap()).unwrap();
+        SessionRepo::remove_tags(&conn, session_id, &["meeting".to_string()]).unwrap();
+        let tags = SessionRepo::tags_by_session(&conn).unwrap();
+        assert_eq!(tags[&session_id], vec!["review"]);
+        assert_eq!(tags[&split.id.unwrap()], vec!["meeting", "review"]);
+        assert_eq!(SessionRepo::get_by_id(&conn, split.id.unwrap()).unwrap().unwrap().note.as_deref(), Some("pairing"));
+
+        // Tags go with the session
+        SessionRepo::delete(&conn, session_id).unwrap();
+        assert!(SessionRepo::get_tags(&conn, session_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_session_pomodoro() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let task = TaskRepo::create(&conn, "Test task", None).unwrap();
+        let first = SessionRepo::create_closed(&conn, task.id.unwrap(), 1000, 2500).unwrap();
+        let second = SessionRepo::create_closed(&conn, task.id.unwrap(), 3000, 3100).unwrap();
+        assert!(SessionRepo::get_last_pomodoro(&conn).unwrap().is_none());
+
+        let pomodoro = Pomodoro { work_secs: 1500, break_secs: 300 };
+        SessionRepo::set_pomodoro(&conn, first.id.unwrap(), &pomodoro).unwrap();
+        assert_eq!(SessionRepo::get_pomodoro(&conn, first.id.unwrap()).unwrap(), Some(pomodoro));
+        assert_eq!(SessionRepo::get_pomodoro(&conn, second.id.unwrap()).unwrap(), None);
+        let (last, _) = SessionRepo::get_last_pomodoro(&conn).unwrap().unwrap();
+        assert_eq!(last.id, first.id);
+
+        SessionRepo::delete(&conn, first.id.unwrap()).unwrap();
+        assert_eq!(SessionRepo::get_pomodoro(&conn, first.id.unwrap()).unwrap(), None);
+    }
+
     #[test]
     fn test_utc_storage() {
         let conn = DbConnection::connect_in_memory().unwrap();
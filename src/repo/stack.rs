@@ -2,6 +2,7 @@ use rusqlite::{Connection, OptionalExtension};
 use crate::models::{Stack, StackItem};
 use crate::repo::{EventRepo, TaskRepo, ExternalRepo};
 use anyhow::Result;
+use std::collections::HashSet;
 
 /// Stack repository for database operations
 ///
@@ -14,6 +15,7 @@ use anyhow::Result;
 /// - Clearing the stack
 ///
 /// The default stack (name='default') is auto-created on first operation.
+/// Additional named stacks (queues) can be created; one of them is active at a time.
 ///
 /// # Example
 ///
@@ -32,30 +34,28 @@ impl StackRepo {
     /// Get or create the default stack
     /// Auto-creates the default stack if it doesn't exist
     pub fn get_or_create_default(conn: &Connection) -> Result<Stack> {
-        // Try to get existing default stack
-        let mut stmt = conn.prepare("SELECT id, name, created_ts, modified_ts FROM stacks WHERE name = 'default'")?;
-        let stack_opt = stmt.query_row([], |row| {
-            Ok(Stack {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                created_ts: row.get(2)?,
-                modified_ts: row.get(3)?,
-            })
-        }).optional()?;
-        
-        if let Some(stack) = stack_opt {
+        Self::get_or_create(conn, &Stack::default().name)
+    }
+
+    /// Get a stack by name
+    pub fn get_by_name(conn: &Connection, name: &str) -> Result<Option<Stack>> {
+        let mut stmt = conn.prepare("SELECT id, name, created_ts, modified_ts FROM stacks WHERE name = ?1")?;
+        let stack_opt = stmt.query_row([name], Self::row_to_stack).optional()?;
+        Ok(stack_opt)
+    }
+
+    /// Get or create a stack by name
+    pub fn get_or_create(conn: &Connection, name: &str) -> Result<Stack> {
+        if let Some(stack) = Self::get_by_name(conn, name)? {
             return Ok(stack);
         }
-        
-        // Create default stack
-        let stack = Stack::default();
-        let now = chrono::Utc::now().timestamp();
-        
+
+        let stack = Stack::new(name.to_string());
         conn.execute(
             "INSERT INTO stacks (name, created_ts, modified_ts) VALUES (?1, ?2, ?3)",
-            rusqlite::params![stack.name, now, now],
+            rusqlite::params![stack.name, stack.created_ts, stack.modified_ts],
         )?;
-        
+
         let id = conn.last_insert_rowid();
         Ok(Stack {
             id: Some(id),
@@ -63,6 +63,99 @@ impl StackRepo {
         })
     }
 
+    /// Get the active stack
+    /// Commands that act on "the queue" use this. Falls back to the default stack.
+    pub fn get_active(conn: &Connection) -> Result<Stack> {
+        let mut stmt = conn.prepare("SELECT id, name, created_ts, modified_ts FROM stacks WHERE active = 1")?;
+        let stack_opt = stmt.query_row([], Self::row_to_stack).optional()?;
+        match stack_opt {
+            Some(stack) => Ok(stack),
+            None => Self::get_or_create_default(conn),
+        }
+    }
+
+    /// Make the named stack active, creating it if needed
+    pub fn set_active(conn: &Connection, name: &str) -> Result<Stack> {
+        let stack = Self::get_or_create(conn, name)?;
+        conn.execute("UPDATE stacks SET active = 0 WHERE active = 1", [])?;
+        conn.execute(
+            "UPDATE stacks SET active = 1 WHERE id = ?1",
+            [stack.id.unwrap()],
+        )?;
+        Ok(stack)
+    }
+
+    /// List all stacks ordered by name
+    pub fn list(conn: &Connection) -> Result<Vec<Stack>> {
+        let mut stmt = conn.prepare("SELECT id, name, created_ts, modified_ts FROM stacks ORDER BY name")?;
+        let rows = stmt.query_map([], Self::row_to_stack)?;
+
+        let mut stacks = Vec::new();
+        for row in rows {
+            stacks.push(row?);
+        }
+        Ok(stacks)
+    }
+
+    /// Get the names of all stacks containing a task
+    pub fn get_names_for_task(conn: &Connection, task_id: i64) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare(
+            "SELECT s.name FROM stacks s
+             JOIN stack_items si ON si.stack_id = s.id
+             WHERE si.task_id = ?1 ORDER BY s.name"
+        )?;
+        let rows = stmt.query_map([task_id], |row| row.get(0))?;
+
+        let mut names = Vec::new();
+        for row in rows {
+            names.push(row?);
+        }
+        Ok(names)
+    }
+
+    /// Get the IDs of all tasks in any stack
+    pub fn get_queued_task_ids(conn: &Connection) -> Result<HashSet<i64>> {
+        let mut stmt = conn.prepare("SELECT DISTINCT task_id FROM stack_items")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+
+        let mut ids = HashSet::new();
+        for row in rows {
+            ids.insert(row?);
+        }
+        Ok(ids)
+    }
+
+    /// Check if a task is in any stack
+    pub fn is_queued(conn: &Connection, task_id: i64) -> Result<bool> {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM stack_items WHERE task_id = ?1",
+            [task_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Remove a task from every stack it is in
+    /// Used when a task leaves the workflow (closed, cancelled, sent to an external party).
+    pub fn remove_from_all(conn: &Connection, task_id: i64) -> Result<()> {
+        let mut stmt = conn.prepare("SELECT stack_id FROM stack_items WHERE task_id = ?1")?;
+        let stack_ids: Vec<i64> = stmt.query_map([task_id], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for stack_id in stack_ids {
+            Self::remove_task(conn, stack_id, task_id)?;
+        }
+        Ok(())
+    }
+
+    fn row_to_stack(row: &rusqlite::Row) -> rusqlite::Result<Stack> {
+        Ok(Stack {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            created_ts: row.get(2)?,
+            modified_ts: row.get(3)?,
+        })
+    }
+
     /// Get stack items ordered by ordinal
     pub fn get_items(conn: &Connection, stack_id: i64) -> Result<Vec<StackItem>> {
         let mut stmt = conn.prepare(
@@ -120,9 +213,12 @@ impl StackRepo {
             |row| row.get(0),
         ).ok();
         
-        if let Some(_) = existing {
+        if let Some(old_ordinal) = existing {
             // Task already in stack - move to end
-            Self::move_to_end(conn, stack_id, task_id)?;
+            let new_ordinal = Self::move_to_end(conn, stack_id, task_id)?;
+            if new_ordinal != old_ordinal {
+                EventRepo::record_stack_added(conn, task_id, stack_id, new_ordinal, Some(old_ordinal))?;
+            }
             return Ok(());
         }
         
@@ -142,7 +238,7 @@ impl StackRepo {
         )?;
         
         // Record stack_added event
-        EventRepo::record_stack_added(conn, task_id, stack_id, new_ordinal)?;
+        EventRepo::record_stack_added(conn, task_id, stack_id, new_ordinal, None)?;
         
         Self::update_modified(conn, stack_id)?;
         Ok(())
@@ -186,6 +282,11 @@ impl StackRepo {
         
         // Renumber to ensure clean ordinals
         Self::renumber(conn, stack_id)?;
+
+        if existing_ordinal != Some(0) {
+            EventRepo::record_stack_added(conn, task_id, stack_id, 0, existing_ordinal)?;
+        }
+
         Self::update_modified(conn, stack_id)?;
         Ok(())
     }
@@ -218,52 +319,65 @@ impl StackRepo {
     }
 
     /// Rotate stack by n positions
+    /// Roll: [a,b,c] with roll 1 becomes [b,c,a]; negative n rotates right.
     /// If called within a transaction, the transaction should be passed as conn
     pub fn roll(conn: &Connection, stack_id: i64, n: i32) -> Result<()> {
         let items = Self::get_items(conn, stack_id)?;
         if items.len() <= 1 {
             return Ok(()); // Nothing to rotate
         }
-        
+
         let item_count = items.len() as i32;
-        let effective_n = n % item_count;
+        let effective_n = n.rem_euclid(item_count);
         if effective_n == 0 {
             return Ok(()); // No rotation needed
         }
-        
-        // Store current ordinals and task_ids
-        let mut task_ordinals: Vec<(i64, i32)> = Vec::new();
-        for item in &items {
-            task_ordinals.push((item.task_id, item.ordinal));
+
+        let mut order: Vec<i64> = items.iter().map(|item| item.task_id).collect();
+        order.rotate_left(effective_n as usize);
+        Self::reorder(conn, stack_id, &order)
+    }
+
+    /// Swap the tasks at two positions
+    pub fn swap(conn: &Connection, stack_id: i64, a: i32, b: i32) -> Result<()> {
+        let mut order: Vec<i64> = Self::get_items(conn, stack_id)?
+            .iter()
+            .map(|item| item.task_id)
+            .collect();
+        let len = order.len() as i32;
+        if a < 0 || b < 0 || a >= len || b >= len {
+            anyhow::bail!("Queue position out of range (queue has {} tasks)", len);
         }
-        
-        // Clear all items temporarily
-        conn.execute("DELETE FROM stack_items WHERE stack_id = ?1", [stack_id])?;
-        
-        // Reinsert with new ordinals
-        // Roll: [a,b,c] with roll 1 becomes [b,c,a]
-        // This is a left rotation: each item moves left by n positions
-        // Item at position i moves to position (i - n) mod count
-        let now = chrono::Utc::now().timestamp();
-        for (task_id, old_ordinal) in task_ordinals {
-            let new_ordinal = if effective_n > 0 {
-                // Left rotation: (i - n) mod count
-                (old_ordinal - effective_n + item_count) % item_count
-            } else {
-                // Right rotation (negative n): (i + |n|) mod count
-                let abs_n = (-effective_n) % item_count;
-                (old_ordinal + abs_n) % item_count
-            };
-            
-            conn.execute(
-                "INSERT INTO stack_items (stack_id, task_id, ordinal, added_ts) VALUES (?1, ?2, ?3, ?4)",
-                rusqlite::params![stack_id, task_id, new_ordinal, now],
-            )?;
+        order.swap(a as usize, b as usize);
+        Self::reorder(conn, stack_id, &order)
+    }
+
+    /// Reorder the stack to match the given task order
+    ///
+    /// The order must contain exactly the tasks currently in the stack. The reorder is
+    /// applied as a series of single moves, each recorded as a stack_added event with
+    /// its old position, so replaying the events backwards restores the original order.
+    pub fn reorder(conn: &Connection, stack_id: i64, order: &[i64]) -> Result<()> {
+        let mut current: Vec<i64> = Self::get_items(conn, stack_id)?
+            .iter()
+            .map(|item| item.task_id)
+            .collect();
+
+        let mut expected = current.clone();
+        let mut requested = order.to_vec();
+        expected.sort_unstable();
+        requested.sort_unstable();
+        if expected != requested {
+            anyhow::bail!("Reorder must contain exactly the tasks in the queue");
+        }
+
+        for (position, &task_id) in order.iter().enumerate() {
+            if current[position] != task_id {
+                Self::move_to_position(conn, stack_id, task_id, position as i32)?;
+                current.retain(|&id| id != task_id);
+                current.insert(position, task_id);
+            }
         }
-        
-        // Renumber to ensure clean ordinals (within same transaction if applicable)
-        Self::renumber(conn, stack_id)?;
-        Self::update_modified(conn, stack_id)?;
         Ok(())
     }
 
@@ -297,7 +411,7 @@ impl StackRepo {
         
         // Record stack_removed event
         if let Some(task_id) = task_id {
-            EventRepo::record_stack_removed(conn, task_id, stack_id)?;
+            EventRepo::record_stack_removed(conn, task_id, stack_id, clamped_index)?;
         }
         
         // Renumber remaining items
@@ -307,17 +421,23 @@ impl StackRepo {
     }
 
     /// Clear all items from stack
+    /// Each task is removed individually so the removals are recorded as events.
     pub fn clear(conn: &Connection, stack_id: i64) -> Result<()> {
-        conn.execute(
-            "DELETE FROM stack_items WHERE stack_id = ?1",
-            [stack_id],
-        )?;
+        for item in Self::get_items(conn, stack_id)? {
+            Self::remove_task(conn, stack_id, item.task_id)?;
+        }
         Self::update_modified(conn, stack_id)?;
         Ok(())
     }
 
     /// Remove a specific task from the stack by task_id
     pub fn remove_task(conn: &Connection, stack_id: i64, task_id: i64) -> Result<()> {
+        let position: Option<i32> = conn.query_row(
+            "SELECT ordinal FROM stack_items WHERE stack_id = ?1 AND task_id = ?2",
+            rusqlite::params![stack_id, task_id],
+            |row| row.get(0),
+        ).optional()?;
+
         // Delete the item
         conn.execute(
             "DELETE FROM stack_items WHERE stack_id = ?1 AND task_id = ?2",
@@ -325,7 +445,9 @@ impl StackRepo {
         )?;
         
         // Record stack_removed event
-        EventRepo::record_stack_removed(conn, task_id, stack_id)?;
+        if let Some(position) = position {
+            EventRepo::record_stack_removed(conn, task_id, stack_id, position)?;
+        }
         
         // Renumber remaining items
         Self::renumber(conn, stack_id)?;
@@ -333,8 +455,55 @@ impl StackRepo {
         Ok(())
     }
 
+    /// Move a task to a specific position, inserting it if it is not queued.
+    /// Position is clamped to the queue length. Records a stack_added event with the old position.
+    pub fn move_to_position(conn: &Connection, stack_id: i64, task_id: i64, position: i32) -> Result<()> {
+        let items = Self::get_items(conn, stack_id)?;
+        let old_position = items.iter().position(|item| item.task_id == task_id).map(|p| p as i32);
+
+        let mut order: Vec<i64> = items.iter()
+            .map(|item| item.task_id)
+            .filter(|&id| id != task_id)
+            .collect();
+        let new_position = position.clamp(0, order.len() as i32);
+        if old_position == Some(new_position) {
+            return Ok(());
+        }
+        order.insert(new_position as usize, task_id);
+
+        if old_position.is_none() {
+            let now = chrono::Utc::now().timestamp();
+            conn.execute(
+                "INSERT INTO stack_items (stack_id, task_id, ordinal, added_ts) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![stack_id, task_id, items.len() as i32, now],
+            )?;
+        }
+        Self::apply_order(conn, stack_id, &order)?;
+
+        EventRepo::record_stack_added(conn, task_id, stack_id, new_position, old_position)?;
+        Self::update_modified(conn, stack_id)?;
+        Ok(())
+    }
+
+    /// Rewrite ordinals to match the given task order
+    fn apply_order(conn: &Connection, stack_id: i64, order: &[i64]) -> Result<()> {
+        // Use negative ordinals temporarily to avoid UNIQUE(stack_id, ordinal) conflicts
+        for (idx, task_id) in order.iter().enumerate() {
+            conn.execute(
+                "UPDATE stack_items SET ordinal = ?1 WHERE stack_id = ?2 AND task_id = ?3",
+                rusqlite::params![-(idx as i32) - 1, stack_id, task_id],
+            )?;
+        }
+        conn.execute(
+            "UPDATE stack_items SET ordinal = -ordinal - 1 WHERE stack_id = ?1 AND ordinal < 0",
+            [stack_id],
+        )?;
+        Ok(())
+    }
+
     /// Move task to end of stack
-    fn move_to_end(conn: &Connection, stack_id: i64, task_id: i64) -> Result<()> {
+    /// Returns the task's new position
+    fn move_to_end(conn: &Connection, stack_id: i64, task_id: i64) -> Result<i32> {
         // Remove from current position
         conn.execute(
             "DELETE FROM stack_items WHERE stack_id = ?1 AND task_id = ?2",
@@ -356,7 +525,7 @@ impl StackRepo {
         )?;
         
         Self::renumber(conn, stack_id)?;
-        Ok(())
+        Ok(max_ordinal + 1)
     }
 
     /// Renumber stack items to ensure clean ordinals (0, 1, 2, ...)
@@ -543,4 +712,112 @@ mod tests {
         let items = StackRepo::get_items(&conn, stack_id).unwrap();
         assert_eq!(items[0].task_id, task1.id.unwrap());
     }
+
+    #[test]
+    fn test_move_to_position() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let stack = StackRepo::get_or_create_default(&conn).unwrap();
+        let stack_id = stack.id.unwrap();
+
+        let task1 = TaskRepo::create(&conn, "Task 1", None).unwrap().id.unwrap();
+        let task2 = TaskRepo::create(&conn, "Task 2", None).unwrap().id.unwrap();
+        let task3 = TaskRepo::create(&conn, "Task 3", None).unwrap().id.unwrap();
+
+        StackRepo::enqueue(&conn, stack_id, task1).unwrap();
+        StackRepo::enqueue(&conn, stack_id, task2).unwrap();
+
+        // Move existing task
+        StackRepo::move_to_position(&conn, stack_id, task2, 0).unwrap();
+        let order: Vec<i64> = StackRepo::get_items(&conn, stack_id).unwrap().iter().map(|i| i.task_id).collect();
+        assert_eq!(order, vec![task2, task1]);
+
+        // Insert new task in the middle
+        StackRepo::move_to_position(&conn, stack_id, task3, 1).unwrap();
+        let items = StackRepo::get_items(&conn, stack_id).unwrap();
+        let order: Vec<i64> = items.iter().map(|i| i.task_id).collect();
+        assert_eq!(order, vec![task2, task3, task1]);
+        let ordinals: Vec<i32> = items.iter().map(|i| i.ordinal).collect();
+        assert_eq!(ordinals, vec![0, 1, 2]);
+
+        // Position past the end is clamped
+        StackRepo::move_to_position(&conn, stack_id, task2, 10).unwrap();
+        let order: Vec<i64> = StackRepo::get_items(&conn, stack_id).unwrap().iter().map(|i| i.task_id).collect();
+        assert_eq!(order, vec![task3, task1, task2]);
+    }
+
+    #[test]
+    fn test_named_queues() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let task1 = TaskRepo::create(&conn, "Task 1", None).unwrap().id.unwrap();
+        let task2 = TaskRepo::create(&conn, "Task 2", None).unwrap().id.unwrap();
+
+        // Default queue is active until another is switched to
+        let default_stack = StackRepo::get_active(&conn).unwrap();
+        assert_eq!(default_stack.name, "default");
+
+        let oncall = StackRepo::set_active(&conn, "oncall").unwrap();
+        assert_eq!(StackRepo::get_active(&conn).unwrap().id, oncall.id);
+        assert_eq!(StackRepo::get_or_create(&conn, "oncall").unwrap().id, oncall.id);
+
+        StackRepo::enqueue(&conn, default_stack.id.unwrap(), task1).unwrap();
+        StackRepo::enqueue(&conn, oncall.id.unwrap(), task1).unwrap();
+        StackRepo::enqueue(&conn, oncall.id.unwrap(), task2).unwrap();
+
+        assert_eq!(StackRepo::get_names_for_task(&conn, task1).unwrap(), vec!["default", "oncall"]);
+        assert!(StackRepo::is_queued(&conn, task2).unwrap());
+        assert_eq!(StackRepo::get_queued_task_ids(&conn).unwrap().len(), 2);
+
+        // Removing from all queues leaves other tasks in place
+        StackRepo::remove_from_all(&conn, task1).unwrap();
+        assert!(!StackRepo::is_queued(&conn, task1).unwrap());
+        let items = StackRepo::get_items(&conn, oncall.id.unwrap()).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].task_id, task2);
+        assert_eq!(items[0].ordinal, 0);
+
+        let names: Vec<String> = StackRepo::list(&conn).unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["default", "oncall"]);
+    }
+
+    #[test]
+    fn test_roll_negative() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let stack_id = StackRepo::get_or_create_default(&conn).unwrap().id.unwrap();
+        let ids: Vec<i64> = (1..=3)
+            .map(|i| TaskRepo::create(&conn, &format!("Task {}", i), None).unwrap().id.unwrap())
+            .collect();
+        for id in &ids {
+            StackRepo::enqueue(&conn, stack_id, *id).unwrap();
+        }
+
+        StackRepo::roll(&conn, stack_id, -1).unwrap();
+        let order: Vec<i64> = StackRepo::get_items(&conn, stack_id).unwrap().iter().map(|i| i.task_id).collect();
+        assert_eq!(order, vec![ids[2], ids[0], ids[1]]);
+    }
+
+    #[test]
+    fn test_swap_and_reorder() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let stack_id = StackRepo::get_or_create_default(&conn).unwrap().id.unwrap();
+        let ids: Vec<i64> = (1..=4)
+            .map(|i| TaskRepo::create(&conn, &format!("Task {}", i), None).unwrap().id.unwrap())
+            .collect();
+        for id in &ids {
+            StackRepo::enqueue(&conn, stack_id, *id).unwrap();
+        }
+
+        StackRepo::swap(&conn, stack_id, 0, 2).unwrap();
+        let order: Vec<i64> = StackRepo::get_items(&conn, stack_id).unwrap().iter().map(|i| i.task_id).collect();
+        assert_eq!(order, vec![ids[2], ids[1], ids[0], ids[3]]);
+        assert!(StackRepo::swap(&conn, stack_id, 0, 4).is_err());
+
+        let reversed: Vec<i64> = ids.iter().rev().copied().collect();
+        StackRepo::reorder(&conn, stack_id, &reversed).unwrap();
+        let items = StackRepo::get_items(&conn, stack_id).unwrap();
+        assert_eq!(items.iter().map(|i| i.task_id).collect::<Vec<_>>(), reversed);
+        assert_eq!(items.iter().map(|i| i.ordinal).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        // Reorder must be a permutation of the queue
+        assert!(StackRepo::reorder(&conn, stack_id, &ids[..3]).is_err());
+    }
 }
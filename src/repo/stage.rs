@@ -9,7 +9,7 @@ impl StageRepo {
     pub fn list_all(conn: &Connection) -> Result<Vec<StageMapping>> {
         let mut stmt = conn.prepare(
             "SELECT id, status, in_queue, has_sessions, has_open_session, has_externals,
-                    stage, sort_order, color
+                    stage, sort_order, color, is_blocked
              FROM stage_map ORDER BY id"
         )?;
 
@@ -21,6 +21,7 @@ impl StageRepo {
                 has_sessions: row.get(3)?,
                 has_open_session: row.get(4)?,
                 has_externals: row.get(5)?,
+                is_blocked: row.get(9)?,
                 stage: row.get(6)?,
                 sort_order: row.get(7)?,
                 color: row.get(8)?,
@@ -49,12 +50,13 @@ impl StageRepo {
         has_sessions: bool,
         has_open_session: bool,
         has_externals: bool,
+        is_blocked: bool,
     ) -> Result<StageMapping> {
         // Terminal statuses use wildcard rows (in_queue = -1)
         if status == "closed" || status == "cancelled" {
             let mut stmt = conn.prepare(
                 "SELECT id, status, in_queue, has_sessions, has_open_session, has_externals,
-                        stage, sort_order, color
+                        stage, sort_order, color, is_blocked
                  FROM stage_map
                  WHERE status = ?1 AND in_queue = -1
                  LIMIT 1"
@@ -67,6 +69,7 @@ impl StageRepo {
                     has_sessions: row.get(3)?,
                     has_open_session: row.get(4)?,
                     has_externals: row.get(5)?,
+                    is_blocked: row.get(9)?,
                     stage: row.get(6)?,
                     sort_order: row.get(7)?,
                     color: row.get(8)?,
@@ -78,10 +81,10 @@ impl StageRepo {
         // Open status: exact match on all booleans
         let mut stmt = conn.prepare(
             "SELECT id, status, in_queue, has_sessions, has_open_session, has_externals,
-                    stage, sort_order, color
+                    stage, sort_order, color, is_blocked
              FROM stage_map
              WHERE status = ?1 AND in_queue = ?2 AND has_sessions = ?3
-                   AND has_open_session = ?4 AND has_externals = ?5
+                   AND has_open_session = ?4 AND has_externals = ?5 AND is_blocked = ?6
              LIMIT 1"
         )?;
         let mapping = stmt.query_row(
@@ -91,6 +94,7 @@ impl StageRepo {
                 has_sessions as i8,
                 has_open_session as i8,
                 has_externals as i8,
+                is_blocked as i8,
             ],
             |row| {
                 Ok(StageMapping {
@@ -100,14 +104,15 @@ impl StageRepo {
                     has_sessions: row.get(3)?,
                     has_open_session: row.get(4)?,
                     has_externals: row.get(5)?,
+                    is_blocked: row.get(9)?,
                     stage: row.get(6)?,
                     sort_order: row.get(7)?,
                     color: row.get(8)?,
                 })
             },
         ).with_context(|| format!(
-            "No stage mapping found for status='{}' in_queue={} has_sessions={} has_open_session={} has_externals={}",
-            status, in_queue, has_sessions, has_open_session, has_externals
+            "No stage mapping found for status='{}' in_queue={} has_sessions={} has_open_session={} has_externals={} is_blocked={}",
+            status, in_queue, has_sessions, has_open_session, has_externals, is_blocked
         ))?;
         Ok(mapping)
     }
@@ -121,6 +126,7 @@ impl StageRepo {
         has_sessions: bool,
         has_open_session: bool,
         has_externals: bool,
+        is_blocked: bool,
     ) -> Option<&'a StageMapping> {
         if status == "closed" || status == "cancelled" {
             return mappings.iter().find(|m| m.status == status && m.in_queue == -1);
@@ -130,6 +136,7 @@ impl StageRepo {
         let hs = has_sessions as i8;
         let hos = has_open_session as i8;
         let he = has_externals as i8;
+        let ib = is_blocked as i8;
 
         mappings.iter().find(|m| {
             m.status == status
@@ -137,6 +144,7 @@ impl StageRepo {
                 && m.has_sessions == hs
                 && m.has_open_session == hos
                 && m.has_externals == he
+                && m.is_blocked == ib
         })
     }
 
@@ -1,6 +1,6 @@
 use rusqlite::{Connection, OptionalExtension};
 use crate::models::Task;
-use crate::repo::EventRepo;
+use crate::repo::{EventRepo, SessionRepo, StackRepo};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 
@@ -124,8 +124,14 @@ impl TaskRepo {
         )
     }
 
-    /// Get task by ID
+    /// Get task by ID. Deleted tasks are treated as missing.
     pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Task>> {
+        Ok(Self::get_by_id_with_deleted(conn, id)?
+            .filter(|task| task.status != crate::models::TaskStatus::Deleted))
+    }
+
+    /// Get task by ID, including deleted tasks (for history and undo)
+    pub fn get_by_id_with_deleted(conn: &Connection, id: i64) -> Result<Option<Task>> {
         let mut stmt = conn.prepare(
             "SELECT id, uuid, description, status, project_id, due_ts, scheduled_ts,
                     wait_ts, alloc_secs, template, respawn, udas_json, created_ts, modified_ts, activity_ts, parent_id
@@ -179,15 +185,30 @@ impl TaskRepo {
         Ok(tags)
     }
 
-    /// List all tasks (basic - no filtering yet)
+    /// List all tasks
     pub fn list_all(conn: &Connection) -> Result<Vec<(Task, Vec<String>)>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, uuid, description, status, project_id, due_ts, scheduled_ts,
-                    wait_ts, alloc_secs, template, respawn, udas_json, created_ts, modified_ts, activity_ts, parent_id
-             FROM tasks WHERE status != 'deleted' ORDER BY id"
-        )?;
+        Self::list_matching(conn, "1", &[])
+    }
 
-        let rows = stmt.query_map([], |row| {
+    /// List tasks matching a SQL condition on the tasks table (aliased `t`)
+    ///
+    /// Used by the filter engine to push filter terms down into SQL. Deleted tasks
+    /// are always excluded. Tags are loaded in a single query rather than per task.
+    pub fn list_matching(
+        conn: &Connection,
+        condition: &str,
+        params: &[rusqlite::types::Value],
+    ) -> Result<Vec<(Task, Vec<String>)>> {
+        let sql = format!(
+            "SELECT t.id, t.uuid, t.description, t.status, t.project_id, t.due_ts, t.scheduled_ts,
+                    t.wait_ts, t.alloc_secs, t.template, t.respawn, t.udas_json, t.created_ts, t.modified_ts,
+                    t.activity_ts, t.parent_id
+             FROM tasks t WHERE t.status != 'deleted' AND ({}) ORDER BY t.id",
+            condition
+        );
+        let mut stmt = conn.prepare(&sql)?;
+
+        let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
             let id: i64 = row.get(0)?;
             let udas_json: Option<String> = row.get(11)?;
             let mut udas = HashMap::new();
@@ -217,15 +238,29 @@ impl TaskRepo {
                 activity_ts: row.get::<_, Option<i64>>(14)?.unwrap_or(row.get(13)?),
             })
         })?;
-        
+
         let mut tasks = Vec::new();
         for task_result in rows {
-            let task = task_result?;
-            let tags = Self::get_tags(conn, task.id.unwrap())?;
-            tasks.push((task, tags));
+            tasks.push(task_result?);
         }
-        
-        Ok(tasks)
+
+        // Load tags for all tasks at once
+        let mut tags_by_task: HashMap<i64, Vec<String>> = HashMap::new();
+        if !tasks.is_empty() {
+            let mut stmt = conn.prepare("SELECT task_id, tag FROM task_tags ORDER BY task_id, tag")?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+            for row in rows {
+                let (task_id, tag) = row?;
+                tags_by_task.entry(task_id).or_default().push(tag);
+            }
+        }
+
+        Ok(tasks.into_iter()
+            .map(|task| {
+                let tags = tags_by_task.remove(&task.id.unwrap()).unwrap_or_default();
+                (task, tags)
+            })
+            .collect())
     }
 
     /// Modify a task
@@ -369,10 +404,27 @@ impl TaskRepo {
 
         // Update UDAs
         for (key, value) in udas_to_add {
-            task.udas.insert(key.clone(), value.clone());
+            let old_value = task.udas.insert(key.clone(), value.clone());
+            if old_value.as_ref() != Some(value) {
+                EventRepo::record_modified(
+                    conn,
+                    task_id,
+                    &format!("uda.{}", key),
+                    old_value.map(serde_json::Value::String),
+                    Some(serde_json::Value::String(value.clone())),
+                )?;
+            }
         }
         for key in udas_to_remove {
-            task.udas.remove(key);
+            if let Some(old_value) = task.udas.remove(key) {
+                EventRepo::record_modified(
+                    conn,
+                    task_id,
+                    &format!("uda.{}", key),
+                    Some(serde_json::Value::String(old_value)),
+                    None,
+                )?;
+            }
         }
         
         // Serialize UDAs
@@ -452,7 +504,7 @@ impl TaskRepo {
         let query = format!(
             "SELECT id, uuid, description, status, project_id, due_ts, scheduled_ts,
                     wait_ts, alloc_secs, template, respawn, udas_json, created_ts, modified_ts, activity_ts, parent_id
-             FROM tasks WHERE id IN ({})",
+             FROM tasks WHERE id IN ({}) AND status != 'deleted'",
             placeholders
         );
 
@@ -497,7 +549,7 @@ impl TaskRepo {
     /// Update task status
     /// Also clears wait_ts when status changes to a terminal state
     pub fn set_status(conn: &Connection, task_id: i64, new_status: crate::models::TaskStatus) -> Result<()> {
-        let old_task = Self::get_by_id(conn, task_id)?
+        let old_task = Self::get_by_id_with_deleted(conn, task_id)?
             .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_id))?;
         let old_status = old_task.status.as_str();
         let new_status_str = new_status.as_str();
@@ -527,6 +579,17 @@ impl TaskRepo {
             EventRepo::record_status_changed(conn, task_id, old_status, new_status_str)?;
         }
 
+        // Record the cleared wait date so the change can be undone
+        if should_clear_wait && old_task.wait_ts.is_some() {
+            EventRepo::record_modified(
+                conn,
+                task_id,
+                "wait_ts",
+                old_task.wait_ts.map(|ts| serde_json::Value::Number(ts.into())),
+                None,
+            )?;
+        }
+
         Ok(())
     }
     
@@ -545,9 +608,6 @@ impl TaskRepo {
         Self::set_status(conn, task_id, crate::models::TaskStatus::Open)
     }
 
-    /// Permanently delete a task and all related data
-    /// 
-    /// This operation is atomic - all related data is deleted in a transaction.
     /// Touch activity_ts to record meaningful interaction (annotation, session, external)
     pub fn touch_activity(conn: &Connection, task_id: i64) -> Result<()> {
         let now = chrono::Utc::now().timestamp();
@@ -558,48 +618,52 @@ impl TaskRepo {
         Ok(())
     }
 
-    /// Related data includes:
-    /// - Task tags (CASCADE)
-    /// - Task annotations (CASCADE)
-    /// - Task sessions (CASCADE)
-    /// - Stack items (CASCADE)
-    /// - Task events (CASCADE)
-    /// - Respawn rules (part of task)
-    /// 
+    /// Delete a task
+    ///
+    /// The task is kept as a tombstone with status `deleted`, so its event history
+    /// survives and `tatl undo` can restore it. Deleted tasks are hidden from
+    /// lookups, listings and session reports. This operation is atomic; see
+    /// [`TaskRepo::mark_deleted`] for what it changes.
+    ///
     /// # Example
-    /// 
+    ///
     /// ```no_run
     /// use tatl::db::DbConnection;
     /// use tatl::repo::TaskRepo;
-    /// 
+    ///
     /// let conn = DbConnection::connect().unwrap();
     /// TaskRepo::delete(&conn, 5).unwrap();
     /// ```
     pub fn delete(conn: &Connection, task_id: i64) -> Result<()> {
-        // Verify task exists
-        let _task = Self::get_by_id(conn, task_id)?
-            .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_id))?;
-        
-        // Use transaction to ensure atomicity
         let tx = conn.unchecked_transaction()?;
-        
-        // Delete the task - CASCADE will handle related data automatically
-        // But we'll also explicitly clean up to be safe and clear
-        let rows_affected = tx.execute(
-            "DELETE FROM tasks WHERE id = ?1",
-            rusqlite::params![task_id],
-        )?;
-        
-        if rows_affected == 0 {
-            anyhow::bail!("Task {} not found", task_id);
-        }
-        
-        // Commit transaction
+        Self::mark_deleted(&tx, task_id)?;
         tx.commit()?;
-        
         Ok(())
     }
-    
+
+    /// Mark a task deleted without opening a transaction. Every change is
+    /// recorded as an event in the current batch:
+    /// - Subtasks are detached from the task
+    /// - The task is removed from every queue
+    /// - A running session on the task is stopped
+    /// - The status becomes `deleted`
+    pub fn mark_deleted(conn: &Connection, task_id: i64) -> Result<()> {
+        Self::get_by_id(conn, task_id)?
+            .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_id))?;
+
+        for child in Self::get_children(conn, task_id)? {
+            if let Some(child_id) = child.id {
+                Self::modify(conn, child_id, None, None, None, None, None, None, None, None,
+                    &HashMap::new(), &[], &[], &[], Some(None))?;
+            }
+        }
+        StackRepo::remove_from_all(conn, task_id)?;
+        if SessionRepo::get_open(conn)?.is_some_and(|session| session.task_id == task_id) {
+            SessionRepo::close_open(conn, chrono::Utc::now().timestamp())?;
+        }
+        Self::set_status(conn, task_id, crate::models::TaskStatus::Deleted)
+    }
+
     /// Get direct children of a task
     pub fn get_children(conn: &Connection, parent_id: i64) -> Result<Vec<Task>> {
         let mut stmt = conn.prepare(
@@ -645,6 +709,48 @@ impl TaskRepo {
         Ok(tasks)
     }
 
+    /// Recreate every descendant of `source_parent_id` as fresh open tasks under
+    /// `new_parent_id`, shifting their dates by `shift` seconds.
+    /// Returns `(source task, new task ID, depth)` in creation order, depth 1 for direct children.
+    pub fn clone_subtree(conn: &Connection, source_parent_id: i64, new_parent_id: i64, shift: i64) -> Result<Vec<(Task, i64, usize)>> {
+        let mut cloned = Vec::new();
+        Self::clone_subtree_into(conn, source_parent_id, new_parent_id, shift, 1, &mut cloned)?;
+        Ok(cloned)
+    }
+
+    fn clone_subtree_into(
+        conn: &Connection,
+        source_parent_id: i64,
+        new_parent_id: i64,
+        shift: i64,
+        depth: usize,
+        cloned: &mut Vec<(Task, i64, usize)>,
+    ) -> Result<()> {
+        for child in Self::get_children(conn, source_parent_id)? {
+            let child_id = child.id.ok_or_else(|| anyhow::anyhow!("Subtask has no ID"))?;
+            let tags = Self::get_tags(conn, child_id)?;
+            let clone = Self::create_full(
+                conn,
+                &child.description,
+                child.project_id,
+                child.due_ts.map(|ts| ts + shift),
+                child.scheduled_ts.map(|ts| ts + shift),
+                child.wait_ts.map(|ts| ts + shift),
+                child.alloc_secs,
+                child.template.clone(),
+                None,
+                &child.udas,
+                &tags,
+                Some(new_parent_id),
+            )
+            .context("Failed to create cloned subtask")?;
+            let clone_id = clone.id.ok_or_else(|| anyhow::anyhow!("Cloned subtask has no ID"))?;
+            cloned.push((child, clone_id, depth));
+            Self::clone_subtree_into(conn, child_id, clone_id, shift, depth + 1, cloned)?;
+        }
+        Ok(())
+    }
+
     /// Validate that setting proposed_parent_id as parent of task_id won't create a cycle.
     /// Walks up the parent chain from proposed_parent_id; if task_id is encountered, it's a cycle.
     pub fn validate_no_cycle(conn: &Connection, task_id: i64, proposed_parent_id: i64) -> Result<()> {
@@ -663,15 +769,39 @@ impl TaskRepo {
         Ok(())
     }
 
-    /// Orphan all children of a task (set their parent_id to NULL)
-    pub fn orphan_children(conn: &Connection, parent_id: i64) -> Result<()> {
+    /// Record that a task was generated by respawning another one
+    pub fn set_respawned_from(conn: &Connection, task_id: i64, source_id: i64) -> Result<()> {
         conn.execute(
-            "UPDATE tasks SET parent_id = NULL WHERE parent_id = ?1",
-            [parent_id],
+            "UPDATE tasks SET respawned_from = ?1 WHERE id = ?2",
+            [source_id, task_id],
         )?;
         Ok(())
     }
 
+    /// Get the respawn chain ending at a task, oldest instance first.
+    /// A task that was not generated by respawn is a chain of one.
+    pub fn get_respawn_chain(conn: &Connection, task_id: i64) -> Result<Vec<i64>> {
+        let mut chain = vec![task_id];
+        let mut current = task_id;
+        loop {
+            let source: Option<Option<i64>> = conn.query_row(
+                "SELECT respawned_from FROM tasks WHERE id = ?1",
+                [current],
+                |row| row.get(0),
+            ).optional()?;
+            match source.flatten() {
+                // Guard against a malformed cycle
+                Some(id) if !chain.contains(&id) => {
+                    chain.push(id);
+                    current = id;
+                }
+                _ => break,
+            }
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
     /// Get total logged time for a task (sum of all session durations)
     pub fn get_total_logged_time(conn: &Connection, task_id: i64) -> Result<i64> {
         use crate::repo::SessionRepo;
@@ -1,12 +1,19 @@
 use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
 use serde_json;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::collections::HashMap;
 
 /// Template repository for database operations
 pub struct TemplateRepo;
 
 /// Template model (stored in database)
+///
+/// Payload keys: `project_id`, `due`/`scheduled`/`wait` (date expressions such as
+/// `+2d` or `monday`, evaluated when the template is used), `alloc_secs`, `udas`,
+/// `tags`, `description` (pattern), `respawn`, `parent_id` and `children` (subtask
+/// hierarchy, see `TemplateChild`). Templates saved by older versions may hold
+/// absolute `due_ts`/`scheduled_ts`/`wait_ts` instead.
 #[derive(Debug, Clone)]
 pub struct Template {
     pub name: String,
@@ -15,7 +22,159 @@ pub struct Template {
     pub modified_ts: i64,
 }
 
+/// Subtask created under a task made from a template
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemplateChild {
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<i64>,        // Defaults to the parent's project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_offset: Option<String>,     // Relative to the parent's due date, e.g. "-2d" or "+4h"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alloc_secs: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub udas: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TemplateChild>,
+}
+
+impl TemplateChild {
+    /// Parse a due offset such as "+2d", "-1h30m" or "3d" (positive) into seconds
+    pub fn parse_offset(offset: &str) -> Result<i64> {
+        let (sign, duration) = match offset.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, offset.strip_prefix('+').unwrap_or(offset)),
+        };
+        let secs = crate::utils::parse_duration(duration)
+            .with_context(|| format!("Invalid due offset '{}'", offset))?;
+        Ok(sign * secs)
+    }
+
+    /// Due date for this child given its parent's due date.
+    /// Offsets are relative to now when the parent has no due date.
+    pub fn resolve_due(&self, parent_due_ts: Option<i64>) -> Result<Option<i64>> {
+        let Some(offset) = &self.due_offset else {
+            return Ok(None);
+        };
+        let base = parent_due_ts.unwrap_or_else(|| chrono::Utc::now().timestamp());
+        Ok(Some(base + Self::parse_offset(offset)?))
+    }
+
+    /// Number of subtasks in this child's hierarchy, including itself
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(TemplateChild::count).sum::<usize>()
+    }
+}
+
+/// Parse a 1-based child path such as "2" or "2.1"
+pub fn parse_child_path(path: &str) -> Result<Vec<usize>> {
+    path.split('.')
+        .map(|part| match part.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n - 1),
+            _ => Err(anyhow::anyhow!("Invalid child path '{}': expected positions like 2 or 2.1", path)),
+        })
+        .collect()
+}
+
+/// Children list of the node at `path` (empty path = top level)
+pub fn children_at_mut<'a>(children: &'a mut Vec<TemplateChild>, path: &[usize]) -> Option<&'a mut Vec<TemplateChild>> {
+    match path.split_first() {
+        None => Some(children),
+        Some((first, rest)) => children_at_mut(&mut children.get_mut(*first)?.children, rest),
+    }
+}
+
+impl Template {
+    /// Subtask hierarchy created under tasks made from this template
+    pub fn children(&self) -> Result<Vec<TemplateChild>> {
+        match self.payload.get("children") {
+            Some(value) => serde_json::from_value(value.clone())
+                .with_context(|| format!("Template '{}' has malformed subtasks", self.name)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Store a subtask hierarchy in a template payload
+    pub fn set_children(payload: &mut HashMap<String, serde_json::Value>, children: &[TemplateChild]) -> Result<()> {
+        if children.is_empty() {
+            payload.remove("children");
+        } else {
+            payload.insert("children".to_string(), serde_json::to_value(children)?);
+        }
+        Ok(())
+    }
+
+    /// String value of a payload key
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.payload.get(key).and_then(|v| v.as_str())
+    }
+
+    /// Description pattern; `{desc}` is replaced by the description given on add
+    /// and `{date}` by today's date
+    pub fn description_pattern(&self) -> Option<&str> {
+        self.get_str("description")
+    }
+
+    /// Respawn rule applied to tasks created from this template
+    pub fn respawn(&self) -> Option<&str> {
+        self.get_str("respawn")
+    }
+
+    /// Default parent task
+    pub fn parent_id(&self) -> Option<i64> {
+        self.payload.get("parent_id").and_then(|v| v.as_i64())
+    }
+
+    /// Build a task description from the pattern and the description given on add.
+    /// A given description replaces a pattern without a `{desc}` placeholder.
+    pub fn render_description(&self, description: &str) -> String {
+        match self.description_pattern() {
+            Some(pattern) if pattern.contains("{desc}") || description.is_empty() => {
+                let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+                pattern.replace("{desc}", description).replace("{date}", &today).trim().to_string()
+            }
+            _ => description.to_string(),
+        }
+    }
+
+    /// Resolve a date field: relative expressions are evaluated now,
+    /// legacy absolute `<field>_ts` values are returned as stored
+    pub fn resolve_date(&self, field: &str) -> Result<Option<i64>> {
+        if let Some(expr) = self.get_str(field) {
+            let ts = crate::utils::parse_date_expr(expr)
+                .with_context(|| format!("Template '{}' has an invalid {} expression '{}'", self.name, field, expr))?;
+            return Ok(Some(ts));
+        }
+        Ok(self.payload.get(&format!("{}_ts", field)).and_then(|v| v.as_i64()))
+    }
+}
+
 impl TemplateRepo {
+    /// List all templates ordered by name
+    pub fn list(conn: &Connection) -> Result<Vec<Template>> {
+        let mut stmt = conn.prepare(
+            "SELECT name FROM templates ORDER BY name"
+        )?;
+        let names = stmt.query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut templates = Vec::new();
+        for name in names {
+            if let Some(template) = Self::get_by_name(conn, &name)? {
+                templates.push(template);
+            }
+        }
+        Ok(templates)
+    }
+
+    /// Delete a template. Returns false if it did not exist.
+    pub fn delete(conn: &Connection, name: &str) -> Result<bool> {
+        let deleted = conn.execute("DELETE FROM templates WHERE name = ?1", [name])?;
+        Ok(deleted > 0)
+    }
+
     /// Get a template by name
     pub fn get_by_name(conn: &Connection, name: &str) -> Result<Option<Template>> {
         let mut stmt = conn.prepare(
@@ -71,14 +230,15 @@ impl TemplateRepo {
     }
     
     /// Create a template from task attributes
-    /// Used when creating a task with --template flag
+    /// Used when creating a task with template=<name> for a template that doesn't exist yet.
+    /// Dates are stored as the expressions given (e.g. `+2d`), not as absolute timestamps.
     pub fn create_from_task(
         conn: &Connection,
         name: &str,
         project_id: Option<i64>,
-        due_ts: Option<i64>,
-        scheduled_ts: Option<i64>,
-        wait_ts: Option<i64>,
+        due: Option<&str>,
+        scheduled: Option<&str>,
+        wait: Option<&str>,
         alloc_secs: Option<i64>,
         udas: &HashMap<String, String>,
         tags: &[String],
@@ -88,14 +248,14 @@ impl TemplateRepo {
         if let Some(pid) = project_id {
             payload.insert("project_id".to_string(), serde_json::Value::Number(pid.into()));
         }
-        if let Some(due) = due_ts {
-            payload.insert("due_ts".to_string(), serde_json::Value::Number(due.into()));
+        if let Some(due) = due {
+            payload.insert("due".to_string(), serde_json::Value::String(due.to_string()));
         }
-        if let Some(scheduled) = scheduled_ts {
-            payload.insert("scheduled_ts".to_string(), serde_json::Value::Number(scheduled.into()));
+        if let Some(scheduled) = scheduled {
+            payload.insert("scheduled".to_string(), serde_json::Value::String(scheduled.to_string()));
         }
-        if let Some(wait) = wait_ts {
-            payload.insert("wait_ts".to_string(), serde_json::Value::Number(wait.into()));
+        if let Some(wait) = wait {
+            payload.insert("wait".to_string(), serde_json::Value::String(wait.to_string()));
         }
         if let Some(alloc) = alloc_secs {
             payload.insert("alloc_secs".to_string(), serde_json::Value::Number(alloc.into()));
@@ -114,7 +274,7 @@ impl TemplateRepo {
     }
     
     /// Merge template attributes with task attributes
-    /// Template provides base, task overrides
+    /// Template provides base, task overrides. Relative template dates are evaluated now.
     pub fn merge_attributes(
         template: &Template,
         task_project_id: Option<i64>,
@@ -124,7 +284,7 @@ impl TemplateRepo {
         task_alloc_secs: Option<i64>,
         task_udas: &HashMap<String, String>,
         task_tags: &[String],
-    ) -> (
+    ) -> Result<(
         Option<i64>,
         Option<i64>,
         Option<i64>,
@@ -132,16 +292,13 @@ impl TemplateRepo {
         Option<i64>,
         HashMap<String, String>,
         Vec<String>,
-    ) {
+    )> {
         // Start with template values
         let mut project_id = template.payload.get("project_id")
             .and_then(|v| v.as_i64());
-        let mut due_ts = template.payload.get("due_ts")
-            .and_then(|v| v.as_i64());
-        let mut scheduled_ts = template.payload.get("scheduled_ts")
-            .and_then(|v| v.as_i64());
-        let mut wait_ts = template.payload.get("wait_ts")
-            .and_then(|v| v.as_i64());
+        let mut due_ts = template.resolve_date("due")?;
+        let mut scheduled_ts = template.resolve_date("scheduled")?;
+        let mut wait_ts = template.resolve_date("wait")?;
         let mut alloc_secs = template.payload.get("alloc_secs")
             .and_then(|v| v.as_i64());
         
@@ -200,7 +357,7 @@ impl TemplateRepo {
             }
         }
         
-        (project_id, due_ts, scheduled_ts, wait_ts, alloc_secs, udas, tags)
+        Ok((project_id, due_ts, scheduled_ts, wait_ts, alloc_secs, udas, tags))
     }
 }
 
@@ -249,11 +406,69 @@ mod tests {
                 None, // Task doesn't override alloc_secs
                 &HashMap::new(),
                 &["urgent".to_string()], // Task adds tag
-            );
+            ).unwrap();
         
         assert_eq!(project_id, Some(2)); // Task value
         assert_eq!(alloc_secs, Some(1800)); // Template value
         assert!(tags.contains(&"meeting".to_string())); // From template
         assert!(tags.contains(&"urgent".to_string())); // From task
     }
+
+    #[test]
+    fn test_template_relative_dates_and_pattern() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+
+        TemplateRepo::create_from_task(
+            &conn, "review", None, Some("+2d"), None, None, None, &HashMap::new(), &[],
+        ).unwrap();
+        let template = TemplateRepo::get_by_name(&conn, "review").unwrap().unwrap();
+        assert_eq!(template.get_str("due"), Some("+2d"));
+        assert!(!template.payload.contains_key("due_ts"));
+
+        let expected = crate::utils::parse_date_expr("+2d").unwrap();
+        let (_, due_ts, ..) = TemplateRepo::merge_attributes(
+            &template, None, None, None, None, None, &HashMap::new(), &[],
+        ).unwrap();
+        assert!((due_ts.unwrap() - expected).abs() <= 1);
+
+        let mut payload = template.payload.clone();
+        payload.insert("description".to_string(), serde_json::json!("Review: {desc}"));
+        TemplateRepo::save(&conn, "review", &payload).unwrap();
+        let template = TemplateRepo::get_by_name(&conn, "review").unwrap().unwrap();
+        assert_eq!(template.render_description("PR 42"), "Review: PR 42");
+
+        assert_eq!(TemplateRepo::list(&conn).unwrap().len(), 1);
+        assert!(TemplateRepo::delete(&conn, "review").unwrap());
+        assert!(!TemplateRepo::delete(&conn, "review").unwrap());
+    }
+
+    #[test]
+    fn test_template_children() {
+        let freeze = TemplateChild {
+            description: "Freeze branch".to_string(),
+            due_offset: Some("-2d".to_string()),
+            ..Default::default()
+        };
+        let mut children = vec![freeze.clone()];
+        let nested = children_at_mut(&mut children, &parse_child_path("1").unwrap()).unwrap();
+        nested.push(TemplateChild { description: "Tag RC".to_string(), ..Default::default() });
+        assert!(children_at_mut(&mut children, &[3]).is_none());
+        assert!(parse_child_path("0").is_err());
+        assert_eq!(children[0].count(), 2);
+
+        let mut payload = HashMap::new();
+        Template::set_children(&mut payload, &children).unwrap();
+        let template = Template { name: "release".to_string(), payload, created_ts: 0, modified_ts: 0 };
+        assert_eq!(template.children().unwrap(), children);
+
+        let mut payload = HashMap::new();
+        payload.insert("children".to_string(), serde_json::json!([{ "due_offset": "-2d" }]));
+        let broken = Template { name: "broken".to_string(), payload, created_ts: 0, modified_ts: 0 };
+        assert!(broken.children().unwrap_err().to_string().contains("malformed subtasks"));
+
+        assert_eq!(freeze.resolve_due(Some(1_000_000)).unwrap(), Some(1_000_000 - 2 * 86400));
+        assert_eq!(TemplateChild::parse_offset("+1h30m").unwrap(), 5400);
+        assert_eq!(TemplateChild::parse_offset("3d").unwrap(), 3 * 86400);
+        assert!(TemplateChild::parse_offset("soon").is_err());
+    }
 }
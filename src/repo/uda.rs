@@ -0,0 +1,118 @@
+use rusqlite::{Connection, OptionalExtension};
+use crate::models::{UdaDefinition, UdaType};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+/// Repository for typed UDA definitions
+pub struct UdaRepo;
+
+impl UdaRepo {
+    /// List all definitions ordered by name
+    pub fn list(conn: &Connection) -> Result<Vec<UdaDefinition>> {
+        let mut stmt = conn.prepare(
+            "SELECT name, type, values_json, default_value, label FROM uda_definitions ORDER BY name"
+        )?;
+        let rows = stmt.query_map([], Self::row_to_definition)?;
+
+        let mut definitions = Vec::new();
+        for row in rows {
+            definitions.push(row?);
+        }
+        Ok(definitions)
+    }
+
+    /// Load all definitions keyed by name
+    pub fn load_map(conn: &Connection) -> Result<HashMap<String, UdaDefinition>> {
+        Ok(Self::list(conn)?
+            .into_iter()
+            .map(|def| (def.name.clone(), def))
+            .collect())
+    }
+
+    /// Get a definition by attribute name
+    pub fn get(conn: &Connection, name: &str) -> Result<Option<UdaDefinition>> {
+        let mut stmt = conn.prepare(
+            "SELECT name, type, values_json, default_value, label FROM uda_definitions WHERE name = ?1"
+        )?;
+        let definition = stmt.query_row([name], Self::row_to_definition).optional()?;
+        Ok(definition)
+    }
+
+    /// Create or replace a definition
+    pub fn upsert(conn: &Connection, definition: &UdaDefinition) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let values_json = serde_json::to_string(&definition.values)?;
+        conn.execute(
+            "INSERT INTO uda_definitions (name, type, values_json, default_value, label, created_ts, modified_ts)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+             ON CONFLICT(name) DO UPDATE SET
+               type = excluded.type,
+               values_json = excluded.values_json,
+               default_value = excluded.default_value,
+               label = excluded.label,
+               modified_ts = excluded.modified_ts",
+            rusqlite::params![
+                definition.name,
+                definition.uda_type.as_str(),
+                values_json,
+                definition.default_value,
+                definition.label,
+                now,
+            ],
+        )
+        .with_context(|| format!("Failed to save UDA definition '{}'", definition.name))?;
+        Ok(())
+    }
+
+    /// Delete a definition. Returns false if it did not exist.
+    /// Values already stored on tasks are kept as plain strings.
+    pub fn delete(conn: &Connection, name: &str) -> Result<bool> {
+        let deleted = conn.execute("DELETE FROM uda_definitions WHERE name = ?1", [name])?;
+        Ok(deleted > 0)
+    }
+
+    fn row_to_definition(row: &rusqlite::Row) -> rusqlite::Result<UdaDefinition> {
+        let type_str: String = row.get(1)?;
+        let values_json: String = row.get(2)?;
+        Ok(UdaDefinition {
+            name: row.get(0)?,
+            uda_type: UdaType::parse(&type_str).unwrap_or(UdaType::String),
+            values: serde_json::from_str(&values_json).unwrap_or_default(),
+            default_value: row.get(3)?,
+            label: row.get(4)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DbConnection;
+
+    #[test]
+    fn test_upsert_get_delete() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let mut def = UdaDefinition {
+            name: "severity".to_string(),
+            uda_type: UdaType::Enum,
+            values: vec!["low".to_string(), "high".to_string()],
+            default_value: Some("low".to_string()),
+            label: Some("Sev".to_string()),
+        };
+        UdaRepo::upsert(&conn, &def).unwrap();
+
+        let loaded = UdaRepo::get(&conn, "severity").unwrap().unwrap();
+        assert_eq!(loaded.uda_type, UdaType::Enum);
+        assert_eq!(loaded.values, vec!["low", "high"]);
+        assert_eq!(loaded.default_value.as_deref(), Some("low"));
+
+        def.label = None;
+        UdaRepo::upsert(&conn, &def).unwrap();
+        assert_eq!(UdaRepo::list(&conn).unwrap().len(), 1);
+        assert!(UdaRepo::get(&conn, "severity").unwrap().unwrap().label.is_none());
+
+        assert!(UdaRepo::delete(&conn, "severity").unwrap());
+        assert!(!UdaRepo::delete(&conn, "severity").unwrap());
+        assert!(UdaRepo::load_map(&conn).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,414 @@
+use rusqlite::Connection;
+use crate::models::{TaskEvent, TaskStatus};
+use crate::repo::{EventRepo, TaskRepo, StackRepo, SessionRepo, AnnotationRepo, DependencyRepo};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// A group of events written by one CLI invocation
+#[derive(Debug, Clone)]
+pub struct UndoBatch {
+    pub batch_id: String,
+    pub events: Vec<TaskEvent>,
+}
+
+impl UndoBatch {
+    /// Timestamp of the last event in the batch
+    pub fn ts(&self) -> i64 {
+        self.events.last().map(|e| e.ts).unwrap_or(0)
+    }
+
+    /// Events that undo will revert, newest first
+    pub fn revertible_events(&self) -> Vec<&TaskEvent> {
+        self.events.iter().rev().collect()
+    }
+}
+
+/// Undo repository: reverts event batches by applying their inverse operations
+///
+/// Each CLI invocation records its events under one batch (see `EventRepo::begin_batch`).
+/// Undoing a batch applies the inverse of each event in reverse order. The inverse
+/// operations go through the regular repositories, so they are recorded as events
+/// themselves (under the undo's own batch) and show up in `tatl history`.
+pub struct UndoRepo;
+
+impl UndoRepo {
+    /// Get the last `n` undoable batches, newest first
+    pub fn pending(conn: &Connection, n: usize) -> Result<Vec<UndoBatch>> {
+        let mut batches = Vec::new();
+        for batch_id in EventRepo::list_undoable_batches(conn, n)? {
+            let events = EventRepo::get_by_batch(conn, &batch_id)?;
+            batches.push(UndoBatch { batch_id, events });
+        }
+        Ok(batches)
+    }
+
+    /// Revert the given batches (newest first) in a single transaction
+    pub fn apply(conn: &Connection, batches: &[UndoBatch]) -> Result<()> {
+        let undo_batch_id = EventRepo::current_batch()
+            .unwrap_or_else(EventRepo::begin_batch);
+
+        let tx = conn.unchecked_transaction()?;
+        for batch in batches {
+            let sessions_started: HashSet<i64> = batch.events.iter()
+                .filter(|e| e.event_type == "session_started")
+                .filter_map(|e| e.payload_i64("session_id"))
+                .collect();
+            for event in batch.revertible_events() {
+                Self::revert_event(&tx, event, &sessions_started)?;
+            }
+            EventRepo::mark_batch_undone(&tx, &batch.batch_id, &undo_batch_id)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Apply the inverse of a single event
+    fn revert_event(conn: &Connection, event: &TaskEvent, sessions_started: &HashSet<i64>) -> Result<()> {
+        let task_id = event.task_id;
+        // A deleted task only comes back through its status change; other changes
+        // to it are left alone
+        match TaskRepo::get_by_id_with_deleted(conn, task_id)? {
+            None => return Ok(()),
+            Some(task) if task.status == TaskStatus::Deleted && event.event_type != "status_changed" => return Ok(()),
+            Some(_) => {}
+        }
+
+        match event.event_type.as_str() {
+            "created" => {
+                // Keep the task as a tombstone so its history survives
+                TaskRepo::mark_deleted(conn, task_id)?;
+            }
+            "modified" => {
+                let field = event.payload_str("field").unwrap_or("");
+                Self::restore_field(conn, task_id, field, &event.payload["old_value"])?;
+            }
+            "status_changed" => {
+                let old_status = event.payload_str("old_status")
+                    .and_then(TaskStatus::from_str)
+                    .ok_or_else(|| anyhow::anyhow!("Event {} has no valid old status", event.id))?;
+                TaskRepo::set_status(conn, task_id, old_status)?;
+            }
+            "tag_added" | "tag_removed" => {
+                let tag = event.payload_str("tag").unwrap_or("").to_string();
+                let (add, remove) = if event.event_type == "tag_added" {
+                    (vec![], vec![tag])
+                } else {
+                    (vec![tag], vec![])
+                };
+                TaskRepo::modify(
+                    conn, task_id, None, None, None, None, None, None, None, None,
+                    &HashMap::new(), &[], &add, &remove, None,
+                )?;
+            }
+            "annotation_added" => {
+                let annotation_id = event.payload_i64("annotation_id").unwrap_or(0);
+                if AnnotationRepo::get_by_id(conn, annotation_id)?.is_some() {
+                    AnnotationRepo::delete_for_task(conn, task_id, annotation_id)?;
+                }
+            }
+            "annotation_deleted" => {
+                if let Some(note) = event.payload_str("note") {
+                    let session_id = match event.payload_i64("session_id") {
+                        Some(id) if SessionRepo::get_by_id(conn, id)?.is_some() => Some(id),
+                        _ => None,
+                    };
+                    AnnotationRepo::create(conn, task_id, note.to_string(), session_id)?;
+                }
+            }
+            "stack_added" => {
+                let stack_id = event.payload_i64("stack_id").unwrap_or(0);
+                match event.payload_i64("old_position") {
+                    Some(old_position) => StackRepo::move_to_position(conn, stack_id, task_id, old_position as i32)?,
+                    None => StackRepo::remove_task(conn, stack_id, task_id)?,
+                }
+            }
+            "stack_removed" => {
+                if let Some(position) = event.payload_i64("position") {
+                    let stack_id = event.payload_i64("stack_id").unwrap_or(0);
+                    StackRepo::move_to_position(conn, stack_id, task_id, position as i32)?;
+                }
+            }
+            "dependency_added" => {
+                if let Some(depends_on_id) = event.payload_i64("depends_on") {
+                    DependencyRepo::remove(conn, task_id, depends_on_id)?;
+                }
+            }
+            "dependency_removed" => {
+                if let Some(depends_on_id) = event.payload_i64("depends_on") {
+                    if TaskRepo::get_by_id(conn, depends_on_id)?.is_some() {
+                        DependencyRepo::add(conn, task_id, depends_on_id)?;
+                    }
+                }
+            }
+            "session_started" => {
+                if let Some(session_id) = event.payload_i64("session_id") {
+                    SessionRepo::delete(conn, session_id)?;
+                }
+            }
+            "session_ended" => {
+                let session_id = event.payload_i64("session_id").unwrap_or(0);
+                // Sessions created in the same batch are deleted by their session_started inverse
+                if !sessions_started.contains(&session_id) {
+                    if let Some(session) = SessionRepo::get_by_id(conn, session_id)? {
+                        Self::ensure_no_open_session(conn, session_id, &session)?;
+                        SessionRepo::modify_end_time(conn, session_id, None)?;
+                    }
+                }
+            }
+            "session_modified" => {
+                let session_id = event.payload_i64("session_id").unwrap_or(0);
+                if let Some(session) = SessionRepo::get_by_id(conn, session_id)? {
+                    let old_value = &event.payload["old_value"];
+                    match event.payload_str("field").unwrap_or("") {
+                        "start_ts" => if let Some(start_ts) = old_value.as_i64() {
+                            SessionRepo::modify_start_time(conn, session_id, start_ts)?;
+                        },
+                        "end_ts" => {
+                            if old_value.is_null() {
+                                Self::ensure_no_open_session(conn, session_id, &session)?;
+                            }
+                            SessionRepo::modify_end_time(conn, session_id, old_value.as_i64())?;
+                        }
+                        "note" => SessionRepo::set_note(conn, session_id, old_value.as_str())?,
+                        "tags" => {
+                            let old_tags: Vec<String> = serde_json::from_value(old_value.clone()).unwrap_or_default();
+                            SessionRepo::remove_tags(conn, session_id, &SessionRepo::get_tags(conn, session_id)?)?;
+                            SessionRepo::add_tags(conn, session_id, &old_tags)?;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            "session_deleted" => {
+                let session_id = event.payload_i64("session_id").unwrap_or(0);
+                if SessionRepo::get_by_id(conn, session_id)?.is_none() {
+                    let end_ts = event.payload_i64("end_ts");
+                    if end_ts.is_none() {
+                        if let Some(open) = SessionRepo::get_open(conn)? {
+                            anyhow::bail!(
+                                "Cannot restore session {}: a session is already running for task {}",
+                                session_id, open.task_id
+                            );
+                        }
+                    }
+                    let tags: Vec<String> = serde_json::from_value(event.payload["tags"].clone()).unwrap_or_default();
+                    SessionRepo::restore(
+                        conn, task_id, session_id,
+                        event.payload_i64("start_ts").unwrap_or(event.ts),
+                        end_ts, event.payload_str("note"), &tags,
+                    )?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Fail if a session other than `session` is running, since reopening it
+    /// would leave two open sessions
+    fn ensure_no_open_session(conn: &Connection, session_id: i64, session: &crate::models::Session) -> Result<()> {
+        if let Some(open) = SessionRepo::get_open(conn)? {
+            if open.id != session.id {
+                anyhow::bail!(
+                    "Cannot reopen session {}: a session is already running for task {}",
+                    session_id, open.task_id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Restore a modified field to its old value
+    fn restore_field(conn: &Connection, task_id: i64, field: &str, old_value: &Value) -> Result<()> {
+        let as_int = || Some(old_value.as_i64());
+        let as_string = || Some(old_value.as_str().map(|s| s.to_string()));
+        let empty = HashMap::new();
+
+        if let Some(key) = field.strip_prefix("uda.") {
+            return match old_value.as_str() {
+                Some(value) => {
+                    let mut udas = HashMap::new();
+                    udas.insert(key.to_string(), value.to_string());
+                    TaskRepo::modify(conn, task_id, None, None, None, None, None, None, None, None, &udas, &[], &[], &[], None)
+                }
+                None => TaskRepo::modify(conn, task_id, None, None, None, None, None, None, None, None, &empty, &[key.to_string()], &[], &[], None),
+            };
+        }
+
+        match field {
+            "description" => TaskRepo::modify(conn, task_id, old_value.as_str().map(|s| s.to_string()), None, None, None, None, None, None, None, &empty, &[], &[], &[], None),
+            "project_id" => TaskRepo::modify(conn, task_id, None, as_int(), None, None, None, None, None, None, &empty, &[], &[], &[], None),
+            "due_ts" => TaskRepo::modify(conn, task_id, None, None, as_int(), None, None, None, None, None, &empty, &[], &[], &[], None),
+            "scheduled_ts" => TaskRepo::modify(conn, task_id, None, None, None, as_int(), None, None, None, None, &empty, &[], &[], &[], None),
+            "wait_ts" => TaskRepo::modify(conn, task_id, None, None, None, None, as_int(), None, None, None, &empty, &[], &[], &[], None),
+            "alloc_secs" => TaskRepo::modify(conn, task_id, None, None, None, None, None, as_int(), None, None, &empty, &[], &[], &[], None),
+            "template" => TaskRepo::modify(conn, task_id, None, None, None, None, None, None, as_string(), None, &empty, &[], &[], &[], None),
+            "respawn" => TaskRepo::modify(conn, task_id, None, None, None, None, None, None, None, as_string(), &empty, &[], &[], &[], None),
+            "parent_id" => TaskRepo::modify(conn, task_id, None, None, None, None, None, None, None, None, &empty, &[], &[], &[], as_int()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DbConnection;
+    use crate::repo::ProjectRepo;
+
+    #[test]
+    fn test_undo_modify() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        EventRepo::begin_batch();
+        let project = ProjectRepo::create(&conn, "work").unwrap();
+        let task_id = TaskRepo::create(&conn, "Task", None).unwrap().id.unwrap();
+
+        EventRepo::begin_batch();
+        let mut udas = HashMap::new();
+        udas.insert("size".to_string(), "large".to_string());
+        TaskRepo::modify(&conn, task_id, Some("Renamed".to_string()), Some(project.id), Some(Some(1000)),
+            None, None, None, None, None, &udas, &[], &["urgent".to_string()], &[], None).unwrap();
+
+        EventRepo::begin_batch();
+        let batches = UndoRepo::pending(&conn, 1).unwrap();
+        assert_eq!(batches.len(), 1);
+        UndoRepo::apply(&conn, &batches).unwrap();
+
+        let task = TaskRepo::get_by_id(&conn, task_id).unwrap().unwrap();
+        assert_eq!(task.description, "Task");
+        assert_eq!(task.project_id, None);
+        assert_eq!(task.due_ts, None);
+        assert!(task.udas.is_empty());
+        assert!(TaskRepo::get_tags(&conn, task_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_undo_close_restores_status_and_queue() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let stack_id = StackRepo::get_or_create_default(&conn).unwrap().id.unwrap();
+        EventRepo::begin_batch();
+        let task1 = TaskRepo::create(&conn, "Task 1", None).unwrap().id.unwrap();
+        let task2 = TaskRepo::create(&conn, "Task 2", None).unwrap().id.unwrap();
+        StackRepo::enqueue(&conn, stack_id, task1).unwrap();
+        StackRepo::enqueue(&conn, stack_id, task2).unwrap();
+
+        EventRepo::begin_batch();
+        TaskRepo::close(&conn, task1).unwrap();
+        StackRepo::drop(&conn, stack_id, 0).unwrap();
+
+        EventRepo::begin_batch();
+        let batches = UndoRepo::pending(&conn, 1).unwrap();
+        UndoRepo::apply(&conn, &batches).unwrap();
+
+        let task = TaskRepo::get_by_id(&conn, task1).unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Open);
+        let order: Vec<i64> = StackRepo::get_items(&conn, stack_id).unwrap().iter().map(|i| i.task_id).collect();
+        assert_eq!(order, vec![task1, task2]);
+    }
+
+    #[test]
+    fn test_undo_create_deletes_task() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        EventRepo::begin_batch();
+        let task_id = TaskRepo::create_full(&conn, "Task", None, None, None, None, None, None, None,
+            &HashMap::new(), &["tag".to_string()], None).unwrap().id.unwrap();
+
+        EventRepo::begin_batch();
+        let batches = UndoRepo::pending(&conn, 1).unwrap();
+        assert_eq!(batches[0].revertible_events().len(), 2);
+        UndoRepo::apply(&conn, &batches).unwrap();
+        assert!(TaskRepo::get_by_id(&conn, task_id).unwrap().is_none());
+
+        // The task is kept as a tombstone with its history
+        let task = TaskRepo::get_by_id_with_deleted(&conn, task_id).unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Deleted);
+        assert!(EventRepo::get_by_task(&conn, task_id).unwrap().iter().any(|e| e.event_type == "created"));
+    }
+
+    #[test]
+    fn test_undo_delete_restores_task() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        let stack_id = StackRepo::get_or_create_default(&conn).unwrap().id.unwrap();
+        EventRepo::begin_batch();
+        let parent = TaskRepo::create(&conn, "Parent", None).unwrap().id.unwrap();
+        let child = TaskRepo::create_full(&conn, "Child", None, None, None, None, None, None, None,
+            &HashMap::new(), &[], Some(parent)).unwrap().id.unwrap();
+        StackRepo::enqueue(&conn, stack_id, parent).unwrap();
+        SessionRepo::create(&conn, parent, 1000).unwrap();
+
+        EventRepo::begin_batch();
+        TaskRepo::delete(&conn, parent).unwrap();
+        assert!(TaskRepo::get_by_id(&conn, parent).unwrap().is_none());
+        assert_eq!(TaskRepo::get_by_id(&conn, child).unwrap().unwrap().parent_id, None);
+        assert!(SessionRepo::get_open(&conn).unwrap().is_none());
+
+        EventRepo::begin_batch();
+        UndoRepo::apply(&conn, &UndoRepo::pending(&conn, 1).unwrap()).unwrap();
+        assert_eq!(TaskRepo::get_by_id(&conn, parent).unwrap().unwrap().status, TaskStatus::Open);
+        assert_eq!(TaskRepo::get_by_id(&conn, child).unwrap().unwrap().parent_id, Some(parent));
+        assert!(StackRepo::is_queued(&conn, parent).unwrap());
+        assert_eq!(SessionRepo::get_open(&conn).unwrap().unwrap().task_id, parent);
+    }
+
+    #[test]
+    fn test_undo_session_edits() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        EventRepo::begin_batch();
+        let task_id = TaskRepo::create(&conn, "Task", None).unwrap().id.unwrap();
+        let session_id = SessionRepo::create_closed(&conn, task_id, 1000, 2000).unwrap().id.unwrap();
+        SessionRepo::add_tags(&conn, session_id, &["review".to_string()]).unwrap();
+
+        EventRepo::begin_batch();
+        SessionRepo::update_times(&conn, session_id, 1500, Some(2500)).unwrap();
+        SessionRepo::set_note(&conn, session_id, Some("Notes")).unwrap();
+        SessionRepo::remove_tags(&conn, session_id, &["review".to_string()]).unwrap();
+        EventRepo::begin_batch();
+        SessionRepo::delete(&conn, session_id).unwrap();
+
+        EventRepo::begin_batch();
+        UndoRepo::apply(&conn, &UndoRepo::pending(&conn, 2).unwrap()).unwrap();
+        let session = SessionRepo::get_by_id(&conn, session_id).unwrap().unwrap();
+        assert_eq!((session.start_ts, session.end_ts, session.note), (1000, Some(2000), None));
+        assert_eq!(SessionRepo::get_tags(&conn, session_id).unwrap(), vec!["review".to_string()]);
+    }
+
+    #[test]
+    fn test_undo_skips_undone_batches() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        EventRepo::begin_batch();
+        let task_id = TaskRepo::create(&conn, "Task", None).unwrap().id.unwrap();
+        EventRepo::begin_batch();
+        TaskRepo::modify(&conn, task_id, Some("Second".to_string()), None, None, None, None, None, None, None,
+            &HashMap::new(), &[], &[], &[], None).unwrap();
+        EventRepo::begin_batch();
+        TaskRepo::modify(&conn, task_id, Some("Third".to_string()), None, None, None, None, None, None, None,
+            &HashMap::new(), &[], &[], &[], None).unwrap();
+
+        // First undo reverts "Third"
+        EventRepo::begin_batch();
+        UndoRepo::apply(&conn, &UndoRepo::pending(&conn, 1).unwrap()).unwrap();
+        assert_eq!(TaskRepo::get_by_id(&conn, task_id).unwrap().unwrap().description, "Second");
+
+        // Second undo reverts "Second", not the first undo
+        EventRepo::begin_batch();
+        UndoRepo::apply(&conn, &UndoRepo::pending(&conn, 1).unwrap()).unwrap();
+        assert_eq!(TaskRepo::get_by_id(&conn, task_id).unwrap().unwrap().description, "Task");
+    }
+
+    #[test]
+    fn test_undo_session_stop_reopens_session() {
+        let conn = DbConnection::connect_in_memory().unwrap();
+        EventRepo::begin_batch();
+        let task_id = TaskRepo::create(&conn, "Task", None).unwrap().id.unwrap();
+        let session = SessionRepo::create(&conn, task_id, 1000).unwrap();
+
+        EventRepo::begin_batch();
+        SessionRepo::close_open(&conn, 2000).unwrap();
+
+        EventRepo::begin_batch();
+        UndoRepo::apply(&conn, &UndoRepo::pending(&conn, 1).unwrap()).unwrap();
+        let open = SessionRepo::get_open(&conn).unwrap().unwrap();
+        assert_eq!(open.id, session.id);
+    }
+}
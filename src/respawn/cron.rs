@@ -0,0 +1,214 @@
+//! Cron-style respawn schedules
+//!
+//! Supports the standard five fields (minute hour day-of-month month day-of-week)
+//! with `*`, values, ranges (`1-5`), steps (`*/15`, `1-10/2`), lists (`1,15`) and
+//! weekday names. Times are local. As in cron, when both day-of-month and
+//! day-of-week are restricted a day matches if either does.
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+
+/// How far ahead to search for a matching day (covers Feb 29 across a skipped
+/// century leap year, e.g. 2096 -> 2104)
+const MAX_SEARCH_DAYS: i64 = 366 * 9;
+
+/// Parsed cron expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    pub source: String,
+    pub minutes: Vec<u32>,
+    pub hours: Vec<u32>,
+    pub days_of_month: Vec<u32>,
+    pub months: Vec<u32>,
+    /// 0=Monday .. 6=Sunday, matching the rest of the respawn module
+    pub days_of_week: Vec<u32>,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression, e.g. `0 9 * * mon-fri`
+    pub fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            anyhow::bail!(
+                "Invalid cron expression '{}': expected 5 fields (minute hour day month weekday)",
+                expr
+            );
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, "minute")?;
+        let hours = parse_field(fields[1], 0, 23, "hour")?;
+        let days_of_month = parse_field(fields[2], 1, 31, "day of month")?;
+        let months = parse_field(fields[3], 1, 12, "month")?;
+        // Cron weekdays are 0/7=Sunday; store them as 0=Monday
+        let mut days_of_week: Vec<u32> = parse_field(fields[4], 0, 7, "weekday")?
+            .into_iter()
+            .map(|d| (d + 6) % 7)
+            .collect();
+        days_of_week.sort();
+        days_of_week.dedup();
+
+        Ok(CronSchedule {
+            source: fields.join(" "),
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    /// Whether the schedule fires on a given date (ignoring the time fields)
+    pub fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+        let dom = self.days_of_month.contains(&date.day());
+        let dow = self.days_of_week.contains(&date.weekday().num_days_from_monday());
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// First matching local time strictly after `from_ts`
+    pub fn next_after(&self, from_ts: i64) -> Option<i64> {
+        let from = Local.timestamp_opt(from_ts, 0).single()?;
+        let start = from.date_naive();
+
+        for offset in 0..MAX_SEARCH_DAYS {
+            let date = start + Duration::days(offset);
+            if !self.matches_date(date) {
+                continue;
+            }
+            for &hour in &self.hours {
+                for &minute in &self.minutes {
+                    let Some(time) = NaiveTime::from_hms_opt(hour, minute, 0) else { continue };
+                    // Times inside a DST gap do not exist and are skipped
+                    let Some(dt) = Local.from_local_datetime(&date.and_time(time)).earliest() else { continue };
+                    if dt.timestamp() > from_ts {
+                        return Some(dt.timestamp());
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Parse one cron field into its sorted list of allowed values
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<Vec<u32>> {
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse()
+                    .with_context(|| format!("Invalid step '{}' in cron {} field", step, name))?;
+                if step == 0 {
+                    anyhow::bail!("Step must be greater than 0 in cron {} field", name);
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, name)?, parse_value(b, name)?)
+        } else {
+            let value = parse_value(range, name)?;
+            // "5/10" means every 10 starting at 5
+            (value, if step > 1 { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            anyhow::bail!("Cron {} field '{}' is out of range ({}-{})", name, part, min, max);
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    values.sort();
+    values.dedup();
+    Ok(values)
+}
+
+fn parse_value(value: &str, name: &str) -> Result<u32> {
+    if let Ok(n) = value.parse::<u32>() {
+        return Ok(n);
+    }
+    // Weekday names, as cron numbers (0=Sunday)
+    let weekday = match value {
+        "sun" | "sunday" => 0,
+        "mon" | "monday" => 1,
+        "tue" | "tuesday" => 2,
+        "wed" | "wednesday" => 3,
+        "thu" | "thursday" => 4,
+        "fri" | "friday" => 5,
+        "sat" | "saturday" => 6,
+        _ => anyhow::bail!("Invalid value '{}' in cron {} field", value, name),
+    };
+    if name != "weekday" {
+        anyhow::bail!("Invalid value '{}' in cron {} field", value, name);
+    }
+    Ok(weekday)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDateTime, Timelike, Weekday};
+
+    fn local_ts(s: &str) -> i64 {
+        let ndt = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&ndt).earliest().unwrap().timestamp()
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let cron = CronSchedule::parse("*/15 9-17 * * mon-fri").unwrap();
+        assert_eq!(cron.minutes, vec![0, 15, 30, 45]);
+        assert_eq!(cron.hours, (9..=17).collect::<Vec<_>>());
+        assert_eq!(cron.days_of_week, vec![0, 1, 2, 3, 4]);
+
+        // 0 and 7 are both Sunday
+        let cron = CronSchedule::parse("0 0 * * 0,7").unwrap();
+        assert_eq!(cron.days_of_week, vec![6]);
+
+        assert!(CronSchedule::parse("0 9 * *").is_err());
+        assert!(CronSchedule::parse("60 9 * * *").is_err());
+        assert!(CronSchedule::parse("0 9 32 * *").is_err());
+        assert!(CronSchedule::parse("0 9 * jan *").is_err());
+        assert!(CronSchedule::parse("*/0 9 * * *").is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        // Weekdays at 09:00: Friday evening -> Monday morning
+        let cron = CronSchedule::parse("0 9 * * 1-5").unwrap();
+        let next = cron.next_after(local_ts("2026-01-23 18:00")).unwrap();
+        let dt = Local.timestamp_opt(next, 0).unwrap();
+        assert_eq!(dt.weekday(), Weekday::Mon);
+        assert_eq!((dt.day(), dt.hour(), dt.minute()), (26, 9, 0));
+
+        // Later the same day
+        let cron = CronSchedule::parse("30 9,17 * * *").unwrap();
+        let next = cron.next_after(local_ts("2026-01-23 10:00")).unwrap();
+        assert_eq!(next, local_ts("2026-01-23 17:30"));
+
+        // Day-of-month OR day-of-week when both are restricted
+        let cron = CronSchedule::parse("0 0 1 * fri").unwrap();
+        let next = cron.next_after(local_ts("2026-01-26 12:00")).unwrap();
+        assert_eq!(next, local_ts("2026-01-30 00:00"));
+
+        // Feb 29 skips the non-leap century year 2100
+        let cron = CronSchedule::parse("0 12 29 2 *").unwrap();
+        let next = cron.next_after(local_ts("2096-03-01 00:00")).unwrap();
+        assert_eq!(next, local_ts("2104-02-29 12:00"));
+    }
+}
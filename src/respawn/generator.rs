@@ -2,13 +2,17 @@
 //!
 //! When a task with a respawn rule is finished or closed, this module
 //! calculates the next due date and creates a new task instance.
+//! Scheduled and wait dates keep their offset from the due date.
+//! Rules with `until=` or `count=` stop respawning once the end condition is met,
+//! and `carry=` copies pinned annotations, subtasks and externals forward.
 
-use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc, Weekday};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Timelike, Utc, Weekday};
 use rusqlite::Connection;
 use anyhow::Result;
-use crate::respawn::parser::{RespawnRule, RespawnPattern};
-use crate::repo::TaskRepo;
+use crate::respawn::parser::{RespawnAnchor, RespawnRule, RespawnPattern};
+use crate::repo::{AnnotationRepo, EventRepo, ExternalRepo, TaskRepo};
 use crate::models::Task;
+use crate::utils::{Calendar, Period};
 
 /// Calculate the next occurrence timestamp from a given completion time
 ///
@@ -20,6 +24,14 @@ use crate::models::Task;
 /// # Returns
 /// The next occurrence timestamp, or None if invalid
 pub fn next_occurrence(rule: &RespawnRule, from_ts: i64, original_due_ts: Option<i64>) -> Option<i64> {
+    // Cron schedules carry their own time of day
+    if let RespawnPattern::Cron(schedule) = &rule.pattern {
+        return schedule.next_after(from_ts);
+    }
+    if rule.pattern == RespawnPattern::Weekly {
+        return next_weekly(from_ts, original_due_ts);
+    }
+
     let from_dt = DateTime::<Utc>::from_timestamp(from_ts, 0)?;
     
     // Extract time-of-day from original due date, or use midnight
@@ -33,13 +45,7 @@ pub fn next_occurrence(rule: &RespawnRule, from_ts: i64, original_due_ts: Option
     let next_date = match &rule.pattern {
         RespawnPattern::Daily => {
             // Next day at the same time
-            let next = from_dt.date_naive() + Duration::days(1);
-            next
-        }
-        RespawnPattern::Weekly => {
-            // Same weekday next week
-            let next = from_dt.date_naive() + Duration::weeks(1);
-            next
+            from_dt.date_naive() + Duration::days(1)
         }
         RespawnPattern::Monthly => {
             // Same day next month
@@ -50,12 +56,10 @@ pub fn next_occurrence(rule: &RespawnRule, from_ts: i64, original_due_ts: Option
             next_year_same_date(from_dt.date_naive())
         }
         RespawnPattern::EveryDays(n) => {
-            let next = from_dt.date_naive() + Duration::days(*n as i64);
-            next
+            from_dt.date_naive() + Duration::days(*n as i64)
         }
         RespawnPattern::EveryWeeks(n) => {
-            let next = from_dt.date_naive() + Duration::weeks(*n as i64);
-            next
+            from_dt.date_naive() + Duration::weeks(*n as i64)
         }
         RespawnPattern::EveryMonths(n) => {
             add_months(from_dt.date_naive(), *n)
@@ -75,6 +79,45 @@ pub fn next_occurrence(rule: &RespawnRule, from_ts: i64, original_due_ts: Option
             // Find the next Nth weekday of a month
             next_nth_weekday(from_dt.date_naive(), *nth, *weekday)
         }
+        RespawnPattern::NthWeekdays { nths, weekday } => {
+            next_in_months(from_dt.date_naive(), |year, month| {
+                nths.iter()
+                    .filter_map(|nth| nth_weekday_of_month(year, month, *nth, to_weekday(*weekday)))
+                    .collect()
+            })
+        }
+        RespawnPattern::LastWeekday(weekday) => {
+            next_in_months(from_dt.date_naive(), |year, month| {
+                last_weekday_of_month(year, month, to_weekday(*weekday)).into_iter().collect()
+            })
+        }
+        RespawnPattern::WeekdayAfterDay { nth, weekday, day } => {
+            next_in_months(from_dt.date_naive(), |year, month| {
+                weekday_after_day(year, month, *nth, to_weekday(*weekday), *day).into_iter().collect()
+            })
+        }
+        RespawnPattern::LastDayOfMonth => {
+            next_in_months(from_dt.date_naive(), |year, month| {
+                NaiveDate::from_ymd_opt(year, month, days_in_month(year, month)).into_iter().collect()
+            })
+        }
+        RespawnPattern::BusinessDay(nth) => {
+            let calendar = Calendar::current();
+            next_in_months(from_dt.date_naive(), |year, month| {
+                business_day_of_month(&calendar, year, month, *nth).into_iter().collect()
+            })
+        }
+        RespawnPattern::QuarterlyDay(day) => {
+            next_in_months(from_dt.date_naive(), |year, month| {
+                if month % 3 != 1 {
+                    return Vec::new();
+                }
+                NaiveDate::from_ymd_opt(year, month, (*day).min(days_in_month(year, month)))
+                    .into_iter()
+                    .collect()
+            })
+        }
+        RespawnPattern::Cron(_) | RespawnPattern::Weekly => unreachable!("returned early"),
     };
     
     // Combine date with preserved time
@@ -84,10 +127,131 @@ pub fn next_occurrence(rule: &RespawnRule, from_ts: i64, original_due_ts: Option
     Some(next_utc.timestamp())
 }
 
+/// Next due date for a respawned task
+///
+/// Completion-anchored rules count from `completion_ts`. Due-anchored rules count from
+/// the previous due date and skip occurrences that are already past at completion, so
+/// the schedule stays fixed however early or late the task was done.
+pub fn next_due(rule: &RespawnRule, completion_ts: i64, due_ts: Option<i64>) -> Option<i64> {
+    let Some(due_ts) = due_ts.filter(|_| rule.anchor == RespawnAnchor::Due) else {
+        return next_occurrence(rule, completion_ts, due_ts);
+    };
+
+    let mut next = next_occurrence(rule, due_ts, Some(due_ts))?;
+    // Bounded so a malformed rule can never loop forever
+    for _ in 0..10_000 {
+        if next > completion_ts {
+            break;
+        }
+        next = next_occurrence(rule, next, Some(due_ts))?;
+    }
+    Some(next)
+}
+
+/// Whether an occurrence at `ts` falls after the rule's `until` date
+fn past_until(rule: &RespawnRule, ts: i64) -> bool {
+    let Some(until) = rule.until else { return false };
+    Local.timestamp_opt(ts, 0).single().is_some_and(|dt| dt.date_naive() > until)
+}
+
+/// Occurrences left after the current instance under the rule's `count`, or None
+/// if the rule has no count
+///
+/// `count` covers the whole respawn chain ending at `task_id`: every generated
+/// instance and every skipped occurrence uses one up.
+pub fn remaining_occurrences(conn: &Connection, rule: &RespawnRule, task_id: i64) -> Result<Option<u32>> {
+    let Some(count) = rule.count else { return Ok(None) };
+    let chain = TaskRepo::get_respawn_chain(conn, task_id)?;
+    let used = chain.len() as i64 + EventRepo::count_skipped(conn, &chain)?;
+    Ok(Some((count as i64 - used).max(0) as u32))
+}
+
+/// Check a rule's end conditions for another occurrence due at `next_due_ts`
+pub fn rule_allows(conn: &Connection, rule: &RespawnRule, task_id: i64, next_due_ts: Option<i64>) -> Result<bool> {
+    if next_due_ts.is_some_and(|next| past_until(rule, next)) {
+        return Ok(false);
+    }
+    Ok(remaining_occurrences(conn, rule, task_id)? != Some(0))
+}
+
+/// Project up to `n` occurrences after `from_ts`, as if each instance were completed
+/// on its due date. Stops at the rule's `until` date; `count` is left to the caller
+/// since it depends on the task's respawn chain.
+///
+/// # Arguments
+/// * `rule` - The respawn rule
+/// * `from_ts` - Occurrences strictly after this timestamp are returned
+/// * `time_ref_ts` - Timestamp whose time of day the occurrences keep (midnight if None)
+/// * `n` - Maximum number of occurrences
+pub fn project_occurrences(rule: &RespawnRule, from_ts: i64, time_ref_ts: Option<i64>, n: usize) -> Vec<i64> {
+    let mut occurrences = Vec::new();
+    let mut current = from_ts;
+    while occurrences.len() < n {
+        match next_occurrence(rule, current, time_ref_ts) {
+            Some(next) if next > current && !past_until(rule, next) => {
+                occurrences.push(next);
+                current = next;
+            }
+            _ => break,
+        }
+    }
+    occurrences
+}
+
+/// Move an open respawning task to its next occurrence without closing it
+///
+/// The next occurrence is counted from the current due date (or from `now` without
+/// one), and scheduled and wait dates move by the same amount. A `skipped` event is
+/// recorded so skips can be told apart from completions.
+///
+/// # Returns
+/// The new due timestamp, or None if the rule has no further occurrences
+pub fn skip_occurrence(conn: &Connection, task: &Task, now: i64) -> Result<Option<i64>> {
+    let task_id = task.id.ok_or_else(|| anyhow::anyhow!("Task has no ID"))?;
+    let respawn_str = match &task.respawn {
+        Some(s) if !s.is_empty() => s,
+        _ => anyhow::bail!("Task {} has no respawn rule", task_id),
+    };
+    let rule = RespawnRule::parse(respawn_str)?;
+
+    let reference_ts = task.due_ts.unwrap_or(now);
+    let next = next_occurrence(&rule, reference_ts, task.due_ts)
+        .ok_or_else(|| anyhow::anyhow!("Could not compute the next occurrence of '{}'", respawn_str))?;
+    if !rule_allows(conn, &rule, task_id, Some(next))? {
+        return Ok(None);
+    }
+
+    let shift = |ts: Option<i64>| ts.map(|ts| Some(ts + (next - reference_ts)));
+    TaskRepo::modify(
+        conn,
+        task_id,
+        None,
+        None,
+        Some(Some(next)),
+        shift(task.scheduled_ts),
+        shift(task.wait_ts),
+        None,
+        None,
+        None,
+        &std::collections::HashMap::new(),
+        &[],
+        &[],
+        &[],
+        None,
+    )?;
+    EventRepo::record_skipped(conn, task_id, task.due_ts, next)?;
+
+    Ok(Some(next))
+}
+
 /// Respawn a task after completion
 ///
 /// Creates a new task instance with the respawn rule, updated due date,
-/// and all other attributes carried forward.
+/// and all other attributes carried forward. Scheduled and wait dates move by
+/// the same amount as the due date (or, without a due date, keep their offset
+/// from the completion time). The rule's `carry=` option also copies pinned
+/// annotations, recreates subtasks as open tasks with dates moved the same way,
+/// and re-sends to the recipient the task was last sent to.
 ///
 /// # Arguments
 /// * `conn` - Database connection
@@ -95,7 +259,8 @@ pub fn next_occurrence(rule: &RespawnRule, from_ts: i64, original_due_ts: Option
 /// * `completion_ts` - When the task was completed
 ///
 /// # Returns
-/// The new task ID if respawned, or None if the task has no respawn rule
+/// The new task ID if respawned, or None if the task has no respawn rule or the
+/// rule's end condition has been reached
 pub fn respawn_task(conn: &Connection, task: &Task, completion_ts: i64) -> Result<Option<i64>> {
     // Check if task has a respawn rule
     let respawn_str = match &task.respawn {
@@ -107,10 +272,19 @@ pub fn respawn_task(conn: &Connection, task: &Task, completion_ts: i64) -> Resul
     let rule = RespawnRule::parse(respawn_str)?;
     
     // Calculate next due date
-    let next_due_ts = next_occurrence(&rule, completion_ts, task.due_ts);
+    let next_due_ts = next_due(&rule, completion_ts, task.due_ts);
+
+    let task_id = task.id.ok_or_else(|| anyhow::anyhow!("Task has no ID"))?;
+    if !rule_allows(conn, &rule, task_id, next_due_ts)? {
+        return Ok(None);
+    }
+
+    // Keep scheduled and wait at the same offset from the due date
+    let reference_ts = task.due_ts.unwrap_or(completion_ts);
+    let delta = next_due_ts.map(|next| next - reference_ts).unwrap_or(0);
+    let shift = |ts: Option<i64>| ts.map(|ts| ts + delta);
     
     // Get task tags
-    let task_id = task.id.ok_or_else(|| anyhow::anyhow!("Task has no ID"))?;
     let tags = TaskRepo::get_tags(conn, task_id)?;
     
     // Create new task instance with carried-forward attributes
@@ -119,8 +293,8 @@ pub fn respawn_task(conn: &Connection, task: &Task, completion_ts: i64) -> Resul
         &task.description,
         task.project_id,
         next_due_ts,
-        task.scheduled_ts, // Keep scheduled_ts? Could also recalculate
-        task.wait_ts,      // Keep wait_ts? Could also clear it
+        shift(task.scheduled_ts),
+        shift(task.wait_ts),
         task.alloc_secs,
         task.template.clone(),
         Some(respawn_str.clone()), // Carry respawn rule forward
@@ -128,8 +302,27 @@ pub fn respawn_task(conn: &Connection, task: &Task, completion_ts: i64) -> Resul
         &tags,
         task.parent_id, // Carry parent forward
     )?;
+    let new_task_id = new_task.id.ok_or_else(|| anyhow::anyhow!("Respawned task has no ID"))?;
+    TaskRepo::set_respawned_from(conn, new_task_id, task_id)?;
+
+    if rule.carry.annotations {
+        for annotation in AnnotationRepo::get_by_task(conn, task_id)?.into_iter().filter(|a| a.pinned) {
+            let copy = AnnotationRepo::create(conn, new_task_id, annotation.note, None)?;
+            if let Some(copy_id) = copy.id {
+                AnnotationRepo::set_pinned(conn, new_task_id, copy_id, true)?;
+            }
+        }
+    }
+    if rule.carry.children {
+        TaskRepo::clone_subtree(conn, task_id, new_task_id, delta)?;
+    }
+    if rule.carry.externals {
+        if let Some(external) = ExternalRepo::get_latest_for_task(conn, task_id)? {
+            ExternalRepo::create(conn, new_task_id, external.recipient, external.request)?;
+        }
+    }
     
-    Ok(new_task.id)
+    Ok(Some(new_task_id))
 }
 
 // Helper functions for date calculations
@@ -236,8 +429,59 @@ fn next_matching_monthday(from: NaiveDate, days: &[u32]) -> NaiveDate {
 }
 
 fn next_nth_weekday(from: NaiveDate, nth: u32, weekday: u32) -> NaiveDate {
-    // Convert weekday number (0=Mon) to chrono::Weekday
-    let target_weekday = match weekday {
+    // Months without a 5th occurrence are skipped
+    next_in_months(from, |year, month| {
+        nth_weekday_of_month(year, month, nth, to_weekday(weekday)).into_iter().collect()
+    })
+}
+
+/// First candidate date after `from`, searching month by month. `candidates` returns
+/// the dates generated by a given month; the previous month is included because some
+/// patterns (e.g. 1st-mon-after-28) can generate a date in the following month.
+fn next_in_months(from: NaiveDate, candidates: impl Fn(i32, u32) -> Vec<NaiveDate>) -> NaiveDate {
+    // Two years covers every pattern (a 5th weekday occurs at least every few months)
+    for offset in -1..24 {
+        let month_start = add_months(NaiveDate::from_ymd_opt(from.year(), from.month(), 1).unwrap_or(from), offset);
+        let mut dates = candidates(month_start.year(), month_start.month());
+        dates.sort();
+        if let Some(date) = dates.into_iter().find(|date| *date > from) {
+            return date;
+        }
+    }
+
+    // Should never reach here for valid patterns
+    from + Duration::days(1)
+}
+
+/// Next weekly occurrence: the due date's weekday in the calendar week
+/// (`week.start`) after the later of `from_ts` and the due date, at the due
+/// date's time of day. Dates are local, so a late-evening due keeps its weekday
+/// west of UTC, and completing early never repeats the current due date.
+fn next_weekly(from_ts: i64, due_ts: Option<i64>) -> Option<i64> {
+    let from = Local.timestamp_opt(from_ts, 0).single()?;
+    let due = match due_ts {
+        Some(due_ts) => Some(Local.timestamp_opt(due_ts, 0).single()?),
+        None => None,
+    };
+    let base = due.map_or(from.date_naive(), |due| due.date_naive().max(from.date_naive()));
+    let weekday = due.unwrap_or(from).weekday();
+    let date = weekday_in_next_week(&Calendar::current(), base, weekday);
+    let time = due.map_or(NaiveTime::MIN, |due| due.time());
+    Local.from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+/// The `weekday` of the calendar week after the one containing `from`
+fn weekday_in_next_week(calendar: &Calendar, from: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let next_start = Period::Week.next_start(calendar.week_start_of(from));
+    let offset = (weekday.num_days_from_monday() + 7 - calendar.week_start.num_days_from_monday()) % 7;
+    next_start + Duration::days(offset as i64)
+}
+
+/// Convert a weekday number (0=Mon) to chrono::Weekday
+fn to_weekday(weekday: u32) -> Weekday {
+    match weekday {
         0 => Weekday::Mon,
         1 => Weekday::Tue,
         2 => Weekday::Wed,
@@ -245,28 +489,35 @@ fn next_nth_weekday(from: NaiveDate, nth: u32, weekday: u32) -> NaiveDate {
         4 => Weekday::Fri,
         5 => Weekday::Sat,
         _ => Weekday::Sun,
-    };
-    
-    // Try current month first
-    if let Some(date) = nth_weekday_of_month(from.year(), from.month(), nth, target_weekday) {
-        if date > from {
-            return date;
-        }
     }
-    
-    // Try next month
-    let (next_year, next_month) = if from.month() == 12 {
-        (from.year() + 1, 1)
+}
+
+fn last_weekday_of_month(year: i32, month: u32, weekday: Weekday) -> Option<NaiveDate> {
+    let last = NaiveDate::from_ymd_opt(year, month, days_in_month(year, month))?;
+    let days_back = (last.weekday().num_days_from_monday() as i64 - weekday.num_days_from_monday() as i64 + 7) % 7;
+    Some(last - Duration::days(days_back))
+}
+
+/// The Nth `weekday` strictly after `day` of the month. May fall in the following
+/// month when `day` is near the end of the month.
+fn weekday_after_day(year: i32, month: u32, nth: u32, weekday: Weekday, day: u32) -> Option<NaiveDate> {
+    let anchor = NaiveDate::from_ymd_opt(year, month, day.min(days_in_month(year, month)))?;
+    let days_until = (weekday.num_days_from_monday() as i64 - anchor.weekday().num_days_from_monday() as i64 + 6) % 7 + 1;
+    Some(anchor + Duration::days(days_until + (nth as i64 - 1) * 7))
+}
+
+/// Nth business day (working day of `calendar`) of a month, counting from the end when `nth` is negative
+fn business_day_of_month(calendar: &Calendar, year: i32, month: u32, nth: i32) -> Option<NaiveDate> {
+    let business_days: Vec<NaiveDate> = (1..=days_in_month(year, month))
+        .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+        .filter(|date| calendar.is_workday(*date))
+        .collect();
+    if nth > 0 {
+        business_days.get(nth as usize - 1).copied()
     } else {
-        (from.year(), from.month() + 1)
-    };
-    
-    if let Some(date) = nth_weekday_of_month(next_year, next_month, nth, target_weekday) {
-        return date;
+        business_days.len().checked_sub(nth.unsigned_abs() as usize)
+            .and_then(|i| business_days.get(i).copied())
     }
-    
-    // Fallback (shouldn't happen for nth <= 5)
-    from + Duration::days(28)
 }
 
 fn nth_weekday_of_month(year: i32, month: u32, nth: u32, weekday: Weekday) -> Option<NaiveDate> {
@@ -315,6 +566,22 @@ mod tests {
         assert_eq!(next_dt.day(), 28);
     }
 
+    #[test]
+    fn test_weekly_follows_week_start() {
+        let ymd = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let monday = Calendar::default();
+        let sunday = Calendar { week_start: Weekday::Sun, ..Calendar::default() };
+
+        // Friday task done on Wednesday: the next Friday either way
+        assert_eq!(weekday_in_next_week(&monday, ymd(2026, 1, 21), Weekday::Fri), ymd(2026, 1, 30));
+        assert_eq!(weekday_in_next_week(&sunday, ymd(2026, 1, 21), Weekday::Fri), ymd(2026, 1, 30));
+        // Done on Sunday: still the Monday week, but already a new Sunday week
+        assert_eq!(weekday_in_next_week(&monday, ymd(2026, 1, 25), Weekday::Fri), ymd(2026, 1, 30));
+        assert_eq!(weekday_in_next_week(&sunday, ymd(2026, 1, 25), Weekday::Fri), ymd(2026, 2, 6));
+        // Without a due date the weekday is the completion day's: seven days on
+        assert_eq!(weekday_in_next_week(&sunday, ymd(2026, 1, 25), Weekday::Sun), ymd(2026, 2, 1));
+    }
+
     #[test]
     fn test_next_occurrence_monthdays() {
         let rule = RespawnRule::parse("monthdays:14,30").unwrap();
@@ -359,6 +626,40 @@ mod tests {
         assert_eq!(next_dt.weekday(), Weekday::Mon);
     }
 
+    #[test]
+    fn test_next_due_anchor() {
+        let due_ts = Some(Utc.with_ymd_and_hms(2026, 1, 19, 9, 0, 0).unwrap().timestamp()); // Monday
+        let late = Utc.with_ymd_and_hms(2026, 1, 21, 15, 0, 0).unwrap().timestamp(); // Wednesday
+
+        // Completion anchor: the due weekday in the calendar week after completion.
+        // This used to be Wednesday the 28th (seven days after completion), which let
+        // a weekly task drift to whichever day it was done; weekly now follows
+        // calendar weeks and keeps its Monday.
+        let rule = RespawnRule::parse("weekly").unwrap();
+        let next = DateTime::<Utc>::from_timestamp(next_due(&rule, late, due_ts).unwrap(), 0).unwrap();
+        assert_eq!((next.day(), next.hour()), (26, 9));
+
+        // Completed early: the week after the due date, not the current due date again
+        let friday = Some(Utc.with_ymd_and_hms(2026, 1, 30, 9, 0, 0).unwrap().timestamp());
+        let early = Utc.with_ymd_and_hms(2026, 1, 19, 9, 0, 0).unwrap().timestamp();
+        let next = DateTime::<Utc>::from_timestamp(next_due(&rule, early, friday).unwrap(), 0).unwrap();
+        assert_eq!((next.month(), next.day()), (2, 6));
+
+        // Due anchor: a week after the previous due date
+        let rule = RespawnRule::parse("weekly:anchor=due").unwrap();
+        let next = DateTime::<Utc>::from_timestamp(next_due(&rule, late, due_ts).unwrap(), 0).unwrap();
+        assert_eq!((next.day(), next.hour()), (26, 9));
+
+        // Missed occurrences are skipped
+        let very_late = Utc.with_ymd_and_hms(2026, 2, 3, 12, 0, 0).unwrap().timestamp();
+        let next = DateTime::<Utc>::from_timestamp(next_due(&rule, very_late, due_ts).unwrap(), 0).unwrap();
+        assert_eq!((next.month(), next.day()), (2, 9));
+
+        // Without a due date the anchor falls back to completion
+        let next = DateTime::<Utc>::from_timestamp(next_due(&rule, late, None).unwrap(), 0).unwrap();
+        assert_eq!(next.day(), 28);
+    }
+
     #[test]
     fn test_month_boundary_handling() {
         // Jan 31 + 1 month should be Feb 28
@@ -368,6 +669,135 @@ mod tests {
         assert_eq!(next.day(), 28);
     }
 
+    fn next_date(rule: &str, from: NaiveDate) -> NaiveDate {
+        let rule = RespawnRule::parse(rule).unwrap();
+        let from_ts = Utc.from_utc_datetime(&from.and_hms_opt(12, 0, 0).unwrap()).timestamp();
+        let next = next_occurrence(&rule, from_ts, None).unwrap();
+        DateTime::<Utc>::from_timestamp(next, 0).unwrap().date_naive()
+    }
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_next_occurrence_month_patterns() {
+        // Last Friday: January 2026 has no 5th Friday after the 30th
+        assert_eq!(next_date("last-fri", ymd(2026, 1, 10)), ymd(2026, 1, 30));
+        assert_eq!(next_date("last-fri", ymd(2026, 1, 30)), ymd(2026, 2, 27));
+        // 5th Friday skips months that have only four
+        assert_eq!(next_date("5th-fri", ymd(2026, 1, 31)), ymd(2026, 5, 29));
+        // 2nd and 4th Thursday
+        assert_eq!(next_date("2nd,4th-thu", ymd(2026, 1, 8)), ymd(2026, 1, 22));
+        assert_eq!(next_date("2nd,4th-thu", ymd(2026, 1, 22)), ymd(2026, 2, 12));
+        // First Monday after the 10th (Aug 10 2026 is itself a Monday)
+        assert_eq!(next_date("1st-mon-after-10th", ymd(2026, 8, 1)), ymd(2026, 8, 17));
+        // Spills into the next month when the day is late
+        assert_eq!(next_date("1st-mon-after-30", ymd(2026, 1, 31)), ymd(2026, 2, 2));
+        // Last day and last business day (Feb 28 2026 is a Saturday)
+        assert_eq!(next_date("last-day", ymd(2024, 2, 1)), ymd(2024, 2, 29));
+        assert_eq!(next_date("last-bday", ymd(2026, 2, 1)), ymd(2026, 2, 27));
+        assert_eq!(next_date("3rd-business-day", ymd(2026, 2, 4)), ymd(2026, 3, 4));
+        // Quarterly on the 15th, and every business day
+        assert_eq!(next_date("quarterly-15", ymd(2026, 1, 15)), ymd(2026, 4, 15));
+        assert_eq!(next_date("quarterly-31", ymd(2026, 1, 31)), ymd(2026, 4, 30));
+        assert_eq!(next_date("weekdays", ymd(2026, 1, 23)), ymd(2026, 1, 26));
+
+        // Business days follow the working days of the calendar (Feb 28 2026 is a Saturday)
+        let calendar = Calendar {
+            workdays: vec![Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat],
+            ..Calendar::default()
+        };
+        assert_eq!(business_day_of_month(&calendar, 2026, 2, -1), Some(ymd(2026, 2, 28)));
+        assert_eq!(business_day_of_month(&calendar, 2026, 3, 1), Some(ymd(2026, 3, 3)));
+    }
+
+    /// Brute-force definition of each month pattern, independent of the generator
+    fn matches(pattern: &RespawnPattern, date: NaiveDate) -> bool {
+        let weekday = date.weekday().num_days_from_monday();
+        let last_of_month = (date + Duration::days(1)).day() == 1;
+        match pattern {
+            RespawnPattern::Weekdays(days) => days.contains(&weekday),
+            RespawnPattern::NthWeekday { nth, weekday: wd } => *wd == weekday && (date.day() - 1) / 7 + 1 == *nth,
+            RespawnPattern::NthWeekdays { nths, weekday: wd } => *wd == weekday && nths.contains(&((date.day() - 1) / 7 + 1)),
+            RespawnPattern::LastWeekday(wd) => *wd == weekday && (date + Duration::days(7)).month() != date.month(),
+            RespawnPattern::WeekdayAfterDay { nth, weekday: wd, day } => {
+                // Anchored in this month or, when spilling over, the previous one
+                *wd == weekday && [date, date.with_day(1).unwrap() - Duration::days(1)].iter().any(|m| {
+                    let anchor = ymd(m.year(), m.month(), (*day).min(days_in_month(m.year(), m.month())));
+                    let gap = (date - anchor).num_days();
+                    gap > (*nth as i64 - 1) * 7 && gap <= *nth as i64 * 7
+                })
+            }
+            RespawnPattern::LastDayOfMonth => last_of_month,
+            RespawnPattern::BusinessDay(nth) => {
+                let month_days = (1..=days_in_month(date.year(), date.month()))
+                    .map(|d| ymd(date.year(), date.month(), d))
+                    .filter(|d| d.weekday().num_days_from_monday() < 5);
+                let business: Vec<NaiveDate> = month_days.collect();
+                let index = if *nth > 0 { *nth as usize - 1 } else { business.len() - 1 };
+                business.get(index) == Some(&date)
+            }
+            RespawnPattern::QuarterlyDay(day) => {
+                date.month() % 3 == 1 && date.day() == (*day).min(days_in_month(date.year(), date.month()))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_month_patterns_across_month_and_leap_year_boundaries() {
+        // Property: the next occurrence is after `from`, matches the pattern, and no
+        // matching date is skipped. Checked for every day around leap (2024, 2000)
+        // and non-leap century (2100) Februaries and year ends.
+        let rules = [
+            "weekdays", "2nd-tue", "5th-fri", "last-fri", "last-sun", "2nd,4th-thu",
+            "1st,3rd,5th-mon", "1st-mon-after-10", "2nd-fri-after-28", "last-day",
+            "1st-bday", "3rd-bday", "last-bday", "quarterly-15", "quarterly-31",
+        ];
+        let ranges = [
+            (ymd(2023, 11, 1), ymd(2025, 3, 31)),
+            (ymd(1999, 11, 1), ymd(2000, 3, 31)),
+            (ymd(2099, 11, 1), ymd(2100, 3, 31)),
+        ];
+
+        for rule_str in rules {
+            let pattern = RespawnRule::parse(rule_str).unwrap().pattern;
+            for (start, end) in ranges {
+                let mut from = start;
+                while from <= end {
+                    let next = next_date(rule_str, from);
+                    assert!(next > from, "{}: {} -> {}", rule_str, from, next);
+                    assert!(matches(&pattern, next), "{}: {} -> {} does not match", rule_str, from, next);
+                    let mut day = from + Duration::days(1);
+                    while day < next {
+                        assert!(!matches(&pattern, day), "{}: {} -> {} skipped {}", rule_str, from, next, day);
+                        day += Duration::days(1);
+                    }
+                    from += Duration::days(1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_project_occurrences() {
+        let due_ts = Utc.with_ymd_and_hms(2026, 1, 31, 9, 0, 0).unwrap().timestamp();
+
+        // Each projection continues from the previous one and keeps the time of day
+        let rule = RespawnRule::parse("monthly").unwrap();
+        let dates: Vec<(u32, u32, u32)> = project_occurrences(&rule, due_ts, Some(due_ts), 3)
+            .into_iter()
+            .map(|ts| DateTime::<Utc>::from_timestamp(ts, 0).unwrap())
+            .map(|dt| (dt.month(), dt.day(), dt.hour()))
+            .collect();
+        assert_eq!(dates, vec![(2, 28, 9), (3, 28, 9), (4, 28, 9)]);
+
+        // until= cuts the projection short
+        let rule = RespawnRule::parse("weekly;until=2026-02-14").unwrap();
+        assert_eq!(project_occurrences(&rule, due_ts, Some(due_ts), 5).len(), 2);
+    }
+
     #[test]
     fn test_leap_year() {
         assert!(is_leap_year(2024));
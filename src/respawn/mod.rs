@@ -5,6 +5,8 @@
 
 pub mod parser;
 pub mod generator;
+pub mod cron;
 
 pub use parser::*;
 pub use generator::*;
+pub use cron::CronSchedule;
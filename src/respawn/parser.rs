@@ -4,6 +4,9 @@
 //! after the current one is completed.
 
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use crate::respawn::cron::CronSchedule;
+use crate::utils::Calendar;
 
 /// Respawn frequency/pattern
 #[derive(Debug, Clone, PartialEq)]
@@ -30,12 +33,101 @@ pub enum RespawnPattern {
     Monthdays(Vec<u32>),
     /// Nth weekday of month (e.g., 2nd Tuesday)
     NthWeekday { nth: u32, weekday: u32 },
+    /// Several Nth weekdays of month (e.g., 2nd and 4th Thursday)
+    NthWeekdays { nths: Vec<u32>, weekday: u32 },
+    /// Last weekday of month (e.g., last Friday)
+    LastWeekday(u32),
+    /// Nth weekday after a day of month (e.g., 1st Monday after the 10th)
+    WeekdayAfterDay { nth: u32, weekday: u32, day: u32 },
+    /// Last day of month
+    LastDayOfMonth,
+    /// Nth business day (a `week.workdays` day) of month; -1 is the last business day
+    BusinessDay(i32),
+    /// Day of month in the first month of each quarter (Jan, Apr, Jul, Oct)
+    QuarterlyDay(u32),
+    /// Cron-style schedule (`cron:<min> <hour> <dom> <month> <dow>`)
+    Cron(CronSchedule),
 }
 
+/// Date the next occurrence is computed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RespawnAnchor {
+    /// From the completion date (the schedule drifts with late completions)
+    #[default]
+    Completion,
+    /// From the previous due date (the schedule stays fixed)
+    Due,
+}
+
+/// What a new instance copies from the one it respawns from, beyond the
+/// description, project, tags, UDAs and parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RespawnCarry {
+    /// Pinned annotations (checklists, links)
+    pub annotations: bool,
+    /// Child subtasks, recreated as fresh open tasks
+    pub children: bool,
+    /// Re-send to the external recipient the task was last sent to
+    pub externals: bool,
+}
+
+impl RespawnCarry {
+    pub fn is_empty(&self) -> bool {
+        !self.annotations && !self.children && !self.externals
+    }
+
+    /// Parse a comma-separated list, e.g. `annotations,children` or `all`
+    fn parse(value: &str) -> Result<Self> {
+        let mut carry = RespawnCarry::default();
+        for item in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match item {
+                "annotations" | "annotation" | "notes" => carry.annotations = true,
+                "children" | "child" | "subtasks" => carry.children = true,
+                "externals" | "external" => carry.externals = true,
+                "all" => {
+                    carry = RespawnCarry { annotations: true, children: true, externals: true };
+                }
+                _ => anyhow::bail!(
+                    "Invalid carry item '{}' (expected annotations, children, externals or all)",
+                    item
+                ),
+            }
+        }
+        if carry.is_empty() {
+            anyhow::bail!("Empty carry option (expected annotations, children, externals or all)");
+        }
+        Ok(carry)
+    }
+
+    fn describe(&self) -> String {
+        let mut items = Vec::new();
+        if self.annotations {
+            items.push("pinned annotations");
+        }
+        if self.children {
+            items.push("subtasks");
+        }
+        if self.externals {
+            items.push("externals");
+        }
+        items.join(", ")
+    }
+}
+
+/// Options that can follow a pattern, as `<pattern>:<key>=<value>` or `<pattern>;<key>=<value>`
+const RULE_OPTIONS: &[&str] = &["anchor", "until", "count", "carry"];
+
 /// Respawn rule
 #[derive(Debug, Clone)]
 pub struct RespawnRule {
     pub pattern: RespawnPattern,
+    pub anchor: RespawnAnchor,
+    /// Last date an occurrence may fall on (inclusive)
+    pub until: Option<NaiveDate>,
+    /// Total number of occurrences in the chain, skipped ones included
+    pub count: Option<u32>,
+    /// What to copy into each new instance
+    pub carry: RespawnCarry,
 }
 
 impl RespawnRule {
@@ -47,6 +139,19 @@ impl RespawnRule {
     /// - `mon,wed,fri` - specific weekdays
     /// - `1,15` - specific days of month
     /// - `2nd-tue` - Nth weekday of month (e.g., 2nd Tuesday)
+    /// - `weekdays` - every business day (the `week.workdays` setting, Mon-Fri by default)
+    /// - `last-fri`, `2nd,4th-thu`, `1st-mon-after-10` - more weekday-of-month forms
+    /// - `last-day`, `3rd-bday`, `last-bday` - last day / Nth business day of month
+    /// - `quarterly`, `quarterly-15` - every 3 months / day 15 of Jan, Apr, Jul, Oct
+    /// - `cron:0 9 * * mon-fri` - five-field cron expression (local time)
+    ///
+    /// Options follow the pattern as `:key=value` or `;key=value`:
+    /// - `anchor=completion|due` - compute the next occurrence from the completion
+    ///   date (default) or from the previous due date
+    /// - `until=YYYY-MM-DD` - stop respawning after this date
+    /// - `count=N` - stop after N occurrences
+    /// - `carry=annotations,children,externals|all` - copy pinned annotations,
+    ///   recreate subtasks and re-send to the same external recipient
     pub fn parse(rule_str: &str) -> Result<Self> {
         let rule_lower = rule_str.to_lowercase().trim().to_string();
 
@@ -54,14 +159,74 @@ impl RespawnRule {
             anyhow::bail!("Empty respawn rule");
         }
 
-        let pattern = Self::parse_pattern(&rule_lower)?;
+        let (pattern_str, options) = Self::split_options(&rule_lower);
+        let pattern = Self::parse_pattern(pattern_str)?;
 
-        Ok(RespawnRule { pattern })
+        let mut rule = RespawnRule {
+            pattern,
+            anchor: RespawnAnchor::default(),
+            until: None,
+            count: None,
+            carry: RespawnCarry::default(),
+        };
+        for (key, value) in options {
+            match key {
+                "anchor" => {
+                    rule.anchor = match value {
+                        "completion" | "completed" | "done" => RespawnAnchor::Completion,
+                        "due" => RespawnAnchor::Due,
+                        _ => anyhow::bail!("Invalid anchor '{}' (expected completion or due)", value),
+                    };
+                }
+                "until" => {
+                    // Absolute dates only: a relative expression would move every time the rule is read
+                    let date = value.split('t').next().unwrap_or(value);
+                    rule.until = Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+                        anyhow::anyhow!("Invalid until date '{}' (expected YYYY-MM-DD)", value)
+                    })?);
+                }
+                "count" => {
+                    rule.count = match value.parse::<u32>() {
+                        Ok(n) if n > 0 => Some(n),
+                        _ => anyhow::bail!("Invalid count '{}' (expected a positive number)", value),
+                    };
+                }
+                "carry" => {
+                    rule.carry = RespawnCarry::parse(value)?;
+                }
+                _ => unreachable!("split_options only yields known option keys"),
+            }
+        }
+
+        Ok(rule)
+    }
+
+    /// Split `<pattern>:key=value;key=value` into the pattern and its options.
+    /// Only known option keys start an option, so legacy patterns like `nth:2:tue`
+    /// are left intact.
+    fn split_options(rule_str: &str) -> (&str, Vec<(&str, &str)>) {
+        let starts: Vec<usize> = rule_str.match_indices([':', ';'])
+            .map(|(i, _)| i)
+            .filter(|&i| RULE_OPTIONS.iter().any(|key| rule_str[i + 1..].starts_with(&format!("{}=", key))))
+            .collect();
+        let Some(&first) = starts.first() else {
+            return (rule_str, Vec::new());
+        };
+
+        let mut options = Vec::new();
+        for (n, &start) in starts.iter().enumerate() {
+            let end = starts.get(n + 1).copied().unwrap_or(rule_str.len());
+            let option = &rule_str[start + 1..end];
+            if let Some((key, value)) = option.split_once('=') {
+                options.push((key, value));
+            }
+        }
+        (&rule_str[..first], options)
     }
 
     /// Return a human-readable description of the respawn pattern
     pub fn describe(&self) -> String {
-        match &self.pattern {
+        let description = match &self.pattern {
             RespawnPattern::Daily => "When completed, a new task will be created for the next day".to_string(),
             RespawnPattern::Weekly => "When completed, a new task will be created for the next week".to_string(),
             RespawnPattern::Monthly => "When completed, a new task will be created for the next month".to_string(),
@@ -71,10 +236,7 @@ impl RespawnRule {
             RespawnPattern::EveryMonths(n) => format!("When completed, a new task will be created for {} months later", n),
             RespawnPattern::EveryYears(n) => format!("When completed, a new task will be created for {} years later", n),
             RespawnPattern::Weekdays(days) => {
-                let day_names: Vec<&str> = days.iter().map(|d| match d {
-                    0 => "Mon", 1 => "Tue", 2 => "Wed", 3 => "Thu",
-                    4 => "Fri", 5 => "Sat", 6 => "Sun", _ => "?",
-                }).collect();
+                let day_names: Vec<&str> = days.iter().map(|d| &weekday_name(*d)[..3]).collect();
                 format!("When completed, a new task will be created for the next {}", day_names.join(", "))
             }
             RespawnPattern::Monthdays(days) => {
@@ -82,16 +244,44 @@ impl RespawnRule {
                 format!("When completed, a new task will be created for day {} of the next month", day_strs.join(" or "))
             }
             RespawnPattern::NthWeekday { nth, weekday } => {
-                let weekday_name = match weekday {
-                    0 => "Monday", 1 => "Tuesday", 2 => "Wednesday", 3 => "Thursday",
-                    4 => "Friday", 5 => "Saturday", 6 => "Sunday", _ => "?",
-                };
-                let ordinal = match nth {
-                    1 => "1st", 2 => "2nd", 3 => "3rd", 4 => "4th", 5 => "5th", _ => "?",
-                };
-                format!("When completed, a new task will be created for the {} {} of the next month", ordinal, weekday_name)
+                format!("When completed, a new task will be created for the {} {} of the next month", ordinal(*nth), weekday_name(*weekday))
+            }
+            RespawnPattern::NthWeekdays { nths, weekday } => {
+                let ordinals: Vec<String> = nths.iter().map(|n| ordinal(*n)).collect();
+                format!("When completed, a new task will be created for the next {} {} of the month", ordinals.join(" or "), weekday_name(*weekday))
             }
+            RespawnPattern::LastWeekday(weekday) => {
+                format!("When completed, a new task will be created for the last {} of the next month", weekday_name(*weekday))
+            }
+            RespawnPattern::WeekdayAfterDay { nth, weekday, day } => {
+                format!("When completed, a new task will be created for the {} {} after day {} of the next month", ordinal(*nth), weekday_name(*weekday), day)
+            }
+            RespawnPattern::LastDayOfMonth => "When completed, a new task will be created for the last day of the next month".to_string(),
+            RespawnPattern::BusinessDay(-1) => "When completed, a new task will be created for the last business day of the next month".to_string(),
+            RespawnPattern::BusinessDay(n) => {
+                format!("When completed, a new task will be created for the {} business day of the next month", ordinal(*n as u32))
+            }
+            RespawnPattern::QuarterlyDay(day) => {
+                format!("When completed, a new task will be created for day {} of the next quarter (Jan, Apr, Jul, Oct)", day)
+            }
+            RespawnPattern::Cron(schedule) => {
+                format!("When completed, a new task will be created for the next time matching cron '{}'", schedule.source)
+            }
+        };
+        let mut description = match self.anchor {
+            RespawnAnchor::Completion => description,
+            RespawnAnchor::Due => format!("{} (counted from the previous due date)", description),
+        };
+        if let Some(count) = self.count {
+            description.push_str(&format!(", {} times in total", count));
+        }
+        if let Some(until) = self.until {
+            description.push_str(&format!(", until {}", until.format("%Y-%m-%d")));
+        }
+        if !self.carry.is_empty() {
+            description.push_str(&format!(", carrying {}", self.carry.describe()));
         }
+        description
     }
 
     fn parse_pattern(pattern_str: &str) -> Result<RespawnPattern> {
@@ -100,10 +290,18 @@ impl RespawnRule {
             "daily" => return Ok(RespawnPattern::Daily),
             "weekly" => return Ok(RespawnPattern::Weekly),
             "monthly" => return Ok(RespawnPattern::Monthly),
+            "quarterly" => return Ok(RespawnPattern::EveryMonths(3)),
             "yearly" => return Ok(RespawnPattern::Yearly),
+            "weekdays" | "businessdays" | "bdays" => return Ok(RespawnPattern::Weekdays(Calendar::current().workday_numbers())),
+            "eom" => return Ok(RespawnPattern::LastDayOfMonth),
             _ => {}
         }
 
+        // Cron expression (checked first: its fields may contain '-' and ',')
+        if let Some(expr) = pattern_str.strip_prefix("cron:") {
+            return Ok(RespawnPattern::Cron(CronSchedule::parse(expr)?));
+        }
+
         // Check for Nth-weekday pattern (e.g., "2nd-tue", "1st-mon", "last-fri")
         if pattern_str.contains('-') {
             if let Ok(pattern) = Self::parse_nth_weekday(pattern_str) {
@@ -260,27 +458,59 @@ impl RespawnRule {
         Ok(days)
     }
 
-    /// Parse Nth-weekday format: "2nd-tue", "1st-mon", "last-fri"
+    /// Parse dash-separated month patterns: "2nd-tue", "last-fri", "2nd,4th-thu",
+    /// "1st-mon-after-10", "last-day", "3rd-bday", "last-bday", "quarterly-15"
     fn parse_nth_weekday(nth_str: &str) -> Result<RespawnPattern> {
-        let parts: Vec<&str> = nth_str.split('-').collect();
-
-        if parts.len() != 2 {
-            anyhow::bail!("Invalid nth weekday format: expected 'Nth-weekday' (e.g., '2nd-tue')");
+        let normalized = nth_str.replace("business-day", "bday");
+        let parts: Vec<&str> = normalized.split('-').collect();
+
+        match parts.as_slice() {
+            ["quarterly", day] => Ok(RespawnPattern::QuarterlyDay(Self::parse_day_of_month(day)?)),
+            ["last", "day"] => Ok(RespawnPattern::LastDayOfMonth),
+            ["last", "bday"] => Ok(RespawnPattern::BusinessDay(-1)),
+            [nth, "bday"] => Ok(RespawnPattern::BusinessDay(Self::parse_ordinal(nth)? as i32)),
+            ["last", weekday] => Ok(RespawnPattern::LastWeekday(Self::parse_weekday(weekday)?)),
+            [nths, weekday] => {
+                let weekday = Self::parse_weekday(weekday)?;
+                let mut nths = nths.split(',')
+                    .map(Self::parse_ordinal)
+                    .collect::<Result<Vec<u32>>>()?;
+                nths.sort();
+                nths.dedup();
+                match nths.as_slice() {
+                    [nth] => Ok(RespawnPattern::NthWeekday { nth: *nth, weekday }),
+                    _ => Ok(RespawnPattern::NthWeekdays { nths, weekday }),
+                }
+            }
+            [nth, weekday, "after", day] => Ok(RespawnPattern::WeekdayAfterDay {
+                nth: Self::parse_ordinal(nth)?,
+                weekday: Self::parse_weekday(weekday)?,
+                day: Self::parse_day_of_month(day)?,
+            }),
+            _ => anyhow::bail!("Invalid nth weekday format: expected 'Nth-weekday' (e.g., '2nd-tue')"),
         }
+    }
 
-        let nth: u32 = match parts[0] {
-            "1st" | "first" => 1,
-            "2nd" | "second" => 2,
-            "3rd" | "third" => 3,
-            "4th" | "fourth" => 4,
-            "5th" | "fifth" => 5,
-            "last" => 5,
-            _ => anyhow::bail!("Invalid ordinal: '{}' (expected 1st, 2nd, 3rd, 4th, 5th, or last)", parts[0]),
-        };
-
-        let weekday = Self::parse_weekday(parts[1])?;
+    fn parse_ordinal(ordinal: &str) -> Result<u32> {
+        match ordinal {
+            "1st" | "first" => Ok(1),
+            "2nd" | "second" => Ok(2),
+            "3rd" | "third" => Ok(3),
+            "4th" | "fourth" => Ok(4),
+            "5th" | "fifth" => Ok(5),
+            _ => anyhow::bail!("Invalid ordinal: '{}' (expected 1st, 2nd, 3rd, 4th, 5th, or last)", ordinal),
+        }
+    }
 
-        Ok(RespawnPattern::NthWeekday { nth, weekday })
+    /// Day of month, with or without an ordinal suffix ("10", "10th")
+    fn parse_day_of_month(day_str: &str) -> Result<u32> {
+        let digits = day_str.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let day: u32 = digits.parse()
+            .with_context(|| format!("Invalid day of month: '{}'", day_str))?;
+        if !(1..=31).contains(&day) {
+            anyhow::bail!("Day of month must be between 1 and 31: {}", day);
+        }
+        Ok(day)
     }
 
     /// Legacy format: "N:weekday" (e.g., "2:tue")
@@ -304,6 +534,22 @@ impl RespawnRule {
     }
 }
 
+fn weekday_name(weekday: u32) -> &'static str {
+    match weekday {
+        0 => "Monday", 1 => "Tuesday", 2 => "Wednesday", 3 => "Thursday",
+        4 => "Friday", 5 => "Saturday", 6 => "Sunday", _ => "?",
+    }
+}
+
+fn ordinal(n: u32) -> String {
+    match n {
+        1 => "1st".to_string(),
+        2 => "2nd".to_string(),
+        3 => "3rd".to_string(),
+        n => format!("{}th", n),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,7 +598,36 @@ mod tests {
         assert_eq!(rule.pattern, RespawnPattern::NthWeekday { nth: 1, weekday: 0 });
 
         let rule = RespawnRule::parse("last-fri").unwrap();
-        assert_eq!(rule.pattern, RespawnPattern::NthWeekday { nth: 5, weekday: 4 });
+        assert_eq!(rule.pattern, RespawnPattern::LastWeekday(4));
+    }
+
+    #[test]
+    fn test_parse_month_patterns() {
+        let business_week = RespawnPattern::Weekdays(vec![0, 1, 2, 3, 4]);
+        assert_eq!(RespawnRule::parse("weekdays").unwrap().pattern, business_week);
+        assert_eq!(RespawnRule::parse("quarterly").unwrap().pattern, RespawnPattern::EveryMonths(3));
+        assert_eq!(RespawnRule::parse("quarterly-15th").unwrap().pattern, RespawnPattern::QuarterlyDay(15));
+        assert_eq!(RespawnRule::parse("last-day").unwrap().pattern, RespawnPattern::LastDayOfMonth);
+        assert_eq!(RespawnRule::parse("last-bday").unwrap().pattern, RespawnPattern::BusinessDay(-1));
+        assert_eq!(RespawnRule::parse("last-business-day").unwrap().pattern, RespawnPattern::BusinessDay(-1));
+        assert_eq!(RespawnRule::parse("2nd-bday").unwrap().pattern, RespawnPattern::BusinessDay(2));
+
+        let rule = RespawnRule::parse("4th,2nd-thu").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::NthWeekdays { nths: vec![2, 4], weekday: 3 });
+        assert!(rule.describe().contains("2nd or 4th Thursday"));
+
+        let rule = RespawnRule::parse("1st-mon-after-10th:anchor=due").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::WeekdayAfterDay { nth: 1, weekday: 0, day: 10 });
+        assert_eq!(rule.anchor, RespawnAnchor::Due);
+
+        let rule = RespawnRule::parse("cron:0 9 * * mon-fri;count=10").unwrap();
+        assert!(matches!(rule.pattern, RespawnPattern::Cron(ref cron) if cron.hours == vec![9]));
+        assert_eq!(rule.count, Some(10));
+
+        assert!(RespawnRule::parse("6th-mon").is_err());
+        assert!(RespawnRule::parse("1st-mon-after-32").is_err());
+        assert!(RespawnRule::parse("quarterly-0").is_err());
+        assert!(RespawnRule::parse("cron:0 9 * *").is_err());
     }
 
     #[test]
@@ -374,6 +649,64 @@ mod tests {
         assert_eq!(rule.pattern, RespawnPattern::NthWeekday { nth: 2, weekday: 1 });
     }
 
+    #[test]
+    fn test_parse_anchor_option() {
+        let rule = RespawnRule::parse("weekly").unwrap();
+        assert_eq!(rule.anchor, RespawnAnchor::Completion);
+
+        let rule = RespawnRule::parse("weekly:anchor=due").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::Weekly);
+        assert_eq!(rule.anchor, RespawnAnchor::Due);
+
+        let rule = RespawnRule::parse("nth:2:tue:anchor=due").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::NthWeekday { nth: 2, weekday: 1 });
+        assert_eq!(rule.anchor, RespawnAnchor::Due);
+
+        let rule = RespawnRule::parse("mon,fri:anchor=completion").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::Weekdays(vec![0, 4]));
+        assert_eq!(rule.anchor, RespawnAnchor::Completion);
+
+        assert!(RespawnRule::parse("weekly:anchor=never").is_err());
+        assert!(RespawnRule::parse("weekly:color=red").is_err());
+    }
+
+    #[test]
+    fn test_parse_end_conditions() {
+        let rule = RespawnRule::parse("weekly;until=2026-12-31").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::Weekly);
+        assert_eq!(rule.until, NaiveDate::from_ymd_opt(2026, 12, 31));
+        assert_eq!(rule.count, None);
+
+        let rule = RespawnRule::parse("monthly;count=6;anchor=due").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::Monthly);
+        assert_eq!(rule.count, Some(6));
+        assert_eq!(rule.anchor, RespawnAnchor::Due);
+        assert!(rule.describe().contains("6 times in total"));
+
+        let rule = RespawnRule::parse("nth:2:tue:count=3").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::NthWeekday { nth: 2, weekday: 1 });
+        assert_eq!(rule.count, Some(3));
+
+        assert!(RespawnRule::parse("weekly;count=0").is_err());
+        assert!(RespawnRule::parse("weekly;until=eom").is_err());
+    }
+
+    #[test]
+    fn test_parse_carry_option() {
+        let rule = RespawnRule::parse("weekly;carry=annotations,children").unwrap();
+        assert_eq!(rule.pattern, RespawnPattern::Weekly);
+        assert!(rule.carry.annotations && rule.carry.children && !rule.carry.externals);
+        assert!(rule.describe().ends_with(", carrying pinned annotations, subtasks"));
+
+        let rule = RespawnRule::parse("2nd,4th-thu:count=4:carry=all").unwrap();
+        assert_eq!(rule.count, Some(4));
+        assert_eq!(rule.carry, RespawnCarry { annotations: true, children: true, externals: true });
+
+        assert!(RespawnRule::parse("daily").unwrap().carry.is_empty());
+        assert!(RespawnRule::parse("weekly;carry=").is_err());
+        assert!(RespawnRule::parse("weekly;carry=time").is_err());
+    }
+
     #[test]
     fn test_invalid_patterns() {
         assert!(RespawnRule::parse("").is_err());
@@ -0,0 +1,232 @@
+//! Calendar settings and periods
+//!
+//! The week start (`week.start`), working days (`week.workdays`) and fiscal year
+//! start (`fiscal.year_start`) come from the configuration file. Date expressions
+//! (`sow`, `eow`, ...), `tatl report` periods, burndown bins and the weekly and
+//! business-day respawn patterns all use [`Calendar`], so they agree on where a
+//! week, quarter or fiscal year begins.
+//!
+//! Fiscal years are named after the calendar year they end in: with
+//! `fiscal.year_start=april`, April 2026 - March 2027 is FY2027.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use crate::config::Config;
+
+/// Calendar settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calendar {
+    pub week_start: Weekday,
+    pub workdays: Vec<Weekday>,
+    /// First month of the fiscal year (1-12)
+    pub fiscal_year_start: u32,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar {
+            week_start: Weekday::Mon,
+            workdays: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+            fiscal_year_start: 1,
+        }
+    }
+}
+
+impl Calendar {
+    /// Calendar settings from the current configuration
+    pub fn current() -> Calendar {
+        let config = Config::current();
+        Calendar {
+            week_start: config.week_start(),
+            workdays: config.workdays(),
+            fiscal_year_start: config.fiscal_year_start(),
+        }
+    }
+
+    /// First day of the week containing `date`
+    pub fn week_start_of(&self, date: NaiveDate) -> NaiveDate {
+        let days_since_start = (date.weekday().num_days_from_monday() + 7
+            - self.week_start.num_days_from_monday()) % 7;
+        date - Duration::days(days_since_start as i64)
+    }
+
+    pub fn is_workday(&self, date: NaiveDate) -> bool {
+        self.workdays.contains(&date.weekday())
+    }
+
+    /// Working days as weekday numbers (0=Monday)
+    pub fn workday_numbers(&self) -> Vec<u32> {
+        let mut days: Vec<u32> = self.workdays.iter().map(|day| day.num_days_from_monday()).collect();
+        days.sort();
+        days
+    }
+
+    /// Fiscal year containing `date`, named after the calendar year it ends in
+    pub fn fiscal_year(&self, date: NaiveDate) -> i32 {
+        if self.fiscal_year_start == 1 || date.month() < self.fiscal_year_start {
+            date.year()
+        } else {
+            date.year() + 1
+        }
+    }
+}
+
+/// A calendar period used for report ranges and chart bins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+    FiscalQuarter,
+    FiscalYear,
+}
+
+impl Period {
+    /// Period names accepted by `--period` and `--bin`
+    pub const NAMES: &'static str = "day, week, month, quarter, year, fiscal-quarter, fiscal-year";
+
+    pub fn parse(name: &str) -> Option<Period> {
+        match name.to_lowercase().as_str() {
+            "day" => Some(Period::Day),
+            "week" => Some(Period::Week),
+            "month" => Some(Period::Month),
+            "quarter" => Some(Period::Quarter),
+            "year" => Some(Period::Year),
+            "fiscal-quarter" | "fq" => Some(Period::FiscalQuarter),
+            "fiscal-year" | "fy" => Some(Period::FiscalYear),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+            Period::Quarter => "quarter",
+            Period::Year => "year",
+            Period::FiscalQuarter => "fiscal quarter",
+            Period::FiscalYear => "fiscal year",
+        }
+    }
+
+    /// First day of the period containing `date`
+    pub fn start_of(&self, calendar: &Calendar, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => calendar.week_start_of(date),
+            Period::Month => first_of_month(date.year(), date.month()),
+            Period::Quarter => first_of_month(date.year(), (date.month() - 1) / 3 * 3 + 1),
+            Period::Year => first_of_month(date.year(), 1),
+            Period::FiscalQuarter | Period::FiscalYear => {
+                let months = if *self == Period::FiscalYear { 12 } else { 3 };
+                // Months since the fiscal year started, rounded down to the period length
+                let offset = (date.month() + 12 - calendar.fiscal_year_start) % 12;
+                add_months(first_of_month(date.year(), date.month()), -((offset % months) as i32))
+            }
+        }
+    }
+
+    /// First day of the following period, given the first day of a period
+    pub fn next_start(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => start + Duration::days(1),
+            Period::Week => start + Duration::days(7),
+            Period::Month => add_months(start, 1),
+            Period::Quarter | Period::FiscalQuarter => add_months(start, 3),
+            Period::Year | Period::FiscalYear => add_months(start, 12),
+        }
+    }
+
+    /// Last day of the period containing `date`
+    pub fn end_of(&self, calendar: &Calendar, date: NaiveDate) -> NaiveDate {
+        self.next_start(self.start_of(calendar, date)) - Duration::days(1)
+    }
+
+    /// Short label for a period, given its first day (e.g. `03/09`, `Mar`, `Q1 2026`, `FY2027`)
+    pub fn label(&self, calendar: &Calendar, start: NaiveDate) -> String {
+        match self {
+            Period::Day | Period::Week => start.format("%m/%d").to_string(),
+            Period::Month => start.format("%b").to_string(),
+            Period::Quarter => format!("Q{} {}", (start.month() - 1) / 3 + 1, start.year()),
+            Period::Year => start.year().to_string(),
+            Period::FiscalQuarter => {
+                let quarter = (start.month() + 12 - calendar.fiscal_year_start) % 12 / 3 + 1;
+                format!("Q{} FY{}", quarter, calendar.fiscal_year(start))
+            }
+            Period::FiscalYear => format!("FY{}", calendar.fiscal_year(start)),
+        }
+    }
+}
+
+/// First day of the week containing `date`, per the `week.start` setting (Monday by default)
+pub fn week_start_of(date: NaiveDate) -> NaiveDate {
+    Calendar::current().week_start_of(date)
+}
+
+fn first_of_month(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).expect("valid month")
+}
+
+/// Add months to the first day of a month
+fn add_months(first: NaiveDate, months: i32) -> NaiveDate {
+    let index = first.year() * 12 + first.month() as i32 - 1 + months;
+    first_of_month(index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_week_start_and_workdays() {
+        // 2026-03-12 is a Thursday
+        let mut calendar = Calendar::default();
+        assert_eq!(calendar.week_start_of(ymd(2026, 3, 12)), ymd(2026, 3, 9));
+        assert_eq!(Period::Week.end_of(&calendar, ymd(2026, 3, 12)), ymd(2026, 3, 15));
+
+        calendar.week_start = Weekday::Sun;
+        assert_eq!(calendar.week_start_of(ymd(2026, 3, 12)), ymd(2026, 3, 8));
+        assert_eq!(calendar.week_start_of(ymd(2026, 3, 8)), ymd(2026, 3, 8));
+        assert_eq!(Period::Week.end_of(&calendar, ymd(2026, 3, 12)), ymd(2026, 3, 14));
+
+        assert!(!calendar.is_workday(ymd(2026, 3, 14)));
+        calendar.workdays = vec![Weekday::Sun, Weekday::Mon];
+        assert!(calendar.is_workday(ymd(2026, 3, 15)));
+        assert_eq!(calendar.workday_numbers(), vec![0, 6]);
+    }
+
+    #[test]
+    fn test_quarters_and_fiscal_periods() {
+        let calendar = Calendar { fiscal_year_start: 4, ..Calendar::default() };
+        let date = ymd(2026, 2, 17);
+
+        assert_eq!(Period::Quarter.start_of(&calendar, date), ymd(2026, 1, 1));
+        assert_eq!(Period::Quarter.end_of(&calendar, date), ymd(2026, 3, 31));
+        assert_eq!(Period::Quarter.label(&calendar, ymd(2026, 1, 1)), "Q1 2026");
+
+        // April fiscal year: Feb 2026 is in Q4 of FY2026 (Apr 2025 - Mar 2026)
+        assert_eq!(Period::FiscalYear.start_of(&calendar, date), ymd(2025, 4, 1));
+        assert_eq!(Period::FiscalYear.end_of(&calendar, date), ymd(2026, 3, 31));
+        assert_eq!(Period::FiscalYear.label(&calendar, ymd(2025, 4, 1)), "FY2026");
+        assert_eq!(Period::FiscalQuarter.start_of(&calendar, date), ymd(2026, 1, 1));
+        assert_eq!(Period::FiscalQuarter.label(&calendar, ymd(2026, 1, 1)), "Q4 FY2026");
+        assert_eq!(Period::FiscalQuarter.start_of(&calendar, ymd(2026, 5, 31)), ymd(2026, 4, 1));
+        assert_eq!(Period::FiscalQuarter.label(&calendar, ymd(2026, 4, 1)), "Q1 FY2027");
+
+        // A January fiscal year is the calendar year
+        let calendar = Calendar::default();
+        assert_eq!(Period::FiscalYear.start_of(&calendar, date), ymd(2026, 1, 1));
+        assert_eq!(Period::FiscalYear.label(&calendar, ymd(2026, 1, 1)), "FY2026");
+        assert_eq!(Period::FiscalQuarter.start_of(&calendar, ymd(2026, 12, 31)), ymd(2026, 10, 1));
+
+        assert_eq!(Period::Month.next_start(ymd(2026, 12, 1)), ymd(2027, 1, 1));
+        assert_eq!(Period::parse("FY"), Some(Period::FiscalYear));
+        assert_eq!(Period::parse("fortnight"), None);
+    }
+}
@@ -1,10 +1,14 @@
 //! Date expression parsing
 //!
 //! Comprehensive date expression parser supporting:
-//! - Absolute dates: `2026-01-15`, `2026-01-15T09:00`
-//! - Relative dates: `today`, `tomorrow`, `+2d`, `-1w`
-//! - Time-only expressions: `09:00`, `17:30` (with 24-hour window rule)
-//! - End-of-period: `eod`, `eow`, `eom`
+//! - Absolute dates: `2026-01-15`, `2026-01-15T09:00`, ISO weeks `2026-W12`, `2026-W12-5`
+//! - Relative dates: `now`, `today`, `tomorrow`, `yesterday`, `+2d`, `-1w`, `in 3 days`
+//! - Weekdays: `friday` (today or the coming one), `next tue` (in next week), `last mon`
+//! - Time-only expressions: `09:00`, `9:30am`, `noon` (with 24-hour window rule)
+//! - Period boundaries: `eod`, `sow`/`eow`, `som`/`eom`, `soq`/`eoq`, `soy`/`eoy`
+//!   (weeks start on the `week.start` day)
+//! - Combined: a date, then signed offsets, then a time of day:
+//!   `friday 14:00`, `tomorrow 9am`, `2026-03-01 +3d`
 //!
 //! # Time-Only Expression Rule
 //!
@@ -20,8 +24,9 @@
 //! - Fall back hour (ambiguous): use first occurrence
 //! - Spring forward hour (invalid): error
 
-use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Datelike, Timelike};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
 use anyhow::Result;
+use crate::utils::calendar::{Calendar, Period};
 
 /// Parse a date expression and return Unix timestamp (UTC)
 ///
@@ -39,122 +44,163 @@ use anyhow::Result;
 /// let ts = parse_date_expr("2026-01-15").unwrap();
 /// let ts2 = parse_date_expr("tomorrow").unwrap();
 /// let ts3 = parse_date_expr("09:00").unwrap();
+/// let ts4 = parse_date_expr("friday 14:00").unwrap();
 /// ```
 pub fn parse_date_expr(expr: &str) -> Result<i64> {
+    parse_date_expr_at(expr, Local::now())
+}
+
+/// Parse a date expression relative to `now`
+///
+/// An expression is a date, optionally followed by signed offsets and a time of
+/// day (`tomorrow 9am`, `2026-03-01 +3d`, `next tue 14:00`), or a time of day on
+/// its own, which resolves to the nearest occurrence.
+pub fn parse_date_expr_at(expr: &str, now: DateTime<Local>) -> Result<i64> {
+    let words: Vec<&str> = expr.split_whitespace().collect();
+    if words.is_empty() {
+        anyhow::bail!("Empty date expression");
+    }
+    if words.len() == 1 && words[0].eq_ignore_ascii_case("now") {
+        return Ok(now.timestamp());
+    }
+
+    // The longest leading run of words that forms a date, then offsets and a time
+    for split in (1..=words.len()).rev() {
+        let Some(mut datetime) = parse_base_date(&words[..split].join(" "), now.date_naive())? else {
+            continue;
+        };
+        let rest = &words[split..];
+        for (idx, word) in rest.iter().enumerate() {
+            if let Some(days) = parse_relative_offset(&word.to_lowercase())? {
+                datetime += chrono::Duration::days(days);
+            } else if let Some(time) = parse_time_of_day(word).filter(|_| idx == rest.len() - 1) {
+                datetime = datetime.date().and_time(time);
+            } else {
+                anyhow::bail!("Unsupported date expression: {} (unexpected '{}')", expr, word);
+            }
+        }
+        return parse_local_datetime(datetime);
+    }
+
+    // Time-only expressions: 9am, 14:30, noon, midnight
+    if let Some(time) = parse_time_of_day(expr.trim()) {
+        return Ok(resolve_time_only(time, &now)?.timestamp());
+    }
+
+    anyhow::bail!("Unsupported date expression: {}", expr)
+}
+
+/// Parse the date part of an expression: a local date and time, or None if `expr`
+/// is not a date. Dates without a time of day are at 00:00.
+fn parse_base_date(expr: &str, today: NaiveDate) -> Result<Option<NaiveDateTime>> {
     let expr_lower = expr.to_lowercase();
-    
-    // Absolute dates: 2026-01-10, 2026-01-10T14:30
+    let midnight = |date: NaiveDate| Some(date.and_time(NaiveTime::MIN));
+
+    // Absolute dates: 2026-01-10, 2026-01-10T14:30[:00], ISO weeks: 2026-W12, 2026-W12-5
     if let Ok(date) = NaiveDate::parse_from_str(expr, "%Y-%m-%d") {
-        let datetime = date.and_hms_opt(0, 0, 0)
-            .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-        return parse_local_datetime(datetime);
+        return Ok(midnight(date));
     }
-    
     if let Ok(datetime) = NaiveDateTime::parse_from_str(expr, "%Y-%m-%dT%H:%M") {
-        return parse_local_datetime(datetime);
+        return Ok(Some(datetime));
     }
-    
-    // Relative dates: today, tomorrow, eod, eow, eom
-    let now = Local::now();
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(expr, "%Y-%m-%dT%H:%M:%S") {
+        return Ok(Some(datetime));
+    }
+    if let Some(date) = parse_iso_week(&expr_lower) {
+        return Ok(midnight(date));
+    }
+
+    // Start/end of the current week, month, quarter or year: sow, eow, som, eom, ...
+    if let Some(datetime) = parse_period_boundary(&expr_lower, today) {
+        return Ok(Some(datetime));
+    }
+
     match expr_lower.as_str() {
-        "today" => {
-            let today = now.date_naive().and_hms_opt(0, 0, 0)
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-            return parse_local_datetime(today);
-        }
-        "tomorrow" => {
-            let tomorrow = (now.date_naive() + chrono::Duration::days(1))
-                .and_hms_opt(0, 0, 0)
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-            return parse_local_datetime(tomorrow);
-        }
-        "eod" => {
-            // End of day: 23:59:59
-            let today = now.date_naive();
-            let eod = today.and_hms_opt(23, 59, 59)
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-            return parse_local_datetime(eod);
-        }
-        "eow" => {
-            // End of week: Sunday 23:59:59
-            let today = now.date_naive();
-            let days_until_sunday = (7 - today.weekday().num_days_from_sunday()) % 7;
-            let sunday = if days_until_sunday == 0 && now.time().hour() == 23 && now.time().minute() == 59 {
-                today // Already at end of week
-            } else {
-                today + chrono::Duration::days(days_until_sunday as i64)
-            };
-            let eow = sunday.and_hms_opt(23, 59, 59)
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-            return parse_local_datetime(eow);
-        }
-        "eom" => {
-            // End of month: last day of month 23:59:59
-            let today = now.date_naive();
-            let year = today.year();
-            let month = today.month();
-            // Get first day of next month, then subtract 1 day
-            let next_month = if month == 12 {
-                NaiveDate::from_ymd_opt(year + 1, 1, 1)
-            } else {
-                NaiveDate::from_ymd_opt(year, month + 1, 1)
-            };
-            let last_day = next_month
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?
-                .checked_sub_signed(chrono::Duration::days(1))
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-            let eom = last_day.and_hms_opt(23, 59, 59)
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-            return parse_local_datetime(eom);
-        }
+        "today" => return Ok(midnight(today)),
+        "tomorrow" => return Ok(midnight(today + chrono::Duration::days(1))),
+        "yesterday" => return Ok(midnight(today - chrono::Duration::days(1))),
+        // End of day: 23:59:59
+        "eod" => return Ok(today.and_hms_opt(23, 59, 59)),
+        "next week" => return Ok(midnight(today + chrono::Duration::days(7))),
         _ => {}
     }
-    
-    // Relative date offsets: +2d, +1w, -3d, etc.
-    if expr_lower.starts_with('+') || expr_lower.starts_with('-') {
-        if let Some(offset) = parse_relative_offset(&expr_lower)? {
-            let base_date = now.date_naive();
-            let target_date = base_date + chrono::Duration::days(offset);
-            let target_datetime = target_date.and_hms_opt(0, 0, 0)
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-            return parse_local_datetime(target_datetime);
-        }
-    }
 
-    // Relative date offsets without sign: 1w, 2weeks, 3 days, 1week
-    if let Some(offset) = parse_relative_offset_without_sign(&expr_lower)? {
-        let base_date = now.date_naive();
-        let target_date = base_date + chrono::Duration::days(offset);
-        let target_datetime = target_date.and_hms_opt(0, 0, 0)
-            .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-        return parse_local_datetime(target_datetime);
+    // Weekdays: friday (today or the next friday), next fri (in next week), last fri
+    let (qualifier, day) = match expr_lower.split_once(' ') {
+        Some((qualifier @ ("next" | "last"), day)) => (Some(qualifier), day),
+        _ => (None, expr_lower.as_str()),
+    };
+    if let Ok(weekday) = day.parse::<Weekday>() {
+        let days_between = |from: Weekday, to: Weekday| {
+            ((to.num_days_from_monday() + 7 - from.num_days_from_monday()) % 7) as i64
+        };
+        let date = match qualifier {
+            None => today + chrono::Duration::days(days_between(today.weekday(), weekday)),
+            Some("next") => {
+                let calendar = Calendar::current();
+                let next_week = calendar.week_start_of(today) + chrono::Duration::days(7);
+                next_week + chrono::Duration::days(days_between(calendar.week_start, weekday))
+            }
+            _ => {
+                let days_back = days_between(weekday, today.weekday());
+                today - chrono::Duration::days(if days_back == 0 { 7 } else { days_back })
+            }
+        };
+        return Ok(midnight(date));
     }
 
-    // "in <n> <unit>" expressions
-    if let Some(expr) = expr_lower.strip_prefix("in ") {
-        if let Some(offset) = parse_relative_offset_without_sign(expr)? {
-            let base_date = now.date_naive();
-            let target_date = base_date + chrono::Duration::days(offset);
-            let target_datetime = target_date.and_hms_opt(0, 0, 0)
-                .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-            return parse_local_datetime(target_datetime);
-        }
+    // Relative date offsets: +2d, -1w, 1week, 3 days, in 2 weeks
+    let offset = if expr_lower.starts_with('+') || expr_lower.starts_with('-') {
+        parse_relative_offset(&expr_lower)?
+    } else if let Some(rest) = expr_lower.strip_prefix("in ") {
+        parse_relative_offset_without_sign(rest)?
+    } else {
+        parse_relative_offset_without_sign(&expr_lower)?
+    };
+    Ok(offset.and_then(|days| midnight(today + chrono::Duration::days(days))))
+}
+
+/// ISO week date: `2026-w12` (Monday of week 12) or `2026-w12-5` (its Friday)
+fn parse_iso_week(expr: &str) -> Option<NaiveDate> {
+    let (year, rest) = expr.split_once("-w")?;
+    let (week, day) = match rest.split_once('-') {
+        Some((week, day)) => (week, day.parse::<u32>().ok()?),
+        None => (rest, 1),
+    };
+    if year.len() != 4 || !(1..=7).contains(&day) {
+        return None;
     }
+    let weekday = Weekday::try_from(day as u8 - 1).ok()?;
+    NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, weekday)
+}
 
-    if expr_lower == "next week" {
-        let base_date = now.date_naive();
-        let target_date = base_date + chrono::Duration::days(7);
-        let target_datetime = target_date.and_hms_opt(0, 0, 0)
-            .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
-        return parse_local_datetime(target_datetime);
+/// `so<p>` (first day, 00:00) and `eo<p>` (last day, 23:59:59) of the period containing
+/// `today`, where `<p>` is w(eek), m(onth), q(uarter) or y(ear)
+fn parse_period_boundary(expr: &str, today: NaiveDate) -> Option<NaiveDateTime> {
+    let period = match expr.get(2..) {
+        Some("w") => Period::Week,
+        Some("m") => Period::Month,
+        Some("q") => Period::Quarter,
+        Some("y") => Period::Year,
+        _ => return None,
+    };
+    let calendar = Calendar::current();
+    match &expr[..2] {
+        "so" => period.start_of(&calendar, today).and_hms_opt(0, 0, 0),
+        "eo" => period.end_of(&calendar, today).and_hms_opt(23, 59, 59),
+        _ => None,
     }
-    
-    // Time-only expressions: 9am, 14:30, noon, midnight
-    if let Some(time) = parse_time_only(expr, &now)? {
-        return Ok(time.timestamp());
+}
+
+/// Parse a time of day: `14:30`, `9am`, `9:30pm`, `noon`, `midnight`
+pub fn parse_time_of_day(expr: &str) -> Option<NaiveTime> {
+    let expr_lower = expr.to_lowercase();
+    match expr_lower.as_str() {
+        "noon" => NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => NaiveTime::from_hms_opt(0, 0, 0),
+        _ => parse_12hour_format(&expr_lower)
+            .or_else(|| NaiveTime::parse_from_str(&expr_lower, "%H:%M").ok()),
     }
-    
-    anyhow::bail!("Unsupported date expression: {}", expr)
 }
 
 /// Parse a local datetime and convert to UTC timestamp
@@ -262,30 +308,10 @@ fn parse_relative_offset_without_sign(expr: &str) -> Result<Option<i64>> {
     Ok(Some(total_days))
 }
 
-/// Parse time-only expression with 24-hour window rule
+/// Resolve a time of day to a date with the 24-hour window rule
 /// Window: 8 hours in the past, 16 hours in the future
 /// If future is no more than twice as far as past, choose future; otherwise choose nearest
-fn parse_time_only(expr: &str, now: &DateTime<Local>) -> Result<Option<DateTime<Local>>> {
-    let expr_lower = expr.to_lowercase();
-    
-    // Special times: noon, midnight
-    let time = match expr_lower.as_str() {
-        "noon" => NaiveTime::from_hms_opt(12, 0, 0)
-            .ok_or_else(|| anyhow::anyhow!("Invalid time"))?,
-        "midnight" => NaiveTime::from_hms_opt(0, 0, 0)
-            .ok_or_else(|| anyhow::anyhow!("Invalid time"))?,
-        _ => {
-            // Try 12-hour format: 9am, 2pm
-            if let Some(time) = parse_12hour_format(&expr_lower) {
-                time
-            } else if let Ok(time) = NaiveTime::parse_from_str(expr, "%H:%M") {
-                time
-            } else {
-                return Ok(None);
-            }
-        }
-    };
-    
+fn resolve_time_only(time: NaiveTime, now: &DateTime<Local>) -> Result<DateTime<Local>> {
     let today = now.date_naive();
     let yesterday = today.checked_sub_signed(chrono::Duration::days(1))
         .ok_or_else(|| anyhow::anyhow!("Invalid date"))?;
@@ -323,7 +349,7 @@ fn parse_time_only(expr: &str, now: &DateTime<Local>) -> Result<Option<DateTime<
     if today_dist_past > 0 && today_dist_past <= window_past {
         // Today's time is in past window
         if today_dist_past <= past_dist {
-            return Ok(Some(today_time));
+            return Ok(today_time);
         }
     }
     
@@ -332,12 +358,12 @@ fn parse_time_only(expr: &str, now: &DateTime<Local>) -> Result<Option<DateTime<
         if past_dist > 0 && past_dist <= window_past {
             // Both past and future in window - check "twice as close" rule
             if today_dist_future <= 2 * past_dist {
-                return Ok(Some(today_time));
+                return Ok(today_time);
             } else {
-                return Ok(Some(past));
+                return Ok(past);
             }
         } else {
-            return Ok(Some(today_time));
+            return Ok(today_time);
         }
     }
     
@@ -346,18 +372,18 @@ fn parse_time_only(expr: &str, now: &DateTime<Local>) -> Result<Option<DateTime<
         if future_dist > 0 && future_dist <= window_future {
             // Both in window - check "twice as close" rule
             if future_dist <= 2 * past_dist {
-                return Ok(Some(future));
+                return Ok(future);
             } else {
-                return Ok(Some(past));
+                return Ok(past);
             }
         } else {
-            return Ok(Some(past));
+            return Ok(past);
         }
     }
     
     // Check tomorrow's time
     if future_dist > 0 && future_dist <= window_future {
-        return Ok(Some(future));
+        return Ok(future);
     }
     
     // Default: choose nearest
@@ -373,10 +399,10 @@ fn parse_time_only(expr: &str, now: &DateTime<Local>) -> Result<Option<DateTime<
         nearest = future;
     }
     
-    Ok(Some(nearest))
+    Ok(nearest)
 }
 
-/// Parse 12-hour format: 9am, 2pm, etc.
+/// Parse 12-hour format: 9am, 2pm, 9:30am, etc.
 fn parse_12hour_format(expr: &str) -> Option<NaiveTime> {
     let expr = expr.trim();
     
@@ -389,8 +415,13 @@ fn parse_12hour_format(expr: &str) -> Option<NaiveTime> {
         return None;
     };
     
-    // Parse hour
-    let hour: u32 = time_str.parse().ok()?;
+    // Parse hour and optional minutes
+    let (hour_str, minute) = match time_str.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour, minute.parse::<u32>().ok()?),
+        Some(_) => return None,
+        None => (time_str, 0),
+    };
+    let hour: u32 = hour_str.parse().ok()?;
     
     if hour < 1 || hour > 12 {
         return None;
@@ -402,7 +433,7 @@ fn parse_12hour_format(expr: &str) -> Option<NaiveTime> {
         _ => return None,
     };
     
-    NaiveTime::from_hms_opt(hour_24, 0, 0)
+    NaiveTime::from_hms_opt(hour_24, minute, 0)
 }
 
 #[cfg(test)]
@@ -413,6 +444,7 @@ mod tests {
     fn test_absolute_date() {
         assert!(parse_date_expr("2026-01-10").is_ok());
         assert!(parse_date_expr("2026-01-10T14:30").is_ok());
+        assert!(parse_date_expr("2026-01-10T14:30:15").is_ok());
     }
 
     #[test]
@@ -434,6 +466,49 @@ mod tests {
         assert!(parse_date_expr("eod").is_ok());
         assert!(parse_date_expr("eow").is_ok());
         assert!(parse_date_expr("eom").is_ok());
+        assert!(parse_date_expr("eow").unwrap() > parse_date_expr("sow").unwrap());
+        assert!(parse_date_expr("eoq").unwrap() >= parse_date_expr("eom").unwrap());
+        assert!(parse_date_expr("soy").unwrap() <= parse_date_expr("soq").unwrap());
+        assert!(parse_date_expr("eox").is_err());
+    }
+
+    fn local(date: &str, time: &str) -> i64 {
+        let datetime = NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&datetime).earliest().unwrap().timestamp()
+    }
+
+    #[test]
+    fn test_weekdays_and_combined_expressions() {
+        // Wednesday 2026-03-11, 10:00
+        let now = Local.timestamp_opt(local("2026-03-11", "10:00"), 0).unwrap();
+        let parse = |expr: &str| parse_date_expr_at(expr, now).unwrap();
+
+        assert_eq!(parse("friday"), local("2026-03-13", "00:00"));
+        assert_eq!(parse("Wed"), local("2026-03-11", "00:00"));
+        assert_eq!(parse("monday"), local("2026-03-16", "00:00"));
+        assert_eq!(parse("next tue"), local("2026-03-17", "00:00"));
+        assert_eq!(parse("next friday"), local("2026-03-20", "00:00"));
+        assert_eq!(parse("last wed"), local("2026-03-04", "00:00"));
+        assert_eq!(parse("last mon"), local("2026-03-09", "00:00"));
+        assert_eq!(parse("yesterday"), local("2026-03-10", "00:00"));
+        assert_eq!(parse("now"), now.timestamp());
+
+        assert_eq!(parse("friday 14:00"), local("2026-03-13", "14:00"));
+        assert_eq!(parse("tomorrow 9am"), local("2026-03-12", "09:00"));
+        assert_eq!(parse("next tue 9:30pm"), local("2026-03-17", "21:30"));
+        assert_eq!(parse("2026-03-01 +3d"), local("2026-03-04", "00:00"));
+        assert_eq!(parse("2026-03-01 -1w noon"), local("2026-02-22", "12:00"));
+        assert_eq!(parse("in 2 days 8am"), local("2026-03-13", "08:00"));
+        assert_eq!(parse("2026-W12"), local("2026-03-16", "00:00"));
+        assert_eq!(parse("2026-w12-5 17:00"), local("2026-03-20", "17:00"));
+        assert_eq!(parse("som"), local("2026-03-01", "00:00"));
+        assert_eq!(parse("noon"), local("2026-03-11", "12:00"));
+
+        assert!(parse_date_expr_at("friday 9am 2026-03-01", now).is_err());
+        assert!(parse_date_expr_at("2026-W54", now).is_err());
+        assert!(parse_date_expr_at("next", now).is_err());
+        assert_eq!(parse_time_of_day("12:30am"), NaiveTime::from_hms_opt(0, 30, 0));
+        assert_eq!(parse_time_of_day("friday"), None);
     }
 
     #[test]
@@ -1,6 +1,8 @@
+pub mod calendar;
 pub mod date;
 pub mod duration;
 pub mod fuzzy;
 
+pub use calendar::*;
 pub use date::*;
 pub use duration::*;
@@ -0,0 +1,108 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env(billing: &str) -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n{}", db_path.display(), billing)).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+fn report(temp_dir: &TempDir) -> String {
+    let output = get_task_cmd(temp_dir)
+        .args(["sessions", "report", "--billing", "2026-03-01..2026-03-31"])
+        .assert()
+        .success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+/// Billed hours and amount of the first row with this label
+fn billed<'a>(report: &'a str, label: &str) -> Vec<&'a str> {
+    let line = report.lines()
+        .find(|line| line.trim_start_matches(['│', '├', '└', '─', ' ']).starts_with(label))
+        .unwrap_or_else(|| panic!("no row '{}' in:\n{}", label, report));
+    let cells: Vec<&str> = line.split_whitespace().collect();
+    cells[cells.len() - 2..].to_vec()
+}
+
+fn setup_sessions(temp_dir: &TempDir) {
+    get_task_cmd(temp_dir)
+        .args(["add", "-y", "API work", "project=work.acme", "uda.client=Acme"])
+        .assert()
+        .success();
+    get_task_cmd(temp_dir)
+        .args(["add", "-y", "Support", "project=work.globex", "uda.client=Globex"])
+        .assert()
+        .success();
+    get_task_cmd(temp_dir)
+        .args(["add", "-y", "Timesheets", "project=internal"])
+        .assert()
+        .success();
+    for (interval, task) in [
+        ("2026-03-02T09:00..2026-03-02T09:10", "1"),
+        ("2026-03-02T10:00..2026-03-02T10:10", "1"),
+        ("2026-03-02T11:00..2026-03-02T11:04", "2"),
+        ("2026-03-02T12:00..2026-03-02T12:30", "3"),
+    ] {
+        get_task_cmd(temp_dir).args(["onoff", interval, task, "-y"]).assert().success();
+    }
+}
+
+#[test]
+fn test_sessions_report_billing_per_session() {
+    let (temp_dir, _guard) = setup_test_env(
+        "billing.increment=15m\n\
+         billing.rate=100\n\
+         billing.rate.project.work.acme=150\n\
+         billing.increment.uda.client.globex=6m\n\
+         billing.billable.project.internal=false\n",
+    );
+    setup_sessions(&temp_dir);
+
+    let output = report(&temp_dir);
+    // Two 10-minute sessions, each rounded up to 15 minutes at 150/h
+    assert_eq!(billed(&output, "acme"), ["0.50", "75.00"]);
+    // 4 minutes rounded up to 6 minutes at 100/h
+    assert_eq!(billed(&output, "globex"), ["0.10", "10.00"]);
+    assert_eq!(billed(&output, "work"), ["0.60", "85.00"]);
+    assert_eq!(billed(&output, "internal"), ["0.00", "0.00"]);
+    assert_eq!(billed(&output, "Acme"), ["0.50", "75.00"]);
+    assert_eq!(billed(&output, "(no client)"), ["0.00", "0.00"]);
+    assert!(output.lines().filter(|line| line.starts_with("TOTAL")).all(|line| line.ends_with("0.60      85.00")));
+}
+
+#[test]
+fn test_sessions_report_billing_per_day() {
+    let (temp_dir, _guard) = setup_test_env(
+        "billing.increment=15m\n\
+         billing.round_per=day\n\
+         billing.rate=100\n",
+    );
+    setup_sessions(&temp_dir);
+
+    let output = report(&temp_dir);
+    // 20 minutes in one day rounds up to 30 minutes
+    assert_eq!(billed(&output, "acme"), ["0.50", "50.00"]);
+    assert_eq!(billed(&output, "internal"), ["0.50", "50.00"]);
+
+    // Without --billing the report is unchanged
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "report", "2026-03-01..2026-03-31"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Amount").not());
+}
@@ -0,0 +1,148 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env(rc_extra: &str) -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n{}", db_path.display(), rc_extra)).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd.env_remove("TATL_RC");
+    cmd.env_remove("TATL_DATA");
+    cmd
+}
+
+#[test]
+fn test_config_set_get_unset() {
+    let (temp_dir, _guard) = setup_test_env("# my settings\n");
+    let rc = temp_dir.path().join(".tatl").join("rc");
+
+    get_task_cmd(&temp_dir)
+        .args(&["config", "set", "week.start", "Sun"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Set week.start=sunday"));
+    get_task_cmd(&temp_dir)
+        .args(&["config", "get", "week.start"])
+        .assert()
+        .success()
+        .stdout("sunday\n");
+
+    // Invalid values and unknown keys are rejected without touching the file
+    get_task_cmd(&temp_dir)
+        .args(&["config", "set", "session.micro", "soon"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a whole number"));
+    get_task_cmd(&temp_dir)
+        .args(&["config", "set", "colour", "red"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown config key 'colour'"));
+
+    let text = fs::read_to_string(&rc).unwrap();
+    assert!(text.starts_with("data.location="));
+    assert!(text.contains("# my settings\nweek.start=sunday\n"));
+
+    get_task_cmd(&temp_dir)
+        .args(&["config", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"week\.start\s+weekday\s+sunday\s+.*rc").unwrap())
+        .stdout(predicate::str::is_match(r"session\.micro\s+count\s+30\s+default").unwrap());
+
+    get_task_cmd(&temp_dir).args(&["config", "unset", "week.start"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(&["config", "get", "week.start"])
+        .assert()
+        .success()
+        .stdout("monday\n");
+    get_task_cmd(&temp_dir)
+        .args(&["config", "unset", "week.start"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not set"));
+}
+
+#[test]
+fn test_include_and_invalid_values() {
+    let (temp_dir, _guard) = setup_test_env("include=shared.rc\nsession.micro=lots\n");
+    fs::write(temp_dir.path().join(".tatl").join("shared.rc"), "date.format=%d.%m.%Y\n").unwrap();
+
+    get_task_cmd(&temp_dir)
+        .args(&["add", "Task", "due=2026-03-04"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("ignoring 'session.micro=lots'"));
+
+    get_task_cmd(&temp_dir)
+        .args(&["show", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("04.03.2026"));
+    get_task_cmd(&temp_dir)
+        .args(&["config", "get", "session.micro"])
+        .assert()
+        .success()
+        .stdout("30\n");
+}
+
+#[test]
+fn test_env_overrides() {
+    let (temp_dir, _guard) = setup_test_env("");
+    let other_rc = temp_dir.path().join("other.rc");
+    let other_db = temp_dir.path().join("other.db");
+    fs::write(&other_rc, "abbrev.verbose=true\n").unwrap();
+
+    // TATL_RC selects the rc file; TATL_DATA the database
+    get_task_cmd(&temp_dir)
+        .env("TATL_RC", &other_rc)
+        .env("TATL_DATA", &other_db)
+        .args(&["add", "Elsewhere"])
+        .assert()
+        .success();
+    assert!(other_db.exists());
+
+    get_task_cmd(&temp_dir)
+        .env("TATL_RC", &other_rc)
+        .env("TATL_DATA", &other_db)
+        .args(&["l"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("(l -> list)"))
+        .stdout(predicate::str::contains("Elsewhere"));
+
+    get_task_cmd(&temp_dir)
+        .args(&["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks found"));
+}
+
+#[test]
+fn test_list_default_view() {
+    let (temp_dir, _guard) = setup_test_env("list.default_view=work\n");
+
+    get_task_cmd(&temp_dir).args(&["add", "-y", "Report", "project=work"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Groceries"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["list", "project=work", "alias:work"]).assert().success();
+
+    get_task_cmd(&temp_dir)
+        .args(&["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Report"))
+        .stdout(predicate::str::contains("Groceries").not());
+}
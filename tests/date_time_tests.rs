@@ -105,3 +105,79 @@ fn test_duration_integration() {
     let mut cmd = get_task_cmd();
     cmd.args(&["add", "Task 3", "due=9am"]).assert().success();
 }
+
+#[test]
+fn test_period_boundaries_follow_week_start() {
+    use chrono::{Datelike, Duration, Local};
+
+    let (temp_dir, _guard) = setup_test_env();
+    let today = Local::now().date_naive();
+    let tomorrow = today + Duration::days(1);
+
+    // Weeks starting tomorrow end today; weeks starting today start today
+    let rc = temp_dir.path().join(".tatl").join("rc");
+    let mut config = fs::read_to_string(&rc).unwrap();
+    config.push_str(&format!("week.start={}\n", tomorrow.weekday()));
+    fs::write(&rc, config).unwrap();
+
+    get_task_cmd().args(&["add", "Task 1", "due=eow"]).assert().success();
+    get_task_cmd().args(&["add", "Task 2", "scheduled=sow", "due=eoy"]).assert().success();
+
+    get_task_cmd()
+        .args(&["list", "due<=eod"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Task 1"));
+    get_task_cmd()
+        .args(&["show", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains((tomorrow - Duration::days(7)).format("%Y-%m-%d").to_string()))
+        .stdout(predicate::str::contains(format!("{}-12-31", today.year())));
+}
+
+#[test]
+fn test_combined_date_expressions_in_fields_filters_and_intervals() {
+    use chrono::{Duration, Local};
+
+    let (_temp_dir, _guard) = setup_test_env();
+    let yesterday = (Local::now().date_naive() - Duration::days(1)).format("%Y-%m-%d").to_string();
+
+    // Fields
+    get_task_cmd().args(&["add", "Task 1", "due=2026-03-01 +3d", "scheduled=2026-W12"]).assert().success();
+    get_task_cmd()
+        .args(&["show", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2026-03-04"))
+        .stdout(predicate::str::contains("2026-03-16"));
+
+    // Filters
+    get_task_cmd()
+        .args(&["list", "due<2026-W10-5 noon"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Task 1"));
+    get_task_cmd()
+        .args(&["list", "due>2026-03-04 9am"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks found"));
+
+    // Intervals and session modify
+    get_task_cmd()
+        .args(&["onoff", "yesterday 9am..yesterday 10:30am", "1", "-y"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("09:00 - 10:30"));
+    get_task_cmd()
+        .args(&["sessions", "modify", "1", "yesterday 8:15am..yesterday noon", "-y"])
+        .assert()
+        .success();
+    get_task_cmd()
+        .args(&["sessions", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("{} 08:15:00", yesterday)))
+        .stdout(predicate::str::contains(format!("{} 12:00:00", yesterday)));
+}
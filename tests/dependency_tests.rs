@@ -0,0 +1,128 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env() -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n", db_path.display())).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+fn list_ids(temp_dir: &TempDir, filter: &str) -> Vec<i64> {
+    let output = get_task_cmd(temp_dir)
+        .args(&["list", "--json", filter])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    if stdout.starts_with("No tasks found") {
+        return Vec::new();
+    }
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    json.as_array().unwrap().iter().map(|t| t["id"].as_i64().unwrap()).collect()
+}
+
+#[test]
+fn test_depends_blocks_until_dependencies_close() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Design"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Review"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Build", "depends=1,2"]).assert().success();
+
+    assert_eq!(list_ids(&temp_dir, "+blocked"), vec![3]);
+    assert_eq!(list_ids(&temp_dir, "+blocking"), vec![1, 2]);
+    assert_eq!(list_ids(&temp_dir, "stage=blocked"), vec![3]);
+
+    get_task_cmd(&temp_dir)
+        .args(&["show", "3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Depends On (2):"))
+        .stdout(predicate::str::contains("Design"));
+    get_task_cmd(&temp_dir)
+        .args(&["show", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Blocks (1):"))
+        .stdout(predicate::str::contains("Build"));
+
+    // Closing one of two blockers unblocks nothing; closing the last one reports it
+    get_task_cmd(&temp_dir)
+        .args(&["close", "1", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unblocked").not());
+    get_task_cmd(&temp_dir)
+        .args(&["cancel", "2", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unblocked task 3: Build"));
+
+    assert!(list_ids(&temp_dir, "+blocked").is_empty());
+}
+
+#[test]
+fn test_on_warns_when_task_is_blocked() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Design"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Build", "depends=1"]).assert().success();
+
+    get_task_cmd(&temp_dir)
+        .args(&["on", "2"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Task 2 is blocked by open task(s): 1 (Design)"));
+}
+
+#[test]
+fn test_modify_depends_edits_and_rejects_cycles() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    for name in ["A", "B", "C"] {
+        get_task_cmd(&temp_dir).args(&["add", name]).assert().success();
+    }
+    get_task_cmd(&temp_dir).args(&["modify", "2", "--yes", "depends=1"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["modify", "3", "--yes", "depends=2"]).assert().success();
+
+    // 1 -> 3 would close the loop 1 -> 3 -> 2 -> 1
+    get_task_cmd(&temp_dir)
+        .args(&["modify", "1", "--yes", "depends=3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Circular dependency"));
+    get_task_cmd(&temp_dir)
+        .args(&["modify", "1", "--yes", "depends=1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot depend on itself"));
+    get_task_cmd(&temp_dir)
+        .args(&["modify", "1", "--yes", "depends=+2,3"])
+        .assert()
+        .failure();
+
+    get_task_cmd(&temp_dir).args(&["modify", "3", "--yes", "depends=+1,-2"]).assert().success();
+    assert_eq!(list_ids(&temp_dir, "+blocked"), vec![2, 3]);
+    assert_eq!(list_ids(&temp_dir, "+blocking"), vec![1]);
+
+    get_task_cmd(&temp_dir).args(&["modify", "2,3", "--yes", "depends=none"]).assert().success();
+    assert!(list_ids(&temp_dir, "+blocked").is_empty());
+
+    // Dependency edits are recorded and undoable
+    get_task_cmd(&temp_dir).args(&["undo", "--yes"]).assert().success();
+    assert_eq!(list_ids(&temp_dir, "+blocked"), vec![2, 3]);
+}
@@ -0,0 +1,120 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env() -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n", db_path.display())).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+fn export(temp_dir: &TempDir, args: &[&str]) -> String {
+    let output = get_task_cmd(temp_dir)
+        .args(["sessions", "export"])
+        .args(args)
+        .assert()
+        .success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+fn setup_sessions(temp_dir: &TempDir) {
+    get_task_cmd(temp_dir)
+        .args(["add", "-y", "Write, review", "project=work.client", "+billable", "uda.client=Acme"])
+        .assert()
+        .success();
+    get_task_cmd(temp_dir).args(["add", "Groceries"]).assert().success();
+    get_task_cmd(temp_dir)
+        .args(["onoff", "2026-03-02T09:07..2026-03-02T10:22", "1", "-y"])
+        .assert()
+        .success();
+    get_task_cmd(temp_dir)
+        .args(["onoff", "2026-03-03T13:00..2026-03-03T13:20", "2", "-y"])
+        .assert()
+        .success();
+    get_task_cmd(temp_dir)
+        .args(["onoff", "2026-04-01T09:00..2026-04-01T10:00", "1", "-y"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_sessions_export_csv() {
+    let (temp_dir, _guard) = setup_test_env();
+    setup_sessions(&temp_dir);
+
+    let csv = export(&temp_dir, &["2026-03-01..2026-03-31"]);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "session_id,task_id,date,start,end,duration_secs,hours,description,project,tags,uda.client"
+    );
+    assert_eq!(lines.len(), 3, "April session is outside the range: {}", csv);
+    assert!(lines[1].starts_with("1,1,2026-03-02,2026-03-02T09:07:00"));
+    assert!(lines[1].ends_with(",4500,1.25,\"Write, review\",work.client,billable,Acme"));
+    assert!(lines[2].contains(",1200,0.33,Groceries,,,"));
+
+    // Task filters and rounding to the nearest 15 minutes
+    let csv = export(&temp_dir, &["--round", "15m", "2026-03-01..2026-03-31", "project=work"]);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains(",2026-03-02T09:00:00"));
+    assert!(lines[1].contains(",2026-03-02T10:15:00"));
+    assert!(lines[1].contains(",4500,1.25,"));
+}
+
+#[test]
+fn test_sessions_export_ics_and_jsonl() {
+    let (temp_dir, _guard) = setup_test_env();
+    setup_sessions(&temp_dir);
+
+    let ics = export(&temp_dir, &["--format", "ics", "+billable"]);
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(ics.contains("SUMMARY:Write\\, review\r\n"));
+    assert!(ics.contains("CATEGORIES:work.client,billable\r\n"));
+    assert!(ics.contains("-1@tatl\r\n"));
+
+    let jsonl = export(&temp_dir, &["--format", "jsonl"]);
+    let rows: Vec<serde_json::Value> = jsonl.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["description"], "Write, review");
+    assert_eq!(rows[0]["udas"]["client"], "Acme");
+    assert_eq!(rows[1]["description"], "Groceries");
+    assert_eq!(rows[1]["duration_secs"], 1200);
+
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "export", "--format", "xml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown export format 'xml'"));
+}
+
+#[test]
+fn test_sessions_export_skips_running_session() {
+    let (temp_dir, _guard) = setup_test_env();
+    setup_sessions(&temp_dir);
+    get_task_cmd(&temp_dir).args(["on", "2"]).assert().success();
+
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "export", "task=2"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("skipping 1 running session"))
+        .stdout(predicate::str::contains("Groceries").count(1));
+}
@@ -386,3 +386,49 @@ fn test_desc_filter_combined_with_other_filters() {
     assert!(stdout.contains("Work meeting"), "Should find matching task");
     assert!(!stdout.contains("Personal meeting"), "Should not find non-matching task");
 }
+
+#[test]
+fn test_filter_grouping_with_parentheses() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    new_cmd(&temp_dir).args(&["projects", "add", "work"]).assert().success();
+    new_cmd(&temp_dir).args(&["add", "Urgent work", "project=work", "+urgent"]).assert().success();
+    new_cmd(&temp_dir).args(&["add", "Blocked work", "project=work", "+urgent", "+blocked"]).assert().success();
+    new_cmd(&temp_dir).args(&["add", "Important home", "+important"]).assert().success();
+    new_cmd(&temp_dir).args(&["add", "Plain work", "project=work"]).assert().success();
+
+    // Attached parentheses as separate arguments
+    new_cmd(&temp_dir)
+        .args(&["list", "project=work", "(+urgent", "or", "+important)", "not", "+blocked"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Urgent work"))
+        .stdout(predicate::str::contains("Blocked work").not())
+        .stdout(predicate::str::contains("Important home").not())
+        .stdout(predicate::str::contains("Plain work").not());
+
+    // Unbalanced input is rejected
+    new_cmd(&temp_dir)
+        .args(&["list", "(+urgent", "or", "+important"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing ')'"));
+
+    // Single-argument filters (modify, sessions list) accept a quoted group
+    new_cmd(&temp_dir)
+        .args(&["modify", "(+important or +blocked)", "--yes", "+grouped"])
+        .assert()
+        .success();
+    new_cmd(&temp_dir)
+        .args(&["list", "+grouped"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Blocked work"))
+        .stdout(predicate::str::contains("Important home"))
+        .stdout(predicate::str::contains("Urgent work").not());
+
+    new_cmd(&temp_dir)
+        .args(&["sessions", "list", "(+urgent or +important)"])
+        .assert()
+        .success();
+}
@@ -0,0 +1,102 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env() -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n", db_path.display())).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+#[test]
+fn test_history_shows_task_timeline() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Write report", "project=work", "-y"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["modify", "1", "+urgent", "allocation=1h", "-y"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["close", "1"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["history", "1"]).assert().success()
+        .stdout(predicate::str::contains("Task 1: Write report"))
+        .stdout(predicate::str::contains("created"))
+        .stdout(predicate::str::contains("allocation: none → 1h"))
+        .stdout(predicate::str::contains("+urgent"))
+        .stdout(predicate::str::contains("open → closed"));
+}
+
+#[test]
+fn test_history_project_change_shows_names() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Task", "project=home", "-y"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["modify", "1", "project=work", "-y"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["history", "1"]).assert().success()
+        .stdout(predicate::str::contains("project: home → work"));
+}
+
+#[test]
+fn test_history_with_filter() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Work task", "+urgent"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Other task"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["history", "+urgent"]).assert().success()
+        .stdout(predicate::str::contains("Work task"))
+        .stdout(predicate::str::contains("Other task").not());
+}
+
+#[test]
+fn test_history_json() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["close", "1"]).assert().success();
+
+    let output = get_task_cmd(&temp_dir).args(&["history", "1", "--json"]).output().unwrap();
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let events = value[0]["events"].as_array().unwrap();
+    assert_eq!(value[0]["task_id"], 1);
+    assert_eq!(events[0]["event_type"], "created");
+    assert_eq!(events.last().unwrap()["payload"]["new_status"], "closed");
+}
+
+#[test]
+fn test_history_global_feed() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "First"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Second"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["history", "-7d"]).assert().success()
+        .stdout(predicate::str::contains("First"))
+        .stdout(predicate::str::contains("Second"));
+
+    let output = get_task_cmd(&temp_dir).args(&["history", "--json"]).output().unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value.as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_history_task_not_found() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["history", "99"]).assert().failure()
+        .stderr(predicate::str::contains("not found"));
+}
@@ -66,6 +66,42 @@ fn test_task_list_group_by_project() {
     drop(temp_dir);
 }
 
+#[test]
+fn test_task_list_uda_sort_group_and_show() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Big ticket", "uda.customer=acme", "uda.estimate=10"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Small ticket", "uda.customer=globex", "uda.estimate=3"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Other ticket", "uda.customer=acme", "uda.estimate=5"]).assert().success();
+
+    // Numeric sort: 3 < 5 < 10; the sorted UDA is shown as a column
+    let output = get_task_cmd(&temp_dir).args(&["list", "sort:uda.estimate"]).assert().success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let header_line = stdout.lines().find(|l| l.contains("ID")).expect("Header line not found");
+    assert!(header_line.starts_with("estimate"));
+    let pos = |desc: &str| stdout.lines().position(|l| l.contains(desc)).unwrap();
+    assert!(pos("Small ticket") < pos("Other ticket"));
+    assert!(pos("Other ticket") < pos("Big ticket"));
+
+    // Grouping by a UDA
+    let output = get_task_cmd(&temp_dir).args(&["list", "group:uda.customer"]).assert().success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("acme"));
+    assert!(stdout.contains("globex"));
+
+    // show: adds a UDA column, and UDA filters narrow the list
+    get_task_cmd(&temp_dir)
+        .args(&["list", "uda.estimate>4", "show:uda.customer", "--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("customer"))
+        .stdout(predicate::str::contains("Big ticket"))
+        .stdout(predicate::str::contains("Other ticket"))
+        .stdout(predicate::str::contains("Small ticket").not());
+
+    drop(temp_dir);
+}
+
 #[test]
 fn test_task_list_view_alias() {
     let (temp_dir, _guard) = setup_test_env();
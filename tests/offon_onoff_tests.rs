@@ -157,6 +157,39 @@ fn test_onoff_with_specific_task() {
     assert!(stdout.contains("task 2"));
 }
 
+#[test]
+fn test_onoff_note_colon_adds_annotation() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = get_task_cmd(&temp_dir)
+        .args(&["add", "Test task"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // note: annotates the task, note= sets the session note
+    let output = get_task_cmd(&temp_dir)
+        .args(&["onoff", "09:00..12:00", "1", "note:Fixed the retry loop", "note=pairing"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = get_task_cmd(&temp_dir)
+        .args(&["show", "1"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Fixed the retry loop"));
+    assert!(!stdout.contains("pairing"));
+
+    let output = get_task_cmd(&temp_dir)
+        .args(&["sessions", "list"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("pairing"));
+}
+
 #[test]
 fn test_onoff_requires_interval() {
     let temp_dir = TempDir::new().unwrap();
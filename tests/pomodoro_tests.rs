@@ -0,0 +1,148 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+use tatl::db::DbConnection;
+use tatl::models::Pomodoro;
+use tatl::repo::SessionRepo;
+use tatl::utils::parse_date_expr;
+mod test_env;
+
+fn setup_test_env(config: &str) -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n{}", db_path.display(), config)).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+#[test]
+fn test_on_pomodoro_ends_session_at_work_interval() {
+    let (temp_dir, _guard) = setup_test_env("");
+    get_task_cmd(&temp_dir).args(["add", "-y", "Write docs"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["on", "1", "2026-03-02T09:00", "--pomodoro=25m/5m"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pomodoro: 25m work until 09:25, then 5m break"));
+
+    let conn = DbConnection::connect().unwrap();
+    assert_eq!(
+        SessionRepo::get_pomodoro(&conn, 1).unwrap(),
+        Some(Pomodoro { work_secs: 1500, break_secs: 300 })
+    );
+
+    get_task_cmd(&temp_dir)
+        .args(["off", "2026-03-02T10:00"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("the end of its pomodoro work interval"));
+    let start = parse_date_expr("2026-03-02T09:00").unwrap();
+    assert_eq!(SessionRepo::get_by_id(&conn, 1).unwrap().unwrap().end_ts, Some(start + 1500));
+}
+
+#[test]
+fn test_on_pomodoro_uses_config_defaults() {
+    let (temp_dir, _guard) = setup_test_env("pomodoro.work=50m\npomodoro.break=10m\n");
+    get_task_cmd(&temp_dir).args(["add", "-y", "Write docs"]).assert().success();
+    get_task_cmd(&temp_dir).args(["on", "1", "--pomodoro"]).assert().success();
+
+    let conn = DbConnection::connect().unwrap();
+    assert_eq!(
+        SessionRepo::get_pomodoro(&conn, 1).unwrap(),
+        Some(Pomodoro { work_secs: 3000, break_secs: 600 })
+    );
+
+    get_task_cmd(&temp_dir)
+        .args(["status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Timing task 1: Write docs"))
+        .stdout(predicate::str::contains("Focus: "))
+        .stdout(predicate::str::contains("] 0%"));
+}
+
+#[test]
+fn test_on_pomodoro_rejects_interval() {
+    let (temp_dir, _guard) = setup_test_env("");
+    get_task_cmd(&temp_dir).args(["add", "-y", "Write docs"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["on", "1", "--pomodoro=25m/5m", "09:00..10:00"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with an interval"));
+    get_task_cmd(&temp_dir)
+        .args(["on", "1", "--pomodoro=25m/later"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid pomodoro"));
+}
+
+#[test]
+fn test_status_shows_break_after_work_interval() {
+    let (temp_dir, _guard) = setup_test_env("");
+    get_task_cmd(&temp_dir).args(["add", "-y", "Write docs"]).assert().success();
+    get_task_cmd(&temp_dir).args(["on", "1", "--pomodoro=1s/10m"]).assert().success();
+    std::thread::sleep(std::time::Duration::from_secs(2));
+
+    get_task_cmd(&temp_dir)
+        .args(["status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pomodoro work interval ended at"))
+        .stdout(predicate::str::contains("Break: 9:5"));
+
+    // status only reads; off ends the session at the end of the interval
+    let conn = DbConnection::connect().unwrap();
+    assert!(SessionRepo::get_by_id(&conn, 1).unwrap().unwrap().is_open());
+    get_task_cmd(&temp_dir)
+        .args(["off"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("the end of its pomodoro work interval"));
+    let session = SessionRepo::get_by_id(&conn, 1).unwrap().unwrap();
+    assert_eq!(session.end_ts, Some(session.start_ts + 1));
+}
+
+#[test]
+fn test_timer_runs_hook_when_interval_ends() {
+    let (temp_dir, _guard) = setup_test_env("");
+    let hook_output = temp_dir.path().join("hook.txt");
+    fs::write(
+        temp_dir.path().join(".tatl").join("rc"),
+        format!(
+            "data.location={}\npomodoro.hook=echo \"$TATL_INTERVAL $TATL_TASK_ID $TATL_SESSION_ID\" > {}\n",
+            temp_dir.path().join("test.db").display(),
+            hook_output.display()
+        ),
+    ).unwrap();
+
+    get_task_cmd(&temp_dir)
+        .args(["timer"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No pomodoro is running"));
+
+    get_task_cmd(&temp_dir).args(["add", "-y", "Write docs"]).assert().success();
+    get_task_cmd(&temp_dir).args(["on", "1", "--pomodoro=3s/1m"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["timer", "--once"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Focus: "))
+        .stdout(predicate::str::contains("Work interval over"));
+
+    assert_eq!(fs::read_to_string(&hook_output).unwrap().trim(), "work 1 1");
+    let conn = DbConnection::connect().unwrap();
+    assert!(SessionRepo::get_open(&conn).unwrap().is_none());
+}
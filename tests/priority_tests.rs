@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env(rc_extra: &str) -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n{}", db_path.display(), rc_extra)).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+#[test]
+fn test_explain_priority_lists_configured_terms() {
+    let (temp_dir, _guard) = setup_test_env(
+        "urgency.tag.urgent=6.0\nurgency.project.work=1.0\nurgency.scheduled=4.0\nurgency.uda.severity.high=5.0\n",
+    );
+
+    get_task_cmd(&temp_dir)
+        .args(&["add", "-y", "Fix outage", "project=work.ops", "+urgent", "scheduled=-1d", "uda.severity=high"])
+        .assert()
+        .success();
+
+    get_task_cmd(&temp_dir)
+        .args(&["show", "--explain-priority", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Priority of task 1: Fix outage"))
+        .stdout(predicate::str::contains("tag.urgent"))
+        .stdout(predicate::str::contains("project.work"))
+        .stdout(predicate::str::contains("scheduled date has passed"))
+        .stdout(predicate::str::contains("uda.severity.high"))
+        .stdout(predicate::str::is_match(r"Total\s+17\.00").unwrap());
+}
+
+#[test]
+fn test_urgency_coefficients_change_priority_sort() {
+    let (temp_dir, _guard) = setup_test_env("urgency.tag.urgent=20\n");
+
+    get_task_cmd(&temp_dir).args(&["add", "Due soon", "due=+1d"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Urgent", "+urgent"]).assert().success();
+
+    let output = get_task_cmd(&temp_dir)
+        .args(&["list", "sort:-priority"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let urgent = stdout.find("Urgent").unwrap();
+    let due_soon = stdout.find("Due soon").unwrap();
+    assert!(urgent < due_soon, "tag coefficient should rank the urgent task first:\n{}", stdout);
+}
+
+#[test]
+fn test_invalid_urgency_keys_warn() {
+    let (temp_dir, _guard) = setup_test_env("urgency.tag.urgent=high\n");
+
+    get_task_cmd(&temp_dir).args(&["add", "Task"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(&["show", "--explain-priority", "1"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("'high' is not a number"))
+        .stdout(predicate::str::contains("open"));
+}
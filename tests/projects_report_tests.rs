@@ -77,3 +77,30 @@ fn test_projects_report_shows_stage_statuses() {
     assert!(stdout.contains("External"), "Should show External column");
     assert!(stdout.contains("Completed"), "Should show Completed column");
 }
+
+#[test]
+fn test_projects_report_quarter_and_fiscal_bins() {
+    let (temp_dir, _guard) = setup_test_env();
+    let rc = temp_dir.path().join(".tatl").join("rc");
+    let mut config = fs::read_to_string(&rc).unwrap();
+    config.push_str("fiscal.year_start=april\n");
+    fs::write(&rc, config).unwrap();
+
+    get_task_cmd(&temp_dir).args(&["add", "-y", "Work task", "project=work"]).assert().success();
+
+    get_task_cmd(&temp_dir)
+        .args(&["projects", "report", "--bin", "quarter", "2025-01-01..2025-12-31"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Q1 2025"))
+        .stdout(predicate::str::contains("Q4 2025"));
+
+    // April fiscal year: January 2025 falls in Q4 of FY2025
+    get_task_cmd(&temp_dir)
+        .args(&["projects", "report", "--bin", "fiscal-quarter", "2025-01-01..2025-12-31"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Q4 FY2025"))
+        .stdout(predicate::str::contains("Q1 FY2026"))
+        .stdout(predicate::str::contains("Q3 FY2026"));
+}
@@ -0,0 +1,198 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env() -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n", db_path.display())).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+#[test]
+fn test_enqueue_to_named_queue() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Work task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Pager task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "1"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "2", "queue=oncall"]).assert().success()
+        .stdout(predicate::str::contains("Enqueued task 2 to queue 'oncall'"));
+
+    get_task_cmd(&temp_dir).args(&["list", "queue=oncall"]).assert().success()
+        .stdout(predicate::str::contains("Pager task"))
+        .stdout(predicate::str::contains("Work task").not());
+
+    get_task_cmd(&temp_dir).args(&["queue"]).assert().success()
+        .stdout(predicate::str::contains("* default"))
+        .stdout(predicate::str::contains("oncall"));
+}
+
+#[test]
+fn test_queue_switch_changes_active_queue() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Work task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Pager task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "1"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "2", "queue=oncall"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["queue", "switch", "oncall"]).assert().success()
+        .stdout(predicate::str::contains("Switched to queue 'oncall' (1 tasks)"));
+
+    // on acts on the active queue
+    get_task_cmd(&temp_dir).args(&["on"]).assert().success()
+        .stdout(predicate::str::contains("Started timing task 2"));
+    get_task_cmd(&temp_dir).args(&["off"]).assert().success();
+
+    // dequeue acts on the active queue
+    get_task_cmd(&temp_dir).args(&["dequeue"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["queue", "switch", "default"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["on"]).assert().success()
+        .stdout(predicate::str::contains("Started timing task 1"));
+}
+
+#[test]
+fn test_close_removes_from_all_queues() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Shared task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "1"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "1", "queue=oncall"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["close", "1"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["list", "queue=any"]).assert().success()
+        .stdout(predicate::str::contains("No tasks found"));
+}
+
+#[test]
+fn test_stage_counts_any_queue() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Pager task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "1", "queue=oncall"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["list", "stage=planned"]).assert().success()
+        .stdout(predicate::str::contains("Pager task"));
+}
+
+#[test]
+fn test_invalid_queue_name() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "1", "queue=on call"]).assert().failure()
+        .stderr(predicate::str::contains("Invalid queue name"));
+    get_task_cmd(&temp_dir).args(&["queue", "switch", "none"]).assert().failure()
+        .stderr(predicate::str::contains("reserved"));
+}
+
+/// Task IDs in the active queue, in order (`queue roll 0` prints the queue without changing it)
+fn queue_order(temp_dir: &TempDir) -> Vec<String> {
+    let output = get_task_cmd(temp_dir).args(&["queue", "roll", "0"]).output().unwrap();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('[').map(|rest| rest.to_string()))
+        .filter_map(|rest| rest.split_once("] ").map(|(_, task)| task.split(':').next().unwrap().to_string()))
+        .collect()
+}
+
+fn setup_queue(temp_dir: &TempDir) {
+    for desc in ["Task A", "Task B", "Task C"] {
+        get_task_cmd(temp_dir).args(&["add", desc]).assert().success();
+    }
+    get_task_cmd(temp_dir).args(&["enqueue", "1,2,3"]).assert().success();
+}
+
+#[test]
+fn test_queue_move() {
+    let (temp_dir, _guard) = setup_test_env();
+    setup_queue(&temp_dir);
+
+    get_task_cmd(&temp_dir).args(&["queue", "move", "3", "0"]).assert().success()
+        .stdout(predicate::str::contains("Moved task 3 to queue[0]"));
+    assert_eq!(queue_order(&temp_dir), vec!["3", "1", "2"]);
+
+    get_task_cmd(&temp_dir).args(&["add", "Not queued"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["queue", "move", "4", "0"]).assert().failure()
+        .stderr(predicate::str::contains("not in queue"));
+
+    // Reorders show up in the task history
+    get_task_cmd(&temp_dir).args(&["history", "3"]).assert().success()
+        .stdout(predicate::str::contains("queue[2] → queue[0]"));
+}
+
+#[test]
+fn test_queue_roll_and_swap() {
+    let (temp_dir, _guard) = setup_test_env();
+    setup_queue(&temp_dir);
+
+    get_task_cmd(&temp_dir).args(&["queue", "roll"]).assert().success();
+    assert_eq!(queue_order(&temp_dir), vec!["2", "3", "1"]);
+
+    get_task_cmd(&temp_dir).args(&["queue", "roll", "-1"]).assert().success();
+    assert_eq!(queue_order(&temp_dir), vec!["1", "2", "3"]);
+
+    get_task_cmd(&temp_dir).args(&["queue", "swap", "0", "2"]).assert().success();
+    assert_eq!(queue_order(&temp_dir), vec!["3", "2", "1"]);
+
+    get_task_cmd(&temp_dir).args(&["queue", "swap", "0", "5"]).assert().failure()
+        .stderr(predicate::str::contains("out of range"));
+}
+
+#[test]
+fn test_queue_sort() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Later", "due=2030-06-01"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "No due"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Sooner", "due=2030-01-01"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["enqueue", "1,2,3"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["queue", "sort", "due"]).assert().success();
+    assert_eq!(queue_order(&temp_dir), vec!["3", "1", "2"]);
+
+    get_task_cmd(&temp_dir).args(&["queue", "sort", "-due"]).assert().success();
+    assert_eq!(queue_order(&temp_dir), vec!["1", "3", "2"]);
+
+    get_task_cmd(&temp_dir).args(&["queue", "sort", "bogus"]).assert().failure()
+        .stderr(predicate::str::contains("Unknown sort field"));
+}
+
+#[test]
+fn test_queue_clear_and_undo() {
+    let (temp_dir, _guard) = setup_test_env();
+    setup_queue(&temp_dir);
+
+    get_task_cmd(&temp_dir).args(&["queue", "clear", "-y"]).assert().success()
+        .stdout(predicate::str::contains("Cleared 3 task(s)"));
+    assert!(queue_order(&temp_dir).is_empty());
+
+    get_task_cmd(&temp_dir).args(&["undo", "-y"]).assert().success();
+    assert_eq!(queue_order(&temp_dir), vec!["1", "2", "3"]);
+}
+
+#[test]
+fn test_queue_roll_undo_restores_order() {
+    let (temp_dir, _guard) = setup_test_env();
+    setup_queue(&temp_dir);
+
+    get_task_cmd(&temp_dir).args(&["queue", "sort", "-id"]).assert().success();
+    assert_eq!(queue_order(&temp_dir), vec!["3", "2", "1"]);
+
+    get_task_cmd(&temp_dir).args(&["undo", "-y"]).assert().success();
+    assert_eq!(queue_order(&temp_dir), vec!["1", "2", "3"]);
+}
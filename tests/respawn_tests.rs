@@ -176,3 +176,276 @@ fn test_respawn_carries_attributes() {
     assert!(new_tags.contains(&"important".to_string()));
     assert_eq!(new_task.respawn, Some("daily".to_string()));
 }
+
+#[test]
+fn test_respawn_shifts_scheduled_and_wait() {
+    let (_temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd()
+        .args(&["add", "Weekly report", "respawn=weekly", "due=2030-03-08", "scheduled=2030-03-06", "wait=2030-03-04"])
+        .assert()
+        .success();
+
+    let conn = DbConnection::connect().unwrap();
+    let task = TaskRepo::get_by_id(&conn, 1).unwrap().unwrap();
+    get_task_cmd().args(&["close", "1", "-y"]).assert().success();
+
+    let new_task = TaskRepo::get_by_id(&conn, 2).unwrap().unwrap();
+    let new_due = new_task.due_ts.unwrap();
+    assert!(new_due > chrono::Utc::now().timestamp());
+    // Offsets from the due date are preserved
+    assert_eq!(new_due - new_task.scheduled_ts.unwrap(), task.due_ts.unwrap() - task.scheduled_ts.unwrap());
+    assert_eq!(new_due - new_task.wait_ts.unwrap(), task.due_ts.unwrap() - task.wait_ts.unwrap());
+}
+
+#[test]
+fn test_respawn_anchor_due_keeps_schedule() {
+    let (_temp_dir, _guard) = setup_test_env();
+
+    // Due far in the future: completing early must not pull the next instance earlier
+    get_task_cmd()
+        .args(&["add", "Rent", "respawn=monthly:anchor=due", "due=2030-03-01", "wait=2030-02-25"])
+        .assert()
+        .success();
+    let conn = DbConnection::connect().unwrap();
+    let task = TaskRepo::get_by_id(&conn, 1).unwrap().unwrap();
+    get_task_cmd().args(&["close", "1", "-y"]).assert().success();
+
+    let new_task = TaskRepo::get_by_id(&conn, 2).unwrap().unwrap();
+    // One month (31 days in March) after the previous due date, not after completion
+    let expected_due = task.due_ts.unwrap() + 31 * 86400;
+    assert_eq!(new_task.due_ts, Some(expected_due));
+    assert_eq!(new_task.wait_ts, Some(expected_due - (task.due_ts.unwrap() - task.wait_ts.unwrap())));
+    assert_eq!(new_task.respawn, Some("monthly:anchor=due".to_string()));
+}
+
+#[test]
+fn test_respawn_count_and_until_end_the_chain() {
+    let (_temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd()
+        .args(&["add", "Physio", "respawn=daily;count=3", "due=2030-01-01"])
+        .assert()
+        .success();
+    get_task_cmd().args(&["close", "1", "-y"]).assert().success();
+    get_task_cmd().args(&["close", "2", "-y"]).assert().success();
+    get_task_cmd()
+        .args(&["close", "3", "-y"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Respawned").not());
+
+    let conn = DbConnection::connect().unwrap();
+    assert!(TaskRepo::get_by_id(&conn, 4).unwrap().is_none());
+    assert_eq!(TaskRepo::get_respawn_chain(&conn, 3).unwrap(), vec![1, 2, 3]);
+
+    // until= is inclusive of the last day; the next occurrence after it is not created
+    get_task_cmd()
+        .args(&["add", "Sprint review", "respawn=weekly;anchor=due;until=2030-01-08", "due=2030-01-01"])
+        .assert()
+        .success();
+    get_task_cmd().args(&["close", "4", "-y"]).assert().success();
+    let next = TaskRepo::get_by_id(&conn, 5).unwrap().unwrap();
+    assert_eq!(next.description, "Sprint review");
+    get_task_cmd().args(&["close", "5", "-y"]).assert().success();
+    assert!(TaskRepo::get_by_id(&conn, 6).unwrap().is_none());
+}
+
+#[test]
+fn test_skip_advances_without_closing() {
+    let (_temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd()
+        .args(&["add", "Standup", "respawn=daily;count=3", "due=2030-01-01T09:00", "scheduled=2029-12-31T09:00"])
+        .assert()
+        .success();
+    let conn = DbConnection::connect().unwrap();
+    let before = TaskRepo::get_by_id(&conn, 1).unwrap().unwrap();
+
+    get_task_cmd()
+        .args(&["skip", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped task 1: Standup"));
+
+    // Same task, still open, moved one day; no new instance and no close
+    let after = TaskRepo::get_by_id(&conn, 1).unwrap().unwrap();
+    assert_eq!(after.status, tatl::models::TaskStatus::Open);
+    assert_eq!(after.due_ts, Some(before.due_ts.unwrap() + 86400));
+    assert_eq!(after.scheduled_ts, Some(before.scheduled_ts.unwrap() + 86400));
+    assert!(TaskRepo::get_by_id(&conn, 2).unwrap().is_none());
+    let events = tatl::repo::EventRepo::get_by_task(&conn, 1).unwrap();
+    assert!(events.iter().any(|e| e.event_type == "skipped"));
+    assert!(!events.iter().any(|e| e.event_type == "status_changed"));
+
+    // The skipped occurrence counts toward count=3: one more instance, then the rule ends
+    get_task_cmd().args(&["close", "1", "-y"]).assert().success();
+    assert!(TaskRepo::get_by_id(&conn, 2).unwrap().is_some());
+    get_task_cmd()
+        .args(&["skip", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no further occurrences"));
+    get_task_cmd().args(&["close", "2", "-y"]).assert().success();
+    assert!(TaskRepo::get_by_id(&conn, 3).unwrap().is_none());
+
+    // Tasks without a respawn rule cannot be skipped
+    get_task_cmd().args(&["add", "One-off"]).assert().success();
+    get_task_cmd()
+        .args(&["skip", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has no respawn rule"));
+}
+
+#[test]
+fn test_respawn_preview() {
+    let (_temp_dir, _guard) = setup_test_env();
+
+    let output = get_task_cmd()
+        .args(&["respawn", "preview", "2nd-tue", "--from", "2026-01-01", "-n", "12"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Next 12 occurrences after 2026-01-01"))
+        .stdout(predicate::str::contains(" 1. 2026-01-13 Tue"))
+        .stdout(predicate::str::contains("12. 2026-12-08 Tue"));
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert_eq!(stdout.lines().filter(|l| l.ends_with(" Tue")).count(), 12);
+
+    // End conditions cap the preview
+    get_task_cmd()
+        .args(&["respawn", "preview", "weekly;until=2026-01-20", "--from", "2026-01-01", "-n", "10"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Next 2 occurrences"));
+
+    get_task_cmd()
+        .args(&["respawn", "preview", "fortnightly"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid respawn rule"));
+}
+
+#[test]
+fn test_show_lists_projected_respawn_dates() {
+    let (_temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd()
+        .args(&["add", "Invoice", "respawn=last-bday", "due=2030-01-31"])
+        .assert()
+        .success();
+    get_task_cmd()
+        .args(&["show", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Next:        2030-02-28 Thu"))
+        .stdout(predicate::str::contains("2030-03-29 Fri"))
+        .stdout(predicate::str::contains("2030-04-30 Tue"));
+
+    // The projection stops where the count ends
+    get_task_cmd()
+        .args(&["add", "Course", "respawn=weekly;count=2", "due=2030-01-07"])
+        .assert()
+        .success();
+    get_task_cmd()
+        .args(&["show", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Remaining:   1 more occurrence\n"))
+        .stdout(predicate::str::contains("2030-01-14 Mon"))
+        .stdout(predicate::str::contains("2030-01-21").not());
+
+    get_task_cmd()
+        .args(&["respawn"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 respawning tasks, next due: 2 Course"));
+}
+
+#[test]
+fn test_respawn_carries_pinned_annotations_children_and_externals() {
+    let (_temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd()
+        .args(&["add", "Monthly close", "respawn=monthly;carry=all", "due=2030-01-31"])
+        .assert()
+        .success();
+    get_task_cmd().args(&["add", "Reconcile bank", "parent=1", "due=2030-01-30"]).assert().success();
+    get_task_cmd().args(&["add", "Match receipts", "parent=2"]).assert().success();
+    get_task_cmd().args(&["annotate", "1", "Checklist: https://wiki/close"]).assert().success();
+    get_task_cmd().args(&["annotate", "1", "Took longer this month"]).assert().success();
+    get_task_cmd()
+        .args(&["annotate", "1", "--pin", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pinned annotation 1 on task 1"));
+    get_task_cmd().args(&["send", "1", "accounting", "sign-off"]).assert().success();
+    get_task_cmd().args(&["on", "1", "2026-01-10T09:00..2026-01-10T10:30"]).assert().success();
+    // Closed children are recreated as open ones too
+    get_task_cmd().args(&["close", "3", "-y"]).assert().success();
+
+    get_task_cmd().args(&["close", "1", "-y"]).assert().success();
+
+    let conn = DbConnection::connect().unwrap();
+    let new_task = TaskRepo::get_by_id(&conn, 4).unwrap().unwrap();
+    assert_eq!(new_task.description, "Monthly close");
+
+    // Only the pinned annotation is copied, and it stays pinned
+    let annotations = tatl::repo::AnnotationRepo::get_by_task(&conn, 4).unwrap();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations[0].note, "Checklist: https://wiki/close");
+    assert!(annotations[0].pinned);
+
+    // The subtree is recreated as open tasks, with dates moved like the root's
+    let children = TaskRepo::get_children(&conn, 4).unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].description, "Reconcile bank");
+    assert_eq!(children[0].status, tatl::models::TaskStatus::Open);
+    let old_child = TaskRepo::get_by_id(&conn, 2).unwrap().unwrap();
+    assert_eq!(
+        children[0].due_ts.unwrap() - old_child.due_ts.unwrap(),
+        new_task.due_ts.unwrap() - TaskRepo::get_by_id(&conn, 1).unwrap().unwrap().due_ts.unwrap()
+    );
+    let grandchildren = TaskRepo::get_children(&conn, children[0].id.unwrap()).unwrap();
+    assert_eq!(grandchildren.len(), 1);
+    assert_eq!(grandchildren[0].status, tatl::models::TaskStatus::Open);
+
+    // Re-sent to the same recipient with the same request
+    let externals = tatl::repo::ExternalRepo::get_active_for_task(&conn, 4).unwrap();
+    assert_eq!(externals.len(), 1);
+    assert_eq!(externals[0].recipient, "accounting");
+    assert_eq!(externals[0].request.as_deref(), Some("sign-off"));
+
+    // show walks the chain and totals the time of every instance
+    get_task_cmd()
+        .args(&["show", "4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Respawn Chain (2 instances):"))
+        .stdout(predicate::str::contains("(this task)"))
+        .stdout(predicate::str::contains("Total:      1h30m"))
+        .stdout(predicate::str::contains("(pinned)"));
+}
+
+#[test]
+fn test_respawn_without_carry_copies_nothing_extra() {
+    let (_temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd().args(&["add", "Water plants", "respawn=weekly"]).assert().success();
+    get_task_cmd().args(&["add", "Fill can", "parent=1"]).assert().success();
+    get_task_cmd().args(&["annotate", "1", "Ferns first"]).assert().success();
+    get_task_cmd().args(&["annotate", "1", "--pin", "1"]).assert().success();
+    get_task_cmd().args(&["close", "1", "-y"]).assert().success();
+
+    let conn = DbConnection::connect().unwrap();
+    assert!(tatl::repo::AnnotationRepo::get_by_task(&conn, 3).unwrap().is_empty());
+    assert!(TaskRepo::get_children(&conn, 3).unwrap().is_empty());
+    assert_eq!(TaskRepo::get_respawn_chain(&conn, 3).unwrap(), vec![1, 3]);
+
+    // Pinning checks that the annotation belongs to the task
+    get_task_cmd()
+        .args(&["annotate", "2", "--pin", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not belong to task 2"));
+}
@@ -0,0 +1,122 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+use tatl::db::DbConnection;
+use tatl::repo::SessionRepo;
+use tatl::utils::parse_date_expr;
+mod test_env;
+
+fn setup_test_env(config: &str) -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n{}", db_path.display(), config)).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+fn ts(expr: &str) -> i64 {
+    parse_date_expr(expr).unwrap()
+}
+
+#[test]
+fn test_sessions_audit_lists_and_fixes() {
+    let (temp_dir, _guard) = setup_test_env("session.max_duration=4h\n");
+    get_task_cmd(&temp_dir).args(["add", "-y", "Coding"]).assert().success();
+    get_task_cmd(&temp_dir).args(["add", "-y", "Support"]).assert().success();
+    for (interval, task) in [
+        ("2026-03-02T08:00..2026-03-02T13:00", "1"),
+        ("2026-03-02T23:00..2026-03-03T01:00", "2"),
+    ] {
+        get_task_cmd(&temp_dir).args(["onoff", interval, task, "-y"]).assert().success();
+    }
+
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "audit"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("longer than session.max_duration"))
+        .stdout(predicate::str::contains("crosses midnight"));
+
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "audit", "--fix", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Session 1: ended after 4h0m0s."))
+        .stdout(predicate::str::contains("Session 2: split at midnight."));
+
+    let conn = DbConnection::connect().unwrap();
+    assert_eq!(SessionRepo::get_by_id(&conn, 1).unwrap().unwrap().end_ts, Some(ts("2026-03-02T12:00")));
+    assert_eq!(SessionRepo::get_by_id(&conn, 2).unwrap().unwrap().end_ts, Some(ts("2026-03-03T00:00")));
+    let split = SessionRepo::get_by_id(&conn, 3).unwrap().unwrap();
+    assert_eq!((split.start_ts, split.end_ts), (ts("2026-03-03T00:00"), Some(ts("2026-03-03T01:00"))));
+
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "audit"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No suspicious sessions found."));
+}
+
+#[test]
+fn test_sessions_audit_fixes_overlap() {
+    let (temp_dir, _guard) = setup_test_env("");
+    get_task_cmd(&temp_dir).args(["add", "-y", "Coding"]).assert().success();
+    get_task_cmd(&temp_dir).args(["add", "-y", "Support"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["onoff", "2026-03-02T09:00..2026-03-02T12:00", "1", "-y"])
+        .assert()
+        .success();
+    // Overlapping session written directly, as an old import might have
+    let conn = DbConnection::connect().unwrap();
+    SessionRepo::create_closed(&conn, 2, ts("2026-03-02T10:00"), ts("2026-03-02T10:30")).unwrap();
+
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "audit", "--fix", "1", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("overlaps session 2"));
+
+    let first = SessionRepo::get_by_id(&conn, 1).unwrap().unwrap();
+    assert_eq!(first.end_ts, Some(ts("2026-03-02T10:00")));
+    let sessions = SessionRepo::get_by_task(&conn, 1).unwrap();
+    assert!(sessions.iter().any(|s| s.start_ts == ts("2026-03-02T10:30") && s.end_ts == Some(ts("2026-03-02T12:00"))));
+}
+
+#[test]
+fn test_auto_cap_max_duration_on_off() {
+    let (temp_dir, _guard) = setup_test_env("session.max_duration=4h\nsession.auto_cap=max_duration\n");
+    get_task_cmd(&temp_dir).args(["add", "-y", "Coding"]).assert().success();
+    get_task_cmd(&temp_dir).args(["on", "1", "2026-03-02T08:00"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["off", "2026-03-02T18:00"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Capped session 1"));
+
+    let conn = DbConnection::connect().unwrap();
+    assert_eq!(SessionRepo::get_by_id(&conn, 1).unwrap().unwrap().end_ts, Some(ts("2026-03-02T12:00")));
+}
+
+#[test]
+fn test_on_warns_about_long_running_session() {
+    let (temp_dir, _guard) = setup_test_env("session.max_duration=4h\n");
+    get_task_cmd(&temp_dir).args(["add", "-y", "Coding"]).assert().success();
+    get_task_cmd(&temp_dir).args(["add", "-y", "Support"]).assert().success();
+    get_task_cmd(&temp_dir).args(["on", "1", "2026-03-02T08:00"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["on", "2"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("longer than session.max_duration"));
+}
@@ -0,0 +1,117 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+use tatl::db::DbConnection;
+use tatl::repo::SessionRepo;
+mod test_env;
+
+fn setup_test_env() -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n", db_path.display())).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+fn stdout(temp_dir: &TempDir, args: &[&str]) -> String {
+    let output = get_task_cmd(temp_dir).args(args).assert().success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_off_with_note_and_tag() {
+    let (temp_dir, _guard) = setup_test_env();
+    get_task_cmd(&temp_dir).args(["add", "-y", "Fix retry"]).assert().success();
+    get_task_cmd(&temp_dir).args(["on", "1", "2026-03-02T09:00"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["off", "2026-03-02T10:00", "fixed the retry loop", "+meeting"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Session 1: +meeting \"fixed the retry loop\""));
+
+    let conn = DbConnection::connect().unwrap();
+    let session = SessionRepo::get_by_id(&conn, 1).unwrap().unwrap();
+    assert_eq!(session.note.as_deref(), Some("fixed the retry loop"));
+    assert_eq!(session.end_ts, Some(tatl::utils::parse_date_expr("2026-03-02T10:00").unwrap()));
+    assert_eq!(SessionRepo::get_tags(&conn, 1).unwrap(), vec!["meeting".to_string()]);
+}
+
+#[test]
+fn test_onoff_note_and_modify_tags() {
+    let (temp_dir, _guard) = setup_test_env();
+    get_task_cmd(&temp_dir).args(["add", "-y", "Pairing"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["onoff", "2026-03-02T09:00..2026-03-02T10:00", "1", "note=pairing", "+meeting", "-y"])
+        .assert()
+        .success();
+
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "modify", "1", "+review", "-meeting", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tags: +review -meeting"));
+
+    let conn = DbConnection::connect().unwrap();
+    assert_eq!(SessionRepo::get_by_id(&conn, 1).unwrap().unwrap().note.as_deref(), Some("pairing"));
+    assert_eq!(SessionRepo::get_tags(&conn, 1).unwrap(), vec!["review".to_string()]);
+
+    get_task_cmd(&temp_dir)
+        .args(["sessions", "modify", "1", "note=none", "--yes"])
+        .assert()
+        .success();
+    assert_eq!(SessionRepo::get_by_id(&conn, 1).unwrap().unwrap().note, None);
+}
+
+#[test]
+fn test_sessions_list_and_report_tag_filters() {
+    let (temp_dir, _guard) = setup_test_env();
+    get_task_cmd(&temp_dir).args(["add", "-y", "Standup", "project=meetings"]).assert().success();
+    get_task_cmd(&temp_dir).args(["add", "-y", "Coding", "project=work"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["onoff", "2026-03-02T09:00..2026-03-02T09:30", "1", "+meeting", "-y"])
+        .assert()
+        .success();
+    get_task_cmd(&temp_dir)
+        .args(["onoff", "2026-03-02T10:00..2026-03-02T12:00", "2", "note=retry loop", "-y"])
+        .assert()
+        .success();
+
+    let list = stdout(&temp_dir, &["sessions", "list", "tag=meeting"]);
+    assert!(list.contains("Tags"));
+    assert!(list.contains("Standup"));
+    assert!(!list.contains("Coding"));
+
+    let list = stdout(&temp_dir, &["sessions", "list", "note=RETRY"]);
+    assert!(list.contains("Coding") && list.contains("retry loop"));
+    assert!(!list.contains("Standup"));
+
+    let report = stdout(&temp_dir, &["sessions", "report", "2026-03-01..2026-03-31", "tag!=meeting"]);
+    assert!(report.contains("work"));
+    assert!(!report.contains("meetings"));
+    assert!(report.contains("Sessions: 1 "));
+}
+
+#[test]
+fn test_sessions_list_hides_empty_tag_and_note_columns() {
+    let (temp_dir, _guard) = setup_test_env();
+    get_task_cmd(&temp_dir).args(["add", "-y", "Coding"]).assert().success();
+    get_task_cmd(&temp_dir)
+        .args(["onoff", "2026-03-02T10:00..2026-03-02T12:00", "1", "-y"])
+        .assert()
+        .success();
+
+    let list = stdout(&temp_dir, &["sessions", "list"]);
+    assert!(!list.contains("Tags"));
+    assert!(!list.contains("Note"));
+}
@@ -177,3 +177,29 @@ fn test_report_period_option() {
         .success()
         .stdout(predicate::str::contains("THIS YEAR"));
 }
+
+#[test]
+fn test_report_quarter_and_fiscal_periods() {
+    let (temp_dir, _guard) = setup_test_env();
+    let rc = temp_dir.path().join(".tatl").join("rc");
+    let mut config = fs::read_to_string(&rc).unwrap();
+    config.push_str("fiscal.year_start=april\n");
+    fs::write(&rc, config).unwrap();
+
+    get_task_cmd(&temp_dir)
+        .args(&["report", "--period=quarter"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"THIS QUARTER \(Q[1-4] \d{4}\)").unwrap());
+    get_task_cmd(&temp_dir)
+        .args(&["report", "--period=fiscal-year"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"THIS FISCAL YEAR \(FY\d{4}\)").unwrap());
+    get_task_cmd(&temp_dir)
+        .args(&["report", "--period=fortnight"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Unknown period 'fortnight'"))
+        .stdout(predicate::str::contains("THIS WEEK"));
+}
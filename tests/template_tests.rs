@@ -66,7 +66,7 @@ fn test_template_merge_attributes() {
             None, // Task doesn't override alloc_secs
             &HashMap::new(),
             &["urgent".to_string()], // Task adds tag
-        );
+        ).unwrap();
     
     assert_eq!(project_id, Some(2)); // Task value
     assert_eq!(alloc_secs, Some(1800)); // Template value
@@ -125,3 +125,167 @@ fn test_respawn_with_template() {
     assert!(tags.contains(&"meeting".to_string()), "Tags: {:?}", tags);
     drop(temp_dir); // Keep temp_dir alive until end
 }
+
+#[test]
+fn test_templates_add_show_modify_delete() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd().args(&["projects", "add", "work"]).assert().success();
+    get_task_cmd()
+        .args(&["templates", "add", "review", "Review: {desc}", "project=work", "due=+2d", "+review"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created template 'review'"));
+
+    // Dates are stored as expressions, not frozen timestamps
+    let conn = DbConnection::connect().unwrap();
+    let tmpl = TemplateRepo::get_by_name(&conn, "review").unwrap().unwrap();
+    assert_eq!(tmpl.payload.get("due").unwrap().as_str(), Some("+2d"));
+    assert!(!tmpl.payload.contains_key("due_ts"));
+
+    get_task_cmd()
+        .args(&["templates", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("review"))
+        .stdout(predicate::str::contains("due=+2d"));
+
+    get_task_cmd()
+        .args(&["templates", "modify", "review", "due=+3d", "-review", "+code"])
+        .assert()
+        .success();
+    get_task_cmd()
+        .args(&["templates", "show", "review"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+3d"))
+        .stdout(predicate::str::contains("+code"))
+        .stdout(predicate::str::contains("+review").not());
+
+    // Invalid attributes are rejected
+    get_task_cmd().args(&["templates", "add", "review", "due=+1d"]).assert().failure();
+    get_task_cmd().args(&["templates", "add", "bad", "due=whenever"]).assert().failure();
+    get_task_cmd().args(&["templates", "add", "bad", "project=nope"]).assert().failure();
+
+    get_task_cmd().args(&["templates", "delete", "review", "-y"]).assert().success();
+    get_task_cmd().args(&["templates", "show", "review"]).assert().failure();
+    drop(temp_dir);
+}
+
+#[test]
+fn test_task_add_with_template_pattern_respawn_and_parent() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd().args(&["add", "Sprint"]).assert().success();
+    get_task_cmd()
+        .args(&["templates", "add", "review", "Review: {desc}", "due=+2d", "respawn=weekly", "parent=1"])
+        .assert()
+        .success();
+    get_task_cmd()
+        .args(&["add", "PR 42", "template=review"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Review: PR 42"));
+
+    let conn = DbConnection::connect().unwrap();
+    let task = TaskRepo::get_by_id(&conn, 2).unwrap().unwrap();
+    assert_eq!(task.description, "Review: PR 42");
+    assert_eq!(task.respawn, Some("weekly".to_string()));
+    assert_eq!(task.parent_id, Some(1));
+    let expected_due = tatl::utils::parse_date_expr("+2d").unwrap();
+    assert!((task.due_ts.unwrap() - expected_due).abs() <= 60);
+
+    // A pattern without {desc} supplies the description when none is given
+    get_task_cmd().args(&["templates", "add", "standup", "Daily standup"]).assert().success();
+    get_task_cmd().args(&["add", "template=standup"]).assert().success();
+    let task = TaskRepo::get_by_id(&conn, 3).unwrap().unwrap();
+    assert_eq!(task.description, "Daily standup");
+    drop(temp_dir);
+}
+
+#[test]
+fn test_template_subtask_tree() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd().args(&["projects", "add", "eng"]).assert().success();
+    get_task_cmd().args(&["projects", "add", "docs"]).assert().success();
+    get_task_cmd().args(&["templates", "add", "release", "Release {desc}", "project=eng"]).assert().success();
+    get_task_cmd().args(&["templates", "add-child", "release", "Freeze branch", "due=-3d", "+freeze"]).assert().success();
+    get_task_cmd()
+        .args(&["templates", "add-child", "release", "--under", "1", "Tag RC", "allocation=30m"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added subtask 1.1"));
+    get_task_cmd().args(&["templates", "add-child", "release", "Write notes", "project=docs", "due=-1d"]).assert().success();
+    get_task_cmd().args(&["templates", "add-child", "release", "--under", "9", "Nope"]).assert().failure();
+    get_task_cmd().args(&["templates", "add-child", "release", "Bad", "due=friday"]).assert().failure();
+
+    get_task_cmd()
+        .args(&["templates", "show", "release"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.1  Tag RC"));
+
+    get_task_cmd()
+        .args(&["add", "5.3", "template=release", "due=2030-06-20"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created subtask 2: Freeze branch"))
+        .stdout(predicate::str::contains("Created subtask 3: Tag RC"))
+        .stdout(predicate::str::contains("Created subtask 4: Write notes"));
+
+    let conn = DbConnection::connect().unwrap();
+    let parent = TaskRepo::get_by_id(&conn, 1).unwrap().unwrap();
+    let freeze = TaskRepo::get_by_id(&conn, 2).unwrap().unwrap();
+    let tag_rc = TaskRepo::get_by_id(&conn, 3).unwrap().unwrap();
+    let notes = TaskRepo::get_by_id(&conn, 4).unwrap().unwrap();
+    assert_eq!(parent.description, "Release 5.3");
+    assert_eq!(freeze.parent_id, Some(1));
+    assert_eq!(freeze.due_ts, Some(parent.due_ts.unwrap() - 3 * 86400));
+    assert_eq!(freeze.project_id, parent.project_id);
+    assert!(TaskRepo::get_tags(&conn, 2).unwrap().contains(&"freeze".to_string()));
+    assert_eq!(tag_rc.parent_id, Some(2));
+    assert_eq!(tag_rc.alloc_secs, Some(1800));
+    assert_eq!(notes.due_ts, Some(parent.due_ts.unwrap() - 86400));
+    assert_ne!(notes.project_id, parent.project_id);
+
+    get_task_cmd().args(&["templates", "remove-child", "release", "1"]).assert().success();
+    let tmpl = TemplateRepo::get_by_name(&conn, "release").unwrap().unwrap();
+    assert_eq!(tmpl.children().unwrap().len(), 1);
+    drop(temp_dir);
+}
+
+#[test]
+fn test_clone_tree() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd().args(&["add", "Onboard Ana", "due=2030-01-10", "+onboarding"]).assert().success();
+    get_task_cmd().args(&["add", "Order laptop", "parent=1", "due=2030-01-03"]).assert().success();
+    get_task_cmd().args(&["add", "Set up accounts", "parent=2"]).assert().success();
+    get_task_cmd().args(&["close", "3", "-y"]).assert().success();
+
+    // Without --tree only the task itself is cloned
+    get_task_cmd().args(&["clone", "1"]).assert().success();
+    let conn = DbConnection::connect().unwrap();
+    assert!(TaskRepo::get_children(&conn, 4).unwrap().is_empty());
+
+    get_task_cmd()
+        .args(&["clone", "--tree", "1", "Onboard Ben", "due=2030-02-10"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cloned task 2 → new task 6"))
+        .stdout(predicate::str::contains("Cloned task 3 → new task 7"));
+
+    let root = TaskRepo::get_by_id(&conn, 5).unwrap().unwrap();
+    let laptop = TaskRepo::get_by_id(&conn, 6).unwrap().unwrap();
+    let accounts = TaskRepo::get_by_id(&conn, 7).unwrap().unwrap();
+    assert_eq!(root.description, "Onboard Ben");
+    assert_eq!(laptop.parent_id, Some(5));
+    assert_eq!(accounts.parent_id, Some(6));
+    assert_eq!(accounts.status, tatl::models::TaskStatus::Open);
+    // Subtask due dates shift with the root's
+    let shift = root.due_ts.unwrap() - TaskRepo::get_by_id(&conn, 1).unwrap().unwrap().due_ts.unwrap();
+    let original_laptop = TaskRepo::get_by_id(&conn, 2).unwrap().unwrap();
+    assert_eq!(laptop.due_ts, Some(original_laptop.due_ts.unwrap() + shift));
+    drop(temp_dir);
+}
@@ -0,0 +1,152 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env() -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n", db_path.display())).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+fn list_output(temp_dir: &TempDir, args: &[&str]) -> String {
+    let output = get_task_cmd(temp_dir).arg("list").args(args).assert().success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_udas_define_list_remove() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir)
+        .args(["udas", "define", "severity", "type=enum", "values=low,medium,high", "default=low"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Defined UDA 'severity'"));
+    get_task_cmd(&temp_dir)
+        .args(["udas", "define", "severity", "label=Sev"])
+        .assert()
+        .success();
+
+    get_task_cmd(&temp_dir)
+        .args(["udas"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("severity"))
+        .stdout(predicate::str::contains("low,medium,high"))
+        .stdout(predicate::str::contains("Sev"));
+
+    // New definitions need a type, enums need values, defaults must validate
+    get_task_cmd(&temp_dir).args(["udas", "define", "points"]).assert().failure();
+    get_task_cmd(&temp_dir).args(["udas", "define", "size", "type=enum"]).assert().failure();
+    get_task_cmd(&temp_dir)
+        .args(["udas", "define", "points", "type=number", "default=many"])
+        .assert()
+        .failure();
+
+    get_task_cmd(&temp_dir)
+        .args(["udas", "remove", "severity"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed UDA definition 'severity'"));
+    get_task_cmd(&temp_dir).args(["udas", "remove", "severity"]).assert().failure();
+
+    drop(temp_dir);
+}
+
+#[test]
+fn test_typed_uda_validation_and_defaults() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir)
+        .args(["udas", "define", "severity", "type=enum", "values=low,medium,high", "default=low"])
+        .assert()
+        .success();
+    get_task_cmd(&temp_dir)
+        .args(["udas", "define", "estimate", "type=duration"])
+        .assert()
+        .success();
+
+    get_task_cmd(&temp_dir)
+        .args(["add", "Typo task", "uda.severity=hgih"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Did you mean 'high'?"));
+    get_task_cmd(&temp_dir)
+        .args(["add", "Bad estimate", "uda.estimate=soon"])
+        .assert()
+        .failure();
+
+    // Enum values normalise to the declared spelling; defaults fill missing values
+    get_task_cmd(&temp_dir).args(["add", "Urgent", "uda.severity=HIGH", "uda.estimate=1h30m"]).assert().success();
+    get_task_cmd(&temp_dir).args(["add", "Routine"]).assert().success();
+
+    let stdout = list_output(&temp_dir, &["uda.severity=high", "show:uda.severity,uda.estimate"]);
+    assert!(stdout.contains("Urgent"));
+    assert!(!stdout.contains("Routine"));
+    assert!(stdout.contains("1h30m"));
+    assert!(list_output(&temp_dir, &["uda.severity=low"]).contains("Routine"));
+
+    // Enum comparisons and sorting follow the declared order
+    let stdout = list_output(&temp_dir, &["uda.severity>medium"]);
+    assert!(stdout.contains("Urgent"));
+    assert!(!stdout.contains("Routine"));
+    let stdout = list_output(&temp_dir, &["sort:uda.severity"]);
+    let routine = stdout.lines().position(|l| l.contains("Routine")).unwrap();
+    let urgent = stdout.lines().position(|l| l.contains("Urgent")).unwrap();
+    assert!(routine < urgent, "low should sort before high");
+
+    // Modify validates too
+    get_task_cmd(&temp_dir)
+        .args(["modify", "2", "uda.severity=meduim", "-y"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Did you mean 'medium'?"));
+
+    drop(temp_dir);
+}
+
+#[test]
+fn test_date_uda_filters_use_date_expressions() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir)
+        .args(["udas", "define", "review", "type=date", "label=Review"])
+        .assert()
+        .success();
+    get_task_cmd(&temp_dir).args(["add", "Soon", "uda.review=tomorrow"]).assert().success();
+    get_task_cmd(&temp_dir).args(["add", "Later", "uda.review=+60d"]).assert().success();
+    get_task_cmd(&temp_dir).args(["add", "Never"]).assert().success();
+
+    let stdout = list_output(&temp_dir, &["uda.review<+7d"]);
+    assert!(stdout.contains("Soon"));
+    assert!(!stdout.contains("Later"));
+    assert!(!stdout.contains("Never"));
+
+    let stdout = list_output(&temp_dir, &["uda.review=tomorrow"]);
+    assert!(stdout.contains("Soon"));
+    assert!(!stdout.contains("Later"));
+
+    let stdout = list_output(&temp_dir, &["uda.review=none"]);
+    assert!(stdout.contains("Never"));
+    assert!(!stdout.contains("Soon"));
+
+    // The display label is used as the column header
+    let stdout = list_output(&temp_dir, &["show:uda.review"]);
+    assert!(stdout.lines().any(|l| l.contains("Review") && l.contains("ID")));
+
+    drop(temp_dir);
+}
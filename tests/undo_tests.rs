@@ -0,0 +1,119 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+use std::fs;
+mod test_env;
+
+fn setup_test_env() -> (TempDir, std::sync::MutexGuard<'static, ()>) {
+    let guard = test_env::lock_test_env();
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let config_dir = temp_dir.path().join(".tatl");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config_file = config_dir.join("rc");
+    fs::write(&config_file, format!("data.location={}\n", db_path.display())).unwrap();
+    std::env::set_var("HOME", temp_dir.path().to_str().unwrap());
+    (temp_dir, guard)
+}
+
+fn get_task_cmd(temp_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("tatl").unwrap();
+    cmd.env("HOME", temp_dir.path());
+    cmd
+}
+
+#[test]
+fn test_undo_modify() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Original", "-y"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["modify", "1", "Renamed", "+urgent", "-y"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["undo", "-y"]).assert().success()
+        .stdout(predicate::str::contains("description: Original → Renamed"))
+        .stdout(predicate::str::contains("Undid 1 command(s)"));
+
+    get_task_cmd(&temp_dir).args(&["show", "1"]).assert().success()
+        .stdout(predicate::str::contains("Original"))
+        .stdout(predicate::str::contains("urgent").not());
+}
+
+#[test]
+fn test_undo_pipe_chain_as_one() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Existing"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Piped", ":", "enqueue", ":", "on"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["undo", "-y"]).assert().success()
+        .stdout(predicate::str::contains("delete task"));
+
+    get_task_cmd(&temp_dir).args(&["show", "2"]).assert().failure();
+    get_task_cmd(&temp_dir).args(&["show", "1"]).assert().success()
+        .stdout(predicate::str::contains("Existing"));
+    get_task_cmd(&temp_dir).args(&["status"]).assert().success()
+        .stdout(predicate::str::contains("Piped").not());
+}
+
+#[test]
+fn test_undo_delete() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "A", "-y"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "B", "-y"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["modify", "1", "-y", "due=tomorrow"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["delete", "2", "-y"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["show", "2"]).assert().failure();
+
+    // Undo reverts the delete, not the modify before it
+    get_task_cmd(&temp_dir).args(&["undo", "-y"]).assert().success()
+        .stdout(predicate::str::contains("open → deleted"));
+    get_task_cmd(&temp_dir).args(&["show", "2"]).assert().success()
+        .stdout(predicate::str::contains("B"));
+    get_task_cmd(&temp_dir).args(&["show", "1"]).assert().success()
+        .stdout(predicate::str::contains("Due:"));
+}
+
+#[test]
+fn test_undo_steps_back() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["modify", "1", "project=work", "-y"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["close", "1"]).assert().success();
+
+    get_task_cmd(&temp_dir).args(&["undo", "-y"]).assert().success()
+        .stdout(predicate::str::contains("open → closed"));
+    get_task_cmd(&temp_dir).args(&["undo", "-y"]).assert().success()
+        .stdout(predicate::str::contains("project: none → work"));
+
+    let output = get_task_cmd(&temp_dir).args(&["list", "--json"]).output().unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value[0]["status"], "open");
+    assert!(value[0]["project_id"].is_null());
+}
+
+#[test]
+fn test_undo_multiple() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "First"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["add", "Second"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["undo", "2", "-y"]).assert().success()
+        .stdout(predicate::str::contains("Undid 2 command(s)"));
+
+    get_task_cmd(&temp_dir).args(&["undo", "-y"]).assert().success()
+        .stdout(predicate::str::contains("Nothing to undo"));
+}
+
+#[test]
+fn test_undo_cancelled() {
+    let (temp_dir, _guard) = setup_test_env();
+
+    get_task_cmd(&temp_dir).args(&["add", "Task"]).assert().success();
+    get_task_cmd(&temp_dir).args(&["undo"]).write_stdin("n\n").assert().success()
+        .stdout(predicate::str::contains("Cancelled"));
+
+    get_task_cmd(&temp_dir).args(&["show", "1"]).assert().success()
+        .stdout(predicate::str::contains("Task"));
+}